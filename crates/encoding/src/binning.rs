@@ -9,4 +9,6 @@ use bytemuck::{Pod, Zeroable};
 pub struct BinHeader {
     pub element_count: u32,
     pub chunk_offset: u32,
+    /// Offset of the chunk holding the elements past the bin capacity, if there are any.
+    pub overflow_offset: u32,
 }
@@ -13,6 +13,9 @@ use std::mem;
 const TILE_WIDTH: u32 = 16;
 const TILE_HEIGHT: u32 = 16;
 
+/// Number of tiles in each bin of the binning and coarse rasterization stages.
+pub const N_TILES_PER_BIN: u32 = 256;
+
 // TODO: Obtain these from the vello_shaders crate
 pub(crate) const PATH_REDUCE_WG: u32 = 256;
 const PATH_BBOX_WG: u32 = 256;
@@ -35,6 +38,56 @@ pub struct BumpAllocators {
     pub segments: u32,
     pub blend: u32,
     pub lines: u32,
+    // Number of tiles of draw objects written as solid fills and as fills with coverage
+    // computed from their segments. These are statistics, rather than allocations.
+    pub solid_tiles: u32,
+    pub coverage_tiles: u32,
+}
+
+// Failure flags written by the GPU stages, matching `shader/shared/bump.wgsl`.
+const STAGE_BINNING: u32 = 0x1;
+const STAGE_TILE_ALLOC: u32 = 0x2;
+const STAGE_COARSE: u32 = 0x8;
+
+impl BumpAllocators {
+    /// Returns the buffer lengths needed to rerun a render that produced these counters
+    /// with `config`, or `None` if none of the bump allocated buffers overflowed.
+    ///
+    /// Stages after a failed one may stop early and undercount, so each overflowed buffer at
+    /// least doubles in size.
+    pub fn grow(&self, config: &RenderConfig) -> Option<BumpAllocators> {
+        let mut grown = false;
+        let mut grow = |needed: u32, len: u32, failed: bool| {
+            if failed || needed > len {
+                grown = true;
+                needed.max(len.saturating_mul(2))
+            } else {
+                len
+            }
+        };
+        let (gpu, sizes) = (&config.gpu, &config.buffer_sizes);
+        let next = BumpAllocators {
+            failed: 0,
+            binning: grow(
+                self.binning,
+                gpu.binning_size,
+                self.failed & STAGE_BINNING != 0,
+            ),
+            ptcl: grow(self.ptcl, gpu.ptcl_size, self.failed & STAGE_COARSE != 0),
+            tile: grow(
+                self.tile,
+                gpu.tiles_size,
+                self.failed & STAGE_TILE_ALLOC != 0,
+            ),
+            seg_counts: grow(self.seg_counts, sizes.seg_counts.len(), false),
+            segments: grow(self.segments, sizes.segments.len(), false),
+            blend: self.blend,
+            lines: grow(self.lines, sizes.lines.len(), false),
+            solid_tiles: 0,
+            coverage_tiles: 0,
+        };
+        grown.then_some(next)
+    }
 }
 
 /// Storage of indirect dispatch size values.
@@ -78,6 +131,35 @@ pub struct ConfigUniform {
     pub segments_size: u32,
     /// Size of per-tile command list buffer allocation (in `u32`s).
     pub ptcl_size: u32,
+    /// Width of a bin in tiles. Bins always contain [`N_TILES_PER_BIN`] tiles.
+    pub bin_width: u32,
+    /// Number of draw objects stored in the chunk of each bin in a partition. Any further
+    /// draw objects in the bin spill into a separate overflow chunk.
+    pub bin_capacity: u32,
+    /// Gamma used by fine rasterization to correct the coverage of glyphs drawn with solid
+    /// colors. 1.0 disables the correction.
+    pub glyph_gamma: f32,
+    /// Non-zero if fine rasterization treats every partially covered pixel of a fill as fully
+    /// covered.
+    pub conservative_coverage: u32,
+    /// Maximum number of line segments the flatten stage produces for a single curve,
+    /// regardless of the tolerance. Zero means there is no limit.
+    pub max_curve_segments: u32,
+    /// Largest change made to each color channel by the film grain noise of fine
+    /// rasterization. Zero disables the noise.
+    pub grain_intensity: f32,
+    /// Seed for stochastic effects, such as the film grain noise.
+    pub seed: u32,
+    /// Non-zero if every draw object is opaque and drawn without layers onto an opaque base
+    /// color, so fine rasterization can write the colors of fully covered pixels without
+    /// blending.
+    pub opaque: u32,
+    /// Distance in pixels within which the flatten stage moves the points of lines onto the
+    /// nearest pixel row boundary. Zero disables this.
+    pub horizontal_epsilon: f32,
+    /// Non-zero if the flatten stage replaces each filled curve whose control points all lie
+    /// past the same edge of the target with a single line between its end points.
+    pub cull_offscreen_curves: u32,
 }
 
 /// CPU side setup and configuration.
@@ -92,14 +174,29 @@ pub struct RenderConfig {
 }
 
 impl RenderConfig {
-    pub fn new(layout: &Layout, width: u32, height: u32, base_color: &peniko::Color) -> Self {
+    /// Creates the configuration for rendering a scene with the given layout.
+    ///
+    /// `bin_width` is the width of a bin in tiles, which must be a power of two no larger
+    /// than [`N_TILES_PER_BIN`].
+    pub fn new(
+        layout: &Layout,
+        width: u32,
+        height: u32,
+        base_color: &peniko::Color,
+        bin_width: u32,
+    ) -> Self {
         let new_width = width.next_multiple_of(TILE_WIDTH);
         let new_height = height.next_multiple_of(TILE_HEIGHT);
         let width_in_tiles = new_width / TILE_WIDTH;
         let height_in_tiles = new_height / TILE_HEIGHT;
         let n_path_tags = layout.path_tags_size();
-        let workgroup_counts =
-            WorkgroupCounts::new(layout, width_in_tiles, height_in_tiles, n_path_tags);
+        let workgroup_counts = WorkgroupCounts::new(
+            layout,
+            width_in_tiles,
+            height_in_tiles,
+            n_path_tags,
+            bin_width,
+        );
         let buffer_sizes = BufferSizes::new(layout, &workgroup_counts);
         Self {
             gpu: ConfigUniform {
@@ -108,16 +205,64 @@ impl RenderConfig {
                 target_width: width,
                 target_height: height,
                 base_color: base_color.to_premul_u32(),
-                binning_size: buffer_sizes.bin_data.len() - layout.bin_data_start,
+                binning_size: buffer_sizes
+                    .bin_data
+                    .len()
+                    .saturating_sub(layout.bin_data_start),
                 tiles_size: buffer_sizes.tiles.len(),
                 segments_size: buffer_sizes.segments.len(),
                 ptcl_size: buffer_sizes.ptcl.len(),
                 layout: *layout,
+                bin_width,
+                bin_capacity: N_TILES_PER_BIN,
+                glyph_gamma: 1.0,
+                conservative_coverage: 0,
+                max_curve_segments: 0,
+                grain_intensity: 0.0,
+                seed: 0,
+                opaque: 0,
+                horizontal_epsilon: 0.0,
+                cull_offscreen_curves: 0,
             },
             workgroup_counts,
             buffer_sizes,
         }
     }
+
+    /// Sets the number of draw objects stored in the chunk of each bin in a partition, and sizes
+    /// the binning buffer to hold a full chunk for every bin of every partition.
+    ///
+    /// The overflow chunks of bins with more draw objects are allocated after the chunks, so
+    /// they overflow the buffer until it's grown to fit them with [`BumpAllocators::grow`].
+    pub fn set_bin_capacity(&mut self, capacity: u32) {
+        let (width_in_bins, height_in_bins, _) = self.workgroup_counts.coarse;
+        let n_partitions = self.workgroup_counts.binning.0;
+        self.gpu.bin_capacity = capacity;
+        self.gpu.binning_size = (n_partitions * width_in_bins * height_in_bins)
+            .saturating_mul(capacity)
+            .max(1);
+        self.buffer_sizes.bin_data =
+            BufferSize::new(self.gpu.layout.bin_data_start + self.gpu.binning_size);
+    }
+
+    /// Grows the bump allocated buffers to hold at least the number of elements in the
+    /// corresponding fields of `min`, such as those returned by [`BumpAllocators::grow`].
+    pub fn reserve_bump_buffers(&mut self, min: &BumpAllocators) {
+        let sizes = &mut self.buffer_sizes;
+        let bin_data_start = self.gpu.layout.bin_data_start;
+        if min.binning > self.gpu.binning_size {
+            sizes.bin_data = BufferSize::new(bin_data_start + min.binning);
+        }
+        sizes.ptcl = BufferSize::new(sizes.ptcl.len().max(min.ptcl));
+        sizes.tiles = BufferSize::new(sizes.tiles.len().max(min.tile));
+        sizes.seg_counts = BufferSize::new(sizes.seg_counts.len().max(min.seg_counts));
+        sizes.segments = BufferSize::new(sizes.segments.len().max(min.segments));
+        sizes.lines = BufferSize::new(sizes.lines.len().max(min.lines));
+        self.gpu.binning_size = sizes.bin_data.len().saturating_sub(bin_data_start);
+        self.gpu.tiles_size = sizes.tiles.len();
+        self.gpu.segments_size = sizes.segments.len();
+        self.gpu.ptcl_size = sizes.ptcl.len();
+    }
 }
 
 /// Type alias for a workgroup size.
@@ -154,6 +299,7 @@ impl WorkgroupCounts {
         width_in_tiles: u32,
         height_in_tiles: u32,
         n_path_tags: u32,
+        bin_width: u32,
     ) -> Self {
         let n_paths = layout.n_paths;
         let n_draw_objects = layout.n_draw_objects;
@@ -171,8 +317,9 @@ impl WorkgroupCounts {
         let clip_reduce_wgs = n_clips.saturating_sub(1) / CLIP_REDUCE_WG;
         let clip_wgs = (n_clips + CLIP_REDUCE_WG - 1) / CLIP_REDUCE_WG;
         let path_wgs = (n_paths + PATH_BBOX_WG - 1) / PATH_BBOX_WG;
-        let width_in_bins = (width_in_tiles + 15) / 16;
-        let height_in_bins = (height_in_tiles + 15) / 16;
+        let bin_height = N_TILES_PER_BIN / bin_width;
+        let width_in_bins = (width_in_tiles + bin_width - 1) / bin_width;
+        let height_in_bins = (height_in_tiles + bin_height - 1) / bin_height;
         Self {
             use_large_path_scan,
             path_reduce: (path_tag_wgs, 1, 1),
@@ -347,8 +494,115 @@ impl BufferSizes {
             ptcl,
         }
     }
+
+    /// Returns the combined size in bytes of all of the buffers.
+    pub fn total_size_in_bytes(&self) -> u64 {
+        [
+            self.path_reduced.size_in_bytes(),
+            self.path_reduced2.size_in_bytes(),
+            self.path_reduced_scan.size_in_bytes(),
+            self.path_monoids.size_in_bytes(),
+            self.path_bboxes.size_in_bytes(),
+            self.draw_reduced.size_in_bytes(),
+            self.draw_monoids.size_in_bytes(),
+            self.info.size_in_bytes(),
+            self.clip_inps.size_in_bytes(),
+            self.clip_els.size_in_bytes(),
+            self.clip_bics.size_in_bytes(),
+            self.clip_bboxes.size_in_bytes(),
+            self.draw_bboxes.size_in_bytes(),
+            self.bump_alloc.size_in_bytes(),
+            self.indirect_count.size_in_bytes(),
+            self.bin_headers.size_in_bytes(),
+            self.paths.size_in_bytes(),
+            self.lines.size_in_bytes(),
+            self.bin_data.size_in_bytes(),
+            self.tiles.size_in_bytes(),
+            self.seg_counts.size_in_bytes(),
+            self.segments.size_in_bytes(),
+            self.ptcl.size_in_bytes(),
+        ]
+        .into_iter()
+        .map(u64::from)
+        .sum()
+    }
 }
 
 const fn align_up(len: u32, alignment: u32) -> u32 {
     len + (len.wrapping_neg() & (alignment - 1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RenderConfig {
+        let layout = Layout {
+            bin_data_start: 100,
+            ..Default::default()
+        };
+        RenderConfig::new(&layout, 256, 256, &peniko::Color::BLACK, 16)
+    }
+
+    #[test]
+    fn test_grow_within_bounds() {
+        let bump = BumpAllocators {
+            tile: 10,
+            ptcl: 10,
+            ..Default::default()
+        };
+        assert!(bump.grow(&config()).is_none());
+    }
+
+    #[test]
+    fn test_grow_overflowed() {
+        let mut config = config();
+        let tiles = config.gpu.tiles_size;
+        let bump = BumpAllocators {
+            failed: STAGE_TILE_ALLOC | STAGE_COARSE,
+            tile: tiles * 3,
+            lines: config.buffer_sizes.lines.len() + 1,
+            ..Default::default()
+        };
+        let next = bump.grow(&config).unwrap();
+        config.reserve_bump_buffers(&next);
+        assert_eq!(config.gpu.tiles_size, tiles * 3);
+        assert_eq!(config.buffer_sizes.tiles.len(), tiles * 3);
+        assert_eq!(config.gpu.ptcl_size, config.buffer_sizes.ptcl.len());
+        assert_eq!(config.gpu.ptcl_size, 2 << 23);
+        assert_eq!(config.buffer_sizes.lines.len(), 2 << 21);
+        assert_eq!(config.buffer_sizes.segments.len(), 1 << 21);
+        assert_eq!(
+            config.buffer_sizes.bin_data.len(),
+            100 + config.gpu.binning_size
+        );
+        // The grown buffers fit the same counters on the next attempt.
+        let bump = BumpAllocators { failed: 0, ..bump };
+        assert!(bump.grow(&config).is_none());
+    }
+
+    #[test]
+    fn test_bin_capacity_sizes_binning() {
+        // Two partitions of draw objects in a single bin.
+        let layout = Layout {
+            bin_data_start: 100,
+            n_draw_objects: 300,
+            ..Default::default()
+        };
+        let mut config = RenderConfig::new(&layout, 256, 256, &peniko::Color::BLACK, 16);
+        config.set_bin_capacity(16);
+        assert_eq!(config.gpu.bin_capacity, 16);
+        assert_eq!(config.gpu.binning_size, 2 * 16);
+        assert_eq!(config.buffer_sizes.bin_data.len(), 100 + 2 * 16);
+        // Overflow chunks grow the buffer.
+        let bump = BumpAllocators {
+            failed: STAGE_BINNING,
+            binning: 300,
+            ..Default::default()
+        };
+        let next = bump.grow(&config).unwrap();
+        config.reserve_bump_buffers(&next);
+        assert_eq!(config.gpu.binning_size, 300);
+        assert_eq!(config.buffer_sizes.bin_data.len(), 100 + 300);
+    }
+}
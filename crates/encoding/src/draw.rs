@@ -25,10 +25,16 @@ impl DrawTag {
     pub const RADIAL_GRADIENT: Self = Self(0x29c);
 
     /// Image fill.
-    pub const IMAGE: Self = Self(0x248);
+    pub const IMAGE: Self = Self(0x2d0);
+
+    /// Bilinear gradient fill between four corner colors.
+    pub const BILINEAR_GRADIENT: Self = Self(0x6cc);
 
     /// Begin layer/clip.
-    pub const BEGIN_CLIP: Self = Self(0x9);
+    pub const BEGIN_CLIP: Self = Self(0x1d);
+
+    /// Begin layer/clip with a color matrix applied to the content of the layer.
+    pub const BEGIN_CLIP_COLOR_MATRIX: Self = Self(0x501d);
 
     /// End layer/clip.
     pub const END_CLIP: Self = Self(0x21);
@@ -46,6 +52,16 @@ impl DrawTag {
 /// 0 represents a non-zero fill. 1 represents an even-odd fill.
 pub const DRAW_INFO_FLAGS_FILL_RULE_BIT: u32 = 1;
 
+/// Set in the draw info flags of glyph outlines.
+pub const DRAW_INFO_FLAGS_GLYPH_BIT: u32 = 2;
+
+/// Set in the draw info flags of draws with hard coverage instead of antialiasing.
+pub const DRAW_INFO_FLAGS_ALIASED_BIT: u32 = 4;
+
+/// The draw info flags hold the clip rect of the draw above this bit, as one more than its
+/// index in the clip rect stream, or 0 when the draw isn't clipped to a rectangle.
+pub const DRAW_INFO_FLAGS_CLIP_RECT_SHIFT: u32 = 8;
+
 /// Draw object bounding box.
 #[derive(Copy, Clone, Pod, Zeroable, Debug, Default)]
 #[repr(C)]
@@ -107,6 +123,88 @@ pub struct DrawImage {
     pub xy: u32,
     /// Packed image dimensions.
     pub width_height: u32,
+    /// Packed [`ImageSampler`].
+    pub sampler: u32,
+    /// Premultiplied color which the sampled image is multiplied by.
+    pub tint: u32,
+}
+
+/// Draw data for a bilinear gradient patch.
+///
+/// The patch covers the unit square of the brush coordinate system, which is mapped onto
+/// the shape by the brush transform.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+#[repr(C)]
+pub struct DrawBilinearGradient {
+    /// Packed premultiplied corner colors at `(0, 0)`, `(1, 0)`, `(0, 1)` and `(1, 1)`.
+    pub colors: [u32; 4],
+}
+
+impl DrawBilinearGradient {
+    /// Creates new bilinear gradient draw data from the colors at `(0, 0)`, `(1, 0)`,
+    /// `(0, 1)` and `(1, 1)`.
+    pub fn new(colors: [Color; 4]) -> Self {
+        Self {
+            colors: colors.map(|color| color.to_premul_u32()),
+        }
+    }
+}
+
+/// How coordinates outside of an image are mapped onto it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageAddressMode {
+    /// Nothing is drawn outside of the image.
+    #[default]
+    ClampToBorder = 0,
+    /// The edge texels are extended outwards.
+    ClampToEdge = 1,
+    /// The image is tiled.
+    Repeat = 2,
+    /// The image is tiled, with every other tile mirrored.
+    MirrorRepeat = 3,
+}
+
+/// How texels are combined when an image is sampled between texel centers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageFilter {
+    /// Bilinear interpolation between the four nearest texels.
+    #[default]
+    Linear = 0,
+    /// The nearest texel.
+    Nearest = 1,
+}
+
+/// Whether the color channels of an image have been multiplied by its alpha.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AlphaType {
+    /// The color channels are independent of alpha, as in a [`peniko::Image`].
+    #[default]
+    Straight = 0,
+    /// The color channels are premultiplied by alpha, so they are used as they are.
+    Premultiplied = 1,
+}
+
+/// Sampler state used by the fine stage when drawing an image brush.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageSampler {
+    /// Address mode in the horizontal direction.
+    pub address_mode_x: ImageAddressMode,
+    /// Address mode in the vertical direction.
+    pub address_mode_y: ImageAddressMode,
+    /// Filter used for sampling.
+    pub filter: ImageFilter,
+    /// Whether the texels of the image are premultiplied.
+    pub alpha_type: AlphaType,
+}
+
+impl ImageSampler {
+    /// Returns the sampler packed into the layout read by the fine stage.
+    pub fn pack(self) -> u32 {
+        self.address_mode_x as u32
+            | (self.address_mode_y as u32) << 2
+            | (self.filter as u32) << 4
+            | (self.alpha_type as u32) << 5
+    }
 }
 
 /// Draw data for a clip or layer.
@@ -117,14 +215,40 @@ pub struct DrawBeginClip {
     pub blend_mode: u32,
     /// Group alpha.
     pub alpha: f32,
+    /// Width of the soft edge of the clip, or zero for a hard edge.
+    pub feather: f32,
+    /// Device space rectangle whose edges are feathered.
+    pub feather_rect: [f32; 4],
 }
 
 impl DrawBeginClip {
+    /// Flag set in the blend mode of a layer which is followed by a color matrix.
+    pub const COLOR_MATRIX_FLAG: u32 = 1 << 16;
+    /// Flag set in the blend mode of a layer whose content is drawn onto a copy of the
+    /// backdrop, rather than onto a transparent one.
+    pub const NON_ISOLATED_FLAG: u32 = 1 << 17;
+    /// Flag set in the blend mode of a layer whose draw objects are each composited with the
+    /// initial backdrop of the layer, replacing rather than covering the objects drawn
+    /// before them in the layer.
+    pub const KNOCKOUT_FLAG: u32 = 1 << 18;
+
     /// Creates new clip draw data.
     pub fn new(blend_mode: BlendMode, alpha: f32) -> Self {
         Self {
             blend_mode: (blend_mode.mix as u32) << 8 | blend_mode.compose as u32,
             alpha,
+            feather: 0.0,
+            feather_rect: [0.0; 4],
+        }
+    }
+
+    /// Creates new clip draw data where the clip coverage ramps from 1 to 0 over a band of
+    /// width `feather` centered on the edges of `rect`.
+    pub fn new_feathered(blend_mode: BlendMode, alpha: f32, rect: [f32; 4], feather: f32) -> Self {
+        Self {
+            feather,
+            feather_rect: rect,
+            ..Self::new(blend_mode, alpha)
         }
     }
 }
@@ -150,7 +274,8 @@ impl Monoid for DrawMonoid {
         Self {
             path_ix: (tag != DrawTag::NOP) as u32,
             clip_ix: tag.0 & 1,
-            scene_offset: (tag.0 >> 2) & 0x7,
+            // Tags with more than 7 words of draw data keep the remainder in bits 10-15.
+            scene_offset: ((tag.0 >> 2) & 0x7) + ((tag.0 >> 10) & 0x3f),
             info_offset: (tag.0 >> 6) & 0xf,
         }
     }
@@ -164,3 +289,36 @@ impl Monoid for DrawMonoid {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draw_data_sizes() {
+        let size = |tag| DrawMonoid::new(tag).scene_offset as usize * 4;
+        assert_eq!(size(DrawTag::COLOR), std::mem::size_of::<DrawColor>());
+        assert_eq!(
+            size(DrawTag::LINEAR_GRADIENT),
+            std::mem::size_of::<DrawLinearGradient>()
+        );
+        assert_eq!(
+            size(DrawTag::RADIAL_GRADIENT),
+            std::mem::size_of::<DrawRadialGradient>()
+        );
+        assert_eq!(size(DrawTag::IMAGE), std::mem::size_of::<DrawImage>());
+        assert_eq!(
+            size(DrawTag::BILINEAR_GRADIENT),
+            std::mem::size_of::<DrawBilinearGradient>()
+        );
+        assert_eq!(
+            size(DrawTag::BEGIN_CLIP),
+            std::mem::size_of::<DrawBeginClip>()
+        );
+        assert_eq!(
+            size(DrawTag::BEGIN_CLIP_COLOR_MATRIX),
+            std::mem::size_of::<DrawBeginClip>() + std::mem::size_of::<[f32; 20]>()
+        );
+        assert_eq!(size(DrawTag::END_CLIP), 0);
+    }
+}
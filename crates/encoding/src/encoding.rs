@@ -1,16 +1,24 @@
 // Copyright 2022 The Vello authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use super::{DrawColor, DrawTag, PathEncoder, PathTag, Style, Transform};
+use super::{
+    DrawBeginClip, DrawBilinearGradient, DrawColor, DrawMonoid, DrawTag, Monoid, PathEncoder,
+    PathTag, Style, Transform,
+};
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 use peniko::{
-    kurbo::{Shape, Stroke},
+    kurbo::{Rect, Shape, Stroke},
     BlendMode, BrushRef, Color, Fill,
 };
 
 #[cfg(feature = "full")]
 use {
-    super::{DrawImage, DrawLinearGradient, DrawRadialGradient, Glyph, GlyphRun, Patch},
+    super::{
+        DrawImage, DrawLinearGradient, DrawRadialGradient, Glyph, GlyphRun, ImageSampler, Patch,
+    },
     peniko::{ColorStop, Extend, GradientKind, Image},
     skrifa::instance::NormalizedCoord,
 };
@@ -35,6 +43,9 @@ pub struct Encoding {
     pub transforms: Vec<Transform>,
     /// The style stream
     pub styles: Vec<Style>,
+    /// The clip rect stream, holding the device space rectangles referenced by styles, as
+    /// `[x0, y0, x1, y1]`.
+    pub clip_rects: Vec<[f32; 4]>,
     /// Late bound resource data.
     #[cfg(feature = "full")]
     pub resources: Resources,
@@ -48,6 +59,25 @@ pub struct Encoding {
     pub n_open_clips: u32,
     /// Flags that capture the current state of the encoding.
     pub flags: u32,
+    /// If set, the number of fractional bits of the fixed-point grid that path coordinates
+    /// are snapped to as they are encoded. This setting is kept when the encoding is reset.
+    pub path_snapping: Option<u32>,
+    /// If set, fills and strokes encoded from now on are drawn with hard coverage instead of
+    /// antialiasing. This setting is kept when the encoding is reset.
+    pub aliased: bool,
+    /// Depths assigned to draw objects, for rendering a depth buffer. Each entry gives the
+    /// index of the first draw tag at that depth, in increasing order. Draw objects before
+    /// the first entry have a depth of zero.
+    pub draw_depths: Vec<(usize, f32)>,
+    /// Indices of transforms, in increasing order, which are kept unchanged when this
+    /// encoding is appended to another with a transform.
+    pub fixed_transforms: Vec<usize>,
+    /// If set, the device space rectangle, as `[x0, y0, x1, y1]`, that fills and strokes
+    /// encoded from now on are clipped to.
+    ///
+    /// This clips without encoding a layer: the rectangle is carried along with the style of
+    /// each draw, and the fine stage tests pixels against it.
+    pub clip_rect: Option<[f32; 4]>,
 }
 
 impl Encoding {
@@ -69,12 +99,31 @@ impl Encoding {
         self.path_tags.is_empty()
     }
 
+    /// Returns the largest number of clips/layers that are open at the same time while
+    /// drawing the encoding.
+    pub fn max_clip_depth(&self) -> u32 {
+        let (mut depth, mut max_depth) = (0u32, 0u32);
+        for &tag in &self.draw_tags {
+            match tag {
+                DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => {
+                    depth += 1;
+                    max_depth = max_depth.max(depth);
+                }
+                DrawTag::END_CLIP => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        max_depth
+    }
+
     /// Clears the encoding.
     pub fn reset(&mut self) {
         self.transforms.clear();
         self.path_tags.clear();
         self.path_data.clear();
         self.styles.clear();
+        self.clip_rects.clear();
+        self.clip_rect = None;
         self.draw_data.clear();
         self.draw_tags.clear();
         self.n_paths = 0;
@@ -82,16 +131,26 @@ impl Encoding {
         self.n_clips = 0;
         self.n_open_clips = 0;
         self.flags = 0;
+        self.draw_depths.clear();
+        self.fixed_transforms.clear();
         #[cfg(feature = "full")]
         self.resources.reset();
     }
 
     /// Appends another encoding to this one with an optional transform.
     pub fn append(&mut self, other: &Self, transform: &Option<Transform>) {
+        let draw_tags_base = self.draw_tags.len();
+        self.draw_depths.extend(
+            other
+                .draw_depths
+                .iter()
+                .map(|&(ix, depth)| (ix + draw_tags_base, depth)),
+        );
         #[cfg(feature = "full")]
         let glyph_runs_base = {
             let offsets = self.stream_offsets();
             let stops_base = self.resources.color_stops.len();
+            let hints_base = self.resources.stop_hints.len();
             let glyph_runs_base = self.resources.glyph_runs.len();
             let glyphs_base = self.resources.glyphs.len();
             let coords_base = self.resources.normalized_coords.len();
@@ -122,12 +181,15 @@ impl Encoding {
                     Patch::Ramp {
                         draw_data_offset: offset,
                         stops,
+                        hints,
                         extend,
                     } => {
                         let stops = stops.start + stops_base..stops.end + stops_base;
+                        let hints = hints.start + hints_base..hints.end + hints_base;
                         Patch::Ramp {
                             draw_data_offset: offset + offsets.draw_data,
                             stops,
+                            hints,
                             extend: *extend,
                         }
                     }
@@ -145,20 +207,63 @@ impl Encoding {
             self.resources
                 .color_stops
                 .extend_from_slice(&other.resources.color_stops);
+            self.resources
+                .stop_hints
+                .extend_from_slice(&other.resources.stop_hints);
             glyph_runs_base
         };
         self.path_tags.extend_from_slice(&other.path_tags);
         self.path_data.extend_from_slice(&other.path_data);
+        let draw_data_base = self.draw_data.len();
         self.draw_tags.extend_from_slice(&other.draw_tags);
         self.draw_data.extend_from_slice(&other.draw_data);
+        if let Some(transform) = transform {
+            self.transform_feathered_clips(&other.draw_tags, draw_data_base, transform);
+        }
         self.n_paths += other.n_paths;
         self.n_path_segments += other.n_path_segments;
         self.n_clips += other.n_clips;
         self.n_open_clips += other.n_open_clips;
         self.flags = other.flags;
+        // The clip rects of the other encoding are moved along with its content, and clipped
+        // to the current clip rect of this one.
+        let current_clip_rect = self.clip_rect_index();
+        let clip_rects_base = self.clip_rects.len() as u32;
+        let clip_rect = self.clip_rect;
+        self.clip_rects
+            .extend(other.clip_rects.iter().map(|&[x0, y0, x1, y1]| {
+                let rect = match transform {
+                    Some(transform) => {
+                        let rect = Rect::new(x0 as f64, y0 as f64, x1 as f64, y1 as f64);
+                        let rect = transform.to_kurbo().transform_rect_bbox(rect);
+                        [
+                            rect.x0 as f32,
+                            rect.y0 as f32,
+                            rect.x1 as f32,
+                            rect.y1 as f32,
+                        ]
+                    }
+                    None => [x0, y0, x1, y1],
+                };
+                clip_rect.map_or(rect, |clip| intersect_clip_rects(clip, rect))
+            }));
+        let map_clip_rect = |ix: u32| match ix {
+            0 => current_clip_rect,
+            _ => ix + clip_rects_base,
+        };
+        let transforms_base = self.transforms.len();
+        self.fixed_transforms
+            .extend(other.fixed_transforms.iter().map(|ix| ix + transforms_base));
         if let Some(transform) = *transform {
+            let mut fixed = other.fixed_transforms.iter().peekable();
             self.transforms
-                .extend(other.transforms.iter().map(|x| transform * *x));
+                .extend(other.transforms.iter().enumerate().map(|(ix, x)| {
+                    if fixed.next_if_eq(&&ix).is_some() {
+                        *x
+                    } else {
+                        transform * *x
+                    }
+                }));
             #[cfg(feature = "full")]
             for run in &mut self.resources.glyph_runs[glyph_runs_base..] {
                 run.transform = transform * run.transform;
@@ -166,7 +271,64 @@ impl Encoding {
         } else {
             self.transforms.extend_from_slice(&other.transforms);
         }
-        self.styles.extend_from_slice(&other.styles);
+        #[cfg(feature = "full")]
+        for run in &mut self.resources.glyph_runs[glyph_runs_base..] {
+            run.clip_rect = map_clip_rect(run.clip_rect);
+        }
+        self.styles.extend(other.styles.iter().map(|style| Style {
+            clip_rect: map_clip_rect(style.clip_rect),
+            ..*style
+        }));
+    }
+
+    /// Moves the device space rects of the feathered clips among `draw_tags`, whose draw
+    /// data starts at `draw_data_base`, along with the content appended with `transform`.
+    ///
+    /// The feather width is scaled by the mean scale factor of the transform.
+    fn transform_feathered_clips(
+        &mut self,
+        draw_tags: &[DrawTag],
+        draw_data_base: usize,
+        transform: &Transform,
+    ) {
+        let transform = transform.to_kurbo();
+        let scale = transform.determinant().abs().sqrt() as f32;
+        let clip_size = std::mem::size_of::<DrawBeginClip>();
+        let mut offset = draw_data_base;
+        for &tag in draw_tags {
+            if tag == DrawTag::BEGIN_CLIP {
+                let data = &mut self.draw_data[offset..offset + clip_size];
+                let mut clip: DrawBeginClip = bytemuck::pod_read_unaligned(data);
+                if clip.feather > 0.0 {
+                    let [x0, y0, x1, y1] = clip.feather_rect;
+                    let rect = Rect::new(x0 as f64, y0 as f64, x1 as f64, y1 as f64);
+                    let rect = transform.transform_rect_bbox(rect);
+                    clip.feather_rect = [
+                        rect.x0 as f32,
+                        rect.y0 as f32,
+                        rect.x1 as f32,
+                        rect.y1 as f32,
+                    ];
+                    clip.feather *= scale;
+                    data.copy_from_slice(bytemuck::bytes_of(&clip));
+                }
+            }
+            offset += DrawMonoid::new(tag).scene_offset as usize * 4;
+        }
+    }
+
+    /// Assigns `depth` to the draw objects encoded from now on.
+    pub fn encode_depth(&mut self, depth: f32) {
+        let ix = self.draw_tags.len();
+        match self.draw_depths.last_mut() {
+            Some(last) if last.0 == ix => last.1 = depth,
+            _ => self.draw_depths.push((ix, depth)),
+        }
+    }
+
+    /// Returns the depth assigned to the draw objects encoded from now on.
+    pub fn current_depth(&self) -> f32 {
+        self.draw_depths.last().map_or(0.0, |&(_, depth)| depth)
     }
 
     /// Returns a snapshot of the current stream offsets.
@@ -181,6 +343,89 @@ impl Encoding {
         }
     }
 
+    /// Returns the value for the `clip_rect` field of styles encoded from now on: one more
+    /// than the index of [`clip_rect`](Self::clip_rect) in the clip rect stream, or 0 if it
+    /// isn't set.
+    ///
+    /// The rectangle is added to the stream if it isn't the last one there.
+    pub fn clip_rect_index(&mut self) -> u32 {
+        let Some(rect) = self.clip_rect else {
+            return 0;
+        };
+        if self.clip_rects.last() != Some(&rect) {
+            self.clip_rects.push(rect);
+        }
+        self.clip_rects.len() as u32
+    }
+
+    /// Returns a hash of the encoded content.
+    ///
+    /// Identical encodings produce the same hash, so this can be compared against the
+    /// hash of a previous frame to skip redundant renders. The value is only stable for
+    /// a given build and should not be persisted.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        bytemuck::cast_slice::<_, u8>(&self.path_tags).hash(&mut hasher);
+        self.path_data.hash(&mut hasher);
+        bytemuck::cast_slice::<_, u8>(&self.draw_tags).hash(&mut hasher);
+        self.draw_data.hash(&mut hasher);
+        bytemuck::cast_slice::<_, u8>(&self.transforms).hash(&mut hasher);
+        bytemuck::cast_slice::<_, u8>(&self.styles).hash(&mut hasher);
+        bytemuck::cast_slice::<_, u8>(&self.clip_rects).hash(&mut hasher);
+        (
+            self.n_paths,
+            self.n_path_segments,
+            self.n_clips,
+            self.n_open_clips,
+        )
+            .hash(&mut hasher);
+        for &(ix, depth) in &self.draw_depths {
+            (ix, depth.to_bits()).hash(&mut hasher);
+        }
+        self.fixed_transforms.hash(&mut hasher);
+        #[cfg(feature = "full")]
+        self.resources.hash_content(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns true if every draw object is opaque and composited directly onto the target.
+    ///
+    /// This holds when each object is a solid color or linear gradient without any
+    /// transparency, and there are no layers. Images and other brushes are assumed to be
+    /// translucent.
+    pub fn is_opaque(&self) -> bool {
+        let mut offset = 0;
+        for &tag in &self.draw_tags {
+            let opaque = match tag {
+                DrawTag::NOP => true,
+                DrawTag::COLOR => {
+                    let color: DrawColor = bytemuck::pod_read_unaligned(
+                        &self.draw_data[offset..offset + std::mem::size_of::<DrawColor>()],
+                    );
+                    color.rgba & 0xff == 0xff
+                }
+                #[cfg(feature = "full")]
+                DrawTag::LINEAR_GRADIENT => true,
+                _ => false,
+            };
+            if !opaque {
+                return false;
+            }
+            offset += DrawMonoid::new(tag).scene_offset as usize * 4;
+        }
+        // The stops are only used by gradients, which are all linear here.
+        #[cfg(feature = "full")]
+        if self
+            .resources
+            .color_stops
+            .iter()
+            .any(|stop| stop.color.a != 255)
+        {
+            return false;
+        }
+        true
+    }
+
     /// Encodes a fill style.
     pub fn encode_fill_style(&mut self, fill: Fill) {
         self.encode_style(Style::from_fill(fill));
@@ -191,7 +436,16 @@ impl Encoding {
         self.encode_style(Style::from_stroke(stroke));
     }
 
-    fn encode_style(&mut self, style: Style) {
+    /// Encodes a style for a stroke whose width is in device pixels.
+    pub fn encode_non_scaling_stroke_style(&mut self, stroke: &Stroke) {
+        self.encode_style(Style::from_non_scaling_stroke(stroke));
+    }
+
+    fn encode_style(&mut self, mut style: Style) {
+        if self.aliased {
+            style.flags_and_miter_limit |= Style::FLAGS_ALIASED_BIT;
+        }
+        style.clip_rect = self.clip_rect_index();
         if self.flags & Self::FORCE_NEXT_STYLE != 0 || self.styles.last() != Some(&style) {
             self.path_tags.push(PathTag::STYLE);
             self.styles.push(style);
@@ -226,6 +480,7 @@ impl Encoding {
             &mut self.n_paths,
             is_fill,
         )
+        .with_snapping(self.path_snapping)
     }
 
     /// Encodes a shape. If `is_fill` is true, all subpaths will be automatically closed.
@@ -249,8 +504,24 @@ impl Encoding {
     }
 
     /// Encodes a brush with an optional alpha modifier.
-    #[allow(unused_variables)]
     pub fn encode_brush<'b>(&mut self, brush: impl Into<BrushRef<'b>>, alpha: f32) {
+        self.encode_brush_with_hints(brush, alpha, &[]);
+    }
+
+    /// Encodes a brush with an optional alpha modifier and interpolation hints for
+    /// gradients.
+    ///
+    /// The hint at index `i` is the relative position, in `0..=1`, of the midpoint of the
+    /// color transition between stops `i` and `i + 1`, as with CSS color hints. Segments
+    /// without a hint use `0.5`, which is plain linear interpolation. Hints are ignored
+    /// for brushes other than gradients.
+    #[allow(unused_variables)]
+    pub fn encode_brush_with_hints<'b>(
+        &mut self,
+        brush: impl Into<BrushRef<'b>>,
+        alpha: f32,
+        hints: &[f32],
+    ) {
         #[cfg(feature = "full")]
         use super::math::point_to_f32;
         match brush.into() {
@@ -272,6 +543,7 @@ impl Encoding {
                             p1: point_to_f32(end),
                         },
                         gradient.stops.iter().copied(),
+                        hints,
                         alpha,
                         gradient.extend,
                     );
@@ -291,6 +563,7 @@ impl Encoding {
                             r1: end_radius,
                         },
                         gradient.stops.iter().copied(),
+                        hints,
                         alpha,
                         gradient.extend,
                     );
@@ -315,16 +588,24 @@ impl Encoding {
         self.draw_data.extend_from_slice(bytemuck::bytes_of(&color));
     }
 
+    /// Encodes a bilinear gradient brush.
+    pub fn encode_bilinear_gradient(&mut self, gradient: DrawBilinearGradient) {
+        self.draw_tags.push(DrawTag::BILINEAR_GRADIENT);
+        self.draw_data
+            .extend_from_slice(bytemuck::bytes_of(&gradient));
+    }
+
     /// Encodes a linear gradient brush.
     #[cfg(feature = "full")]
     pub fn encode_linear_gradient(
         &mut self,
         gradient: DrawLinearGradient,
         color_stops: impl Iterator<Item = ColorStop>,
+        hints: &[f32],
         alpha: f32,
         extend: Extend,
     ) {
-        match self.add_ramp(color_stops, alpha, extend) {
+        match self.add_ramp(color_stops, hints, alpha, extend) {
             RampStops::Empty => self.encode_color(DrawColor::new(Color::TRANSPARENT)),
             RampStops::One(color) => self.encode_color(DrawColor::new(color)),
             _ => {
@@ -341,6 +622,7 @@ impl Encoding {
         &mut self,
         gradient: DrawRadialGradient,
         color_stops: impl Iterator<Item = ColorStop>,
+        hints: &[f32],
         alpha: f32,
         extend: Extend,
     ) {
@@ -349,7 +631,7 @@ impl Encoding {
         if gradient.p0 == gradient.p1 && (gradient.r0 - gradient.r1).abs() < SKIA_EPSILON {
             self.encode_color(DrawColor::new(Color::TRANSPARENT));
         }
-        match self.add_ramp(color_stops, alpha, extend) {
+        match self.add_ramp(color_stops, hints, alpha, extend) {
             RampStops::Empty => self.encode_color(DrawColor::new(Color::TRANSPARENT)),
             RampStops::One(color) => self.encode_color(DrawColor::new(color)),
             _ => {
@@ -362,9 +644,29 @@ impl Encoding {
 
     /// Encodes an image brush.
     #[cfg(feature = "full")]
-    pub fn encode_image(&mut self, image: &Image, _alpha: f32) {
-        // TODO: feed the alpha multiplier through the full pipeline for consistency
-        // with other brushes?
+    pub fn encode_image(&mut self, image: &Image, alpha: f32) {
+        self.encode_image_with_sampler(image, alpha, ImageSampler::default());
+    }
+
+    /// Encodes an image brush which is sampled with the given sampler state.
+    #[cfg(feature = "full")]
+    pub fn encode_image_with_sampler(&mut self, image: &Image, alpha: f32, sampler: ImageSampler) {
+        self.encode_image_with_tint(image, alpha, sampler, Color::WHITE);
+    }
+
+    /// Encodes an image brush with each sampled color multiplied by `tint`, such as to draw
+    /// a white icon in any color.
+    ///
+    /// The alpha multiplier is applied to the alpha of the tint.
+    #[cfg(feature = "full")]
+    pub fn encode_image_with_tint(
+        &mut self,
+        image: &Image,
+        alpha: f32,
+        sampler: ImageSampler,
+        tint: Color,
+    ) {
+        let tint = tint.with_alpha_factor(alpha);
         self.resources.patches.push(Patch::Image {
             image: image.clone(),
             draw_data_offset: self.draw_data.len(),
@@ -374,15 +676,63 @@ impl Encoding {
             .extend_from_slice(bytemuck::bytes_of(&DrawImage {
                 xy: 0,
                 width_height: (image.width << 16) | (image.height & 0xFFFF),
+                sampler: sampler.pack(),
+                tint: tint.to_premul_u32(),
             }));
     }
 
     /// Encodes a begin clip command.
     pub fn encode_begin_clip(&mut self, blend_mode: BlendMode, alpha: f32) {
-        use super::DrawBeginClip;
-        self.draw_tags.push(DrawTag::BEGIN_CLIP);
+        self.encode_begin_clip_data(DrawBeginClip::new(blend_mode, alpha));
+    }
+
+    /// Encodes a begin clip command for a transparency group, with `flags` made of
+    /// [`DrawBeginClip::NON_ISOLATED_FLAG`] and [`DrawBeginClip::KNOCKOUT_FLAG`].
+    pub fn encode_begin_group_clip(&mut self, blend_mode: BlendMode, alpha: f32, flags: u32) {
+        let mut clip = DrawBeginClip::new(blend_mode, alpha);
+        clip.blend_mode |=
+            flags & (DrawBeginClip::NON_ISOLATED_FLAG | DrawBeginClip::KNOCKOUT_FLAG);
+        self.encode_begin_clip_data(clip);
+    }
+
+    /// Encodes a begin clip command where the clip coverage is feathered across the edges
+    /// of the given device space rectangle.
+    pub fn encode_begin_feathered_clip(
+        &mut self,
+        blend_mode: BlendMode,
+        alpha: f32,
+        rect: [f32; 4],
+        feather: f32,
+    ) {
+        self.encode_begin_clip_data(DrawBeginClip::new_feathered(
+            blend_mode, alpha, rect, feather,
+        ));
+    }
+
+    /// Encodes a begin clip command for a layer whose content is transformed by a 4x5 color
+    /// matrix before it is composited.
+    ///
+    /// The matrix is in row-major order and applies to unpremultiplied RGBA values in the
+    /// range 0 to 1, with the fifth column as an offset, as in SVG's `feColorMatrix`.
+    pub fn encode_begin_color_matrix_clip(
+        &mut self,
+        blend_mode: BlendMode,
+        alpha: f32,
+        matrix: [f32; 20],
+    ) {
+        let mut clip = DrawBeginClip::new(blend_mode, alpha);
+        clip.blend_mode |= DrawBeginClip::COLOR_MATRIX_FLAG;
+        self.draw_tags.push(DrawTag::BEGIN_CLIP_COLOR_MATRIX);
+        self.draw_data.extend_from_slice(bytemuck::bytes_of(&clip));
         self.draw_data
-            .extend_from_slice(bytemuck::bytes_of(&DrawBeginClip::new(blend_mode, alpha)));
+            .extend_from_slice(bytemuck::bytes_of(&matrix));
+        self.n_clips += 1;
+        self.n_open_clips += 1;
+    }
+
+    fn encode_begin_clip_data(&mut self, clip: DrawBeginClip) {
+        self.draw_tags.push(DrawTag::BEGIN_CLIP);
+        self.draw_data.extend_from_slice(bytemuck::bytes_of(&clip));
         self.n_clips += 1;
         self.n_open_clips += 1;
     }
@@ -416,6 +766,7 @@ impl Encoding {
     fn add_ramp(
         &mut self,
         color_stops: impl Iterator<Item = ColorStop>,
+        hints: &[f32],
         alpha: f32,
         extend: Extend,
     ) -> RampStops {
@@ -433,9 +784,13 @@ impl Encoding {
             0 => RampStops::Empty,
             1 => RampStops::One(self.resources.color_stops.pop().unwrap().color),
             _ => {
+                let hints_start = self.resources.stop_hints.len();
+                self.resources.stop_hints.extend_from_slice(hints);
+                let hints_end = self.resources.stop_hints.len();
                 self.resources.patches.push(Patch::Ramp {
                     draw_data_offset: offset,
                     stops: stops_start..stops_end,
+                    hints: hints_start..hints_end,
                     extend,
                 });
                 RampStops::Many
@@ -454,6 +809,15 @@ enum RampStops {
     Many,
 }
 
+fn intersect_clip_rects(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    [
+        a[0].max(b[0]),
+        a[1].max(b[1]),
+        a[2].min(b[2]),
+        a[3].min(b[3]),
+    ]
+}
+
 /// Encoded data for late bound resources.
 #[cfg(feature = "full")]
 #[derive(Clone, Default)]
@@ -462,6 +826,8 @@ pub struct Resources {
     pub patches: Vec<Patch>,
     /// Color stop collection for gradients.
     pub color_stops: Vec<ColorStop>,
+    /// Interpolation hints for the segments between color stops.
+    pub stop_hints: Vec<f32>,
     /// Positioned glyph buffer.
     pub glyphs: Vec<Glyph>,
     /// Sequences of glyphs.
@@ -475,10 +841,76 @@ impl Resources {
     fn reset(&mut self) {
         self.patches.clear();
         self.color_stops.clear();
+        self.stop_hints.clear();
         self.glyphs.clear();
         self.glyph_runs.clear();
         self.normalized_coords.clear();
     }
+
+    fn hash_content(&self, hasher: &mut impl Hasher) {
+        for patch in &self.patches {
+            match patch {
+                Patch::Ramp {
+                    draw_data_offset,
+                    stops,
+                    hints,
+                    extend,
+                } => {
+                    (0u8, *draw_data_offset, *extend as u8).hash(hasher);
+                    for stop in &self.color_stops[stops.clone()] {
+                        let color = stop.color;
+                        (stop.offset.to_bits(), color.r, color.g, color.b, color.a).hash(hasher);
+                    }
+                    for hint in &self.stop_hints[hints.clone()] {
+                        hint.to_bits().hash(hasher);
+                    }
+                }
+                Patch::GlyphRun { index } => (1u8, *index).hash(hasher),
+                Patch::Image {
+                    draw_data_offset,
+                    image,
+                } => {
+                    (2u8, *draw_data_offset, image.data.id()).hash(hasher);
+                    (image.width, image.height, image.format as u8).hash(hasher);
+                    (image.extend as u8).hash(hasher);
+                }
+            }
+        }
+        for run in &self.glyph_runs {
+            (run.font.data.id(), run.font.index).hash(hasher);
+            bytemuck::bytes_of(&run.transform).hash(hasher);
+            run.glyph_transform
+                .as_ref()
+                .map(bytemuck::bytes_of)
+                .hash(hasher);
+            (run.font_size.to_bits(), run.hint).hash(hasher);
+            match &run.style {
+                peniko::Style::Fill(fill) => (0u8, *fill as u8).hash(hasher),
+                peniko::Style::Stroke(stroke) => {
+                    (1u8, stroke.width.to_bits(), stroke.miter_limit.to_bits()).hash(hasher);
+                    (
+                        stroke.join as u8,
+                        stroke.start_cap as u8,
+                        stroke.end_cap as u8,
+                    )
+                        .hash(hasher);
+                    stroke.dash_offset.to_bits().hash(hasher);
+                    for dash in &stroke.dash_pattern {
+                        dash.to_bits().hash(hasher);
+                    }
+                }
+            }
+            for glyph in &self.glyphs[run.glyphs.clone()] {
+                (glyph.id, glyph.x.to_bits(), glyph.y.to_bits()).hash(hasher);
+            }
+            for coord in &self.normalized_coords[run.normalized_coords.clone()] {
+                coord.to_bits().hash(hasher);
+            }
+            let offsets = &run.stream_offsets;
+            (offsets.path_tags, offsets.path_data, offsets.draw_tags).hash(hasher);
+            (offsets.draw_data, offsets.transforms, offsets.styles).hash(hasher);
+        }
+    }
 }
 
 /// Snapshot of offsets for encoded streams.
@@ -35,6 +35,11 @@ pub struct GlyphRun {
     pub normalized_coords: Range<usize>,
     /// Fill or stroke style.
     pub style: Style,
+    /// Value for the `clip_rect` field of the styles of the glyphs, as returned by
+    /// [`Encoding::clip_rect_index`].
+    ///
+    /// [`Encoding::clip_rect_index`]: crate::Encoding::clip_rect_index
+    pub clip_rect: u32,
     /// Range of glyphs in the parent encoding.
     pub glyphs: Range<usize>,
     /// Stream offsets where this glyph run should be inserted.
@@ -142,3 +142,43 @@ impl OutlinePen for BezPathPen {
         self.0.close_path();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathSegmentType;
+    use skrifa::{FontRef, MetadataProvider};
+
+    const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+    #[test]
+    fn test_truetype_glyph_encodes_quads() {
+        let font = FontRef::new(ROBOTO).unwrap();
+        let glyph_id = font.charmap().map('o').unwrap();
+        let mut cache = GlyphCache::default();
+        let key = GlyphKey {
+            glyph_id: glyph_id.to_u16() as u32,
+            font_size_bits: 24_f32.to_bits(),
+            ..Default::default()
+        };
+        cache
+            .get_or_insert(
+                &font.outline_glyphs(),
+                key,
+                &Style::Fill(Fill::NonZero),
+                24.,
+                &[],
+            )
+            .unwrap();
+        let count = |ty| {
+            cache
+                .encoding
+                .path_tags
+                .iter()
+                .filter(|tag| tag.path_segment_type() == ty)
+                .count()
+        };
+        assert!(count(PathSegmentType::QUAD_TO) > 0);
+        assert_eq!(count(PathSegmentType::CUBIC_TO), 0);
+    }
+}
@@ -6,7 +6,8 @@ use peniko::Image;
 use std::collections::{hash_map::Entry, HashMap};
 
 const DEFAULT_ATLAS_SIZE: i32 = 1024;
-const MAX_ATLAS_SIZE: i32 = 8192;
+/// Width and height of the largest image atlas, which bounds the size of any single image.
+pub const MAX_ATLAS_SIZE: u32 = 8192;
 
 #[derive(Default)]
 pub struct Images<'a> {
@@ -48,7 +49,7 @@ impl ImageCache {
 
     pub fn bump_size(&mut self) -> bool {
         let new_size = self.atlas.size().width * 2;
-        if new_size > MAX_ATLAS_SIZE {
+        if new_size > MAX_ATLAS_SIZE as i32 {
             return false;
         }
         self.atlas = AtlasAllocator::new(size2(new_size, new_size));
@@ -23,19 +23,25 @@ mod path;
 #[cfg(feature = "full")]
 mod ramp_cache;
 mod resolve;
+#[cfg(feature = "full")]
+mod serialize;
 
 pub use binning::BinHeader;
 pub use clip::{Clip, ClipBbox, ClipBic, ClipElement};
 pub use config::{
     BufferSize, BufferSizes, BumpAllocators, ConfigUniform, IndirectCount, RenderConfig,
-    WorkgroupCounts, WorkgroupSize,
+    WorkgroupCounts, WorkgroupSize, N_TILES_PER_BIN,
 };
 pub use draw::{
-    DrawBbox, DrawBeginClip, DrawColor, DrawImage, DrawLinearGradient, DrawMonoid,
-    DrawRadialGradient, DrawTag, DRAW_INFO_FLAGS_FILL_RULE_BIT,
+    AlphaType, DrawBbox, DrawBeginClip, DrawBilinearGradient, DrawColor, DrawImage,
+    DrawLinearGradient, DrawMonoid, DrawRadialGradient, DrawTag, ImageAddressMode, ImageFilter,
+    ImageSampler, DRAW_INFO_FLAGS_ALIASED_BIT, DRAW_INFO_FLAGS_CLIP_RECT_SHIFT,
+    DRAW_INFO_FLAGS_FILL_RULE_BIT, DRAW_INFO_FLAGS_GLYPH_BIT,
 };
 pub use encoding::{Encoding, StreamOffsets};
-pub use mask::{make_mask_lut, make_mask_lut_16};
+pub use mask::{
+    make_mask_lut, make_mask_lut_16, make_mask_lut_16_for_positions, make_mask_lut_for_positions,
+};
 pub use math::Transform;
 pub use monoid::Monoid;
 pub use path::{
@@ -48,6 +54,8 @@ pub use resolve::{resolve_solid_paths_only, Layout};
 pub use {
     encoding::Resources,
     glyph::{Glyph, GlyphRun},
+    image_cache::MAX_ATLAS_SIZE,
     ramp_cache::Ramps,
     resolve::{Patch, Resolver},
+    serialize::SerializeError,
 };
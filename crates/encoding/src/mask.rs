@@ -10,14 +10,14 @@ const MASK_HEIGHT: usize = 32;
 
 const PATTERN: [u8; 8] = [0, 5, 3, 7, 1, 4, 6, 2];
 
-fn one_mask(slope: f64, mut translation: f64, is_pos: bool) -> u8 {
+fn one_mask(slope: f64, mut translation: f64, is_pos: bool, x_positions: &[f32; 8]) -> u8 {
     if is_pos {
         translation = 1. - translation;
     }
     let mut result = 0;
-    for (i, item) in PATTERN.iter().enumerate() {
+    for (i, item) in x_positions.iter().enumerate() {
         let mut y = (i as f64 + 0.5) * 0.125;
-        let x = (*item as f64 + 0.5) * 0.125;
+        let x = *item as f64;
         if !is_pos {
             y = 1. - y;
         }
@@ -34,6 +34,13 @@ fn one_mask(slope: f64, mut translation: f64, is_pos: bool) -> u8 {
 /// The first block is negative slopes (x decreases as y increates),
 /// the second as positive.
 pub fn make_mask_lut() -> Vec<u8> {
+    make_mask_lut_for_positions(&PATTERN.map(|column| (column as f32 + 0.5) * 0.125))
+}
+
+/// Make a lookup table of half-plane masks for a custom pattern of 8 samples.
+///
+/// Sample `i` is in row `i` of the pixel, at `(x_positions[i], (i + 0.5) / 8)`.
+pub fn make_mask_lut_for_positions(x_positions: &[f32; 8]) -> Vec<u8> {
     (0..MASK_WIDTH * MASK_HEIGHT)
         .map(|i| {
             const HALF_HEIGHT: usize = MASK_HEIGHT / 2;
@@ -42,7 +49,7 @@ pub fn make_mask_lut() -> Vec<u8> {
             let is_pos = v >= HALF_HEIGHT;
             let y = ((v % HALF_HEIGHT) as f64 + 0.5) * (1.0 / HALF_HEIGHT as f64);
             let x = (u as f64 + 0.5) * (1.0 / MASK_WIDTH as f64);
-            one_mask(y, x, is_pos)
+            one_mask(y, x, is_pos, x_positions)
         })
         .collect()
 }
@@ -57,14 +64,14 @@ const MASK16_HEIGHT: usize = 64;
 // [D3D11 standard sample pattern]: https://learn.microsoft.com/en-us/windows/win32/api/d3d11/ne-d3d11-d3d11_standard_multisample_quality_levels
 const PATTERN_16: [u8; 16] = [1, 8, 4, 11, 15, 7, 3, 12, 0, 9, 5, 13, 2, 10, 6, 14];
 
-fn one_mask_16(slope: f64, mut translation: f64, is_pos: bool) -> u16 {
+fn one_mask_16(slope: f64, mut translation: f64, is_pos: bool, x_positions: &[f32; 16]) -> u16 {
     if is_pos {
         translation = 1. - translation;
     }
     let mut result = 0;
-    for (i, item) in PATTERN_16.iter().enumerate() {
+    for (i, item) in x_positions.iter().enumerate() {
         let mut y = (i as f64 + 0.5) * 0.0625;
-        let x = (*item as f64 + 0.5) * 0.0625;
+        let x = *item as f64;
         if !is_pos {
             y = 1. - y;
         }
@@ -81,6 +88,13 @@ fn one_mask_16(slope: f64, mut translation: f64, is_pos: bool) -> u16 {
 /// The first block is negative slopes (x decreases as y increates),
 /// the second as positive.
 pub fn make_mask_lut_16() -> Vec<u8> {
+    make_mask_lut_16_for_positions(&PATTERN_16.map(|column| (column as f32 + 0.5) * 0.0625))
+}
+
+/// Make a lookup table of half-plane masks for a custom pattern of 16 samples.
+///
+/// Sample `i` is in row `i` of the pixel, at `(x_positions[i], (i + 0.5) / 16)`.
+pub fn make_mask_lut_16_for_positions(x_positions: &[f32; 16]) -> Vec<u8> {
     let v16 = (0..MASK16_WIDTH * MASK16_HEIGHT)
         .map(|i| {
             const HALF_HEIGHT: usize = MASK16_HEIGHT / 2;
@@ -89,7 +103,7 @@ pub fn make_mask_lut_16() -> Vec<u8> {
             let is_pos = v >= HALF_HEIGHT;
             let y = ((v % HALF_HEIGHT) as f64 + 0.5) * (1.0 / HALF_HEIGHT as f64);
             let x = (u as f64 + 0.5) * (1.0 / MASK16_WIDTH as f64);
-            one_mask_16(y, x, is_pos)
+            one_mask_16(y, x, is_pos, x_positions)
         })
         .collect::<Vec<_>>();
     // This annoyingly makes another copy. We can avoid that by pushing two
@@ -20,8 +20,8 @@ pub struct Style {
     ///                and join style for strokes. See the FLAGS_* constants below for more
     ///                information.
     /// ```text
-    /// flags: |style|fill|join|start cap|end cap|reserved|
-    ///  bits:  0     1    2-3  4-5       6-7     8-15
+    /// flags: |style|fill|join|start cap|end cap|glyph|aliased|non-scaling|reserved|
+    ///  bits:  0     1    2-3  4-5       6-7     8     9       10          11-15
     /// ```
     ///
     /// - miter_limit: u16 - The miter limit for a stroke, encoded in binary16 (half) floating
@@ -32,6 +32,12 @@ pub struct Style {
 
     /// Encodes the stroke width. This field is ignored for fills.
     pub line_width: f32,
+
+    /// One more than the index of the device space rectangle the draw is clipped to, in the
+    /// clip rect stream of the encoding, or 0 for no rectangle. See [`Encoding::clip_rect`].
+    ///
+    /// [`Encoding::clip_rect`]: crate::Encoding::clip_rect
+    pub clip_rect: u32,
 }
 
 impl Style {
@@ -41,6 +47,20 @@ impl Style {
     /// 0 for non-zero, 1 for even-odd
     pub const FLAGS_FILL_BIT: u32 = 0x4000_0000;
 
+    /// 1 for the outline of a glyph, whose coverage is adjusted by the glyph gamma.
+    ///
+    /// This is set when glyph runs are resolved, rather than by the constructors.
+    pub const FLAGS_GLYPH_BIT: u32 = 0x0080_0000;
+
+    /// 1 for a draw with hard coverage instead of antialiasing. See [`Encoding::aliased`].
+    ///
+    /// [`Encoding::aliased`]: crate::Encoding::aliased
+    pub const FLAGS_ALIASED_BIT: u32 = 0x0040_0000;
+
+    /// 1 for a stroke whose width is in device pixels, rather than in the units of its
+    /// transform. See [`Style::from_non_scaling_stroke`].
+    pub const FLAGS_NON_SCALING_BIT: u32 = 0x0020_0000;
+
     /// Encodings for join style:
     ///    - 0b00 -> bevel
     ///    - 0b01 -> miter
@@ -77,6 +97,7 @@ impl Style {
         Self {
             flags_and_miter_limit: fill_bit,
             line_width: 0.,
+            clip_rect: 0,
         }
     }
 
@@ -101,9 +122,19 @@ impl Style {
         Self {
             flags_and_miter_limit: style | join | start_cap | end_cap | miter_limit,
             line_width: stroke.width as f32,
+            clip_rect: 0,
         }
     }
 
+    /// Creates a style for a stroke which is offset in device space, so that its width is
+    /// the same number of pixels whatever the transform of the path, like the SVG
+    /// `vector-effect: non-scaling-stroke` property.
+    pub fn from_non_scaling_stroke(stroke: &Stroke) -> Self {
+        let mut style = Self::from_stroke(stroke);
+        style.flags_and_miter_limit |= Self::FLAGS_NON_SCALING_BIT;
+        style
+    }
+
     #[cfg(test)]
     fn fill(&self) -> Option<Fill> {
         if self.is_fill() {
@@ -383,7 +414,8 @@ pub struct PathBbox {
     pub x1: i32,
     /// Maximum y value.
     pub y1: i32,
-    /// Style flags
+    /// Style flags, with the clip rect of the style in the bits above
+    /// `DRAW_INFO_FLAGS_CLIP_RECT_SHIFT`.
     pub draw_flags: u32,
     /// Index into the transform stream.
     pub trans_ix: u32,
@@ -424,6 +456,7 @@ pub struct PathEncoder<'a> {
     state: PathState,
     n_encoded_segments: u32,
     is_fill: bool,
+    snap_scale: Option<f32>,
 }
 
 #[derive(PartialEq)]
@@ -487,11 +520,27 @@ impl<'a> PathEncoder<'a> {
             state: PathState::Start,
             n_encoded_segments: 0,
             is_fill,
+            snap_scale: None,
+        }
+    }
+
+    /// Snaps all coordinates passed to this encoder to a fixed-point grid with the given
+    /// number of fractional bits, or leaves them untouched if `None`.
+    pub fn with_snapping(mut self, fractional_bits: Option<u32>) -> Self {
+        self.snap_scale = fractional_bits.map(|bits| (1_u32 << bits.min(23)) as f32);
+        self
+    }
+
+    fn snap(&self, x: f32, y: f32) -> (f32, f32) {
+        match self.snap_scale {
+            Some(scale) => ((x * scale).round() / scale, (y * scale).round() / scale),
+            None => (x, y),
         }
     }
 
     /// Encodes a move, starting a new subpath.
     pub fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.snap(x, y);
         if self.is_fill {
             self.close();
         }
@@ -515,6 +564,7 @@ impl<'a> PathEncoder<'a> {
 
     /// Encodes a line.
     pub fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.snap(x, y);
         if self.state == PathState::Start {
             if self.n_encoded_segments == 0 {
                 // This copies the behavior of kurbo which treats an initial line, quad
@@ -545,6 +595,8 @@ impl<'a> PathEncoder<'a> {
 
     /// Encodes a quadratic bezier.
     pub fn quad_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let (x1, y1) = self.snap(x1, y1);
+        let (x2, y2) = self.snap(x2, y2);
         if self.state == PathState::Start {
             if self.n_encoded_segments == 0 {
                 self.move_to(x2, y2);
@@ -573,6 +625,9 @@ impl<'a> PathEncoder<'a> {
 
     /// Encodes a cubic bezier.
     pub fn cubic_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32) {
+        let (x1, y1) = self.snap(x1, y1);
+        let (x2, y2) = self.snap(x2, y2);
+        let (x3, y3) = self.snap(x3, y3);
         if self.state == PathState::Start {
             if self.n_encoded_segments == 0 {
                 self.move_to(x3, y3);
@@ -795,6 +850,25 @@ const EPSILON: f32 = 1e-12;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_path_snapping() {
+        let mut tags = vec![];
+        let mut data = vec![];
+        let (mut n_segments, mut n_paths) = (0, 0);
+        let mut encoder =
+            PathEncoder::new(&mut tags, &mut data, &mut n_segments, &mut n_paths, true)
+                .with_snapping(Some(4));
+        encoder.move_to(0.01, 1.03);
+        encoder.line_to(10.2, 1.0);
+        encoder.quad_to(10.47, 5.0, 3.333, 7.0);
+        encoder.finish(true);
+        let coords: &[f32] = bytemuck::cast_slice(&data);
+        assert_eq!(coords[..4], [0.0, 1.0, 10.1875, 1.0]);
+        for coord in coords {
+            assert_eq!((coord * 16.0).fract(), 0.0, "{coord}");
+        }
+    }
+
     #[test]
     fn test_fill_style() {
         assert_eq!(Some(Fill::NonZero), Style::from_fill(Fill::NonZero).fill());
@@ -826,4 +900,21 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_non_scaling_stroke_style() {
+        let stroke = Stroke::new(2.0).with_join(Join::Round);
+        let scaling = Style::from_stroke(&stroke);
+        let non_scaling = Style::from_non_scaling_stroke(&stroke);
+        assert_eq!(
+            scaling.flags_and_miter_limit & Style::FLAGS_NON_SCALING_BIT,
+            0
+        );
+        assert_eq!(
+            non_scaling.flags_and_miter_limit,
+            scaling.flags_and_miter_limit | Style::FLAGS_NON_SCALING_BIT
+        );
+        assert_eq!(Some(2.0), non_scaling.stroke_width());
+        assert_eq!(Some(Join::Round), non_scaling.stroke_join());
+    }
 }
@@ -16,10 +16,13 @@ pub struct Ramps<'a> {
     pub height: u32,
 }
 
+/// Color stops along with the bits of their interpolation hints.
+type RampKey = (ColorStops, Vec<u32>);
+
 #[derive(Default)]
 pub struct RampCache {
     epoch: u64,
-    map: HashMap<ColorStops, (u32, u64)>,
+    map: HashMap<RampKey, (u32, u64)>,
     data: Vec<u32>,
 }
 
@@ -33,38 +36,39 @@ impl RampCache {
         }
     }
 
-    pub fn add(&mut self, stops: &[ColorStop]) -> u32 {
-        if let Some(entry) = self.map.get_mut(stops) {
+    pub fn add(&mut self, stops: &[ColorStop], hints: &[f32]) -> u32 {
+        let key: RampKey = (stops.into(), hints.iter().map(|h| h.to_bits()).collect());
+        if let Some(entry) = self.map.get_mut(&key) {
             entry.1 = self.epoch;
             entry.0
         } else if self.map.len() < RETAINED_COUNT {
             let id = (self.data.len() / N_SAMPLES) as u32;
-            self.data.extend(make_ramp(stops));
-            self.map.insert(stops.into(), (id, self.epoch));
+            self.data.extend(make_ramp(stops, hints));
+            self.map.insert(key, (id, self.epoch));
             id
         } else {
             let mut reuse = None;
-            for (stops, (id, epoch)) in &self.map {
+            for (key, (id, epoch)) in &self.map {
                 if *epoch + 2 < self.epoch {
-                    reuse = Some((stops.to_owned(), *id));
+                    reuse = Some((key.to_owned(), *id));
                     break;
                 }
             }
-            if let Some((old_stops, id)) = reuse {
-                self.map.remove(&old_stops);
+            if let Some((old_key, id)) = reuse {
+                self.map.remove(&old_key);
                 let start = id as usize * N_SAMPLES;
                 for (dst, src) in self.data[start..start + N_SAMPLES]
                     .iter_mut()
-                    .zip(make_ramp(stops))
+                    .zip(make_ramp(stops, hints))
                 {
                     *dst = src;
                 }
-                self.map.insert(stops.into(), (id, self.epoch));
+                self.map.insert(key, (id, self.epoch));
                 id
             } else {
                 let id = (self.data.len() / N_SAMPLES) as u32;
-                self.data.extend(make_ramp(stops));
-                self.map.insert(stops.into(), (id, self.epoch));
+                self.data.extend(make_ramp(stops, hints));
+                self.map.insert(key, (id, self.epoch));
                 id
             }
         }
@@ -79,7 +83,7 @@ impl RampCache {
     }
 }
 
-fn make_ramp(stops: &[ColorStop]) -> impl Iterator<Item = u32> + '_ {
+fn make_ramp<'a>(stops: &'a [ColorStop], hints: &'a [f32]) -> impl Iterator<Item = u32> + 'a {
     let mut last_u = 0.0;
     let mut last_c = ColorF64::from_color(stops[0].color);
     let mut this_u = last_u;
@@ -102,12 +106,41 @@ fn make_ramp(stops: &[ColorStop]) -> impl Iterator<Item = u32> + '_ {
         let c = if du < 1e-9 {
             this_c
         } else {
-            last_c.lerp(&this_c, (u - last_u) / du)
+            let t = (u - last_u) / du;
+            // `j` is the index of the stop at the end of the current segment.
+            let hint = j.checked_sub(1).and_then(|ix| hints.get(ix));
+            let t = match hint {
+                Some(&hint) => apply_hint(t, hint as f64),
+                None => t,
+            };
+            last_c.lerp(&this_c, t)
         };
         c.as_premul_u32()
     })
 }
 
+/// Remaps the interpolation parameter within a segment so that the halfway point between
+/// the colors falls at `hint`, following the CSS color hint interpolation.
+fn apply_hint(t: f64, hint: f64) -> f64 {
+    if hint <= 0.0 {
+        if t > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    } else if hint >= 1.0 {
+        if t < 1.0 {
+            0.0
+        } else {
+            1.0
+        }
+    } else if hint == 0.5 {
+        t
+    } else {
+        t.powf(0.5_f64.ln() / hint.ln())
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 struct ColorF64([f64; 4]);
 
@@ -142,3 +175,51 @@ impl ColorF64 {
         r | (g << 8) | (b << 16) | (a << 24)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> [ColorStop; 2] {
+        [
+            ColorStop {
+                offset: 0.0,
+                color: Color::BLACK,
+            },
+            ColorStop {
+                offset: 1.0,
+                color: Color::WHITE,
+            },
+        ]
+    }
+
+    fn red_at(ramp: &[u32], t: f64) -> u32 {
+        ramp[(t * (N_SAMPLES - 1) as f64).round() as usize] & 0xff
+    }
+
+    #[test]
+    fn test_hint_moves_midpoint() {
+        let ramp = make_ramp(&stops(), &[0.25]).collect::<Vec<_>>();
+        assert!(red_at(&ramp, 0.25).abs_diff(127) <= 1);
+        assert!(red_at(&ramp, 0.5) > 160);
+        assert_eq!(red_at(&ramp, 0.0), 0);
+        assert_eq!(red_at(&ramp, 1.0), 255);
+    }
+
+    #[test]
+    fn test_default_hint_is_linear() {
+        let linear = make_ramp(&stops(), &[]).collect::<Vec<_>>();
+        let hinted = make_ramp(&stops(), &[0.5]).collect::<Vec<_>>();
+        assert_eq!(linear, hinted);
+        assert!(red_at(&linear, 0.5).abs_diff(127) <= 1);
+    }
+
+    #[test]
+    fn test_hints_in_cache_key() {
+        let mut cache = RampCache::default();
+        let a = cache.add(&stops(), &[]);
+        let b = cache.add(&stops(), &[0.25]);
+        assert_ne!(a, b);
+        assert_eq!(cache.add(&stops(), &[0.25]), b);
+    }
+}
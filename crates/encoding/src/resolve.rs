@@ -41,6 +41,8 @@ pub struct Layout {
     pub transform_base: u32,
     /// Start of style stream.
     pub style_base: u32,
+    /// Start of clip rect stream.
+    pub clip_rect_base: u32,
 }
 
 impl Layout {
@@ -100,6 +102,13 @@ impl Layout {
     /// Returns the style stream.
     pub fn styles<'a>(&self, data: &'a [u8]) -> &'a [Style] {
         let start = self.style_base as usize * 4;
+        let end = self.clip_rect_base as usize * 4;
+        bytemuck::cast_slice(&data[start..end])
+    }
+
+    /// Returns the clip rect stream.
+    pub fn clip_rects<'a>(&self, data: &'a [u8]) -> &'a [[f32; 4]] {
+        let start = self.clip_rect_base as usize * 4;
         bytemuck::cast_slice(&data[start..])
     }
 }
@@ -154,6 +163,9 @@ pub fn resolve_solid_paths_only(encoding: &Encoding, packed: &mut Vec<u8>) -> La
     // Style stream
     layout.style_base = size_to_words(data.len());
     data.extend_from_slice(bytemuck::cast_slice(&encoding.styles));
+    // Clip rect stream
+    layout.clip_rect_base = size_to_words(data.len());
+    data.extend_from_slice(bytemuck::cast_slice(&encoding.clip_rects));
     layout.n_draw_objects = layout.n_paths;
     assert_eq!(buffer_size, data.len());
     layout
@@ -371,10 +383,19 @@ impl Resolver {
                         data.extend_from_slice(bytemuck::cast_slice(&stream[pos..stream_offset]));
                         pos = stream_offset;
                     }
+                    let clip_rect = resources.glyph_runs[*index].clip_rect;
                     for glyph in &self.glyph_ranges[glyphs.clone()] {
                         let glyph_data =
                             &self.glyph_cache.encoding.styles[glyph.start.styles..glyph.end.styles];
-                        data.extend_from_slice(bytemuck::cast_slice(glyph_data));
+                        for style in glyph_data {
+                            let style = Style {
+                                flags_and_miter_limit: style.flags_and_miter_limit
+                                    | Style::FLAGS_GLYPH_BIT,
+                                clip_rect,
+                                ..*style
+                            };
+                            data.extend_from_slice(bytemuck::bytes_of(&style));
+                        }
                     }
                 }
             }
@@ -382,6 +403,9 @@ impl Resolver {
                 data.extend_from_slice(bytemuck::cast_slice(&stream[pos..]));
             }
         }
+        // Clip rect stream
+        layout.clip_rect_base = size_to_words(data.len());
+        data.extend_from_slice(bytemuck::cast_slice(&encoding.clip_rects));
         layout.n_draw_objects = layout.n_paths;
         assert_eq!(buffer_size, data.len());
         (layout, self.ramp_cache.ramps(), self.image_cache.images())
@@ -401,9 +425,13 @@ impl Resolver {
                 Patch::Ramp {
                     draw_data_offset,
                     stops,
+                    hints,
                     extend,
                 } => {
-                    let ramp_id = self.ramp_cache.add(&resources.color_stops[stops.clone()]);
+                    let ramp_id = self.ramp_cache.add(
+                        &resources.color_stops[stops.clone()],
+                        &resources.stop_hints[hints.clone()],
+                    );
                     self.patches.push(ResolvedPatch::Ramp {
                         draw_data_offset: *draw_data_offset + sizes.draw_data,
                         ramp_id,
@@ -526,6 +554,8 @@ pub enum Patch {
         draw_data_offset: usize,
         /// Range of the gradient stops in the resource set.
         stops: Range<usize>,
+        /// Range of the interpolation hints in the resource set.
+        hints: Range<usize>,
         /// Extend mode for the gradient.
         extend: Extend,
     },
@@ -600,7 +630,8 @@ impl SceneBufferSizes {
             )
             + slice_size_in_bytes(&encoding.draw_data, patch_sizes.draw_data)
             + slice_size_in_bytes(&encoding.transforms, patch_sizes.transforms)
-            + slice_size_in_bytes(&encoding.styles, patch_sizes.styles);
+            + slice_size_in_bytes(&encoding.styles, patch_sizes.styles)
+            + slice_size_in_bytes(&encoding.clip_rects, 0);
         Self {
             buffer_size,
             path_tag_padded,
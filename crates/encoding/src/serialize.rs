@@ -0,0 +1,538 @@
+// Copyright 2023 The Vello authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of encodings to and from bytes, for caching scenes across machines.
+//!
+//! All numbers are stored in little-endian byte order, whatever the byte order of the
+//! machine which wrote them, so serialized encodings can be loaded on any target.
+
+use std::fmt;
+use std::sync::Arc;
+
+use peniko::{Blob, Color, ColorStop, Extend, Format, Image};
+
+use super::{DrawTag, Encoding, Patch, PathTag, Style, Transform};
+
+const MAGIC: [u8; 4] = *b"VENC";
+const VERSION: u32 = 3;
+
+/// Error returned when an encoding can't be converted to or from bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SerializeError {
+    /// The encoding contains glyph runs, which refer to fonts that aren't serialized.
+    GlyphRuns,
+    /// The bytes don't start with the header of a serialized encoding of a known version.
+    BadHeader,
+    /// The bytes end before the end of the encoding.
+    Truncated,
+    /// The bytes don't describe a valid encoding.
+    Invalid,
+}
+
+impl fmt::Display for SerializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::GlyphRuns => "encodings with glyph runs can't be serialized",
+            Self::BadHeader => "bytes aren't a serialized encoding of a known version",
+            Self::Truncated => "serialized encoding is truncated",
+            Self::Invalid => "serialized encoding is invalid",
+        })
+    }
+}
+
+impl std::error::Error for SerializeError {}
+
+/// Byte order of the 32-bit words held by the streams of an encoding in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+
+impl ByteOrder {
+    const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+
+    fn to_le(self, bytes: &mut [u8]) {
+        if self == Self::Big {
+            bytes.reverse();
+        }
+    }
+}
+
+impl Encoding {
+    /// Returns the encoding as bytes which can be stored and later passed to
+    /// [`Encoding::from_bytes`], possibly on a machine with a different byte order.
+    ///
+    /// Gradients and images are included along with the streams, but glyph runs can't be
+    /// serialized, as they refer to fonts.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializeError> {
+        self.write_bytes(ByteOrder::NATIVE)
+    }
+
+    /// Creates an encoding from bytes returned by [`Encoding::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializeError> {
+        Self::read_bytes(bytes, ByteOrder::NATIVE)
+    }
+
+    fn write_bytes(&self, order: ByteOrder) -> Result<Vec<u8>, SerializeError> {
+        let resources = &self.resources;
+        if !resources.glyph_runs.is_empty() {
+            return Err(SerializeError::GlyphRuns);
+        }
+        let mut w = Writer {
+            bytes: MAGIC.to_vec(),
+            order,
+        };
+        w.u32(VERSION);
+        for count in [
+            self.n_paths,
+            self.n_path_segments,
+            self.n_clips,
+            self.n_open_clips,
+            self.flags,
+        ] {
+            w.u32(count);
+        }
+        w.len(self.path_tags.len());
+        w.bytes.extend(self.path_tags.iter().map(|tag| tag.0));
+        w.path_data(&self.path_tags, &self.path_data);
+        w.len(self.draw_tags.len());
+        w.words(bytemuck::cast_slice(&self.draw_tags));
+        w.len(self.draw_data.len() / 4);
+        w.words(&self.draw_data);
+        w.len(self.transforms.len());
+        w.words(bytemuck::cast_slice(&self.transforms));
+        w.len(self.styles.len());
+        w.words(bytemuck::cast_slice(&self.styles));
+        w.len(self.clip_rects.len());
+        w.words(bytemuck::cast_slice(&self.clip_rects));
+        w.len(self.draw_depths.len());
+        for &(ix, depth) in &self.draw_depths {
+            w.len(ix);
+            w.u32(depth.to_bits());
+        }
+        w.len(self.fixed_transforms.len());
+        for &ix in &self.fixed_transforms {
+            w.len(ix);
+        }
+
+        w.len(resources.color_stops.len());
+        for stop in &resources.color_stops {
+            w.u32(stop.offset.to_bits());
+            let color = stop.color;
+            w.bytes.extend([color.r, color.g, color.b, color.a]);
+        }
+        w.len(resources.stop_hints.len());
+        for hint in &resources.stop_hints {
+            w.u32(hint.to_bits());
+        }
+        w.len(resources.patches.len());
+        for patch in &resources.patches {
+            match patch {
+                Patch::Ramp {
+                    draw_data_offset,
+                    stops,
+                    hints,
+                    extend,
+                } => {
+                    w.bytes.push(0);
+                    w.len(*draw_data_offset);
+                    for ix in [stops.start, stops.end, hints.start, hints.end] {
+                        w.len(ix);
+                    }
+                    w.bytes.push(*extend as u8);
+                }
+                Patch::Image {
+                    draw_data_offset,
+                    image,
+                } => {
+                    w.bytes.push(1);
+                    w.len(*draw_data_offset);
+                    w.u32(image.width);
+                    w.u32(image.height);
+                    w.bytes.push(image.format as u8);
+                    w.bytes.push(image.extend as u8);
+                    w.len(image.data.data().len());
+                    w.bytes.extend_from_slice(image.data.data());
+                }
+                Patch::GlyphRun { .. } => return Err(SerializeError::GlyphRuns),
+            }
+        }
+        Ok(w.bytes)
+    }
+
+    fn read_bytes(bytes: &[u8], order: ByteOrder) -> Result<Self, SerializeError> {
+        use SerializeError::Invalid;
+
+        let mut r = Reader {
+            bytes,
+            pos: 0,
+            order,
+        };
+        if bytes.len() < 8 || bytes[..4] != MAGIC {
+            return Err(SerializeError::BadHeader);
+        }
+        r.pos = 4;
+        if r.u32()? != VERSION {
+            return Err(SerializeError::BadHeader);
+        }
+        let mut encoding = Self::new();
+        encoding.n_paths = r.u32()?;
+        encoding.n_path_segments = r.u32()?;
+        encoding.n_clips = r.u32()?;
+        encoding.n_open_clips = r.u32()?;
+        encoding.flags = r.u32()?;
+        let n_path_tags = r.len()?;
+        encoding.path_tags = r
+            .take(n_path_tags)?
+            .iter()
+            .map(|&tag| PathTag(tag))
+            .collect();
+        encoding.path_data = r.path_data(&encoding.path_tags)?;
+        encoding.draw_tags = r.pod_vec::<DrawTag>()?;
+        let n_draw_words = r.len()?;
+        encoding.draw_data = r.words(n_draw_words)?;
+        encoding.transforms = r.pod_vec::<Transform>()?;
+        encoding.styles = r.pod_vec::<Style>()?;
+        encoding.clip_rects = r.pod_vec::<[f32; 4]>()?;
+        // Styles are held in the byte order of the reader, which needn't be native.
+        let clip_rect = |style: &Style| {
+            let mut word = style.clip_rect.to_ne_bytes();
+            r.order.to_le(&mut word);
+            u32::from_le_bytes(word) as usize
+        };
+        if encoding
+            .styles
+            .iter()
+            .any(|style| clip_rect(style) > encoding.clip_rects.len())
+        {
+            return Err(Invalid);
+        }
+        for _ in 0..r.len()? {
+            let ix = r.len()?;
+            let depth = f32::from_bits(r.u32()?);
+            // Depths start at increasing draw tags, and the last may start at the end.
+            if ix > encoding.draw_tags.len()
+                || encoding
+                    .draw_depths
+                    .last()
+                    .is_some_and(|&(last, _)| last >= ix)
+            {
+                return Err(Invalid);
+            }
+            encoding.draw_depths.push((ix, depth));
+        }
+        for _ in 0..r.len()? {
+            let ix = r.len()?;
+            if ix >= encoding.transforms.len()
+                || encoding
+                    .fixed_transforms
+                    .last()
+                    .is_some_and(|&last| last >= ix)
+            {
+                return Err(Invalid);
+            }
+            encoding.fixed_transforms.push(ix);
+        }
+
+        let resources = &mut encoding.resources;
+        for _ in 0..r.len()? {
+            let offset = f32::from_bits(r.u32()?);
+            let [red, green, blue, alpha] = r.array()?;
+            resources.color_stops.push(ColorStop {
+                offset,
+                color: Color::rgba8(red, green, blue, alpha),
+            });
+        }
+        for _ in 0..r.len()? {
+            resources.stop_hints.push(f32::from_bits(r.u32()?));
+        }
+        let extend = |value: u8| match value {
+            0 => Ok(Extend::Pad),
+            1 => Ok(Extend::Repeat),
+            2 => Ok(Extend::Reflect),
+            _ => Err(Invalid),
+        };
+        for _ in 0..r.len()? {
+            let [kind] = r.array()?;
+            let draw_data_offset = r.len()?;
+            // Patches overwrite a word of the draw data when the encoding is resolved.
+            if draw_data_offset.saturating_add(4) > encoding.draw_data.len() {
+                return Err(Invalid);
+            }
+            let patch = match kind {
+                0 => {
+                    let stops = r.len()?..r.len()?;
+                    let hints = r.len()?..r.len()?;
+                    if resources.color_stops.get(stops.clone()).is_none()
+                        || resources.stop_hints.get(hints.clone()).is_none()
+                    {
+                        return Err(Invalid);
+                    }
+                    let [extend_mode] = r.array()?;
+                    Patch::Ramp {
+                        draw_data_offset,
+                        stops,
+                        hints,
+                        extend: extend(extend_mode)?,
+                    }
+                }
+                1 => {
+                    let width = r.u32()?;
+                    let height = r.u32()?;
+                    let [format, extend_mode] = r.array()?;
+                    if format != Format::Rgba8 as u8 {
+                        return Err(Invalid);
+                    }
+                    let len = r.len()?;
+                    if Some(len) != Format::Rgba8.size_in_bytes(width, height) {
+                        return Err(Invalid);
+                    }
+                    let data = Blob::new(Arc::new(r.take(len)?.to_vec()));
+                    let image = Image::new(data, Format::Rgba8, width, height)
+                        .with_extend(extend(extend_mode)?);
+                    Patch::Image {
+                        draw_data_offset,
+                        image,
+                    }
+                }
+                _ => return Err(Invalid),
+            };
+            resources.patches.push(patch);
+        }
+        if r.pos != bytes.len() {
+            return Err(Invalid);
+        }
+        Ok(encoding)
+    }
+}
+
+/// Returns the number of 32-bit words of path data following `tag`, and whether they hold
+/// `f32` coordinates rather than pairs of `i16` coordinates.
+fn path_data_words(tag: PathTag) -> (usize, bool) {
+    if !tag.is_path_segment() {
+        return (0, true);
+    }
+    // The end of a subpath is followed by the start point of the next one.
+    let n_points = (tag.0 & 3) as usize + tag.is_subpath_end() as usize;
+    if tag.is_f32() {
+        (n_points * 2, true)
+    } else {
+        (n_points, false)
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+    order: ByteOrder,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend(value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.bytes.extend((len as u64).to_le_bytes());
+    }
+
+    /// Writes 32-bit words held in the byte order of the writer.
+    fn words(&mut self, words: &[u8]) {
+        for word in words.chunks_exact(4) {
+            let mut word = [word[0], word[1], word[2], word[3]];
+            self.order.to_le(&mut word);
+            self.bytes.extend(word);
+        }
+    }
+
+    fn path_data(&mut self, tags: &[PathTag], data: &[u8]) {
+        self.len(data.len() / 4);
+        let mut pos = 0;
+        for &tag in tags {
+            let (n_words, is_f32) = path_data_words(tag);
+            let end = (pos + n_words * 4).min(data.len());
+            if is_f32 {
+                self.words(&data[pos..end]);
+            } else {
+                for half in data[pos..end].chunks_exact(2) {
+                    let mut half = [half[0], half[1]];
+                    self.order.to_le(&mut half);
+                    self.bytes.extend(half);
+                }
+            }
+            pos = end;
+        }
+        // Any trailing data, such as the start point of an unfinished path, holds `f32`s.
+        self.words(&data[pos..]);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    order: ByteOrder,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SerializeError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(SerializeError::Truncated)?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SerializeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, SerializeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize, SerializeError> {
+        usize::try_from(u64::from_le_bytes(self.array()?)).map_err(|_| SerializeError::Invalid)
+    }
+
+    /// Reads `n_words` 32-bit words into the byte order of the reader.
+    fn words(&mut self, n_words: usize) -> Result<Vec<u8>, SerializeError> {
+        let len = n_words.checked_mul(4).ok_or(SerializeError::Truncated)?;
+        let mut words = self.take(len)?.to_vec();
+        for word in words.chunks_exact_mut(4) {
+            self.order.to_le(word);
+        }
+        Ok(words)
+    }
+
+    fn pod_vec<T: bytemuck::Pod>(&mut self) -> Result<Vec<T>, SerializeError> {
+        let n_words = std::mem::size_of::<T>() / 4;
+        let len = self.len()?;
+        let words = self.words(len.checked_mul(n_words).ok_or(SerializeError::Truncated)?)?;
+        let mut values = vec![T::zeroed(); len];
+        bytemuck::cast_slice_mut(&mut values).copy_from_slice(&words);
+        Ok(values)
+    }
+
+    fn path_data(&mut self, tags: &[PathTag]) -> Result<Vec<u8>, SerializeError> {
+        let n_words = self.len()?;
+        let mut data = self.words(n_words)?;
+        let mut pos = 0;
+        for &tag in tags {
+            let (n_words, is_f32) = path_data_words(tag);
+            let end = (pos + n_words * 4).min(data.len());
+            if !is_f32 {
+                // Undo the swap of whole words, then swap each coordinate on its own.
+                for word in data[pos..end].chunks_exact_mut(4) {
+                    self.order.to_le(word);
+                    for half in word.chunks_exact_mut(2) {
+                        self.order.to_le(half);
+                    }
+                }
+            }
+            pos = end;
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use peniko::kurbo::{Affine, Rect};
+    use peniko::{Fill, Gradient};
+
+    fn encoding() -> Encoding {
+        let mut encoding = Encoding::new();
+        encoding.encode_transform(Transform::from_kurbo(&Affine::rotate(0.5)));
+        encoding.clip_rect = Some([0.0, 0.0, 20.0, 20.0]);
+        encoding.encode_fill_style(Fill::EvenOdd);
+        encoding.encode_shape(&Rect::new(1.0, 2.0, 30.0, 40.0), true);
+        let gradient =
+            Gradient::new_linear((0.0, 0.0), (10.0, 0.0)).with_stops([Color::RED, Color::BLUE]);
+        encoding.encode_brush(&gradient, 0.5);
+        encoding.encode_depth(0.25);
+        encoding.encode_shape(&Rect::new(5.0, 6.0, 7.0, 8.0), true);
+        encoding.encode_brush(Color::GREEN, 1.0);
+        encoding.encode_depth(0.75);
+        encoding.encode_transform(Transform::from_kurbo(&Affine::scale(2.0)));
+        encoding
+            .fixed_transforms
+            .push(encoding.transforms.len() - 1);
+        encoding
+    }
+
+    /// Returns the encoding as it would be held in memory by a machine with the opposite
+    /// byte order.
+    fn swapped(encoding: &Encoding) -> Encoding {
+        fn swap_words(bytes: &mut [u8]) {
+            for word in bytes.chunks_exact_mut(4) {
+                word.reverse();
+            }
+        }
+        let mut swapped = encoding.clone();
+        swap_words(&mut swapped.path_data);
+        swap_words(&mut swapped.draw_data);
+        swap_words(bytemuck::cast_slice_mut(&mut swapped.draw_tags));
+        swap_words(bytemuck::cast_slice_mut(&mut swapped.transforms));
+        swap_words(bytemuck::cast_slice_mut(&mut swapped.styles));
+        swap_words(bytemuck::cast_slice_mut(&mut swapped.clip_rects));
+        swapped
+    }
+
+    fn assert_same_streams(a: &Encoding, b: &Encoding) {
+        assert!(a.path_tags == b.path_tags);
+        assert_eq!(a.path_data, b.path_data);
+        assert!(a.draw_tags == b.draw_tags);
+        assert_eq!(a.draw_data, b.draw_data);
+        assert_eq!(a.transforms, b.transforms);
+        assert_eq!(a.styles, b.styles);
+        assert_eq!(a.clip_rects, b.clip_rects);
+        assert_eq!(a.draw_depths, b.draw_depths);
+        assert_eq!(a.fixed_transforms, b.fixed_transforms);
+        assert_eq!(a.n_paths, b.n_paths);
+        assert_eq!(a.resources.color_stops, b.resources.color_stops);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let encoding = encoding();
+        let bytes = encoding.to_bytes().unwrap();
+        let decoded = Encoding::from_bytes(&bytes).unwrap();
+        assert_same_streams(&encoding, &decoded);
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_byte_order_independent() {
+        let encoding = encoding();
+        let (native, other) = match ByteOrder::NATIVE {
+            ByteOrder::Little => (ByteOrder::Little, ByteOrder::Big),
+            ByteOrder::Big => (ByteOrder::Big, ByteOrder::Little),
+        };
+        let bytes = encoding.write_bytes(native).unwrap();
+        let swapped = swapped(&encoding);
+        assert_eq!(swapped.write_bytes(other).unwrap(), bytes);
+        let decoded = Encoding::read_bytes(&bytes, other).unwrap();
+        assert_same_streams(&swapped, &decoded);
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        let bytes = encoding().to_bytes().unwrap();
+        assert_eq!(
+            Encoding::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(SerializeError::Truncated)
+        );
+        assert_eq!(
+            Encoding::from_bytes(b"not an encoding").err(),
+            Some(SerializeError::BadHeader)
+        );
+    }
+}
@@ -5,11 +5,11 @@ use vello::{
     block_on_wgpu,
     peniko::{Blob, Color, Format, Image},
     util::RenderContext,
-    RendererOptions, Scene,
+    RenderParams, Renderer, RendererOptions, Scene,
 };
 use wgpu::{
-    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
-    TextureDescriptor, TextureFormat, TextureUsages,
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device, Extent3d, ImageCopyBuffer,
+    Queue, Texture, TextureDescriptor, TextureFormat, TextureUsages,
 };
 
 pub fn decode_image(data: &[u8]) -> Result<Image> {
@@ -41,6 +41,16 @@ impl TestParams {
             name: name.into(),
         }
     }
+
+    /// The parameters used to render a frame in [`render`].
+    pub fn render_params(&self) -> RenderParams {
+        RenderParams {
+            base_color: self.base_colour,
+            width: self.width,
+            height: self.height,
+            ..Default::default()
+        }
+    }
 }
 
 pub fn render_sync(scene: Scene, params: &TestParams) -> Result<Image> {
@@ -48,6 +58,101 @@ pub fn render_sync(scene: Scene, params: &TestParams) -> Result<Image> {
 }
 
 pub async fn render(scene: Scene, params: &TestParams) -> Result<Image> {
+    let render_params = params.render_params();
+    render_frames(vec![(scene, render_params)], params).await
+}
+
+pub fn render_frames_sync(
+    frames: Vec<(Scene, RenderParams)>,
+    params: &TestParams,
+) -> Result<Image> {
+    pollster::block_on(render_frames(frames, params))
+}
+
+/// Render each frame in turn to the same target texture, and read back the final contents.
+pub async fn render_frames(
+    frames: Vec<(Scene, RenderParams)>,
+    params: &TestParams,
+) -> Result<Image> {
+    render_with(params, |device, queue, renderer, target| {
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        for (scene, render_params) in &frames {
+            renderer
+                .render_to_texture(device, queue, scene, &view, render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Run `f` with a renderer and an `Rgba8Unorm` target texture of the size given in `params`,
+/// and read back the final contents of the target.
+pub async fn render_with(
+    params: &TestParams,
+    f: impl FnOnce(&Device, &Queue, &mut Renderer, &Texture) -> Result<()>,
+) -> Result<Image> {
+    render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: vello::AaSupport::area_only(),
+                bin_size: vello::BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        f,
+    )
+    .await
+}
+
+/// Like [`render_with`], but with a renderer created by `new_renderer`.
+pub async fn render_with_renderer(
+    params: &TestParams,
+    new_renderer: impl FnOnce(&Device) -> Result<Renderer>,
+    f: impl FnOnce(&Device, &Queue, &mut Renderer, &Texture) -> Result<()>,
+) -> Result<Image> {
+    let image = with_renderer(new_renderer, |device, queue, renderer| {
+        let width = params.width;
+        let height = params.height;
+        let target = device.create_texture(&TextureDescriptor {
+            label: Some("Target texture"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::STORAGE_BINDING
+                | TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_SRC
+                | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        f(device, queue, renderer, &target)?;
+        read_texture(device, queue, &target, (0, 0), width, height)
+    })
+    .await?;
+    if should_debug_png(&params.name, params.use_cpu) {
+        let suffix = if params.use_cpu { "cpu" } else { "gpu" };
+        let name = format!("{}_{suffix}", &params.name);
+        debug_png(&image, &name, params)?;
+    }
+    Ok(image)
+}
+
+/// Run `f` with the first compatible device and a renderer created by `new_renderer`.
+pub async fn with_renderer<T>(
+    new_renderer: impl FnOnce(&Device) -> Result<Renderer>,
+    f: impl FnOnce(&Device, &Queue, &mut Renderer) -> Result<T>,
+) -> Result<T> {
     let mut context = RenderContext::new()
         .or_else(|_| bail!("Got non-Send/Sync error from creating render context"))?;
     let device_id = context
@@ -57,44 +162,85 @@ pub async fn render(scene: Scene, params: &TestParams) -> Result<Image> {
     let device_handle = &mut context.devices[device_id];
     let device = &device_handle.device;
     let queue = &device_handle.queue;
-    let mut renderer = vello::Renderer::new(
-        device,
-        RendererOptions {
-            surface_format: None,
-            use_cpu: params.use_cpu,
-            antialiasing_support: vello::AaSupport::area_only(),
-        },
-    )
-    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
+    let mut renderer = new_renderer(device)?;
+    f(device, queue, &mut renderer)
+}
 
-    let width = params.width;
-    let height = params.height;
-    let render_params = vello::RenderParams {
-        base_color: params.base_colour,
+/// Read back the `width` by `height` region of an `Rgba8Unorm` texture starting at `origin`.
+pub fn read_texture(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    origin: (u32, u32),
+    width: u32,
+    height: u32,
+) -> Result<Image> {
+    read_texture_level(device, queue, texture, 0, origin, width, height)
+}
+
+/// Like [`read_texture`], but reads from the given mip level.
+pub fn read_texture_level(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    mip_level: u32,
+    origin: (u32, u32),
+    width: u32,
+    height: u32,
+) -> Result<Image> {
+    let data = read_texture_bytes(
+        device, queue, texture, mip_level, 0, origin, width, height, 4,
+    )?;
+    Ok(Image::new(
+        Blob::new(Arc::new(data)),
+        Format::Rgba8,
         width,
         height,
-        antialiasing_method: vello::AaConfig::Area,
-    };
-    let size = Extent3d {
+    ))
+}
+
+/// Like [`read_texture`], but reads the whole of the given array layer.
+pub fn read_texture_layer(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    layer: u32,
+) -> Result<Image> {
+    let (width, height) = (texture.width(), texture.height());
+    let data = read_texture_bytes(device, queue, texture, 0, layer, (0, 0), width, height, 4)?;
+    Ok(Image::new(
+        Blob::new(Arc::new(data)),
+        Format::Rgba8,
         width,
         height,
-        depth_or_array_layers: 1,
-    };
-    let target = device.create_texture(&TextureDescriptor {
-        label: Some("Target texture"),
-        size,
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: TextureFormat::Rgba8Unorm,
-        usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
-        view_formats: &[],
-    });
-    let view = target.create_view(&wgpu::TextureViewDescriptor::default());
-    renderer
-        .render_to_texture(device, queue, &scene, &view, &render_params)
-        .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
-    let padded_byte_width = (width * 4).next_multiple_of(256);
+    ))
+}
+
+/// Read back the `width` by `height` region of an `R8Unorm` texture such as a coverage mask,
+/// with one byte per pixel.
+pub fn read_mask(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>> {
+    read_texture_bytes(device, queue, texture, 0, 0, (0, 0), width, height, 1)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn read_texture_bytes(
+    device: &Device,
+    queue: &Queue,
+    texture: &Texture,
+    mip_level: u32,
+    layer: u32,
+    origin: (u32, u32),
+    width: u32,
+    height: u32,
+    bytes_per_pixel: u32,
+) -> Result<Vec<u8>> {
+    let padded_byte_width = (width * bytes_per_pixel).next_multiple_of(256);
     let buffer_size = padded_byte_width as u64 * height as u64;
     let buffer = device.create_buffer(&BufferDescriptor {
         label: Some("val"),
@@ -106,7 +252,16 @@ pub async fn render(scene: Scene, params: &TestParams) -> Result<Image> {
         label: Some("Copy out buffer"),
     });
     encoder.copy_texture_to_buffer(
-        target.as_image_copy(),
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level,
+            origin: wgpu::Origin3d {
+                x: origin.0,
+                y: origin.1,
+                z: layer,
+            },
+            aspect: wgpu::TextureAspect::All,
+        },
         ImageCopyBuffer {
             buffer: &buffer,
             layout: wgpu::ImageDataLayout {
@@ -115,7 +270,11 @@ pub async fn render(scene: Scene, params: &TestParams) -> Result<Image> {
                 rows_per_image: None,
             },
         },
-        size,
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
     );
     queue.submit([encoder.finish()]);
     let buf_slice = buffer.slice(..);
@@ -129,19 +288,13 @@ pub async fn render(scene: Scene, params: &TestParams) -> Result<Image> {
     }
 
     let data = buf_slice.get_mapped_range();
-    let mut result_unpadded = Vec::<u8>::with_capacity((width * height * 4).try_into()?);
+    let row_bytes = (width * bytes_per_pixel) as usize;
+    let mut result_unpadded = Vec::<u8>::with_capacity(row_bytes * height as usize);
     for row in 0..height {
         let start = (row * padded_byte_width).try_into()?;
-        result_unpadded.extend(&data[start..start + (width * 4) as usize]);
+        result_unpadded.extend(&data[start..start + row_bytes]);
     }
-    let data = Blob::new(Arc::new(result_unpadded));
-    let image = Image::new(data, Format::Rgba8, width, height);
-    if should_debug_png(&params.name, params.use_cpu) {
-        let suffix = if params.use_cpu { "cpu" } else { "gpu" };
-        let name = format!("{}_{suffix}", &params.name);
-        debug_png(&image, &name, params)?;
-    }
-    Ok(image)
+    Ok(result_unpadded)
 }
 
 pub fn debug_png(image: &Image, name: &str, params: &TestParams) -> Result<()> {
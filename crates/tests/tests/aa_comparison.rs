@@ -0,0 +1,71 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::{Brush, Color, Fill},
+    AaConfig, AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn aa_comparison_gpu() {
+    aa_comparison(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn aa_comparison_cpu() {
+    aa_comparison(true)
+}
+
+fn aa_comparison(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("aa_comparison", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Circle::new((32., 32.), 20.),
+    );
+    let modes = [AaConfig::Area, AaConfig::Msaa8];
+    let images = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::all(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let textures = renderer
+                .render_aa_comparison(device, queue, &scene, &params.render_params(), &modes)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            assert_eq!(textures.len(), modes.len());
+            textures
+                .iter()
+                .map(|texture| vello_tests::read_texture(device, queue, texture, (0, 0), 64, 64))
+                .collect::<anyhow::Result<Vec<_>>>()
+        },
+    ))
+    .unwrap();
+    let (area, msaa) = (images[0].data.data(), images[1].data.data());
+    let pixel = |data: &[u8], x: usize, y: usize| {
+        let ix = (y * params.width as usize + x) * 4;
+        data[ix..ix + 4].to_vec()
+    };
+    // Well inside and well outside of the circle, both methods agree exactly.
+    for (x, y) in [(32, 32), (24, 40), (2, 2), (61, 61)] {
+        assert_eq!(pixel(area, x, y), pixel(msaa, x, y), "({x}, {y})");
+    }
+    assert_eq!(pixel(area, 32, 32), [255, 0, 0, 255]);
+    // Along the edge, coverage is estimated differently.
+    assert!(area != msaa);
+}
@@ -0,0 +1,107 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    Accumulation, RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+const SIZE: u32 = 64;
+
+/// Accumulates two renders of a square, the second one offset by 16 pixels in both
+/// directions, into a transparent target.
+fn accumulate_offset_squares(use_cpu: bool, color: Color, accumulation: Accumulation) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("accumulate", SIZE, SIZE)
+    };
+    let render_params = RenderParams {
+        base_color: Color::TRANSPARENT,
+        ..params.render_params()
+    };
+    let image = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            for offset in [0., 16.] {
+                let mut scene = Scene::new();
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::translate((offset, offset)),
+                    color,
+                    None,
+                    &Rect::new(8., 8., 40., 40.),
+                );
+                renderer
+                    .render_to_texture_accumulated(
+                        device,
+                        queue,
+                        &scene,
+                        target,
+                        &render_params,
+                        accumulation,
+                    )
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+    image.data.data().to_vec()
+}
+
+fn pixel(data: &[u8], x: u32, y: u32) -> [u8; 4] {
+    let ix = ((y * SIZE + x) * 4) as usize;
+    [data[ix], data[ix + 1], data[ix + 2], data[ix + 3]]
+}
+
+fn assert_close(actual: [u8; 4], expected: [u8; 4]) {
+    for (a, e) in actual.into_iter().zip(expected) {
+        assert!(a.abs_diff(e) <= 1, "{actual:?} isn't {expected:?}");
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn accumulate_add_gpu() {
+    accumulate_add(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn accumulate_add_cpu() {
+    accumulate_add(true)
+}
+
+fn accumulate_add(use_cpu: bool) {
+    let data = accumulate_offset_squares(use_cpu, Color::RED, Accumulation::Add { weight: 0.5 });
+    // Each square adds half of its color, so the overlap holds the sum of both.
+    assert_close(pixel(&data, 12, 12), [128, 0, 0, 128]);
+    assert_close(pixel(&data, 50, 50), [128, 0, 0, 128]);
+    assert_close(pixel(&data, 32, 32), [255, 0, 0, 255]);
+    // The target isn't cleared by the second render.
+    assert_eq!(pixel(&data, 4, 4), [0, 0, 0, 0]);
+    assert_eq!(pixel(&data, 60, 4), [0, 0, 0, 0]);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn accumulate_over_gpu() {
+    accumulate_over(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn accumulate_over_cpu() {
+    accumulate_over(true)
+}
+
+fn accumulate_over(use_cpu: bool) {
+    let color = Color::rgb8(0, 0, 255);
+    let data = accumulate_offset_squares(use_cpu, color, Accumulation::Over { opacity: 0.5 });
+    // The second square is blended over the first, so the overlap has an alpha of
+    // 0.5 + 0.5 * (1 - 0.5), with premultiplied colors.
+    assert_close(pixel(&data, 12, 12), [0, 0, 128, 128]);
+    assert_close(pixel(&data, 50, 50), [0, 0, 128, 128]);
+    assert_close(pixel(&data, 32, 32), [0, 0, 191, 191]);
+    assert_eq!(pixel(&data, 4, 4), [0, 0, 0, 0]);
+}
@@ -0,0 +1,82 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, AlphaType, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn alpha_type_gpu() {
+    alpha_type(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn alpha_type_cpu() {
+    alpha_type(true)
+}
+
+fn alpha_type(use_cpu: bool) {
+    let inner = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("alpha_type_inner", 8, 8)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgba8(255, 0, 0, 128)),
+        None,
+        &Rect::new(0., 0., 8., 8.),
+    );
+    let [straight, premultiplied] =
+        [AlphaType::Straight, AlphaType::Premultiplied].map(|alpha_type| {
+            pollster::block_on(vello_tests::with_renderer(
+                |device| {
+                    let options = RendererOptions {
+                        surface_format: None,
+                        use_cpu,
+                        antialiasing_support: AaSupport::area_only(),
+                        bin_size: BinSize::default(),
+                        max_clip_depth: None,
+                    };
+                    Renderer::new(device, options)
+                        .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+                },
+                |device, queue, renderer| {
+                    renderer
+                        .render_to_image(device, queue, &scene, &inner.render_params(), alpha_type)
+                        .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+                },
+            ))
+            .unwrap()
+        });
+    assert_eq!(straight.alpha_type, AlphaType::Straight);
+    assert_eq!(&straight.image.data.data()[..4], &[255, 0, 0, 128]);
+    assert_eq!(premultiplied.alpha_type, AlphaType::Premultiplied);
+    assert_eq!(&premultiplied.image.data.data()[..4], &[128, 0, 0, 128]);
+
+    // Both images are drawn over white exactly as the original rectangle would be, rather than
+    // darkening the premultiplied one by applying its alpha again.
+    let outer = TestParams {
+        use_cpu,
+        base_colour: Color::WHITE,
+        ..TestParams::new("alpha_type", 16, 8)
+    };
+    let mut scene = Scene::new();
+    scene.draw_rendered_image(&straight, Affine::IDENTITY);
+    scene.draw_rendered_image(&premultiplied, Affine::translate((8., 0.)));
+    let rendered = vello_tests::render_sync(scene, &outer).unwrap();
+    let pixel = |x: usize| {
+        let ix = (4 * outer.width as usize + x) * 4;
+        &rendered.data.data()[ix..ix + 4]
+    };
+    for x in [4, 12] {
+        let [r, g, b, a] = pixel(x).try_into().unwrap();
+        assert_eq!((r, a), (255, 255), "pixel {x}");
+        assert!(g.abs_diff(127) <= 1 && g == b, "pixel {x}: {g}");
+    }
+}
@@ -0,0 +1,93 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+/// The flag in an encoded style which turns off antialiasing.
+const ALIASED_BIT: u32 = 1 << 22;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn aliased_rect_gpu() {
+    aliased_rect(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn aliased_rect_cpu() {
+    aliased_rect(true)
+}
+
+fn aliased_rect(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("aliased_rect", WIDTH, HEIGHT)
+    };
+    let mut scene = Scene::new();
+    scene.set_antialias(false);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(2.25, 2.25, 29.75, 29.75),
+    );
+    scene.set_antialias(true);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Circle::new((48., 16.), 12.),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let alpha = |x: u32, y: u32| image.data.data()[((y * WIDTH + x) * 4 + 3) as usize];
+    // Every pixel of the rect is either fully covered or not at all, with the edges rounded
+    // to the nearest pixel boundary.
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH / 2 {
+            let inside = (2..30).contains(&x) && (2..30).contains(&y);
+            let expected = if inside { 255 } else { 0 };
+            assert_eq!(alpha(x, y), expected, "rect pixel ({x}, {y})");
+        }
+    }
+    // The circle in the same frame still has antialiased edges.
+    let partial = (WIDTH / 2..WIDTH)
+        .flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+        .filter(|&(x, y)| !matches!(alpha(x, y), 0 | 255))
+        .count();
+    assert!(
+        partial > 20,
+        "only {partial} partially covered circle pixels"
+    );
+}
+
+#[test]
+fn antialias_style_flag() {
+    let flags = |scene: &Scene| {
+        let encoding = scene.encoding();
+        encoding
+            .styles
+            .iter()
+            .map(|style| style.flags_and_miter_limit & ALIASED_BIT != 0)
+            .collect::<Vec<_>>()
+    };
+    let rect = Rect::new(0., 0., 8., 8.);
+    let mut scene = Scene::new();
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &rect);
+    scene.set_antialias(false);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &rect);
+    scene.set_antialias(true);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &rect);
+    assert_eq!(flags(&scene), [false, true, false]);
+    // The setting is kept when the scene is reset.
+    scene.set_antialias(false);
+    scene.reset();
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &rect);
+    assert_eq!(flags(&scene), [true]);
+}
@@ -0,0 +1,71 @@
+use std::f64::consts::FRAC_PI_2;
+
+use vello::{
+    kurbo::{Affine, Arc, CubicBez, ParamCurve, Point, Vec2},
+    peniko::{Color, Fill},
+    Scene,
+};
+
+const RADIUS: f64 = 10_000.;
+
+/// Fills a quarter circle arc of a large radius and returns the cubics it was encoded as.
+fn encode_arc(tolerance: Option<f64>) -> Vec<CubicBez> {
+    let arc = Arc::new(Point::ZERO, Vec2::new(RADIUS, RADIUS), 0., FRAC_PI_2, 0.);
+    let mut scene = Scene::new();
+    scene.set_arc_tolerance(tolerance);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &arc);
+    let encoding = scene.encoding();
+    let points: Vec<Point> = encoding
+        .path_data
+        .chunks_exact(8)
+        .map(|pair| {
+            let coord = |bytes: &[u8]| f32::from_le_bytes(bytes.try_into().unwrap()) as f64;
+            Point::new(coord(&pair[..4]), coord(&pair[4..]))
+        })
+        .collect();
+    // The path starts with the first point of the arc and is closed by a line back to it
+    // from the last, with the cubics in between.
+    let cubics = (encoding.n_path_segments - 1) as usize;
+    assert_eq!(points.len(), 3 * cubics + 2);
+    (0..cubics)
+        .map(|i| {
+            CubicBez::new(
+                points[3 * i],
+                points[3 * i + 1],
+                points[3 * i + 2],
+                points[3 * i + 3],
+            )
+        })
+        .collect()
+}
+
+/// Returns the largest distance between the cubics and the true arc.
+fn deviation(cubics: &[CubicBez]) -> f64 {
+    cubics
+        .iter()
+        .flat_map(|cubic| (0..=100).map(|i| cubic.eval(i as f64 / 100.)))
+        .map(|p| (p.to_vec2().hypot() - RADIUS).abs())
+        .fold(0., f64::max)
+}
+
+#[test]
+fn arc_tolerance() {
+    let fine = encode_arc(Some(0.01));
+    let coarse = encode_arc(Some(1.0));
+    assert!(fine.len() > coarse.len(), "{} cubics", fine.len());
+    let (fine_deviation, coarse_deviation) = (deviation(&fine), deviation(&coarse));
+    // Coordinates of this size are only encoded to a precision of about 0.001.
+    assert!(
+        fine_deviation < 0.01 + 0.002,
+        "fine deviation {fine_deviation}"
+    );
+    assert!(
+        coarse_deviation < 1.0,
+        "coarse deviation {coarse_deviation}"
+    );
+    assert!(fine_deviation < coarse_deviation);
+
+    // Without a tolerance, fills use the default one.
+    assert_eq!(Scene::new().arc_tolerance(), 0.1);
+    assert_eq!(encode_arc(None).len(), encode_arc(Some(0.1)).len());
+}
@@ -0,0 +1,79 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, RenderParams, Renderer, RendererOptions, Scene,
+};
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn auto_fit_limits_gpu() {
+    auto_fit_limits(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn auto_fit_limits_cpu() {
+    auto_fit_limits(true)
+}
+
+fn auto_fit_limits(use_cpu: bool) {
+    const WIDTH: u32 = 20000;
+    const HEIGHT: u32 = 100;
+    let mut scene = Scene::new();
+    // The left half of the canvas.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., WIDTH as f64 / 2., HEIGHT as f64),
+    );
+    let params = RenderParams {
+        width: WIDTH,
+        height: HEIGHT,
+        auto_fit_limits: true,
+        ..Default::default()
+    };
+    let new_renderer = |device: &wgpu::Device| {
+        let options = RendererOptions {
+            surface_format: None,
+            use_cpu,
+            antialiasing_support: AaSupport::area_only(),
+            bin_size: BinSize::default(),
+            max_clip_depth: None,
+        };
+        Renderer::new(device, options)
+            .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+    };
+    pollster::block_on(vello_tests::with_renderer(
+        new_renderer,
+        |device, queue, renderer| {
+            assert_eq!(device.limits().max_texture_dimension_2d, 8192);
+            let strict = RenderParams {
+                auto_fit_limits: false,
+                ..params.clone()
+            };
+            assert!(renderer
+                .render_to_fitted_texture(device, queue, &scene, &strict)
+                .is_err());
+            let fitted = renderer
+                .render_to_fitted_texture(device, queue, &scene, &params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            assert_eq!((fitted.width, fitted.height), (8192, 41));
+            assert_eq!(
+                (fitted.texture.width(), fitted.texture.height()),
+                (8192, 41)
+            );
+            assert_eq!(fitted.scale, 8192. / 20000.);
+            // The edge of the fill moves from 10000 to 4096 pixels.
+            let image =
+                vello_tests::read_texture(device, queue, &fitted.texture, (4092, 20), 8, 1)?;
+            let data = image.data.data();
+            assert_eq!(&data[..4], &[255, 0, 0, 255]);
+            assert_eq!(&data[data.len() - 4..], &[0, 0, 0, 255]);
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,54 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+const SIZE: u32 = 1024;
+const LAYERS: u32 = 600;
+
+/// Full canvas rectangles, each of which allocates a tile for every tile of the target.
+fn overflowing_scene() -> Scene {
+    let mut scene = Scene::new();
+    let rect = Rect::new(0., 0., SIZE as f64, SIZE as f64);
+    for i in 0..LAYERS {
+        let color = Color::rgba8((i * 29 % 256) as u8, (i * 71 % 256) as u8, 128, 64);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(color),
+            None,
+            &rect,
+        );
+    }
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::BLUE),
+        None,
+        &rect,
+    );
+    scene
+}
+
+// The CPU stages don't detect overflow, so there is no CPU variant.
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn auto_grow_gpu() {
+    // The scene needs more tiles than the initial allocation of 2^21.
+    let tiles_per_path = (SIZE / 16) * (SIZE / 16);
+    assert!((LAYERS + 1) * tiles_per_path > 1 << 21);
+    let params = TestParams::new("auto_grow", SIZE, SIZE);
+    let render_params = RenderParams {
+        auto_grow: true,
+        ..params.render_params()
+    };
+    let image =
+        vello_tests::render_frames_sync(vec![(overflowing_scene(), render_params)], &params)
+            .unwrap();
+    // The opaque rectangle drawn last covers everything.
+    for (i, pixel) in image.data.data().chunks(4).enumerate() {
+        assert_eq!(pixel, [0, 0, 255, 255], "pixel {i}");
+    }
+}
@@ -0,0 +1,73 @@
+use vello::{
+    kurbo::{Affine, BezPath, Rect, Shape},
+    peniko::{Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+/// Spans several tiles in each direction, so the winding is carried across them by the
+/// backdrop stage.
+const RECT: Rect = Rect::new(3.5, 5.25, 60.5, 40.75);
+
+/// Renders `RECT` filled with `style`, with its outline repeated `copies` times in one path.
+fn overlapping_rects(use_cpu: bool, style: Fill, copies: usize) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("backdrop_overlap", WIDTH, HEIGHT)
+    };
+    let mut path = BezPath::new();
+    for _ in 0..copies {
+        path.extend(RECT.path_elements(0.1));
+    }
+    let mut scene = Scene::new();
+    scene.fill(style, Affine::IDENTITY, Color::WHITE, None, &path);
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    image.data.data().to_vec()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn backdrop_overlap_gpu() {
+    backdrop_overlap(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn backdrop_overlap_cpu() {
+    backdrop_overlap(true)
+}
+
+fn backdrop_overlap(use_cpu: bool) {
+    const COPIES: usize = 4096;
+    let reference = overlapping_rects(use_cpu, Fill::NonZero, 1);
+    // Every pixel inside of the rectangle has a winding number of `COPIES`, which fills it
+    // exactly as a single copy does.
+    let overlapping = overlapping_rects(use_cpu, Fill::NonZero, COPIES);
+    let assert_matches = |actual: &[u8], expected: &[u8]| {
+        for (i, (a, e)) in actual
+            .chunks_exact(4)
+            .zip(expected.chunks_exact(4))
+            .enumerate()
+        {
+            let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+            assert!(
+                a.iter().zip(e).all(|(a, e)| a.abs_diff(*e) <= 1),
+                "pixel ({x}, {y}) is {a:?}, expected {e:?}"
+            );
+        }
+    };
+    assert_matches(&overlapping, &reference);
+    // With the even-odd rule, an odd number of copies fills the rectangle and an even
+    // number leaves it empty.
+    assert_matches(
+        &overlapping_rects(use_cpu, Fill::EvenOdd, COPIES + 1),
+        &reference,
+    );
+    let empty = overlapping_rects(use_cpu, Fill::NonZero, 0);
+    assert_matches(&overlapping_rects(use_cpu, Fill::EvenOdd, COPIES), &empty);
+    // The reference does cover the rectangle.
+    let center = (((HEIGHT / 2) * WIDTH + WIDTH / 2) * 4) as usize;
+    assert_eq!(reference[center..center + 4], [255, 255, 255, 255]);
+}
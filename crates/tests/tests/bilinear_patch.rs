@@ -0,0 +1,60 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::Color,
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bilinear_patch_gpu() {
+    bilinear_patch(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bilinear_patch_cpu() {
+    bilinear_patch(true)
+}
+
+fn bilinear_patch(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("bilinear_patch", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill_bilinear_patch(
+        Affine::IDENTITY,
+        Rect::new(0., 0., 64., 64.),
+        [Color::RED, Color::GREEN, Color::BLUE, Color::WHITE],
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let ix = (y * params.width as usize + x) * 4;
+        &data[ix..ix + 4]
+    };
+    // Pixel centers next to the corners are close to the corner colors.
+    let close = |actual: &[u8], expected: [u8; 4]| {
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(&a, e)| a.abs_diff(e) <= 8)
+    };
+    assert!(close(pixel(0, 0), [255, 0, 0, 255]), "{:?}", pixel(0, 0));
+    assert!(close(pixel(63, 0), [0, 255, 0, 255]), "{:?}", pixel(63, 0));
+    assert!(close(pixel(0, 63), [0, 0, 255, 255]), "{:?}", pixel(0, 63));
+    assert!(close(pixel(63, 63), [255; 4]), "{:?}", pixel(63, 63));
+    // The four pixels around the center of the patch straddle the average of the corners.
+    for channel in 0..3 {
+        let sum: u32 = [(31, 31), (32, 31), (31, 32), (32, 32)]
+            .iter()
+            .map(|&(x, y)| pixel(x, y)[channel] as u32)
+            .sum();
+        assert!(
+            (sum / 4).abs_diff(128) <= 2,
+            "channel {channel}: {}",
+            sum / 4
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Image},
+    AaSupport, BinSize, RenderParams, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+/// One-pixel wide columns across the whole of a single bin, in more than one partition of
+/// draw objects.
+fn columns_scene(count: usize) -> Scene {
+    let mut scene = Scene::new();
+    for i in 0..count {
+        let x = (i % 256) as f64;
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(Color::WHITE),
+            None,
+            &Rect::new(x, 0., x + 1., 32.),
+        );
+    }
+    scene
+}
+
+fn render(scene: &Scene, params: &TestParams, capacity: Option<u32>) -> anyhow::Result<Image> {
+    pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            let mut renderer = Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
+            if let Some(capacity) = capacity {
+                renderer.set_bin_capacity(capacity);
+            }
+            Ok(renderer)
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            // The binning buffer is sized for the chunks only, so overflow chunks grow it.
+            let render_params = RenderParams {
+                auto_grow: true,
+                ..params.render_params()
+            };
+            renderer
+                .render_to_texture(device, queue, scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bin_capacity_gpu() {
+    bin_capacity(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bin_capacity_cpu() {
+    bin_capacity(true)
+}
+
+fn bin_capacity(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("bin_capacity", 256, 32)
+    };
+    let scene = columns_scene(300);
+    let expected = render(&scene, &params, None).unwrap();
+    let data = expected.data.data();
+    for x in 0..params.width as usize {
+        assert_eq!(&data[x * 4..x * 4 + 4], &[255; 4], "column {x} is missing");
+    }
+    for capacity in [0, 1, 16, 255] {
+        let image = render(&scene, &params, Some(capacity)).unwrap();
+        assert!(
+            image.data.data() == data,
+            "bin capacity {capacity} drops draw objects"
+        );
+    }
+}
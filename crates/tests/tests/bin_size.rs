@@ -0,0 +1,149 @@
+use std::time::Instant;
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::{Brush, Color, Fill, Image},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+/// Every valid bin shape, from short and wide to tall and narrow.
+const BIN_SIZES: [BinSize; 7] = [
+    BinSize::new(256, 1),
+    BinSize::new(64, 4),
+    BinSize::new(32, 8),
+    BinSize::SQUARE,
+    BinSize::new(8, 32),
+    BinSize::new(4, 64),
+    BinSize::new(1, 256),
+];
+
+/// Many small circles packed into a horizontal band, so that most draw objects
+/// land in the same few rows of bins.
+fn clustered_scene(width: u32, height: u32, count: usize) -> Scene {
+    let mut scene = Scene::new();
+    let band = height as f64 / 4.;
+    for i in 0..count {
+        let x = (i * 37 % width as usize) as f64;
+        let y = height as f64 / 2. + (i * 13 % 17) as f64 / 17. * band - band / 2.;
+        let color = Color::rgba8((i * 29 % 256) as u8, (i * 71 % 256) as u8, 128, 192);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(color),
+            None,
+            &Circle::new((x, y), 3. + (i % 5) as f64),
+        );
+    }
+    scene
+}
+
+fn render(scene: &Scene, params: &TestParams, bin_size: BinSize) -> anyhow::Result<Image> {
+    pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size,
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+}
+
+#[test]
+fn bin_size_validation() {
+    for bin_size in BIN_SIZES {
+        assert!(bin_size.validate().is_ok(), "{bin_size:?}");
+    }
+    assert!(BinSize::new(16, 8).validate().is_err());
+    assert!(BinSize::new(12, 21).validate().is_err());
+    assert!(BinSize::new(0, 256).validate().is_err());
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bin_sizes_gpu() {
+    bin_sizes(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn bin_sizes_cpu() {
+    bin_sizes(true)
+}
+
+fn bin_sizes(use_cpu: bool) {
+    // Deliberately not a multiple of any bin extent, to exercise partial bins.
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("bin_sizes", 300, 200)
+    };
+    let scene = clustered_scene(params.width, params.height, 200);
+    let expected = render(&scene, &params, BinSize::SQUARE).unwrap();
+    for bin_size in BIN_SIZES {
+        let image = render(&scene, &params, bin_size).unwrap();
+        assert!(
+            image.data.data() == expected.data.data(),
+            "{bin_size:?} differs from the square bins"
+        );
+    }
+}
+
+/// Compares frame times for each bin shape on a clustered scene.
+///
+/// Run with `cargo test -p vello_tests --test bin_size -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_bin_sizes() {
+    const FRAMES: u32 = 20;
+    let params = TestParams::new("bench_bin_sizes", 2048, 512);
+    let scene = clustered_scene(params.width, params.height, 20_000);
+    for bin_size in BIN_SIZES {
+        pollster::block_on(vello_tests::render_with_renderer(
+            &params,
+            |device| {
+                let options = RendererOptions {
+                    surface_format: None,
+                    use_cpu: false,
+                    antialiasing_support: AaSupport::area_only(),
+                    bin_size,
+                    max_clip_depth: None,
+                };
+                Renderer::new(device, options)
+                    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+            },
+            |device, queue, renderer, target| {
+                let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+                let mut frame = || {
+                    renderer
+                        .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                        .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                    device.poll(wgpu::Maintain::Wait);
+                    Ok::<_, anyhow::Error>(())
+                };
+                // Warm up the buffer pools before timing.
+                frame()?;
+                let start = Instant::now();
+                for _ in 0..FRAMES {
+                    frame()?;
+                }
+                let elapsed = start.elapsed() / FRAMES;
+                println!("{:>3}x{:<3} {elapsed:?}", bin_size.width, bin_size.height);
+                Ok(())
+            },
+        ))
+        .unwrap();
+    }
+}
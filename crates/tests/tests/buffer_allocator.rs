@@ -0,0 +1,87 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, BufferAllocator, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+use wgpu::{Buffer, BufferDescriptor, Device};
+
+#[derive(Default)]
+struct CountingAllocator {
+    created: Arc<AtomicUsize>,
+}
+
+impl BufferAllocator for CountingAllocator {
+    fn create_buffer(&mut self, device: &Device, descriptor: &BufferDescriptor) -> Buffer {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        device.create_buffer(descriptor)
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn buffer_allocator_gpu() {
+    buffer_allocator(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn buffer_allocator_cpu() {
+    buffer_allocator(true)
+}
+
+fn buffer_allocator(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("buffer_allocator", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(8., 8., 56., 56.),
+    );
+    let allocator = CountingAllocator::default();
+    let created = allocator.created.clone();
+    let mut after_first = 0;
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        &params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::with_buffer_allocator(device, options, Box::new(allocator))
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            for i in 0..2 {
+                renderer
+                    .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                if i == 0 {
+                    after_first = created.load(Ordering::Relaxed);
+                }
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+    assert!(after_first > 0);
+    // The second render of the same scene is served entirely from the pool.
+    assert_eq!(created.load(Ordering::Relaxed), after_first);
+    let center = (32 * params.width as usize + 32) * 4;
+    assert_eq!(&image.data.data()[center..center + 4], &[255, 0, 0, 255]);
+}
@@ -0,0 +1,102 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, BufferAllocator, CancellationToken, Cancelled, Renderer, RendererOptions,
+    Scene,
+};
+use vello_tests::TestParams;
+use wgpu::{Buffer, BufferDescriptor, Device};
+
+/// Cancels the token as soon as the renderer allocates its first buffer, which happens while
+/// uploading the scene and so before any stage has run.
+struct CancellingAllocator {
+    token: CancellationToken,
+}
+
+impl BufferAllocator for CancellingAllocator {
+    fn create_buffer(&mut self, device: &Device, descriptor: &BufferDescriptor) -> Buffer {
+        self.token.cancel();
+        device.create_buffer(descriptor)
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn cancel_render_gpu() {
+    cancel_render(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn cancel_render_cpu() {
+    cancel_render(true)
+}
+
+fn cancel_render(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("cancel_render", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    let token = CancellationToken::new();
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        &params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            let allocator = CancellingAllocator {
+                token: token.clone(),
+            };
+            let mut renderer =
+                Renderer::with_buffer_allocator(device, options, Box::new(allocator))
+                    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
+            renderer.set_cancellation_token(Some(token.clone()));
+            Ok(renderer)
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Fill with blue"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLUE),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            queue.submit([encoder.finish()]);
+            let Err(error) =
+                renderer.render_to_texture(device, queue, &scene, &view, &params.render_params())
+            else {
+                bail!("Render finished despite being cancelled");
+            };
+            assert!(error.downcast_ref::<Cancelled>().is_some(), "{error}");
+            assert!(token.is_cancelled());
+            Ok(())
+        },
+    ))
+    .unwrap();
+    for pixel in image.data.data().chunks_exact(4) {
+        assert_eq!(pixel, [0, 0, 255, 255]);
+    }
+}
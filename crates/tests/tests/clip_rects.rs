@@ -0,0 +1,48 @@
+use vello::{
+    kurbo::{Affine, Point, Rect},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_rects_gpu() {
+    clip_rects(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_rects_cpu() {
+    clip_rects(true)
+}
+
+fn clip_rects(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("clip_rects", 64, 64)
+    };
+    // Two disjoint damaged regions, on pixel boundaries so that coverage is exact.
+    let rects = [Rect::new(4., 4., 20., 20.), Rect::new(40., 30., 60., 50.)];
+    let mut scene = Scene::new();
+    scene.push_clip_rects(Affine::IDENTITY, &rects);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+        let x = (i as u32 % params.width) as f64 + 0.5;
+        let y = (i as u32 / params.width) as f64 + 0.5;
+        let expected = if rects.iter().any(|rect| rect.contains(Point::new(x, y))) {
+            [255, 0, 0, 255]
+        } else {
+            [0, 0, 0, 255]
+        };
+        assert_eq!(pixel, expected, "pixel ({x}, {y})");
+    }
+}
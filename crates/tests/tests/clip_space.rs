@@ -0,0 +1,102 @@
+use vello::{
+    kurbo::{Affine, Point, Rect},
+    peniko::{Color, Fill, Mix},
+    ClipSpace, Scene,
+};
+use vello_tests::TestParams;
+
+const CENTER: (f64, f64) = (32., 32.);
+
+/// A square clip around the center of the target, with a fill covering the whole target
+/// inside of it.
+fn fragment(space: ClipSpace) -> Scene {
+    let mut scene = Scene::new();
+    let clip = Rect::new(22., 22., 42., 42.);
+    scene.push_layer_in(space, Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(-64., -64., 128., 128.),
+    );
+    scene.pop_layer();
+    scene
+}
+
+fn rotated(space: ClipSpace) -> Scene {
+    let mut scene = Scene::new();
+    let rotation = Affine::rotate_about(std::f64::consts::FRAC_PI_4, Point::from(CENTER));
+    scene.append(&fragment(space), Some(rotation));
+    scene
+}
+
+#[test]
+fn clip_space_transforms() {
+    let scene = rotated(ClipSpace::Target);
+    let encoding = scene.encoding();
+    let [clip, content] = [0, 1].map(|ix| encoding.transforms[ix].to_kurbo());
+    assert_eq!(clip, Affine::IDENTITY);
+    assert_ne!(content, Affine::IDENTITY);
+    assert_eq!(encoding.fixed_transforms, [0]);
+
+    // Nested appends keep the clip fixed too.
+    let mut outer = Scene::new();
+    outer.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::BLUE,
+        None,
+        &Rect::new(0., 0., 1., 1.),
+    );
+    outer.append(&scene, Some(Affine::translate((5., 0.))));
+    let encoding = outer.encoding();
+    assert_eq!(encoding.fixed_transforms, [1]);
+    assert_eq!(encoding.transforms[1].to_kurbo(), Affine::IDENTITY);
+
+    let scene = rotated(ClipSpace::Scene);
+    let encoding = scene.encoding();
+    assert!(encoding.fixed_transforms.is_empty());
+    // The clip and content share the rotated transform.
+    assert_eq!(encoding.transforms.len(), 1);
+    assert_eq!(encoding.transforms[0].to_kurbo(), content);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_space_gpu() {
+    clip_space(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_space_cpu() {
+    clip_space(true)
+}
+
+fn clip_space(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::BLACK,
+        ..TestParams::new("clip_space", 64, 64)
+    };
+    let render = |space| vello_tests::render_sync(rotated(space), &params).unwrap();
+    let pixel = |image: &vello::peniko::Image, x: u32, y: u32| {
+        let ix = ((y * params.width + x) * 4) as usize;
+        image.data.data()[ix..ix + 4].to_vec()
+    };
+    let red = [255, 0, 0, 255];
+    let black = [0, 0, 0, 255];
+    // Above the square, but inside of it once rotated into a diamond.
+    let (tip_x, tip_y) = (CENTER.0 as u32, CENTER.1 as u32 - 13);
+    // Near a corner of the square, which the diamond doesn't reach.
+    let (corner_x, corner_y) = (CENTER.0 as u32 + 8, CENTER.1 as u32 + 8);
+
+    let image = render(ClipSpace::Scene);
+    assert_eq!(pixel(&image, tip_x, tip_y), red);
+    assert_eq!(pixel(&image, corner_x, corner_y), black);
+
+    let image = render(ClipSpace::Target);
+    assert_eq!(pixel(&image, tip_x, tip_y), black);
+    assert_eq!(pixel(&image, corner_x, corner_y), red);
+}
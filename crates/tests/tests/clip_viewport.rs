@@ -0,0 +1,111 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Color, Fill, Mix},
+    AaSupport, BinSize, FrameStats, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_paths_to_viewport_gpu() {
+    clip_paths_to_viewport(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn clip_paths_to_viewport_cpu() {
+    clip_paths_to_viewport(true)
+}
+
+fn clip_paths_to_viewport(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("clip_paths_to_viewport", WIDTH, HEIGHT)
+    };
+    // A clip circle thousands of times larger than the target, whose top edge crosses the
+    // middle of it.
+    let radius = 50_000.;
+    let clip = Circle::new((WIDTH as f64 / 2., HEIGHT as f64 / 2. + radius), radius);
+    let mut scene = Scene::new();
+    scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(0., 0., WIDTH as f64, HEIGHT as f64),
+    );
+    scene.pop_layer();
+
+    let render = |device: &wgpu::Device,
+                  queue: &wgpu::Queue,
+                  renderer: &mut Renderer,
+                  clip_paths_to_viewport: bool|
+     -> anyhow::Result<(FrameStats, Vec<u8>)> {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Target texture"),
+            size: wgpu::Extent3d {
+                width: WIDTH,
+                height: HEIGHT,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let render_params = vello::RenderParams {
+            clip_paths_to_viewport,
+            ..params.render_params()
+        };
+        let stats = renderer
+            .render_to_texture_with_stats(device, queue, &scene, &view, &render_params)
+            .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+        let image = vello_tests::read_texture(device, queue, &texture, (0, 0), WIDTH, HEIGHT)?;
+        Ok((stats, image.data.data().to_vec()))
+    };
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let (clipped_stats, clipped) = render(device, queue, renderer, true)?;
+            let (full_stats, full) = render(device, queue, renderer, false)?;
+            // Only the half of the target below the edge of the circle is drawn.
+            let alpha = |data: &[u8], x: u32, y: u32| data[((y * WIDTH + x) * 4 + 3) as usize];
+            assert_eq!(alpha(&clipped, 32, 8), 0);
+            assert_eq!(alpha(&clipped, 32, 24), 255);
+            for (i, (a, b)) in clipped.iter().zip(&full).enumerate() {
+                assert!(a.abs_diff(*b) <= 1, "byte {i}: {a} and {b}");
+            }
+            // Most of the circle is far outside the target, and isn't flattened finely.
+            assert!(
+                clipped_stats.lines * 4 < full_stats.lines,
+                "{} lines with clipping, {} without",
+                clipped_stats.lines,
+                full_stats.lines
+            );
+            // The tiles within the target are the same either way.
+            assert_eq!(clipped_stats.solid_tiles, full_stats.solid_tiles);
+            assert_eq!(clipped_stats.coverage_tiles, full_stats.coverage_tiles);
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,66 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn grayscale_layer_gpu() {
+    grayscale_layer(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn grayscale_layer_cpu() {
+    grayscale_layer(true)
+}
+
+fn grayscale_layer(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("grayscale_layer", 64, 64)
+    };
+    const WEIGHTS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+    #[rustfmt::skip]
+    let grayscale = [
+        WEIGHTS[0], WEIGHTS[1], WEIGHTS[2], 0., 0.,
+        WEIGHTS[0], WEIGHTS[1], WEIGHTS[2], 0., 0.,
+        WEIGHTS[0], WEIGHTS[1], WEIGHTS[2], 0., 0.,
+        0., 0., 0., 1., 0.,
+    ];
+    let color = Color::rgb8(200, 100, 50);
+    let mut scene = Scene::new();
+    scene.push_color_matrix_layer(
+        grayscale,
+        Mix::Normal,
+        1.0,
+        Affine::IDENTITY,
+        &Rect::new(0., 0., 32., 64.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(color),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let ix = (y * params.width as usize + x) * 4;
+        &data[ix..ix + 4]
+    };
+    let luminance =
+        WEIGHTS[0] * color.r as f32 + WEIGHTS[1] * color.g as f32 + WEIGHTS[2] * color.b as f32;
+    for y in [0, 31, 63] {
+        let inside = pixel(16, y);
+        for channel in &inside[..3] {
+            assert!((*channel as f32 - luminance).abs() <= 1.5, "{inside:?}");
+        }
+        assert_eq!(inside[3], 255);
+        assert_eq!(pixel(48, y), [color.r, color.g, color.b, 255]);
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, BufferAllocator, ComplexityHint, RenderParams, Renderer, RendererOptions,
+    Scene,
+};
+use vello_tests::TestParams;
+use wgpu::{Buffer, BufferDescriptor, Device};
+
+const SIZE: u32 = 1024;
+const LAYERS: u32 = 600;
+/// Each full canvas rectangle covers every tile of the target.
+const TILES: u32 = (LAYERS + 1) * (SIZE / 16) * (SIZE / 16);
+
+fn heavy_scene() -> Scene {
+    let mut scene = Scene::new();
+    let rect = Rect::new(0., 0., SIZE as f64, SIZE as f64);
+    for i in 0..=LAYERS {
+        let color = Color::rgba8((i * 29 % 256) as u8, (i * 71 % 256) as u8, 128, 64);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(color),
+            None,
+            &rect,
+        );
+    }
+    scene
+}
+
+#[derive(Default)]
+struct CountingAllocator {
+    created: Arc<AtomicUsize>,
+}
+
+impl BufferAllocator for CountingAllocator {
+    fn create_buffer(&mut self, device: &Device, descriptor: &BufferDescriptor) -> Buffer {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        device.create_buffer(descriptor)
+    }
+}
+
+/// Renders the heavy scene with a fresh renderer, and returns the number of buffers created.
+fn count_buffers(params: &TestParams, complexity_hint: Option<ComplexityHint>) -> usize {
+    let allocator = CountingAllocator::default();
+    let created = allocator.created.clone();
+    let scene = heavy_scene();
+    let render_params = RenderParams {
+        auto_grow: true,
+        complexity_hint,
+        ..params.render_params()
+    };
+    pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: false,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::with_buffer_allocator(device, options, Box::new(allocator))
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    created.load(Ordering::Relaxed)
+}
+
+#[test]
+fn complexity_hint_memory() {
+    let params = TestParams::new("complexity_hint_memory", SIZE, SIZE).render_params();
+    let hinted = RenderParams {
+        complexity_hint: Some(ComplexityHint {
+            tiles: TILES,
+            segments: 0,
+        }),
+        ..params.clone()
+    };
+    let scene = heavy_scene();
+    let extra = scene.estimate_memory(&hinted) - scene.estimate_memory(&params);
+    // The tile buffer grows from its default of 2^21 tiles of 8 bytes each.
+    assert_eq!(extra, u64::from(TILES - (1 << 21)) * 8);
+    let underestimate = RenderParams {
+        complexity_hint: Some(ComplexityHint::default()),
+        ..params.clone()
+    };
+    assert_eq!(
+        scene.estimate_memory(&underestimate),
+        scene.estimate_memory(&params)
+    );
+}
+
+// The CPU stages don't detect overflow, so there is no CPU variant.
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn complexity_hint_gpu() {
+    // The scene needs more tiles than the initial allocation of 2^21.
+    let params = TestParams::new("complexity_hint", SIZE, SIZE);
+    let grown = count_buffers(&params, None);
+    let hinted = count_buffers(
+        &params,
+        Some(ComplexityHint {
+            tiles: TILES,
+            segments: 0,
+        }),
+    );
+    // Without the hint, the rerun of the coarse stages allocates a larger set of buffers.
+    assert!(
+        hinted < grown,
+        "{hinted} buffers with the hint, {grown} without"
+    );
+}
@@ -0,0 +1,155 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn composite_multiply_gpu() {
+    composite_multiply(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn composite_multiply_cpu() {
+    composite_multiply(true)
+}
+
+fn composite_multiply(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("composite_multiply", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(128, 255, 64)),
+        None,
+        &Rect::new(0., 0., params.width as f64, params.height as f64),
+    );
+    let render_params = RenderParams {
+        base_color: Color::TRANSPARENT,
+        ..params.render_params()
+    };
+    let image = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Fill with blue"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLUE),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            queue.submit([encoder.finish()]);
+            renderer
+                .render_to_texture_composited(
+                    device,
+                    queue,
+                    &scene,
+                    target,
+                    &render_params,
+                    Mix::Multiply,
+                )
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    // Multiplying with pure blue keeps only the blue channel of the source.
+    for pixel in image.data.data().chunks_exact(4) {
+        let &[r, g, b, a] = pixel else { unreachable!() };
+        assert_eq!((r, g, a), (0, 0, 255), "{pixel:?}");
+        assert!(b.abs_diff(64) <= 1, "{pixel:?}");
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn composite_repeated_gpu() {
+    composite_repeated(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn composite_repeated_cpu() {
+    composite_repeated(true)
+}
+
+fn composite_repeated(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("composite_repeated", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(128, 128, 128)),
+        None,
+        &Rect::new(0., 0., params.width as f64, params.height as f64),
+    );
+    let render_params = RenderParams {
+        base_color: Color::TRANSPARENT,
+        ..params.render_params()
+    };
+    let image = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Fill with white"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            queue.submit([encoder.finish()]);
+            for _ in 0..2 {
+                renderer
+                    .render_to_texture_composited(
+                        device,
+                        queue,
+                        &scene,
+                        target,
+                        &render_params,
+                        Mix::Multiply,
+                    )
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+    // The second composite multiplies with the result of the first, rather than with a
+    // stale copy of the target.
+    for pixel in image.data.data().chunks_exact(4) {
+        let &[r, g, b, a] = pixel else { unreachable!() };
+        assert_eq!(a, 255, "{pixel:?}");
+        for channel in [r, g, b] {
+            assert!(channel.abs_diff(64) <= 1, "{pixel:?}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Line, Point, Stroke},
+    peniko::Color,
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn conservative_coverage_gpu() {
+    conservative_coverage(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn conservative_coverage_cpu() {
+    conservative_coverage(true)
+}
+
+fn render_mask(scene: &Scene, params: &TestParams, conservative_coverage: bool) -> Vec<u8> {
+    let render_params = RenderParams {
+        conservative_coverage,
+        ..params.render_params()
+    };
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = vello::RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: vello::AaSupport::area_only(),
+                bin_size: vello::BinSize::default(),
+                max_clip_depth: None,
+            };
+            vello::Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Hit mask"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            renderer
+                .render_coverage_mask(device, queue, scene, &texture, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            vello_tests::read_mask(device, queue, &texture, params.width, params.height)
+        },
+    ))
+    .unwrap()
+}
+
+fn conservative_coverage(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("conservative_coverage", 64, 48)
+    };
+    let line = Line::new((3.3, 2.7), (58.6, 41.2));
+    let mut scene = Scene::new();
+    scene.stroke(
+        &Stroke::new(0.2),
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &line,
+    );
+    let antialiased = render_mask(&scene, &params, false);
+    let mask = render_mask(&scene, &params, true);
+    let width = params.width as usize;
+
+    // A line this thin only partially covers the pixels it crosses.
+    assert!(antialiased.iter().all(|&c| c < 255));
+    assert!(mask.iter().all(|&c| c == 0 || c == 255));
+    // Every pixel the center of the line passes through is marked.
+    for i in 0..=10_000 {
+        let p = line.p0.lerp(line.p1, i as f64 / 10_000.);
+        let ix = p.y as usize * width + p.x as usize;
+        assert_eq!(
+            mask[ix], 255,
+            "pixel at ({}, {})",
+            p.x as usize, p.y as usize
+        );
+    }
+    // Pixels the line doesn't come near are left clear.
+    let direction = (line.p1 - line.p0).normalize();
+    for (ix, &c) in mask.iter().enumerate() {
+        let center = Point::new((ix % width) as f64 + 0.5, (ix / width) as f64 + 0.5);
+        let offset = center - line.p0;
+        let t = offset.dot(direction);
+        let distance = (offset - direction * t).hypot();
+        let beyond_ends = t < -1.0 || t > (line.p1 - line.p0).hypot() + 1.0;
+        if distance > 1.0 || beyond_ends {
+            assert_eq!(c, 0, "pixel {ix} is {distance} from the line");
+        }
+        if antialiased[ix] > 0 {
+            assert_eq!(c, 255, "pixel {ix} is partially covered");
+        }
+    }
+}
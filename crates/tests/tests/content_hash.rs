@@ -0,0 +1,39 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect, Stroke},
+    peniko::{Brush, Color, Fill, Gradient},
+    Scene,
+};
+
+fn build(color: Color, stop: Color) -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(color),
+        None,
+        &Rect::new(8., 8., 56., 56.),
+    );
+    let gradient = Gradient::new_linear((0., 0.), (64., 0.)).with_stops([Color::RED, stop]);
+    scene.stroke(
+        &Stroke::new(2.),
+        Affine::translate((4., 4.)),
+        &gradient,
+        None,
+        &Circle::new((32., 32.), 20.),
+    );
+    scene
+}
+
+#[test]
+fn content_hash() {
+    let a = build(Color::GREEN, Color::BLUE);
+    let b = build(Color::GREEN, Color::BLUE);
+    assert_eq!(a.content_hash(), b.content_hash());
+    assert_eq!(a.content_hash(), a.clone().content_hash());
+    let changed = build(Color::rgb8(0, 128, 1), Color::BLUE);
+    assert_ne!(a.content_hash(), changed.content_hash());
+    // Gradient stops live outside the draw data stream and must contribute too.
+    let restopped = build(Color::GREEN, Color::YELLOW);
+    assert_ne!(a.content_hash(), restopped.content_hash());
+    assert_ne!(a.content_hash(), Scene::new().content_hash());
+}
@@ -0,0 +1,75 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn coverage_mask_gpu() {
+    coverage_mask(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn coverage_mask_cpu() {
+    coverage_mask(true)
+}
+
+fn coverage_mask(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("coverage_mask", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Circle::new((32., 32.), 20.),
+    );
+    let mask = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = vello::RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: vello::AaSupport::area_only(),
+                bin_size: vello::BinSize::default(),
+                max_clip_depth: None,
+            };
+            vello::Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Coverage mask"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            renderer
+                .render_coverage_mask(device, queue, &scene, &texture, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            vello_tests::read_mask(device, queue, &texture, params.width, params.height)
+        },
+    ))
+    .unwrap();
+    let coverage = |x: usize, y: usize| mask[y * params.width as usize + x];
+    assert_eq!(coverage(32, 32), 255);
+    assert_eq!(coverage(2, 2), 0);
+    assert_eq!(coverage(61, 32), 0);
+    // The circle's edge crosses row 43 at x = 15.3, part way through a pixel.
+    let edge = (8..16).map(|x| coverage(x, 43)).collect::<Vec<_>>();
+    assert!(edge.iter().any(|&c| c > 0 && c < 255), "{edge:?}");
+}
@@ -0,0 +1,118 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, CubeFace, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+const SIZE: u32 = 32;
+
+fn solid_scene(color: Color) -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(color),
+        None,
+        &Rect::new(0., 0., SIZE as f64, SIZE as f64 / 2.),
+    );
+    scene
+}
+
+fn create_texture(device: &wgpu::Device, layers: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Cube texture"),
+        size: wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: layers,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn cubemap_face_gpu() {
+    cubemap_face(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn cubemap_face_cpu() {
+    cubemap_face(true)
+}
+
+fn cubemap_face(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("cubemap_face", SIZE, SIZE)
+    };
+    let render_params = params.render_params();
+    let faces = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            // A plain 2D texture is rejected.
+            let flat = create_texture(device, 1);
+            let scene = solid_scene(Color::RED);
+            assert!(renderer
+                .render_to_cubemap_face(
+                    device,
+                    queue,
+                    &flat,
+                    CubeFace::PositiveX,
+                    &scene,
+                    &render_params
+                )
+                .is_err());
+
+            let cube = create_texture(device, 6);
+            for (face, color) in [
+                (CubeFace::PositiveX, Color::RED),
+                (CubeFace::NegativeX, Color::BLUE),
+            ] {
+                renderer
+                    .render_to_cubemap_face(
+                        device,
+                        queue,
+                        &cube,
+                        face,
+                        &solid_scene(color),
+                        &render_params,
+                    )
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            }
+            [CubeFace::PositiveX, CubeFace::NegativeX]
+                .map(|face| vello_tests::read_texture_layer(device, queue, &cube, face as u32))
+                .into_iter()
+                .collect::<anyhow::Result<Vec<_>>>()
+        },
+    ))
+    .unwrap();
+    for (image, expected) in faces.iter().zip([[255, 0, 0, 255], [0, 0, 255, 255]]) {
+        let data = image.data.data();
+        // The top half of each face holds its own shape, and the bottom half the base color.
+        let top = (4 * SIZE as usize + 16) * 4;
+        let bottom = (28 * SIZE as usize + 16) * 4;
+        assert_eq!(data[top..top + 4], expected);
+        assert_eq!(data[bottom..bottom + 4], [0, 0, 0, 255]);
+    }
+}
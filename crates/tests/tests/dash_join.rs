@@ -0,0 +1,55 @@
+use vello::{
+    kurbo::{BezPath, Cap, Join, Stroke},
+    peniko::{Brush, Color},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dash_join_gpu() {
+    dash_join(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dash_join_cpu() {
+    dash_join(true)
+}
+
+fn dash_join(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("dash_join", 64, 64)
+    };
+    let mut corner = BezPath::new();
+    corner.move_to((8., 16.));
+    corner.line_to((40., 16.));
+    corner.line_to((40., 56.));
+    // The first dash covers the corner, ending 8 units past it, and the next one is off the
+    // end of the path.
+    let style = Stroke::new(8.)
+        .with_join(Join::Miter)
+        .with_caps(Cap::Butt)
+        .with_dashes(0., [40., 100.]);
+    let mut scene = Scene::new();
+    scene.stroke(
+        &style,
+        Default::default(),
+        &Brush::Solid(Color::WHITE),
+        None,
+        &corner,
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let pixel = |x: usize, y: usize| {
+        let index = (y * params.width as usize + x) * 4;
+        image.data.data()[index]
+    };
+    // Outside of both butt-capped lines, this pixel is only covered by the miter join.
+    assert_eq!(pixel(42, 13), 255);
+    // Both lines are drawn up to the end of the dash.
+    assert_eq!(pixel(20, 16), 255);
+    assert_eq!(pixel(40, 22), 255);
+    // The dash ends at y = 24.
+    assert_eq!(pixel(40, 28), 0);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "data_url")]
+
+use anyhow::bail;
+use base64::Engine;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn data_url_gpu() {
+    data_url(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn data_url_cpu() {
+    data_url(true)
+}
+
+fn data_url(use_cpu: bool) {
+    const PREFIX: &str = "data:image/png;base64,";
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("data_url", 40, 24)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 40., 24.),
+    );
+    let url = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            renderer
+                .render_to_data_url(device, queue, &scene, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    assert!(url.starts_with(PREFIX), "{url}");
+    let png_data = base64::engine::general_purpose::STANDARD
+        .decode(&url[PREFIX.len()..])
+        .unwrap();
+    let image = vello_tests::decode_image(&png_data).unwrap();
+    assert_eq!((image.width, image.height), (40, 24));
+    assert_eq!(&image.data.data()[..4], &[255, 0, 0, 255]);
+}
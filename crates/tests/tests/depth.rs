@@ -0,0 +1,125 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Brush, Color, Fill, Gradient, Mix},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+fn fill_rect(scene: &mut Scene, rect: Rect) {
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &rect,
+    );
+}
+
+#[test]
+fn depth_follows_content() {
+    let mut scene = Scene::new();
+    fill_rect(&mut scene, Rect::new(0., 0., 1., 1.));
+    scene.set_depth(0.5);
+    fill_rect(&mut scene, Rect::new(0., 0., 1., 1.));
+    // Content moved to another z index keeps the current depth.
+    scene.set_z_index(1);
+    fill_rect(&mut scene, Rect::new(0., 0., 1., 1.));
+    scene.set_z_index(0);
+    assert_eq!(scene.depth(), 0.5);
+    // An appended scene keeps its own depths, and doesn't change the depth of what follows.
+    let mut fragment = Scene::new();
+    fragment.set_depth(0.25);
+    fill_rect(&mut fragment, Rect::new(0., 0., 1., 1.));
+    scene.append(&fragment, None);
+    fill_rect(&mut scene, Rect::new(0., 0., 1., 1.));
+    assert_eq!(
+        scene.encoding().draw_depths,
+        [(1, 0.5), (2, 0.25), (3, 0.5), (4, 0.5)]
+    );
+    scene.reset();
+    assert_eq!(scene.depth(), 0.0);
+    assert!(scene.encoding().draw_depths.is_empty());
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn depth_buffer_gpu() {
+    depth_buffer(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn depth_buffer_cpu() {
+    depth_buffer(true)
+}
+
+fn depth_buffer(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("depth_buffer", 64, 32)
+    };
+    let mut scene = Scene::new();
+    scene.set_depth(0.2);
+    fill_rect(&mut scene, Rect::new(0., 0., 40., 32.));
+    // The nearer shape is translucent and drawn with a gradient inside a blended layer, none
+    // of which affects its depth.
+    scene.set_depth(0.8);
+    scene.push_layer(
+        Mix::Multiply,
+        0.5,
+        Affine::IDENTITY,
+        &Rect::new(0., 0., 64., 32.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Gradient::new_linear((24., 0.), (56., 0.))
+            .with_stops([Color::rgba8(255, 0, 0, 64), Color::BLUE]),
+        None,
+        &Circle::new((40., 16.), 14.),
+    );
+    scene.pop_layer();
+    let depth = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Depth buffer"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            renderer
+                .render_depth(device, queue, &scene, &texture, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            vello_tests::read_mask(device, queue, &texture, params.width, params.height)
+        },
+    ))
+    .unwrap();
+    let at = |x: usize, y: usize| depth[y * params.width as usize + x];
+    // Only the farther rectangle.
+    assert_eq!(at(8, 16), 51);
+    // The circle covers the rectangle, and extends past it.
+    assert_eq!(at(36, 16), 204);
+    assert_eq!(at(48, 16), 204);
+    // Nothing is drawn in the corner.
+    assert_eq!(at(62, 1), 0);
+}
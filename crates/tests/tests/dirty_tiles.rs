@@ -0,0 +1,66 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+const TILE_SIZE: u32 = 16;
+
+fn filled(color: Color, width: u32, height: u32) -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(color),
+        None,
+        &Rect::new(0., 0., width as f64, height as f64),
+    );
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dirty_tiles_gpu() {
+    dirty_tiles(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dirty_tiles_cpu() {
+    dirty_tiles(true)
+}
+
+fn dirty_tiles(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("dirty_tiles", 64, 64)
+    };
+    let width_in_tiles = params.width / TILE_SIZE;
+    // Two tiles which don't share an edge or a corner.
+    let dirty = [(0, 0), (2, 1)];
+    let mut mask = vec![0_u32; 1];
+    for (x, y) in dirty {
+        let ix = y * width_in_tiles + x;
+        mask[(ix / 32) as usize] |= 1 << (ix % 32);
+    }
+    let first = filled(Color::RED, params.width, params.height);
+    let second = filled(Color::BLUE, params.width, params.height);
+    let partial = RenderParams {
+        dirty_tiles: Some(mask),
+        ..params.render_params()
+    };
+    let frames = vec![(first, params.render_params()), (second, partial)];
+    let image = vello_tests::render_frames_sync(frames, &params).unwrap();
+    for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+        let x = i as u32 % params.width;
+        let y = i as u32 / params.width;
+        let tile = (x / TILE_SIZE, y / TILE_SIZE);
+        let expected = if dirty.contains(&tile) {
+            [0, 0, 255, 255]
+        } else {
+            [255, 0, 0, 255]
+        };
+        assert_eq!(pixel, expected, "pixel ({x}, {y}) in tile {tile:?}");
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Gradient},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dither_gradient_gpu() {
+    dither_gradient(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn dither_gradient_cpu() {
+    dither_gradient(true)
+}
+
+fn dither_gradient(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("dither_gradient", 64, 64)
+    };
+    let (width, height) = (params.width as usize, params.height as usize);
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Gradient::new_linear((0., 0.), (width as f64, 0.))
+            .with_stops([Color::BLACK, Color::WHITE]),
+        None,
+        &Rect::new(0., 0., width as f64, height as f64),
+    );
+    let output = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Dithered target"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            renderer
+                .render_to_texture_dithered(
+                    device,
+                    queue,
+                    &scene,
+                    &texture,
+                    &params.render_params(),
+                )
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            vello_tests::read_mask(device, queue, &texture, params.width, params.height)
+        },
+    ))
+    .unwrap();
+    assert!(output.iter().all(|&v| v == 0 || v == 255));
+    // Each band spans whole cells of the dither matrix, so the fraction of white pixels in it
+    // should match its average brightness.
+    const BAND: usize = 8;
+    for band in 0..width / BAND {
+        let columns = band * BAND..(band + 1) * BAND;
+        let white = (0..height)
+            .flat_map(|y| columns.clone().map(move |x| (x, y)))
+            .filter(|&(x, y)| output[y * width + x] == 255)
+            .count();
+        let density = white as f32 / (BAND * height) as f32;
+        let brightness = (band as f32 + 0.5) * BAND as f32 / width as f32;
+        assert!(
+            (density - brightness).abs() <= 0.1,
+            "band {band}: density {density}, brightness {brightness}"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use vello::{
+    kurbo::{Affine, Circle, Line, Rect, Stroke},
+    peniko::{Color, Fill, Mix},
+    Scene,
+};
+
+fn assert_rect_eq(actual: Rect, expected: Rect) {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-3;
+    assert!(
+        close(actual.x0, expected.x0)
+            && close(actual.y0, expected.y0)
+            && close(actual.x1, expected.x1)
+            && close(actual.y1, expected.y1),
+        "{actual:?} isn't {expected:?}"
+    );
+}
+
+#[test]
+fn draw_bounds_of_positioned_shapes() {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::translate((10., 20.)),
+        Color::RED,
+        None,
+        &Rect::new(0., 0., 30., 40.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::translate((100., 50.)) * Affine::scale(2.),
+        Color::GREEN,
+        None,
+        &Circle::new((0., 0.), 5.),
+    );
+    scene.stroke(
+        &Stroke::new(4.),
+        Affine::IDENTITY,
+        Color::BLUE,
+        None,
+        &Line::new((60., 100.), (90., 100.)),
+    );
+    let bounds = scene.draw_bounds();
+    let indices: Vec<_> = bounds.iter().map(|(ix, _)| *ix).collect();
+    assert_eq!(indices, [0, 1, 2]);
+    assert_rect_eq(bounds[0].1, Rect::new(10., 20., 40., 60.));
+    assert_rect_eq(bounds[1].1, Rect::new(90., 40., 110., 60.));
+    // Strokes extend by half their width on each side.
+    assert_rect_eq(bounds[2].1, Rect::new(58., 98., 92., 102.));
+}
+
+#[test]
+fn draw_bounds_of_layers_and_rect_clips() {
+    let mut scene = Scene::new();
+    let square = Rect::new(0., 0., 50., 50.);
+    scene.push_layer(Mix::Clip, 1.0, Affine::translate((5., 5.)), &square);
+    scene.push_rect_clip(Affine::IDENTITY, &Rect::new(20., 0., 30., 100.));
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::RED, None, &square);
+    scene.pop_rect_clip();
+    scene.pop_layer();
+    // The end of the layer has no bounds of its own.
+    let bounds = scene.draw_bounds();
+    assert_eq!(bounds.len(), 2);
+    assert_eq!(bounds[0].0, 0);
+    assert_rect_eq(bounds[0].1, Rect::new(5., 5., 55., 55.));
+    assert_eq!(bounds[1].0, 1);
+    assert_rect_eq(bounds[1].1, Rect::new(20., 0., 30., 50.));
+    assert_eq!(scene.stats().draw_tags, 3);
+}
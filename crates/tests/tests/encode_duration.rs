@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+fn trivial_scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(8., 8., 24., 24.),
+    );
+    scene
+}
+
+fn complex_scene() -> Scene {
+    let mut scene = Scene::new();
+    for i in 0..50_000 {
+        let (x, y) = ((i % 256) as f64, (i / 256 % 256) as f64);
+        let color = Color::rgb8(i as u8, (i >> 8) as u8, 128);
+        let rect = Rect::new(x, y, x + 1.5, y + 1.5);
+        scene.fill(Fill::NonZero, Affine::rotate(i as f64), color, None, &rect);
+    }
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn last_encode_duration_gpu() {
+    last_encode_duration(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn last_encode_duration_cpu() {
+    last_encode_duration(true)
+}
+
+fn last_encode_duration(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("last_encode_duration", 256, 256)
+    };
+    let render_params = params.render_params();
+    let scenes = [trivial_scene(), complex_scene()];
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            assert_eq!(renderer.last_encode_duration(), None);
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Target texture"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            // The fastest of a few renders of each scene, to keep scheduling noise out of it.
+            let mut durations = [Duration::MAX; 2];
+            for _ in 0..3 {
+                for (scene, duration) in scenes.iter().zip(&mut durations) {
+                    renderer
+                        .render_to_texture(device, queue, scene, &view, &render_params)
+                        .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                    device.poll(wgpu::Maintain::Wait);
+                    let encode = renderer.last_encode_duration().unwrap();
+                    *duration = (*duration).min(encode);
+                }
+            }
+            let [trivial, complex] = durations;
+            assert!(complex > Duration::ZERO);
+            assert!(
+                complex > trivial,
+                "complex scene took {complex:?}, trivial scene {trivial:?}"
+            );
+            // Encoding a scene takes a fraction of a second, not minutes.
+            assert!(complex < Duration::from_secs(10), "{complex:?}");
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,104 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn export_layers_gpu() {
+    export_layers(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn export_layers_cpu() {
+    export_layers(true)
+}
+
+fn export_layers(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::WHITE,
+        ..TestParams::new("export_layers", 48, 16)
+    };
+    // Three objects side by side, each in a scene of its own.
+    let mut background = Scene::new();
+    background.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(0., 0., 16., 16.),
+    );
+    let mut avatar = Scene::new();
+    avatar.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::LIME,
+        None,
+        &Circle::new((24., 8.), 6.),
+    );
+    let mut badge = Scene::new();
+    badge.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::BLUE,
+        None,
+        &Rect::new(34., 2., 46., 14.),
+    );
+    let layers = [
+        ("background", &background),
+        ("avatar", &avatar),
+        ("badge", &badge),
+    ];
+    let render_params = params.render_params();
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let exported = renderer
+                .render_layers_for_export(device, queue, &layers, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            let names: Vec<_> = exported.iter().map(|layer| layer.name.as_str()).collect();
+            assert_eq!(names, ["background", "avatar", "badge"]);
+            let expected = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+            for (i, layer) in exported.iter().enumerate() {
+                let image = vello_tests::read_texture(
+                    device,
+                    queue,
+                    &layer.texture,
+                    (0, 0),
+                    params.width,
+                    params.height,
+                )?;
+                let pixel = |x: usize| {
+                    let ix = (8 * params.width as usize + x) * 4;
+                    &image.data.data()[ix..ix + 4]
+                };
+                // Each texture shows its own object, and is transparent rather than the base
+                // color where the other objects are.
+                for (j, x) in [8, 24, 40].into_iter().enumerate() {
+                    if i == j {
+                        assert_eq!(pixel(x), expected[i], "{} at {x}", layer.name);
+                    } else {
+                        assert_eq!(pixel(x), [0; 4], "{} at {x}", layer.name);
+                    }
+                }
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,111 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn feathered_clip_gpu() {
+    feathered_clip(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn feathered_clip_cpu() {
+    feathered_clip(true)
+}
+
+fn feathered_clip(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("feathered_clip", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.push_feathered_layer(
+        Mix::Normal,
+        1.0,
+        Affine::IDENTITY,
+        &Rect::new(16., 16., 48., 48.),
+        8.,
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    // Coverage along the middle row, which crosses the left edge at x = 16.
+    let row = 32;
+    let coverage = |x: usize| data[(row * params.width as usize + x) * 4];
+    for x in 0..12 {
+        assert_eq!(coverage(x), 0, "x = {x}");
+    }
+    for x in 12..20 {
+        assert!(coverage(x) < coverage(x + 1), "x = {x}");
+    }
+    for x in 20..32 {
+        assert_eq!(coverage(x), 255, "x = {x}");
+    }
+    // Halfway through the ramp at the edge of the rectangle.
+    assert!(coverage(16).abs_diff(143) <= 2);
+}
+
+fn feathered_square(transform: Affine, feather: f64) -> Scene {
+    let mut scene = Scene::new();
+    scene.push_feathered_layer(
+        Mix::Normal,
+        1.0,
+        transform,
+        &Rect::new(8., 8., 24., 24.),
+        feather,
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn appended_feathered_clip_gpu() {
+    appended_feathered_clip(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn appended_feathered_clip_cpu() {
+    appended_feathered_clip(true)
+}
+
+fn appended_feathered_clip(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("appended_feathered_clip", 64, 64)
+    };
+    let transform = Affine::translate((4., 6.)) * Affine::scale(2.);
+    let mut appended = Scene::new();
+    appended.append(&feathered_square(Affine::IDENTITY, 4.), Some(transform));
+    // The feather is in device space, so it's twice as wide when drawn directly.
+    let direct = feathered_square(transform, 8.);
+    let appended = vello_tests::render_sync(appended, &params).unwrap();
+    let direct = vello_tests::render_sync(direct, &params).unwrap();
+    let (appended, direct) = (appended.data.data(), direct.data.data());
+    for (ix, (a, d)) in appended.iter().zip(direct).enumerate() {
+        let (x, y) = (ix / 4 % 64, ix / 4 / 64);
+        assert!(a.abs_diff(*d) <= 1, "pixel ({x}, {y}): {a} isn't {d}");
+    }
+    // The square covers (20, 22) to (52, 54), so its center is covered and the corner isn't.
+    assert_eq!(direct[(38 * 64 + 36) * 4], 255);
+    assert_eq!(direct[(4 * 64 + 4) * 4], 0);
+}
@@ -0,0 +1,197 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+/// Side of each square of the checkerboard, in pixels.
+const CELL: u32 = 2;
+
+fn checkerboard(width: u32, height: u32) -> Scene {
+    let mut scene = Scene::new();
+    for y in (0..height).step_by(CELL as usize) {
+        for x in (0..width).step_by(CELL as usize) {
+            if (x / CELL + y / CELL) & 1 == 0 {
+                let (x, y) = (x as f64, y as f64);
+                let cell = CELL as f64;
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    Color::WHITE,
+                    None,
+                    &Rect::new(x, y, x + cell, y + cell),
+                );
+            }
+        }
+    }
+    scene
+}
+
+/// Renders the checkerboard with the given blur, and returns the mean absolute difference
+/// between the red channel of horizontally adjacent pixels, along with the mean red value.
+fn contrast(use_cpu: bool, final_blur: Option<f32>) -> (f64, f64) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("final_blur", 64, 64)
+    };
+    let render_params = RenderParams {
+        final_blur,
+        ..params.render_params()
+    };
+    let scene = checkerboard(params.width, params.height);
+    let image = vello_tests::render_frames_sync(vec![(scene, render_params)], &params).unwrap();
+    let width = params.width as usize;
+    let red = |x: usize, y: usize| f64::from(image.data.data()[(y * width + x) * 4]);
+    // Stay away from the edges, where the blur repeats the border pixels.
+    let interior = 8..width - 8;
+    let (mut difference, mut total, mut count) = (0., 0., 0.);
+    for y in interior.clone() {
+        for x in interior.clone() {
+            difference += (red(x + 1, y) - red(x, y)).abs();
+            total += red(x, y);
+            count += 1.;
+        }
+    }
+    (difference / count, total / count)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn final_blur_gpu() {
+    final_blur(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn final_blur_cpu() {
+    final_blur(true)
+}
+
+fn final_blur(use_cpu: bool) {
+    let (sharp, _) = contrast(use_cpu, None);
+    // Every other pair of neighbours straddles a cell edge.
+    assert!((sharp - 127.5).abs() < 1., "sharp contrast {sharp}");
+    assert_eq!(contrast(use_cpu, Some(0.)).0, sharp);
+    let mut previous = sharp;
+    for std_dev in [0.5, 1., 2.] {
+        let (blurred, mean) = contrast(use_cpu, Some(std_dev));
+        assert!(
+            blurred < previous,
+            "contrast {blurred} at {std_dev} isn't below {previous}"
+        );
+        // Blurring spreads the light around without changing how much of it there is.
+        assert!((mean - 127.5).abs() < 4., "mean {mean} at {std_dev}");
+        previous = blurred;
+    }
+    assert!(
+        previous < sharp / 10.,
+        "contrast {previous} left after blurring"
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn tiled_final_blur_gpu() {
+    tiled_final_blur(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn tiled_final_blur_cpu() {
+    tiled_final_blur(true)
+}
+
+fn tiled_final_blur(use_cpu: bool) {
+    const WIDTH: u32 = 80;
+    const HEIGHT: u32 = 48;
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("tiled_final_blur", WIDTH, HEIGHT)
+    };
+    let render_params = RenderParams {
+        final_blur: Some(2.),
+        ..params.render_params()
+    };
+    let scene = checkerboard(WIDTH, HEIGHT);
+    let mut tiled = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    let single = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let tiles = renderer
+                .render_to_textures_tiled(device, queue, &scene, &render_params, Some(32))
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            assert_eq!(tiles.len(), 6);
+            for tile in &tiles {
+                let image = vello_tests::read_texture(
+                    device,
+                    queue,
+                    &tile.texture,
+                    (0, 0),
+                    tile.width,
+                    tile.height,
+                )?;
+                for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+                    let x = tile.x + i as u32 % tile.width;
+                    let y = tile.y + i as u32 / tile.width;
+                    let ix = ((y * WIDTH + x) * 4) as usize;
+                    tiled[ix..ix + 4].copy_from_slice(pixel);
+                }
+            }
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            Ok(())
+        },
+    ))
+    .unwrap();
+    // The blur crosses the edges between the tiles without leaving seams.
+    let single = single.data.data();
+    for (i, (tiled, single)) in tiled
+        .chunks_exact(4)
+        .zip(single.chunks_exact(4))
+        .enumerate()
+    {
+        let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+        assert_eq!(tiled, single, "canvas pixel ({x}, {y})");
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn streamed_final_blur_gpu() {
+    streamed_final_blur(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn streamed_final_blur_cpu() {
+    streamed_final_blur(true)
+}
+
+fn streamed_final_blur(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("streamed_final_blur", 64, 64)
+    };
+    let render_params = RenderParams {
+        final_blur: Some(2.),
+        ..params.render_params()
+    };
+    pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let mut stream =
+                renderer.stream_to_texture(device, queue, target, &render_params, 1 << 20);
+            stream
+                .draw(|scene| scene.append(&checkerboard(64, 64), None))
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            // Each chunk would be blurred again by the chunks composited over it.
+            assert!(stream.finish().is_err());
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
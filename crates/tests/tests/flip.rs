@@ -0,0 +1,84 @@
+use vello::{
+    kurbo::{Affine, Point, Rect},
+    peniko::{Brush, Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+fn flip_transform() {
+    let params = TestParams::new("flip_transform", 64, 32).render_params();
+    assert!(params.flip_transform().is_none());
+    let flip_x = RenderParams {
+        flip_x: true,
+        ..params.clone()
+    };
+    let flip_xy = RenderParams {
+        flip_y: true,
+        ..flip_x.clone()
+    };
+    let point = Point::new(10., 4.);
+    assert_eq!(
+        flip_x.flip_transform().unwrap() * point,
+        Point::new(54., 4.)
+    );
+    assert_eq!(
+        flip_xy.flip_transform().unwrap() * point,
+        Point::new(54., 28.)
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn flip_gpu() {
+    flip(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn flip_cpu() {
+    flip(true)
+}
+
+fn flip(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("flip", 64, 32)
+    };
+    let mut scene = Scene::new();
+    // A square in the top left corner.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 16., 16.),
+    );
+    let render = |flip_x, flip_y| {
+        let render_params = RenderParams {
+            flip_x,
+            flip_y,
+            ..params.render_params()
+        };
+        vello_tests::render_frames_sync(vec![(scene.clone(), render_params)], &params).unwrap()
+    };
+    let pixel = |image: &vello::peniko::Image, x: usize, y: usize| {
+        let ix = (y * params.width as usize + x) * 4;
+        image.data.data()[ix..ix + 4].to_vec()
+    };
+    let red = vec![255, 0, 0, 255];
+    let black = vec![0, 0, 0, 255];
+
+    let image = render(false, false);
+    assert_eq!(pixel(&image, 8, 8), red);
+    assert_eq!(pixel(&image, 56, 8), black);
+
+    let image = render(true, false);
+    assert_eq!(pixel(&image, 8, 8), black);
+    assert_eq!(pixel(&image, 56, 8), red);
+    assert_eq!(pixel(&image, 56, 24), black);
+
+    let image = render(true, true);
+    assert_eq!(pixel(&image, 56, 8), black);
+    assert_eq!(pixel(&image, 56, 24), red);
+}
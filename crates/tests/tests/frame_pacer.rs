@@ -0,0 +1,31 @@
+use std::time::{Duration, Instant};
+
+use vello::util::FramePacer;
+
+#[test]
+fn frame_pacer_spaces_frames() {
+    const FRAMES: u32 = 10;
+    let target = Duration::from_millis(10);
+    let mut pacer = FramePacer::new(target);
+    let mut presents = vec![];
+    for _ in 0..FRAMES {
+        pacer.wait();
+        presents.push(Instant::now());
+    }
+    for pair in presents.windows(2) {
+        // Allow a little slack, as a frame's deadline is set from when the previous one was due.
+        assert!(pair[1] - pair[0] >= target - Duration::from_millis(2));
+    }
+    let total = *presents.last().unwrap() - presents[0];
+    assert!(total >= target * (FRAMES - 1));
+}
+
+#[test]
+fn frame_pacer_disabled_by_default() {
+    let mut pacer = FramePacer::default();
+    let start = Instant::now();
+    for _ in 0..100 {
+        pacer.wait();
+    }
+    assert!(start.elapsed() < Duration::from_millis(100));
+}
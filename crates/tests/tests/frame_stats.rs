@@ -0,0 +1,98 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, BezPath, Rect, Stroke},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, FrameStats, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+fn render_stats(scene: &Scene, params: &TestParams) -> FrameStats {
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Target texture"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture_with_stats(device, queue, scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn frame_stats_gpu() {
+    frame_stats(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn frame_stats_cpu() {
+    frame_stats(true)
+}
+
+fn frame_stats(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("frame_stats", 256, 256)
+    };
+
+    // Only the 60 tiles around the edge of the target are crossed by the rectangle.
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(0.5, 0.5, 255.5, 255.5),
+    );
+    let stats = render_stats(&scene, &params);
+    assert_eq!(stats.solid_tiles + stats.coverage_tiles, 256, "{stats:?}");
+    assert!(stats.solid_tiles > 3 * stats.coverage_tiles, "{stats:?}");
+
+    // Thin wavy strokes cross every tile they touch.
+    let mut scene = Scene::new();
+    for row in 0..32 {
+        let y = row as f64 * 8. + 4.;
+        let mut path = BezPath::new();
+        path.move_to((0., y));
+        for i in 0..16 {
+            let x = i as f64 * 16.;
+            path.curve_to((x + 4., y - 3.), (x + 12., y + 3.), (x + 16., y));
+        }
+        scene.stroke(
+            &Stroke::new(1.),
+            Affine::IDENTITY,
+            Color::WHITE,
+            None,
+            &path,
+        );
+    }
+    let stats = render_stats(&scene, &params);
+    assert!(stats.coverage_tiles >= 32 * 16, "{stats:?}");
+    assert!(stats.coverage_tiles > 10 * stats.solid_tiles, "{stats:?}");
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "svg")]
+
+use vello::Scene;
+use vello_tests::TestParams;
+
+const SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="32" viewBox="0 0 32 16">
+  <defs>
+    <linearGradient id="fade" x1="0" y1="0" x2="1" y2="0">
+      <stop offset="0" stop-color="#00ff00"/>
+      <stop offset="100%" stop-color="#0000ff"/>
+    </linearGradient>
+  </defs>
+  <rect width="32" height="16" fill="white"/>
+  <rect x="0" y="0" width="8" height="8" fill="red"/>
+  <g transform="translate(16 0)">
+    <path d="M0 0H8V8H0Z" style="fill: rgb(0, 0, 255)"/>
+    <circle cx="4" cy="12" r="3" fill="url(#fade)"/>
+  </g>
+  <rect x="0" y="10" width="8" height="4" fill="black" opacity="0.5"/>
+</svg>"##;
+
+#[test]
+fn diagnostics() {
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg">
+        <text x="1" y="1">Hello</text>
+        <rect width="4" height="4" filter="url(#blur)" fill="url(#missing)"/>
+        <circle r="2" fill="orange"/>
+    </svg>"#;
+    let (scene, diagnostics) = Scene::from_svg_with_diagnostics(svg).unwrap();
+    assert_eq!(
+        diagnostics,
+        [
+            "unsupported element <text>",
+            "unsupported attribute `filter` on <rect>",
+            "reference to unknown gradient `missing`",
+        ]
+    );
+    // Only the circle is drawn.
+    assert_eq!(scene.encoding().draw_tags.len(), 1);
+    assert!(Scene::from_svg("<g/>").is_err());
+    assert!(Scene::from_svg("<svg").is_err());
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn from_svg_gpu() {
+    from_svg(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn from_svg_cpu() {
+    from_svg(true)
+}
+
+fn from_svg(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("from_svg", 64, 32)
+    };
+    let (scene, diagnostics) = Scene::from_svg_with_diagnostics(SVG).unwrap();
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let pixel = |x: u32, y: u32| {
+        let ix = ((y * params.width + x) * 4) as usize;
+        let data = image.data.data();
+        [data[ix], data[ix + 1], data[ix + 2], data[ix + 3]]
+    };
+    // The view box is scaled up by two.
+    assert_eq!(pixel(8, 8), [255, 0, 0, 255]);
+    assert_eq!(pixel(24, 8), [255, 255, 255, 255]);
+    assert_eq!(pixel(40, 8), [0, 0, 255, 255]);
+    assert_eq!(pixel(60, 8), [255, 255, 255, 255]);
+    // The gradient runs across the bounding box of the circle.
+    let left = pixel(35, 24);
+    let right = pixel(45, 24);
+    assert!(
+        left[1] > left[2] && right[2] > right[1],
+        "{left:?} {right:?}"
+    );
+    let gray = pixel(8, 24);
+    for channel in &gray[..3] {
+        assert!(channel.abs_diff(128) <= 2, "{gray:?}");
+    }
+}
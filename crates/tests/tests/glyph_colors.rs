@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Brush, Color, Fill, Font},
+    skrifa::{
+        instance::{LocationRef, Size},
+        FontRef, MetadataProvider,
+    },
+    Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn glyph_colors_gpu() {
+    glyph_colors(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn glyph_colors_cpu() {
+    glyph_colors(true)
+}
+
+fn glyph_colors(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("glyph_colors", 96, 48)
+    };
+    let font = Font::new(Blob::new(Arc::new(ROBOTO)), 0);
+    let font_ref = FontRef::new(ROBOTO).unwrap();
+    let font_size = 32.;
+    let charmap = font_ref.charmap();
+    let metrics = font_ref.glyph_metrics(Size::new(font_size), LocationRef::default());
+    let colors = [Color::RED, Color::LIME, Color::BLUE];
+    let mut pen_x = 8.;
+    let mut glyphs = vec![];
+    // The horizontal extent of each glyph's advance.
+    let mut extents = vec![];
+    for (ch, color) in "ABC".chars().zip(colors) {
+        let gid = charmap.map(ch).unwrap();
+        let advance = metrics.advance_width(gid).unwrap();
+        let glyph = Glyph {
+            id: gid.to_u16() as u32,
+            x: pen_x,
+            y: 0.,
+        };
+        glyphs.push((glyph, Some(Brush::Solid(color))));
+        extents.push(pen_x as usize..(pen_x + advance) as usize);
+        pen_x += advance;
+    }
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font)
+        .font_size(font_size)
+        .transform(Affine::translate((0., 36.)))
+        .brush(Color::WHITE)
+        .draw_with_brushes(
+            Fill::NonZero,
+            glyphs
+                .iter()
+                .map(|(glyph, brush)| (*glyph, brush.as_ref().map(Into::into))),
+        );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    for (channel, extent) in extents.into_iter().enumerate() {
+        let mut covered = 0;
+        for y in 0..params.height as usize {
+            for x in extent.clone() {
+                let pixel = &data[(y * params.width as usize + x) * 4..][..3];
+                for (other, value) in pixel.iter().enumerate() {
+                    if other != channel {
+                        assert_eq!(*value, 0, "glyph {channel} at ({x}, {y}): {pixel:?}");
+                    }
+                }
+                if pixel[channel] == 255 {
+                    covered += 1;
+                }
+            }
+        }
+        assert!(covered > 0, "glyph {channel} has no fully covered pixels");
+    }
+}
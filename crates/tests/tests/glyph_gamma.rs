@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font},
+    skrifa::{FontRef, MetadataProvider},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const GAMMA: f32 = 2.2;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn glyph_gamma_gpu() {
+    glyph_gamma(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn glyph_gamma_cpu() {
+    glyph_gamma(true)
+}
+
+/// Renders a row of stems in `text` color on a `background`, and returns the total coverage
+/// of the text in linear light.
+fn linear_ink(use_cpu: bool, text: Color, background: Color, glyph_gamma: Option<f32>) -> f64 {
+    let params = TestParams {
+        use_cpu,
+        base_colour: background,
+        ..TestParams::new("glyph_gamma", 96, 48)
+    };
+    let font = Font::new(Blob::new(Arc::new(ROBOTO)), 0);
+    let gid = FontRef::new(ROBOTO).unwrap().charmap().map('l').unwrap();
+    // Fractional offsets give partially covered pixels on both sides of each stem.
+    let glyphs = (0..8).map(|i| Glyph {
+        id: gid.to_u16() as u32,
+        x: 4. + i as f32 * 10.3,
+        y: 0.,
+    });
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font)
+        .font_size(32.)
+        .transform(Affine::translate((0., 40.)))
+        .brush(text)
+        .draw(Fill::NonZero, glyphs);
+    let render_params = RenderParams {
+        glyph_gamma,
+        ..params.render_params()
+    };
+    let image = vello_tests::render_frames_sync(vec![(scene, render_params)], &params).unwrap();
+    let light_text = text.r == 255;
+    image
+        .data
+        .data()
+        .chunks_exact(4)
+        .map(|pixel| {
+            let linear = (pixel[0] as f64 / 255.).powf(GAMMA as f64);
+            if light_text {
+                linear
+            } else {
+                1. - linear
+            }
+        })
+        .sum()
+}
+
+fn glyph_gamma(use_cpu: bool) {
+    let ink = |glyph_gamma| {
+        let dark = linear_ink(use_cpu, Color::BLACK, Color::WHITE, glyph_gamma);
+        let light = linear_ink(use_cpu, Color::WHITE, Color::BLACK, glyph_gamma);
+        assert!(dark > 0. && light > 0.);
+        dark / light
+    };
+    // Blending coverage in sRGB space makes dark text heavier than light text.
+    let uncorrected = ink(None);
+    assert!(uncorrected > 1.1, "uncorrected weight ratio {uncorrected}");
+    let corrected = ink(Some(GAMMA));
+    assert!(
+        (corrected - 1.).abs() < 0.03,
+        "corrected weight ratio {corrected}"
+    );
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::{glyphs_to_path, layout_line, GlyphSpacing},
+    kurbo::{BezPath, PathEl, Rect, Shape},
+    peniko::{Blob, Font},
+    skrifa::{
+        instance::{LocationRef, Size},
+        FontRef, GlyphId, MetadataProvider,
+    },
+};
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const FONT_SIZE: f32 = 48.;
+
+fn font() -> Font {
+    Font::new(Blob::new(Arc::new(ROBOTO)), 0)
+}
+
+/// Splits a path into its subpaths.
+fn subpaths(path: &BezPath) -> Vec<BezPath> {
+    let mut subpaths: Vec<BezPath> = vec![];
+    for el in path.elements() {
+        if let PathEl::MoveTo(_) = el {
+            subpaths.push(BezPath::new());
+        }
+        subpaths.last_mut().unwrap().push(*el);
+    }
+    subpaths
+}
+
+fn assert_rect_eq(actual: Rect, expected: Rect) {
+    let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+    assert!(
+        close(actual.x0, expected.x0)
+            && close(actual.y0, expected.y0)
+            && close(actual.x1, expected.x1)
+            && close(actual.y1, expected.y1),
+        "{actual:?} isn't {expected:?}"
+    );
+}
+
+#[test]
+fn glyphs_to_path_joins_outlines() {
+    let font_ref = FontRef::new(ROBOTO).unwrap();
+    let metrics = font_ref.glyph_metrics(Size::new(FONT_SIZE), LocationRef::default());
+    let glyphs = layout_line(&font(), FONT_SIZE, "AB", GlyphSpacing::Proportional);
+    let path = glyphs_to_path(&font(), FONT_SIZE, glyphs.iter().copied());
+
+    // The outline of each letter is a group of subpaths around its position in the line.
+    let mut groups: Vec<Option<Rect>> = vec![None; glyphs.len()];
+    for subpath in subpaths(&path) {
+        let bbox = subpath.bounding_box();
+        let ix = glyphs
+            .iter()
+            .rposition(|glyph| bbox.x0 >= glyph.x as f64 - 1.)
+            .unwrap();
+        groups[ix] = Some(groups[ix].map_or(bbox, |group| group.union(bbox)));
+    }
+    let groups: Vec<Rect> = groups.into_iter().map(Option::unwrap).collect();
+    assert!(groups[0].x1 < groups[1].x0, "{groups:?} overlap");
+    // Each group has the bounds of its glyph, which are defined with y pointing up.
+    for (glyph, group) in glyphs.iter().zip(&groups) {
+        let id = GlyphId::new(glyph.id as u16);
+        let bounds = metrics.bounds(id).unwrap();
+        let expected = Rect::new(
+            (glyph.x + bounds.x_min) as f64,
+            -bounds.y_max as f64,
+            (glyph.x + bounds.x_max) as f64,
+            -bounds.y_min as f64,
+        );
+        assert_rect_eq(*group, expected);
+    }
+    // "A" has a counter and "B" has two.
+    assert_eq!(subpaths(&path).len(), 5);
+}
+
+#[test]
+fn glyphs_to_path_without_outlines() {
+    // A space has no outline.
+    let glyphs = layout_line(&font(), FONT_SIZE, " ", GlyphSpacing::Proportional);
+    assert!(glyphs_to_path(&font(), FONT_SIZE, glyphs)
+        .elements()
+        .is_empty());
+
+    let broken = Font::new(Blob::new(Arc::new(b"not a font".as_slice())), 0);
+    let glyphs = layout_line(&font(), FONT_SIZE, "A", GlyphSpacing::Proportional);
+    assert!(glyphs_to_path(&broken, FONT_SIZE, glyphs)
+        .elements()
+        .is_empty());
+}
@@ -0,0 +1,57 @@
+use vello::{
+    kurbo::{Affine, BezPath, Cap, Line, Stroke},
+    peniko::Color,
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn gradient_along_stroke_gpu() {
+    gradient_along_stroke(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn gradient_along_stroke_cpu() {
+    gradient_along_stroke(true)
+}
+
+fn gradient_along_stroke(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("gradient_along_stroke", 64, 64)
+    };
+    let style = Stroke::new(6.).with_caps(Cap::Butt);
+    let stops = [Color::RED, Color::BLUE];
+    let mut scene = Scene::new();
+    scene.stroke_gradient_along(
+        &style,
+        Affine::IDENTITY,
+        stops.as_slice(),
+        &Line::new((4., 8.), (60., 8.)),
+    );
+    // Halfway along this path is its corner.
+    let mut corner = BezPath::new();
+    corner.move_to((4., 24.));
+    corner.line_to((40., 24.));
+    corner.line_to((40., 60.));
+    scene.stroke_gradient_along(&style, Affine::IDENTITY, stops.as_slice(), &corner);
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let offset = (y * params.width as usize + x) * 4;
+        &data[offset..offset + 4]
+    };
+    let is_red = |p: &[u8]| p[0] > 240 && p[2] < 15;
+    let is_blue = |p: &[u8]| p[2] > 240 && p[0] < 15;
+    assert!(is_red(pixel(4, 8)), "{:?}", pixel(4, 8));
+    assert!(is_blue(pixel(59, 8)), "{:?}", pixel(59, 8));
+    assert!(pixel(31, 8)[0].abs_diff(128) < 12);
+    assert!(is_red(pixel(4, 24)), "{:?}", pixel(4, 24));
+    assert!(is_blue(pixel(40, 59)), "{:?}", pixel(40, 59));
+    // Colors follow the path around the corner, so both legs have the same mix at the same
+    // distance from it.
+    assert!(pixel(40, 40)[2].abs_diff(pixel(24, 24)[0]) < 8);
+    assert!(pixel(40, 24)[0].abs_diff(128) < 16);
+}
@@ -0,0 +1,70 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Image},
+    Grain, RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn grain_gpu() {
+    grain(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn grain_cpu() {
+    grain(true)
+}
+
+fn grain(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("grain", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(128, 128, 128)),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    let render = |grain: Option<(f32, u32)>| -> Image {
+        let render_params = RenderParams {
+            grain: grain.map(|(intensity, _)| Grain { intensity }),
+            seed: grain.map_or(0, |(_, seed)| seed),
+            ..params.render_params()
+        };
+        vello_tests::render_frames_sync(vec![(scene.clone(), render_params)], &params).unwrap()
+    };
+    let grain = |intensity, seed| Some((intensity, seed));
+    let plain = render(None);
+    assert!(plain
+        .data
+        .data()
+        .chunks(4)
+        .all(|p| p == [128, 128, 128, 255]));
+
+    let noisy = render(grain(0.1, 7));
+    // The same seed reproduces the same grain, and a different one changes it.
+    assert_eq!(noisy.data.data(), render(grain(0.1, 7)).data.data());
+    assert_ne!(noisy.data.data(), render(grain(0.1, 8)).data.data());
+    // No intensity leaves the output untouched.
+    assert_eq!(plain.data.data(), render(grain(0.0, 7)).data.data());
+
+    let mut changed = 0;
+    let mut sum = 0;
+    for pixel in noisy.data.data().chunks(4) {
+        // The grain is monochrome, bounded by the intensity, and leaves alpha alone.
+        assert_eq!((pixel[0], pixel[3]), (pixel[1], 255));
+        assert_eq!(pixel[0], pixel[2]);
+        assert!(pixel[0].abs_diff(128) <= 26, "{pixel:?}");
+        changed += (pixel[0] != 128) as usize;
+        sum += pixel[0] as usize;
+    }
+    let n = (params.width * params.height) as usize;
+    assert!(changed > n / 2);
+    // The noise is centered on the original color.
+    assert!((sum / n).abs_diff(128) <= 2, "{}", sum / n);
+}
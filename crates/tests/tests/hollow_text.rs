@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font},
+    skrifa::{FontRef, MetadataProvider},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn hollow_text_gpu() {
+    hollow_text(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn hollow_text_cpu() {
+    hollow_text(true)
+}
+
+fn render(use_cpu: bool, hollow: bool) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("hollow_text", 96, 96)
+    };
+    let font = Font::new(Blob::new(Arc::new(ROBOTO)), 0);
+    let gid = FontRef::new(ROBOTO).unwrap().charmap().map('O').unwrap();
+    let glyph = Glyph {
+        id: gid.to_u16() as u32,
+        x: 8.,
+        y: 0.,
+    };
+    let mut scene = Scene::new();
+    let run = scene
+        .draw_glyphs(&font)
+        .font_size(96.)
+        .transform(Affine::translate((0., 84.)))
+        .brush(Color::WHITE);
+    if hollow {
+        run.draw_hollow(1., std::iter::once(glyph));
+    } else {
+        run.draw(Fill::NonZero, std::iter::once(glyph));
+    }
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    image.data.data().to_vec()
+}
+
+/// Counts the separate runs of covered pixels along a row.
+fn covered_runs(data: &[u8], row: usize) -> usize {
+    let alpha = |x: usize| data[(row * 96 + x) * 4 + 3];
+    (0..96)
+        .filter(|&x| alpha(x) != 0 && (x == 0 || alpha(x - 1) == 0))
+        .count()
+}
+
+fn hollow_text(use_cpu: bool) {
+    let filled = render(use_cpu, false);
+    let hollow = render(use_cpu, true);
+    // A row through the middle of the "O".
+    let row = 50;
+    let center = (row * 96 + 48) * 4;
+    assert_eq!(&filled[center..center + 4], &[0, 0, 0, 0]);
+    assert_eq!(&hollow[center..center + 4], &[0, 0, 0, 0]);
+    // The filled glyph crosses the row twice; the hollow one has both the outer and inner
+    // contours on either side, with nothing drawn between them.
+    assert_eq!(covered_runs(&filled, row), 2);
+    assert_eq!(covered_runs(&hollow, row), 4);
+}
@@ -0,0 +1,46 @@
+use vello::{
+    kurbo::{Affine, BezPath},
+    peniko::{Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn horizontal_epsilon_gpu() {
+    horizontal_epsilon(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn horizontal_epsilon_cpu() {
+    horizontal_epsilon(true)
+}
+
+fn horizontal_epsilon(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::BLACK,
+        ..TestParams::new("horizontal_epsilon", 256, 64)
+    };
+    // The top edge crosses the boundary between pixel rows 15 and 16, which is also a
+    // boundary between rows of tiles, by a tiny fraction of a pixel.
+    let mut path = BezPath::new();
+    path.move_to((8., 15.999996));
+    path.line_to((248., 16.000004));
+    path.line_to((248., 48.));
+    path.line_to((8., 48.));
+    path.close_path();
+    let mut scene = Scene::new();
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &path);
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    for y in 0..params.height {
+        for x in 0..params.width {
+            let value = data[((y * params.width + x) * 4) as usize];
+            let inside = (8..248).contains(&x) && (16..48).contains(&y);
+            let expected = if inside { 255 } else { 0 };
+            assert_eq!(value, expected, "pixel ({x}, {y})");
+        }
+    }
+}
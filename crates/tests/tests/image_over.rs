@@ -0,0 +1,80 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_over_gpu() {
+    image_over(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_over_cpu() {
+    image_over(true)
+}
+
+fn image_over(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("image_over", 16, 8)
+    };
+    // Half transparent red on the left, with nothing drawn on the right.
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::rgba8(255, 0, 0, 128),
+        None,
+        &Rect::new(0., 0., 8., 8.),
+    );
+    let render = |background| {
+        pollster::block_on(vello_tests::with_renderer(
+            |device| {
+                let options = RendererOptions {
+                    surface_format: None,
+                    use_cpu,
+                    antialiasing_support: AaSupport::area_only(),
+                    bin_size: BinSize::default(),
+                    max_clip_depth: None,
+                };
+                Renderer::new(device, options)
+                    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+            },
+            |device, queue, renderer| {
+                renderer
+                    .render_to_image_over(
+                        device,
+                        queue,
+                        &scene,
+                        &params.render_params(),
+                        background,
+                    )
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+            },
+        ))
+        .unwrap()
+    };
+    let pixel = |image: &vello::peniko::Image, x: usize| {
+        let ix = (4 * params.width as usize + x) * 4;
+        image.data.data()[ix..ix + 4].to_vec()
+    };
+
+    let white = render(Color::WHITE);
+    assert_eq!(pixel(&white, 4), [255, 127, 127, 255]);
+    assert_eq!(pixel(&white, 12), [255, 255, 255, 255]);
+    let black = render(Color::BLACK);
+    assert_eq!(pixel(&black, 4), [128, 0, 0, 255]);
+    assert_eq!(pixel(&black, 12), [0, 0, 0, 255]);
+    // A translucent background leaves the result translucent where nothing covers it.
+    let translucent = render(Color::rgba8(0, 0, 255, 128));
+    let [r, g, b, a] = pixel(&translucent, 4).try_into().unwrap();
+    assert_eq!((g, a), (0, 192));
+    assert!(r > b, "{r} {b}");
+    assert_eq!(pixel(&translucent, 12), [0, 0, 255, 128]);
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Blob, Color, Format, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn draw_image_rounded_gpu() {
+    draw_image_rounded(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn draw_image_rounded_cpu() {
+    draw_image_rounded(true)
+}
+
+fn draw_image_rounded(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("draw_image_rounded", 48, 48)
+    };
+    // The left column is red and the right one blue.
+    let mut pixels = vec![];
+    for _ in 0..4 {
+        for x in 0..4 {
+            pixels.extend(if x < 2 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 255, 255]
+            });
+        }
+    }
+    let image = Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, 4, 4);
+    let mut scene = Scene::new();
+    scene.draw_image_rounded(&image, Affine::IDENTITY, Rect::new(8., 8., 40., 40.), 12.);
+    let rendered = vello_tests::render_sync(scene, &params).unwrap();
+    let pixel = |x: usize, y: usize| {
+        let ix = (y * params.width as usize + x) * 4;
+        &rendered.data.data()[ix..ix + 4]
+    };
+    // The corners of the rectangle are cut off, as is everything outside of it.
+    for (x, y) in [(8, 8), (39, 8), (8, 39), (39, 39), (4, 24), (24, 44)] {
+        assert_eq!(pixel(x, y), &[0; 4], "pixel ({x}, {y})");
+    }
+    // The image is stretched over the rectangle, with each column covering half of it.
+    assert_eq!(pixel(14, 24), &[255, 0, 0, 255]);
+    assert_eq!(pixel(33, 24), &[0, 0, 255, 255]);
+    // The edges between the corners are straight.
+    assert_eq!(pixel(8, 24)[3], 255);
+    assert_eq!(pixel(24, 39)[3], 255);
+}
+
+#[test]
+fn draw_image_rounded_without_layer() {
+    let image = Image::new(Blob::new(Arc::new([255; 4])), Format::Rgba8, 1, 1);
+    let mut scene = Scene::new();
+    scene.draw_image_rounded(&image, Affine::IDENTITY, Rect::new(0., 0., 16., 16.), 4.);
+    let encoding = scene.encoding();
+    assert_eq!((encoding.n_paths, encoding.n_clips), (1, 0));
+
+    // Nothing is drawn for an empty rectangle.
+    let mut empty = Scene::new();
+    empty.draw_image_rounded(&image, Affine::IDENTITY, Rect::new(4., 4., 4., 12.), 4.);
+    assert!(empty.encoding().is_empty());
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Blob, Color, Fill, Format, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_sampler_gpu() {
+    image_sampler(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_sampler_cpu() {
+    image_sampler(true)
+}
+
+fn render(use_cpu: bool, address_mode: wgpu::AddressMode) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("image_sampler", 8, 8)
+    };
+    // A 2x2 image of red, green (top row) and blue, white (bottom row).
+    let pixels = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 255, 255],
+    ]
+    .concat();
+    let image = Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, 2, 2);
+    let sampler = wgpu::SamplerDescriptor {
+        address_mode_u: address_mode,
+        address_mode_v: address_mode,
+        mag_filter: wgpu::FilterMode::Nearest,
+        ..Default::default()
+    };
+    let mut scene = Scene::new();
+    // The image covers the top left 4x4 pixels of the rectangle.
+    scene.fill_image_with_sampler(
+        Fill::NonZero,
+        Affine::scale(2.),
+        &image,
+        &sampler,
+        None,
+        &Rect::new(0., 0., 4., 4.),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    image.data.data().to_vec()
+}
+
+fn image_sampler(use_cpu: bool) {
+    let pixel = |data: &[u8], x: usize, y: usize| data[(y * 8 + x) * 4..][..4].to_vec();
+    for address_mode in [
+        wgpu::AddressMode::ClampToBorder,
+        wgpu::AddressMode::ClampToEdge,
+        wgpu::AddressMode::Repeat,
+    ] {
+        let data = render(use_cpu, address_mode);
+        // Inside of the image, all address modes agree.
+        assert_eq!(pixel(&data, 1, 1), [255, 0, 0, 255], "{address_mode:?}");
+        assert_eq!(pixel(&data, 3, 3), [255, 255, 255, 255], "{address_mode:?}");
+        // To the right of the image, in the top row.
+        let expected = match address_mode {
+            wgpu::AddressMode::ClampToBorder => [0, 0, 0, 0],
+            wgpu::AddressMode::ClampToEdge => [0, 255, 0, 255],
+            _ => [255, 0, 0, 255],
+        };
+        assert_eq!(pixel(&data, 5, 1), expected, "{address_mode:?}");
+    }
+}
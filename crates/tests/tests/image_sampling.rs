@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use vello::{
+    kurbo::Affine,
+    peniko::{Blob, Color, Format, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const SCALE: usize = 8;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_edge_fringe_gpu() {
+    image_edge_fringe(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_edge_fringe_cpu() {
+    image_edge_fringe(true)
+}
+
+fn image_edge_fringe(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("image_edge_fringe", 4 * SCALE as u32, 4 * SCALE as u32)
+    };
+    // The left half is fully transparent black, and the right half is opaque white. Filtering
+    // with straight alpha would blend in the black, leaving a dark fringe along the edge.
+    let mut pixels = vec![];
+    for _ in 0..4 {
+        for x in 0..4 {
+            pixels.extend(if x < 2 { [0, 0, 0, 0] } else { [255; 4] });
+        }
+    }
+    let image = Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, 4, 4);
+    let mut scene = Scene::new();
+    scene.draw_image(&image, Affine::scale(SCALE as f64));
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    for y in 0..params.height as usize {
+        let row = &data[y * params.width as usize * 4..][..params.width as usize * 4];
+        let mut last_alpha = 0;
+        for (x, pixel) in row.chunks_exact(4).enumerate() {
+            let &[r, g, b, a] = pixel else { unreachable!() };
+            assert!(a >= last_alpha, "alpha decreases at ({x}, {y}): {pixel:?}");
+            last_alpha = a;
+            if a >= 16 {
+                for c in [r, g, b] {
+                    assert!(c >= 247, "fringe at ({x}, {y}): {pixel:?}");
+                }
+            }
+        }
+        // The edge is filtered rather than being a hard step.
+        let partial = row.chunks_exact(4).filter(|p| p[3] != 0 && p[3] != 255);
+        assert!(partial.count() >= SCALE / 2, "row {y} is not filtered");
+        // The rightmost column samples only the last texels, and nothing beyond the image.
+        assert_eq!(&row[row.len() - 4..], &[255; 4], "row {y}");
+    }
+}
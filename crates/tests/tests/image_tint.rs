@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use vello::{
+    kurbo::Affine,
+    peniko::{Blob, Color, Format, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_tint_gpu() {
+    image_tint(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn image_tint_cpu() {
+    image_tint(true)
+}
+
+/// A white disc of the given diameter on a transparent background.
+fn white_circle(size: u32) -> Image {
+    let radius = size as f64 / 2.;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (dx, dy) = (x as f64 + 0.5 - radius, y as f64 + 0.5 - radius);
+            let inside = dx * dx + dy * dy <= radius * radius;
+            data.extend(if inside { [255; 4] } else { [0; 4] });
+        }
+    }
+    Image::new(Blob::new(Arc::new(data)), Format::Rgba8, size, size)
+}
+
+fn image_tint(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("image_tint", 64, 32)
+    };
+    let circle = white_circle(32);
+    let mut scene = Scene::new();
+    scene.draw_image_with_tint(&circle, Affine::IDENTITY, Color::RED);
+    // Translucent tints scale the alpha of the image too.
+    scene.draw_image_with_tint(
+        &circle,
+        Affine::translate((32., 0.)),
+        Color::rgba8(0, 0, 255, 128),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let offset = (y * params.width as usize + x) * 4;
+        &data[offset..offset + 4]
+    };
+    assert_eq!(pixel(16, 16), [255, 0, 0, 255]);
+    // Outside of the circle, the transparent texels stay transparent.
+    assert_eq!(pixel(1, 1), [0, 0, 0, 255]);
+    let blended = pixel(48, 16);
+    assert_eq!(&blended[..2], [0, 0]);
+    assert!(blended[2].abs_diff(128) <= 1, "{blended:?}");
+}
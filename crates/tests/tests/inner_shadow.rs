@@ -0,0 +1,103 @@
+use vello::{
+    kurbo::{Affine, Rect, Vec2},
+    peniko::{Color, Fill},
+    InnerShadow, Scene,
+};
+use vello_tests::TestParams;
+
+const SIZE: u32 = 64;
+
+fn render_shadowed(use_cpu: bool, shadow: &InnerShadow) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("inner_shadow", SIZE, SIZE)
+    };
+    let mut scene = Scene::new();
+    scene.fill_with_inner_shadow(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(8., 8., 56., 56.),
+        shadow,
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    image.data.data().to_vec()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inner_shadow_gpu() {
+    inner_shadow(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inner_shadow_cpu() {
+    inner_shadow(true)
+}
+
+fn inner_shadow(use_cpu: bool) {
+    let shadow = InnerShadow {
+        color: Color::BLACK,
+        offset: Vec2::ZERO,
+        blur_radius: 8.,
+    };
+    let data = render_shadowed(use_cpu, &shadow);
+    let pixel = |x: u32, y: u32| &data[((y * SIZE + x) * 4) as usize..][..4];
+    // The fill is opaque everywhere, and only the color changes.
+    for y in 8..56 {
+        for x in 8..56 {
+            assert_eq!(pixel(x, y)[3], 255, "pixel ({x}, {y})");
+        }
+    }
+    assert_eq!(pixel(4, 32), [0, 0, 0, 0]);
+    // Each edge is darkened, and the shadow fades out over the blur radius.
+    for (x, y) in [(8, 32), (55, 32), (32, 8), (32, 55)] {
+        assert!(
+            pixel(x, y)[0] < 16,
+            "edge pixel ({x}, {y}) is {:?}",
+            pixel(x, y)
+        );
+    }
+    let halfway = pixel(12, 32)[0];
+    assert!((96..160).contains(&halfway), "{halfway}");
+    // The middle of the fill is unaffected.
+    for y in 16..48 {
+        for x in 16..48 {
+            assert_eq!(pixel(x, y), [255, 255, 255, 255], "pixel ({x}, {y})");
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inner_shadow_offset_gpu() {
+    inner_shadow_offset(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inner_shadow_offset_cpu() {
+    inner_shadow_offset(true)
+}
+
+fn inner_shadow_offset(use_cpu: bool) {
+    // A hard shadow moved down and to the right covers a band along the top and left edges.
+    let shadow = InnerShadow {
+        color: Color::BLACK,
+        offset: Vec2::new(4., 4.),
+        blur_radius: 0.,
+    };
+    let data = render_shadowed(use_cpu, &shadow);
+    let red = |x: u32, y: u32| data[((y * SIZE + x) * 4) as usize];
+    for i in 8..12 {
+        assert_eq!(red(i, 32), 0);
+        assert_eq!(red(32, i), 0);
+    }
+    assert_eq!(red(12, 32), 255);
+    assert_eq!(red(32, 12), 255);
+    assert_eq!(red(55, 32), 255);
+    assert_eq!(red(32, 55), 255);
+}
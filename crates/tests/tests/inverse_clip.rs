@@ -0,0 +1,57 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inverse_clip_gpu() {
+    inverse_clip(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn inverse_clip_cpu() {
+    inverse_clip(true)
+}
+
+fn inverse_clip(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("inverse_clip", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.push_inverse_clip_layer(
+        Mix::Normal,
+        1.0,
+        Affine::translate((32., 32.)),
+        &Circle::new((0., 0.), 16.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let offset = (y * params.width as usize + x) * 4;
+        &data[offset..offset + 4]
+    };
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    for (x, y) in [(32, 32), (20, 32), (43, 32), (32, 20), (32, 43)] {
+        assert_eq!(pixel(x, y), [0, 0, 0, 0], "({x}, {y})");
+    }
+    for (x, y) in [(0, 0), (63, 0), (0, 63), (63, 63), (12, 32), (32, 51)] {
+        assert_eq!(pixel(x, y), RED, "({x}, {y})");
+    }
+    // The edge of the circle is antialiased.
+    let edge = pixel(43, 43)[3];
+    assert!(edge > 0 && edge < 255, "{edge}");
+}
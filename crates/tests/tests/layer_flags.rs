@@ -0,0 +1,161 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Mix},
+    LayerFlags, Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 32;
+const HEIGHT: u32 = 8;
+const BOUNDS: Rect = Rect::new(0., 0., WIDTH as f64, HEIGHT as f64);
+
+fn fill(scene: &mut Scene, color: Color, x0: f64, x1: f64) {
+    let rect = Rect::new(x0, 0., x1, HEIGHT as f64);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+}
+
+fn assert_close(pixel: &[u8], expected: [u8; 4], what: &str) {
+    for (&c, e) in pixel.iter().zip(expected) {
+        assert!(
+            c.abs_diff(e) <= 1,
+            "{what}: {pixel:?}, expected {expected:?}"
+        );
+    }
+}
+
+fn render(scene: Scene, params: &TestParams) -> Vec<u8> {
+    let image = vello_tests::render_sync(scene, params).unwrap();
+    image.data.data().to_vec()
+}
+
+fn pixel(data: &[u8], x: usize) -> &[u8] {
+    let ix = (HEIGHT as usize / 2 * WIDTH as usize + x) * 4;
+    &data[ix..ix + 4]
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn knockout_group_gpu() {
+    knockout_group(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn knockout_group_cpu() {
+    knockout_group(true)
+}
+
+fn knockout_group(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::WHITE,
+        ..TestParams::new("knockout_group", WIDTH, HEIGHT)
+    };
+    let group = |knockout| {
+        let mut scene = Scene::new();
+        let flags = LayerFlags {
+            knockout,
+            ..LayerFlags::default()
+        };
+        scene.push_layer_with_flags(flags, Mix::Normal, 1.0, Affine::IDENTITY, &BOUNDS);
+        fill(&mut scene, Color::rgba8(255, 0, 0, 128), 0., 20.);
+        fill(&mut scene, Color::rgba8(0, 0, 255, 128), 12., 32.);
+        scene.pop_layer();
+        render(scene, &params)
+    };
+    let normal = group(false);
+    let knockout = group(true);
+    // Where the fills don't overlap, knocking out makes no difference.
+    for x in [4, 28] {
+        assert_eq!(pixel(&normal, x), pixel(&knockout, x), "pixel {x}");
+    }
+    assert_close(pixel(&normal, 4), [255, 127, 127, 255], "red");
+    // The blue fill covers the red one in a normal group, but replaces it in a knockout group,
+    // so only the white backdrop shows through.
+    assert_close(pixel(&normal, 16), [128, 64, 191, 255], "normal overlap");
+    assert_close(
+        pixel(&knockout, 16),
+        [127, 127, 255, 255],
+        "knockout overlap",
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn non_isolated_group_gpu() {
+    non_isolated_group(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn non_isolated_group_cpu() {
+    non_isolated_group(true)
+}
+
+fn non_isolated_group(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::WHITE,
+        ..TestParams::new("non_isolated_group", WIDTH, HEIGHT)
+    };
+    // A translucent gray is multiplied into a group over a red backdrop.
+    let group = |isolated, alpha| {
+        let mut scene = Scene::new();
+        fill(&mut scene, Color::RED, 0., 32.);
+        let flags = LayerFlags {
+            isolated,
+            ..LayerFlags::default()
+        };
+        scene.push_layer_with_flags(flags, Mix::Normal, alpha, Affine::IDENTITY, &BOUNDS);
+        fill(&mut scene, Color::rgba8(0, 0, 255, 128), 0., 8.);
+        scene.push_layer(Mix::Multiply, 1.0, Affine::IDENTITY, &BOUNDS);
+        fill(&mut scene, Color::rgba8(128, 128, 128, 128), 16., 32.);
+        scene.pop_layer();
+        scene.pop_layer();
+        render(scene, &params)
+    };
+    let isolated = group(true, 1.0);
+    let non_isolated = group(false, 1.0);
+    // An isolated group has nothing to multiply with, so the gray is drawn as it is, while a
+    // non-isolated group multiplies it with the red underneath.
+    assert_close(pixel(&isolated, 24), [191, 64, 64, 255], "isolated");
+    assert_close(pixel(&non_isolated, 24), [191, 0, 0, 255], "non-isolated");
+    // With only normal blending, isolation makes no difference, whatever the group alpha.
+    for alpha in [1.0, 0.5] {
+        let isolated = group(true, alpha);
+        let non_isolated = group(false, alpha);
+        assert_close(
+            pixel(&non_isolated, 4),
+            pixel(&isolated, 4).try_into().unwrap(),
+            "blue",
+        );
+    }
+}
+
+#[test]
+fn layer_flags_encoding() {
+    let mut plain = Scene::new();
+    plain.push_layer(Mix::Normal, 1.0, Affine::IDENTITY, &BOUNDS);
+    let mut flagged = Scene::new();
+    flagged.push_layer_with_flags(
+        LayerFlags::default(),
+        Mix::Normal,
+        1.0,
+        Affine::IDENTITY,
+        &BOUNDS,
+    );
+    // The default flags are those of a plain layer.
+    assert_eq!(plain.encoding().draw_data, flagged.encoding().draw_data);
+
+    let mut knockout = Scene::new();
+    let flags = LayerFlags {
+        isolated: false,
+        knockout: true,
+    };
+    knockout.push_layer_with_flags(flags, Mix::Normal, 1.0, Affine::IDENTITY, &BOUNDS);
+    let blend = |scene: &Scene| {
+        let data = &scene.encoding().draw_data;
+        u32::from_le_bytes(data[..4].try_into().unwrap())
+    };
+    assert_eq!(blend(&knockout), blend(&plain) | 0b11 << 17);
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::{layout_line, GlyphSpacing},
+    peniko::{Blob, Font},
+    skrifa::{
+        instance::{LocationRef, Size},
+        FontRef, MetadataProvider,
+    },
+};
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const FONT_SIZE: f32 = 24.;
+
+fn font() -> Font {
+    Font::new(Blob::new(Arc::new(ROBOTO)), 0)
+}
+
+#[test]
+fn layout_proportional() {
+    let font_ref = FontRef::new(ROBOTO).unwrap();
+    let charmap = font_ref.charmap();
+    let metrics = font_ref.glyph_metrics(Size::new(FONT_SIZE), LocationRef::default());
+    let (h, i) = (charmap.map('H').unwrap(), charmap.map('i').unwrap());
+
+    let glyphs = layout_line(&font(), FONT_SIZE, "Hi", GlyphSpacing::Proportional);
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!(glyphs[0].id, h.to_u16() as u32);
+    assert_eq!(glyphs[1].id, i.to_u16() as u32);
+    assert_eq!((glyphs[0].x, glyphs[0].y), (0., 0.));
+    // The second glyph starts where the first one ends, on the same baseline.
+    let advance = metrics.advance_width(h).unwrap();
+    assert!(advance > 0.);
+    assert_eq!(glyphs[1].x, advance);
+    assert_eq!(glyphs[1].y, 0.);
+}
+
+#[test]
+fn layout_monospace() {
+    let glyphs = layout_line(&font(), FONT_SIZE, "Hill", GlyphSpacing::Monospace(10.));
+    let xs: Vec<_> = glyphs.iter().map(|glyph| glyph.x).collect();
+    assert_eq!(xs, [0., 10., 20., 30.]);
+}
+
+#[test]
+fn layout_unmapped_and_unreadable() {
+    // A character the font has no glyph for still takes up a place in the line.
+    let glyphs = layout_line(
+        &font(),
+        FONT_SIZE,
+        "H\u{10FFFD}i",
+        GlyphSpacing::Proportional,
+    );
+    assert_eq!(glyphs.len(), 3);
+    assert_eq!(glyphs[1].id, 0);
+    assert!(glyphs[2].x > glyphs[1].x);
+
+    let broken = Font::new(Blob::new(Arc::new(b"not a font".as_slice())), 0);
+    assert!(layout_line(&broken, FONT_SIZE, "Hi", GlyphSpacing::Proportional).is_empty());
+}
@@ -0,0 +1,102 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Mix},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene, UnsupportedFeature,
+};
+use vello_tests::TestParams;
+
+const MAX_CLIP_DEPTH: u32 = 4;
+
+/// A scene which nests `depth` clips, with a smaller square drawn inside each.
+fn nested_clips(depth: u32) -> Scene {
+    let mut scene = Scene::new();
+    for i in 0..depth {
+        let inset = i as f64;
+        let rect = Rect::new(inset, inset, 32. - inset, 32. - inset);
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &rect);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, Color::RED, None, &rect);
+    }
+    for _ in 0..depth {
+        scene.pop_layer();
+    }
+    scene
+}
+
+/// Renders `scene` with a renderer limited to [`MAX_CLIP_DEPTH`] clips, and returns the
+/// unsupported feature reported by the render, if any.
+fn render_limited(scene: &Scene, params: &TestParams) -> Option<UnsupportedFeature> {
+    let mut feature = None;
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: Some(MAX_CLIP_DEPTH),
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            if let Err(error) =
+                renderer.render_to_texture(device, queue, scene, &view, &params.render_params())
+            {
+                feature = error.downcast_ref::<UnsupportedFeature>().cloned();
+            }
+            Ok(())
+        },
+    ));
+    image.unwrap();
+    feature
+}
+
+#[test]
+fn clip_depth_of_encoding() {
+    for depth in [0, 1, MAX_CLIP_DEPTH] {
+        assert_eq!(nested_clips(depth).encoding().max_clip_depth(), depth);
+    }
+    // Clips drawn one after the other don't add up.
+    let mut scene = nested_clips(2);
+    scene.append(&nested_clips(3), None);
+    assert_eq!(scene.encoding().max_clip_depth(), 3);
+}
+
+#[test]
+fn clip_depth_display() {
+    let error = UnsupportedFeature::ClipDepth { depth: 5, max: 4 };
+    assert_eq!(
+        error.to_string(),
+        "clips are nested 5 deep, more than the maximum of 4"
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_clip_depth_gpu() {
+    max_clip_depth(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_clip_depth_cpu() {
+    max_clip_depth(true)
+}
+
+fn max_clip_depth(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("max_clip_depth", 32, 32)
+    };
+    assert_eq!(render_limited(&nested_clips(MAX_CLIP_DEPTH), &params), None);
+    assert_eq!(
+        render_limited(&nested_clips(MAX_CLIP_DEPTH + 1), &params),
+        Some(UnsupportedFeature::ClipDepth {
+            depth: MAX_CLIP_DEPTH + 1,
+            max: MAX_CLIP_DEPTH,
+        })
+    );
+}
@@ -0,0 +1,102 @@
+use vello::{
+    kurbo::{Affine, BezPath},
+    peniko::{Brush, Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_subdivision_depth_gpu() {
+    max_subdivision_depth(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_subdivision_depth_cpu() {
+    max_subdivision_depth(true)
+}
+
+fn max_subdivision_depth(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("max_subdivision_depth", 64, 64)
+    };
+    // A dome whose outline is a single cubic, closed by a line along its base.
+    let mut dome = BezPath::new();
+    dome.move_to((8., 56.));
+    dome.curve_to((8., -20.), (56., -20.), (56., 56.));
+    dome.close_path();
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &dome,
+    );
+    let render = |max_subdivision_depth| {
+        let render_params = RenderParams {
+            max_subdivision_depth,
+            ..params.render_params()
+        };
+        let image =
+            vello_tests::render_frames_sync(vec![(scene.clone(), render_params)], &params).unwrap();
+        let data = image.data.data();
+        let pixel = |x: usize, y: usize| data[(y * params.width as usize + x) * 4];
+        (pixel(32, 30), pixel(12, 40))
+    };
+    // Flattened to the tolerance, the dome covers both points.
+    assert_eq!(render(None), (255, 255));
+    // Two lines cut across the dome, leaving out its flank.
+    assert_eq!(render(Some(1)), (255, 0));
+    // A single line collapses the dome onto its base.
+    assert_eq!(render(Some(0)), (0, 0));
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_subdivision_depth_quads_gpu() {
+    max_subdivision_depth_quads(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn max_subdivision_depth_quads_cpu() {
+    max_subdivision_depth_quads(true)
+}
+
+fn max_subdivision_depth_quads(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("max_subdivision_depth_quads", 64, 64)
+    };
+    // A dome whose outline is a single quadratic, closed by a line along its base. Quadratic
+    // fills are flattened separately from cubics.
+    let mut dome = BezPath::new();
+    dome.move_to((8., 56.));
+    dome.quad_to((32., -40.), (56., 56.));
+    dome.close_path();
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &dome,
+    );
+    let render = |max_subdivision_depth| {
+        let render_params = RenderParams {
+            max_subdivision_depth,
+            ..params.render_params()
+        };
+        let image =
+            vello_tests::render_frames_sync(vec![(scene.clone(), render_params)], &params).unwrap();
+        let data = image.data.data();
+        let pixel = |x: usize, y: usize| data[(y * params.width as usize + x) * 4];
+        (pixel(32, 30), pixel(12, 44))
+    };
+    assert_eq!(render(None), (255, 255));
+    assert_eq!(render(Some(1)), (255, 0));
+    assert_eq!(render(Some(0)), (0, 0));
+}
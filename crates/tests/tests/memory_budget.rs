@@ -0,0 +1,52 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    RenderParams, Scene,
+};
+
+const BUDGET: u64 = 256 << 20;
+
+fn params(width: u32, height: u32) -> RenderParams {
+    RenderParams {
+        width,
+        height,
+        ..Default::default()
+    }
+}
+
+fn rects(count: usize) -> Scene {
+    let mut scene = Scene::new();
+    for i in 0..count {
+        let offset = (i % 1000) as f64;
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::RED,
+            None,
+            &Rect::new(offset, offset, offset + 4., offset + 4.),
+        );
+    }
+    scene
+}
+
+#[test]
+fn small_scene_fits() {
+    let scene = rects(10);
+    let params = params(256, 256);
+    assert!(scene.fits_within(BUDGET, &params));
+    let estimate = scene.estimate_memory(&params);
+    assert!(scene.fits_within(estimate, &params));
+    assert!(!scene.fits_within(estimate - 1, &params));
+}
+
+#[test]
+fn large_scene_exceeds_budget() {
+    let params = params(256, 256);
+    let budget = rects(10).estimate_memory(&params) + (1 << 20);
+    assert!(rects(10).fits_within(budget, &params));
+    assert!(!rects(100_000).fits_within(budget, &params));
+    // Scenes with more draw data than the binning buffer can hold are estimated too.
+    assert!(!rects(1_000_000).fits_within(BUDGET, &params));
+    // The size of the target is accounted for as well.
+    assert!(!rects(10).fits_within(BUDGET, &self::params(8192, 8192)));
+}
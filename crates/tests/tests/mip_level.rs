@@ -0,0 +1,82 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, RenderParams, Renderer, RendererOptions, Scene,
+};
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn render_mip_level_gpu() {
+    render_mip_level(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn render_mip_level_cpu() {
+    render_mip_level(true)
+}
+
+fn render_mip_level(use_cpu: bool) {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(0., 0., 64., 128.),
+    );
+    let new_renderer = |device: &wgpu::Device| {
+        let options = RendererOptions {
+            surface_format: None,
+            use_cpu,
+            antialiasing_support: AaSupport::area_only(),
+            bin_size: BinSize::default(),
+            max_clip_depth: None,
+        };
+        Renderer::new(device, options)
+            .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+    };
+    let image = pollster::block_on(vello_tests::with_renderer(
+        new_renderer,
+        |device, queue, renderer| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Target texture"),
+                size: wgpu::Extent3d {
+                    width: 256,
+                    height: 256,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 2,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            let params = |size| RenderParams {
+                width: size,
+                height: size,
+                ..Default::default()
+            };
+            // The size of the render must match the mip level.
+            assert!(renderer
+                .render_to_texture_mip(device, queue, &scene, &texture, 1, &params(256))
+                .is_err());
+            renderer
+                .render_to_texture_mip(device, queue, &scene, &texture, 1, &params(128))
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            vello_tests::read_texture_level(device, queue, &texture, 1, (0, 0), 128, 128)
+        },
+    ))
+    .unwrap();
+    for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+        let x = i % 128;
+        let expected = if x < 64 {
+            [255, 0, 0, 255]
+        } else {
+            [0, 0, 0, 255]
+        };
+        assert_eq!(pixel, expected, "pixel ({x}, {})", i / 128);
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font},
+    skrifa::{instance::NormalizedCoord, FontRef, MetadataProvider},
+    MissingGlyph, Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const INCONSOLATA: &[u8] = include_bytes!("../../../examples/assets/inconsolata/Inconsolata.ttf");
+
+/// A glyph id well past the end of the font.
+const MISSING: u32 = 60_000;
+
+fn font() -> Font {
+    Font::new(Blob::new(Arc::new(ROBOTO)), 0)
+}
+
+fn glyph(id: u32, x: f32) -> Glyph {
+    Glyph { id, x, y: 0. }
+}
+
+/// Returns the ids of the glyphs encoded when drawing `glyphs` with `missing_glyph`.
+fn encoded_ids(missing_glyph: MissingGlyph, glyphs: &[Glyph]) -> Vec<u32> {
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font())
+        .missing_glyph(missing_glyph)
+        .draw(Fill::NonZero, glyphs.iter().copied());
+    let encoding = scene.encoding();
+    encoding.resources.glyphs.iter().map(|g| g.id).collect()
+}
+
+#[test]
+fn missing_glyph_ids() {
+    let h = FontRef::new(ROBOTO).unwrap().charmap().map('H').unwrap();
+    let h = h.to_u16() as u32;
+    // Ids which only match a glyph once truncated to 16 bits are missing too.
+    let glyphs = [glyph(h, 0.), glyph(MISSING, 10.), glyph(h + 65536, 20.)];
+    assert_eq!(encoded_ids(MissingGlyph::Skip, &glyphs), [h]);
+    assert_eq!(encoded_ids(MissingGlyph::Notdef, &glyphs), [h, 0, 0]);
+    let fallback = |glyph: Glyph| (glyph.x < 15.).then_some(Glyph { id: h, ..glyph });
+    assert_eq!(
+        encoded_ids(MissingGlyph::Fallback(&fallback), &glyphs),
+        [h, h]
+    );
+    // Fallbacks which are missing themselves are skipped.
+    let fallback = |glyph: Glyph| {
+        Some(Glyph {
+            id: MISSING,
+            ..glyph
+        })
+    };
+    assert_eq!(encoded_ids(MissingGlyph::Fallback(&fallback), &glyphs), [h]);
+}
+
+#[test]
+fn unparseable_font_keeps_glyphs() {
+    let broken = Font::new(Blob::new(Arc::new(b"not a font".as_slice())), 0);
+    let mut scene = Scene::new();
+    scene.draw_glyphs(&broken).draw(
+        Fill::NonZero,
+        [glyph(1, 0.), glyph(MISSING, 10.)].into_iter(),
+    );
+    let ids: Vec<u32> = scene
+        .encoding()
+        .resources
+        .glyphs
+        .iter()
+        .map(|g| g.id)
+        .collect();
+    assert_eq!(ids, [1, MISSING]);
+}
+
+/// Draws a run of a variable font in two groups, the first of which only has a missing glyph.
+fn missing_group_scene() -> Scene {
+    let font = Font::new(Blob::new(Arc::new(INCONSOLATA)), 0);
+    let h = FontRef::new(INCONSOLATA)
+        .unwrap()
+        .charmap()
+        .map('H')
+        .unwrap();
+    let h = h.to_u16() as u32;
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font)
+        .font_size(48.)
+        .transform(Affine::translate((8., 48.)))
+        .normalized_coords(&[NormalizedCoord::from_f32(0.5)])
+        .draw_with_brushes(
+            Fill::NonZero,
+            [
+                (glyph(MISSING, 0.), Some(Color::RED.into())),
+                (glyph(h, 0.), Some(Color::WHITE.into())),
+            ]
+            .into_iter(),
+        );
+    scene
+}
+
+#[test]
+fn missing_glyph_group_keeps_coords() {
+    let scene = missing_group_scene();
+    let coords = &scene.encoding().resources.normalized_coords;
+    assert_eq!(coords, &[NormalizedCoord::from_f32(0.5)]);
+    // Resolving the scene reads the coordinates of the present group.
+    let params = TestParams::new("missing_glyph_group", 64, 64).render_params();
+    scene.estimate_memory(&params);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn missing_glyph_group_gpu() {
+    missing_glyph_group(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn missing_glyph_group_cpu() {
+    missing_glyph_group(true)
+}
+
+fn missing_glyph_group(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("missing_glyph_group", 64, 64)
+    };
+    let image = vello_tests::render_sync(missing_group_scene(), &params).unwrap();
+    // The "H" is drawn in white, and the missing glyph in red is skipped.
+    let data = image.data.data();
+    assert!(data.chunks(4).any(|pixel| pixel == [255; 4]));
+    assert!(data.chunks(4).all(|pixel| pixel[0] == pixel[1]));
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn missing_glyph_notdef_gpu() {
+    missing_glyph_notdef(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn missing_glyph_notdef_cpu() {
+    missing_glyph_notdef(true)
+}
+
+fn missing_glyph_notdef(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("missing_glyph_notdef", 64, 100)
+    };
+    let render = |missing_glyph| {
+        let mut scene = Scene::new();
+        scene
+            .draw_glyphs(&font())
+            .font_size(100.)
+            .transform(Affine::translate((0., 90.)))
+            .brush(Color::WHITE)
+            .missing_glyph(missing_glyph)
+            .draw(Fill::NonZero, std::iter::once(glyph(MISSING, 0.)));
+        vello_tests::render_sync(scene, &params).unwrap()
+    };
+    let skipped = render(MissingGlyph::Skip);
+    assert!(skipped.data.data().iter().all(|&c| c == 0));
+    // Roboto's .notdef glyph is a box crossed by diagonals. The left side of the box runs
+    // from 0.05 to 0.075 em.
+    let notdef = render(MissingGlyph::Notdef);
+    let alpha = |x: usize, y: usize| notdef.data.data()[(y * params.width as usize + x) * 4 + 3];
+    assert_eq!(alpha(6, 54), 255);
+    assert_eq!(alpha(2, 54), 0);
+    assert_eq!(alpha(50, 54), 0);
+}
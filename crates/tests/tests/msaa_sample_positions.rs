@@ -0,0 +1,168 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, BezPath, Point},
+    peniko::{Brush, Color, Fill, Image},
+    AaConfig, AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+/// Columns of the samples in each row of the built-in 8x pattern.
+const BUILT_IN: [u8; 8] = [0, 5, 3, 7, 1, 4, 6, 2];
+/// A rotated grid, which steps three columns per row.
+const ROTATED_GRID: [u8; 8] = [0, 3, 6, 1, 4, 7, 2, 5];
+
+fn positions(columns: &[u8; 8]) -> Vec<Point> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(row, &column)| Point::new((column as f64 + 0.5) / 8., (row as f64 + 0.5) / 8.))
+        .collect()
+}
+
+/// A triangle with edges of several slopes, so that the edges cross the samples of each
+/// pattern differently.
+fn triangle() -> Scene {
+    let mut path = BezPath::new();
+    path.move_to((4., 4.));
+    path.line_to((60., 20.));
+    path.line_to((20., 60.));
+    path.close_path();
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &path,
+    );
+    scene
+}
+
+/// Renders the triangle with MSAA8, using the sample positions given by `columns` or the
+/// built-in pattern if `None`.
+fn render(params: &TestParams, columns: Option<&[u8; 8]>) -> Image {
+    pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::all(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            let mut renderer = Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
+            let positions = columns.map(positions);
+            renderer
+                .set_msaa_sample_positions(AaConfig::Msaa8, positions.as_deref())
+                .or_else(|_| bail!("Got non-Send/Sync error from setting sample positions"))?;
+            Ok(renderer)
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let render_params = vello::RenderParams {
+                antialiasing_method: AaConfig::Msaa8,
+                ..params.render_params()
+            };
+            renderer
+                .render_to_texture(device, queue, &triangle(), &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn msaa_sample_positions_gpu() {
+    msaa_sample_positions(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn msaa_sample_positions_cpu() {
+    msaa_sample_positions(true)
+}
+
+fn msaa_sample_positions(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("msaa_sample_positions", 64, 64)
+    };
+    let default = render(&params, None);
+    // Spelling out the built-in pattern makes no difference.
+    assert!(render(&params, Some(&BUILT_IN)).data.data() == default.data.data());
+
+    let rotated = render(&params, Some(&ROTATED_GRID));
+    let mut edge_pixels = 0;
+    let mut differing = 0;
+    for (a, b) in default
+        .data
+        .data()
+        .chunks_exact(4)
+        .zip(rotated.data.data().chunks_exact(4))
+    {
+        let (a, b) = (a[0], b[0]);
+        if a == 0 && b == 0 || a == 255 && b == 255 {
+            continue;
+        }
+        edge_pixels += 1;
+        if a != b {
+            differing += 1;
+        }
+        // Each pattern has 8 samples, so coverage comes in eighths.
+        for value in [a, b] {
+            let eighths = value as f64 / 255. * 8.;
+            assert!((eighths - eighths.round()).abs() < 0.05, "coverage {value}");
+        }
+    }
+    assert!(edge_pixels > 0);
+    assert!(
+        differing > 0,
+        "edge coverage doesn't depend on the sample positions"
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn msaa_sample_positions_validation() {
+    let params = TestParams::new("msaa_sample_positions_validation", 8, 8);
+    let result = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::all(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |_, _, renderer| {
+            let rotated = positions(&ROTATED_GRID);
+            assert!(renderer
+                .set_msaa_sample_positions(AaConfig::Msaa8, Some(&rotated))
+                .is_ok());
+            // The wrong number of samples for the method.
+            assert!(renderer
+                .set_msaa_sample_positions(AaConfig::Msaa16, Some(&rotated))
+                .is_err());
+            assert!(renderer
+                .set_msaa_sample_positions(AaConfig::Msaa8, Some(&rotated[1..]))
+                .is_err());
+            // Two samples in the same row.
+            let mut same_row = rotated.clone();
+            same_row[1].y = same_row[0].y;
+            assert!(renderer
+                .set_msaa_sample_positions(AaConfig::Msaa8, Some(&same_row))
+                .is_err());
+            assert!(renderer
+                .set_msaa_sample_positions(AaConfig::Area, None)
+                .is_err());
+            Ok(())
+        },
+    ));
+    result.unwrap();
+}
@@ -0,0 +1,113 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn nested_clip_edges_gpu() {
+    nested_clip_edges(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn nested_clip_edges_cpu() {
+    nested_clip_edges(true)
+}
+
+/// White fill inside `depth` nested clips which all use the same circle.
+fn clipped_circle(depth: usize) -> Scene {
+    let circle = Circle::new((32., 32.), 20.5);
+    let mut scene = Scene::new();
+    for _ in 0..depth {
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &circle);
+    }
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    for _ in 0..depth {
+        scene.pop_layer();
+    }
+    scene
+}
+
+fn nested_clip_edges(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("nested_clip_edges", 64, 64)
+    };
+    let single = vello_tests::render_sync(clipped_circle(1), &params).unwrap();
+    let nested = vello_tests::render_sync(clipped_circle(2), &params).unwrap();
+    let single = single.data.data();
+    let nested = nested.data.data();
+    let mut edge_pixels = 0;
+    for (i, (a, b)) in single
+        .chunks_exact(4)
+        .zip(nested.chunks_exact(4))
+        .enumerate()
+    {
+        if a[0] != 0 && a[0] != 255 {
+            edge_pixels += 1;
+        }
+        // The shared boundary must not be attenuated a second time.
+        assert!(a[0].abs_diff(b[0]) <= 1, "pixel {i}: {a:?} vs {b:?}");
+    }
+    assert!(edge_pixels > 0);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn misaligned_nested_clips_gpu() {
+    misaligned_nested_clips(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn misaligned_nested_clips_cpu() {
+    misaligned_nested_clips(true)
+}
+
+/// White fill inside a clip to `[0, 10.5]` horizontally, nested inside a clip starting at
+/// `child_x0`.
+fn clipped_columns(child_x0: f64) -> Scene {
+    let mut scene = Scene::new();
+    let parent = Rect::new(0., 0., 10.5, 16.);
+    let child = Rect::new(child_x0, 0., 20., 16.);
+    scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &parent);
+    scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &child);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &Rect::new(0., 0., 32., 16.),
+    );
+    scene.pop_layer();
+    scene.pop_layer();
+    scene
+}
+
+fn misaligned_nested_clips(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("misaligned_nested_clips", 32, 16)
+    };
+    // Clips which only meet along an edge inside a pixel have no intersection, so nothing
+    // leaks through at the pixel they share.
+    let abutting = vello_tests::render_sync(clipped_columns(10.5), &params).unwrap();
+    for (i, pixel) in abutting.data.data().chunks_exact(4).enumerate() {
+        assert_eq!(pixel[0], 0, "pixel {i}: {pixel:?}");
+    }
+    // Clips overlapping by a quarter of a pixel cover a quarter of it.
+    let overlapping = vello_tests::render_sync(clipped_columns(10.25), &params).unwrap();
+    for (i, pixel) in overlapping.data.data().chunks_exact(4).enumerate() {
+        let expected = if i % 32 == 10 { 64 } else { 0 };
+        assert!(pixel[0].abs_diff(expected) <= 1, "pixel {i}: {pixel:?}");
+    }
+}
@@ -0,0 +1,63 @@
+use vello::{
+    kurbo::{Affine, Cap, Line, Stroke},
+    peniko::Color,
+    Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 64;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn non_scaling_stroke_gpu() {
+    non_scaling_stroke(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn non_scaling_stroke_cpu() {
+    non_scaling_stroke(true)
+}
+
+fn non_scaling_stroke(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("non_scaling_stroke", WIDTH, HEIGHT)
+    };
+    // Two horizontal lines 2 units wide, in a scene scaled by 3, which land on device rows 12
+    // and 48.
+    let transform = Affine::scale(3.0);
+    let style = Stroke::new(2.).with_caps(Cap::Butt);
+    let mut scene = Scene::new();
+    scene.stroke(
+        &style,
+        transform,
+        Color::WHITE,
+        None,
+        &Line::new((2., 4.), (20., 4.)),
+    );
+    scene.stroke_non_scaling(
+        &style,
+        transform,
+        Color::WHITE,
+        None,
+        &Line::new((2., 16.), (20., 16.)),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let alpha = |x: u32, y: u32| image.data.data()[((y * WIDTH + x) * 4 + 3) as usize];
+    // Both lines have pixel aligned edges, so each row of the middle column is fully covered
+    // or not at all.
+    for y in 0..HEIGHT {
+        let user_units = (9..15).contains(&y);
+        let device_pixels = (47..49).contains(&y);
+        let expected = if user_units || device_pixels { 255 } else { 0 };
+        assert_eq!(alpha(32, y), expected, "row {y}");
+    }
+    // The length of both lines still scales with the transform.
+    assert_eq!(alpha(5, 48), 0);
+    assert_eq!(alpha(6, 48), 255);
+    assert_eq!(alpha(59, 48), 255);
+    assert_eq!(alpha(60, 48), 0);
+}
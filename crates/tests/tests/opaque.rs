@@ -0,0 +1,191 @@
+use std::time::Instant;
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle, Point, Rect},
+    peniko::{Blob, Brush, Color, Fill, Format, Gradient, Image, Mix},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+/// Overlapping opaque rectangles and circles which cover the whole target.
+fn opaque_scene(width: u32, height: u32, count: usize) -> Scene {
+    let mut scene = Scene::new();
+    let (width, height) = (width as f64, height as f64);
+    let gradient = Gradient::new_linear(Point::ORIGIN, Point::new(width, height))
+        .with_stops([Color::NAVY, Color::TEAL]);
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &gradient,
+        None,
+        &Rect::new(0., 0., width, height),
+    );
+    for i in 0..count {
+        let x = (i * 97 % width as usize) as f64;
+        let y = (i * 53 % height as usize) as f64;
+        let color = Color::rgb8((i * 29 % 256) as u8, (i * 71 % 256) as u8, 128);
+        let brush = Brush::Solid(color);
+        if i % 2 == 0 {
+            let rect = Rect::new(x, y, x + 40. + (i % 7) as f64 * 10., y + 30.);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, &brush, None, &rect);
+        } else {
+            let circle = Circle::new((x, y), 10. + (i % 5) as f64 * 8.);
+            scene.fill(Fill::NonZero, Affine::IDENTITY, &brush, None, &circle);
+        }
+    }
+    scene
+}
+
+/// Returns `scene` with an invisible translucent draw over it, which keeps the renderer off
+/// the opaque fast path without changing the result.
+fn general_scene(scene: &Scene) -> Scene {
+    let mut general = scene.clone();
+    general.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::TRANSPARENT,
+        None,
+        &Rect::new(0., 0., 1., 1.),
+    );
+    assert!(!general.stats().opaque);
+    general
+}
+
+#[test]
+fn stats_opaque() {
+    let rect = Rect::new(0., 0., 10., 10.);
+    let opaque = |draw: &dyn Fn(&mut Scene)| {
+        let mut scene = Scene::new();
+        scene.fill(Fill::NonZero, Affine::IDENTITY, Color::RED, None, &rect);
+        draw(&mut scene);
+        scene.stats().opaque
+    };
+    assert!(opaque(&|_| {}));
+    assert!(opaque(&|scene| {
+        let gradient =
+            Gradient::new_linear((0., 0.), (10., 0.)).with_stops([Color::RED, Color::BLUE]);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, &gradient, None, &rect);
+    }));
+    assert!(!opaque(&|scene| {
+        let gradient =
+            Gradient::new_linear((0., 0.), (10., 0.)).with_stops([Color::RED, Color::TRANSPARENT]);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, &gradient, None, &rect);
+    }));
+    assert!(!opaque(&|scene| {
+        let color = Color::rgba8(0, 0, 255, 254);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+    }));
+    assert!(!opaque(&|scene| {
+        // Layers are blended with their backdrop, even with normal blending at full alpha.
+        scene.push_layer(Mix::Normal, 1.0, Affine::IDENTITY, &rect);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, Color::BLUE, None, &rect);
+        scene.pop_layer();
+    }));
+    assert!(!opaque(&|scene| {
+        let image = Image::new(
+            Blob::new(std::sync::Arc::new([255; 4])),
+            Format::Rgba8,
+            1,
+            1,
+        );
+        scene.draw_image(&image, Affine::IDENTITY);
+    }));
+}
+
+fn render(scene: &Scene, params: &TestParams) -> Vec<u8> {
+    vello_tests::render_sync(scene.clone(), params)
+        .unwrap()
+        .data
+        .data()
+        .to_vec()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn opaque_fast_path_gpu() {
+    opaque_fast_path(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn opaque_fast_path_cpu() {
+    opaque_fast_path(true)
+}
+
+fn opaque_fast_path(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("opaque_fast_path", 256, 192)
+    };
+    let scene = opaque_scene(params.width, params.height, 100);
+    assert!(scene.stats().opaque);
+    let fast = render(&scene, &params);
+    let general = render(&general_scene(&scene), &params);
+    assert!(fast == general, "the fast path changed the output");
+
+    // A translucent base color also needs the general path.
+    let params = TestParams {
+        base_colour: Color::TRANSPARENT,
+        ..params
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Circle::new((128., 96.), 60.),
+    );
+    let image = render(&scene, &params);
+    assert_eq!(&image[..4], &[0, 0, 0, 0]);
+    assert_eq!(render(&general_scene(&scene), &params), image);
+}
+
+/// Compares frame times for an opaque scene on the fast path and the general path.
+///
+/// Run with `cargo test -p vello_tests --test opaque -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_opaque() {
+    const FRAMES: u32 = 20;
+    let params = TestParams::new("bench_opaque", 2048, 2048);
+    let opaque = opaque_scene(params.width, params.height, 20_000);
+    let general = general_scene(&opaque);
+    for (name, scene) in [("fast", &opaque), ("general", &general)] {
+        pollster::block_on(vello_tests::render_with_renderer(
+            &params,
+            |device| {
+                let options = RendererOptions {
+                    surface_format: None,
+                    use_cpu: false,
+                    antialiasing_support: AaSupport::area_only(),
+                    bin_size: BinSize::default(),
+                    max_clip_depth: None,
+                };
+                Renderer::new(device, options)
+                    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+            },
+            |device, queue, renderer, target| {
+                let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+                let mut frame = || {
+                    renderer
+                        .render_to_texture(device, queue, scene, &view, &params.render_params())
+                        .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                    device.poll(wgpu::Maintain::Wait);
+                    Ok::<_, anyhow::Error>(())
+                };
+                // Warm up the buffer pools before timing.
+                frame()?;
+                let start = Instant::now();
+                for _ in 0..FRAMES {
+                    frame()?;
+                }
+                let elapsed = start.elapsed() / FRAMES;
+                println!("{name:>7} {elapsed:?}");
+                Ok(())
+            },
+        ))
+        .unwrap();
+    }
+}
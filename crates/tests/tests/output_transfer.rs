@@ -0,0 +1,95 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene, TransferLut,
+};
+use vello_tests::TestParams;
+
+/// Renders a mid-gray fill with `output_transfer`, and returns the value of its red channel.
+fn render_gray(params: &TestParams, output_transfer: Option<TransferLut>) -> u8 {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::rgb8(128, 128, 128),
+        None,
+        &Rect::new(0., 0., params.width as f64, params.height as f64),
+    );
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let render_params = vello::RenderParams {
+                output_transfer: output_transfer.clone(),
+                ..params.render_params()
+            };
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    let pixel = &image.data.data()[..4];
+    assert_eq!(pixel[0], pixel[1]);
+    assert_eq!(pixel[0], pixel[2]);
+    assert_eq!(pixel[3], 255);
+    pixel[0]
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn output_transfer_gpu() {
+    output_transfer(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn output_transfer_cpu() {
+    output_transfer(true)
+}
+
+fn output_transfer(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("output_transfer", 16, 16)
+    };
+    assert_eq!(render_gray(&params, None), 128);
+    // sRGB 128 is a linear intensity of 0.2159, which a gamma of 2.0 encodes as
+    // sqrt(0.2159) * 255 = 118.5.
+    let gamma = render_gray(&params, Some(TransferLut::gamma(2.0)));
+    assert!(gamma.abs_diff(118) <= 1, "gamma 2.0 gave {gamma}");
+    // An explicit table of the same function gives the same result.
+    let table = (0..=255).map(|i| (i as f32 / 255.).sqrt()).collect();
+    let explicit = render_gray(&params, Some(TransferLut::new(table)));
+    assert!(explicit.abs_diff(gamma) <= 1, "{explicit} and {gamma}");
+}
+
+#[test]
+fn gamma_lut() {
+    let lut = TransferLut::gamma(2.0);
+    let table = lut.table();
+    assert_eq!(table.len(), TransferLut::GAMMA_ENTRIES);
+    assert_eq!(table[0], 0.0);
+    assert_eq!(table[table.len() - 1], 1.0);
+    let mid = table.len() / 2;
+    let expected = (mid as f32 / (table.len() - 1) as f32).sqrt();
+    assert!((table[mid] - expected).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic]
+fn transfer_lut_too_short() {
+    TransferLut::new(vec![1.0]);
+}
@@ -0,0 +1,77 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn overdraw_gpu() {
+    overdraw(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn overdraw_cpu() {
+    overdraw(true)
+}
+
+fn overdraw(use_cpu: bool) {
+    // A 4x4 grid of tiles.
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("overdraw", 64, 64)
+    };
+    let mut scene = Scene::new();
+    for (rect, color) in [
+        // Tiles 0 to 2 of the top two rows.
+        (Rect::new(4., 4., 44., 28.), Color::RED),
+        // Tiles 1 to 3 of the same rows.
+        (Rect::new(20., 4., 60., 28.), Color::BLUE),
+        // Within tile 2 of the second row.
+        (Rect::new(36., 20., 40., 24.), Color::WHITE),
+    ] {
+        scene.fill(Fill::NonZero, Affine::IDENTITY, color, None, &rect);
+    }
+    let mut counts = vec![];
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        &params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            counts = renderer
+                .render_overdraw(device, queue, &scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            Ok(())
+        },
+    ))
+    .unwrap();
+    #[rustfmt::skip]
+    let expected = [
+        1, 2, 2, 1,
+        1, 2, 3, 1,
+        0, 0, 0, 0,
+        0, 0, 0, 0,
+    ];
+    assert_eq!(counts, expected);
+    // The scene is still rendered as usual.
+    let pixel = |x: usize, y: usize| {
+        let start = (y * params.width as usize + x) * 4;
+        &image.data.data()[start..start + 4]
+    };
+    assert_eq!(pixel(10, 10), [255, 0, 0, 255]);
+    assert_eq!(pixel(30, 10), [0, 0, 255, 255]);
+    assert_eq!(pixel(38, 22), [255, 255, 255, 255]);
+}
@@ -0,0 +1,73 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::{Brush, Color, Fill, Format},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn peniko_image_gpu() {
+    peniko_image(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn peniko_image_cpu() {
+    peniko_image(true)
+}
+
+fn peniko_image(use_cpu: bool) {
+    let inner = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("peniko_image_inner", 16, 16)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::LIME),
+        None,
+        &Circle::new((8., 8.), 6.),
+    );
+    let image = pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            renderer
+                .render_to_peniko_image(device, queue, &scene, &inner.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    assert_eq!((image.width, image.height), (16, 16));
+    assert_eq!(image.format, Format::Rgba8);
+    // The corners are outside the circle, and stay transparent.
+    assert_eq!(&image.data.data()[..4], &[0, 0, 0, 0]);
+
+    let outer = TestParams {
+        use_cpu,
+        ..TestParams::new("peniko_image", 48, 32)
+    };
+    let mut scene = Scene::new();
+    scene.draw_image(&image, Affine::translate((24., 8.)));
+    let rendered = vello_tests::render_sync(scene, &outer).unwrap();
+    let pixel = |x: usize, y: usize| {
+        let ix = (y * outer.width as usize + x) * 4;
+        &rendered.data.data()[ix..ix + 4]
+    };
+    assert_eq!(pixel(32, 16), &[0, 255, 0, 255]);
+    assert_eq!(pixel(24, 8), &[0, 0, 0, 255]);
+    assert_eq!(pixel(8, 16), &[0, 0, 0, 255]);
+}
@@ -0,0 +1,64 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    RenderParams, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn pixel_snapping_gpu() {
+    pixel_snapping(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn pixel_snapping_cpu() {
+    pixel_snapping(true)
+}
+
+fn pixel_snapping(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("pixel_snapping", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.set_pixel_snapping(true);
+    // The rectangles meet at x = 32.5 in device space, half way through a pixel.
+    let transform = Affine::scale(0.5);
+    let brush = Brush::Solid(Color::RED);
+    scene.fill(
+        Fill::NonZero,
+        transform,
+        &brush,
+        None,
+        &Rect::new(0., 0., 65., 128.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        transform,
+        &brush,
+        None,
+        &Rect::new(65., 0., 128., 128.),
+    );
+    let render_params = RenderParams {
+        base_color: Color::TRANSPARENT,
+        ..params.render_params()
+    };
+    let image = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+        let x = i as u32 % params.width;
+        let y = i as u32 / params.width;
+        assert_eq!(pixel, [255, 0, 0, 255], "pixel ({x}, {y})");
+    }
+}
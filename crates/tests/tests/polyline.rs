@@ -0,0 +1,87 @@
+use std::time::Instant;
+
+use vello::{
+    kurbo::{Affine, BezPath, Point, Stroke},
+    peniko::{Brush, Color},
+    Scene,
+};
+use vello_tests::TestParams;
+
+fn zigzag() -> Vec<Point> {
+    (0..8)
+        .map(|i| Point::new(4. + i as f64 * 8., if i % 2 == 0 { 8. } else { 56. }))
+        .collect()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stroke_polyline_gpu() {
+    stroke_polyline(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stroke_polyline_cpu() {
+    stroke_polyline(true)
+}
+
+fn stroke_polyline(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("stroke_polyline", 64, 64)
+    };
+    let points = zigzag();
+    let style = Stroke::new(3.);
+    let brush = Brush::Solid(Color::WHITE);
+    let mut polyline = Scene::new();
+    polyline.stroke_polyline(&style, Affine::IDENTITY, &brush, None, &points);
+    let mut path = BezPath::new();
+    path.move_to(points[0]);
+    for &point in &points[1..] {
+        path.line_to(point);
+    }
+    let mut expected = Scene::new();
+    expected.stroke(&style, Affine::IDENTITY, &brush, None, &path);
+    let image = vello_tests::render_sync(polyline, &params).unwrap();
+    let expected = vello_tests::render_sync(expected, &params).unwrap();
+    assert!(image.data.data() == expected.data.data());
+    // The middle of the first line, from (4, 8) to (12, 56), is covered.
+    let ix = (32 * params.width as usize + 8) * 4;
+    assert_eq!(&image.data.data()[ix..ix + 4], &[255; 4]);
+
+    let mut empty = Scene::new();
+    empty.stroke_polyline(&style, Affine::IDENTITY, &brush, None, &points[..1]);
+    assert!(empty.encoding().is_empty());
+}
+
+/// Compares encoding a long polyline directly with building a path to stroke first.
+///
+/// Run with `cargo test -p vello_tests --test polyline --release -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn bench_stroke_polyline() {
+    const POINTS: usize = 100_000;
+    let points: Vec<Point> = (0..POINTS)
+        .map(|i| {
+            let x = i as f64 / POINTS as f64 * 1024.;
+            Point::new(x, 256. + 200. * (x / 10.).sin())
+        })
+        .collect();
+    let style = Stroke::new(1.);
+    let brush = Brush::Solid(Color::WHITE);
+
+    let start = Instant::now();
+    let mut scene = Scene::new();
+    scene.stroke_polyline(&style, Affine::IDENTITY, &brush, None, &points);
+    println!("stroke_polyline: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let mut path = BezPath::new();
+    path.move_to(points[0]);
+    for &point in &points[1..] {
+        path.line_to(point);
+    }
+    let mut scene = Scene::new();
+    scene.stroke(&style, Affine::IDENTITY, &brush, None, &path);
+    println!("stroke with BezPath: {:?}", start.elapsed());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "wgpu-profiler")]
+
+use anyhow::{anyhow, bail};
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn custom_profiler_scope_gpu() {
+    custom_profiler_scope(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn custom_profiler_scope_cpu() {
+    custom_profiler_scope(true)
+}
+
+fn custom_profiler_scope(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("custom_profiler_scope", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(8., 8., 56., 56.),
+    );
+    // The label and time range of each scope, if the device has timers.
+    let mut scopes = None;
+    pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+                // Queries without timers are left out of the results, so there's nothing to
+                // check.
+                return Ok(());
+            }
+            let profiler = renderer.profiler();
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+            let query = profiler.begin_query("custom", &mut encoder, device);
+            profiler.end_query(&mut encoder, query);
+            profiler.resolve_queries(&mut encoder);
+            queue.submit([encoder.finish()]);
+            profiler.end_frame()?;
+            device.poll(wgpu::Maintain::Wait);
+            let results = profiler
+                .process_finished_frame(queue.get_timestamp_period())
+                .ok_or_else(|| anyhow!("Profiler frame didn't finish"))?;
+            scopes = Some(
+                results
+                    .into_iter()
+                    .map(|result| (result.label, result.time))
+                    .collect::<Vec<_>>(),
+            );
+            Ok(())
+        },
+    ))
+    .unwrap();
+    let Some(scopes) = scopes else {
+        eprintln!("skipping custom_profiler_scope: the device doesn't support TIMESTAMP_QUERY");
+        return;
+    };
+    let labels: Vec<_> = scopes.iter().map(|(label, _)| label.as_str()).collect();
+    assert_eq!(labels, ["render_to_texture", "custom"]);
+    // The render took some time, and the custom scope was recorded after it.
+    let (render, custom) = (&scopes[0].1, &scopes[1].1);
+    assert!(render.start < render.end, "render took {render:?}");
+    assert!(custom.start <= custom.end, "custom scope took {custom:?}");
+    assert!(
+        render.end <= custom.start,
+        "custom scope {custom:?} started before the render {render:?} ended"
+    );
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use vello::{
+    kurbo::Affine,
+    peniko::{Blob, Format, Image},
+    PixelRect, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn read_region_gpu() {
+    read_region(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn read_region_cpu() {
+    read_region(true)
+}
+
+fn read_region(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("read_region", 64, 48)
+    };
+    // Each pixel holds its own coordinates in the red and green channels.
+    let mut pattern = Vec::with_capacity(64 * 48 * 4);
+    for y in 0..48u8 {
+        for x in 0..64u8 {
+            pattern.extend([x, y, 0, 255]);
+        }
+    }
+    let image = Image::new(Blob::new(Arc::new(pattern)), Format::Rgba8, 64, 48);
+    let mut scene = Scene::new();
+    scene.draw_image(&image, Affine::IDENTITY);
+    // An odd width, so that the rows of the copy are padded.
+    let rect = PixelRect {
+        x: 13,
+        y: 7,
+        width: 10,
+        height: 10,
+    };
+    let mut region = vec![];
+    pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            region = renderer
+                .read_region(device, queue, target, rect)
+                .or_else(|_| bail!("Got non-Send/Sync error from reading back"))?;
+            let outside = PixelRect { x: 60, ..rect };
+            assert!(renderer
+                .read_region(device, queue, target, outside)
+                .is_err());
+            Ok(())
+        },
+    ))
+    .unwrap();
+    assert_eq!(region.len(), 10 * 10 * 4);
+    for (y, row) in region.chunks(10 * 4).enumerate() {
+        for (x, pixel) in row.chunks(4).enumerate() {
+            assert_eq!(pixel, [13 + x as u8, 7 + y as u8, 0, 255], "({x}, {y})");
+        }
+    }
+}
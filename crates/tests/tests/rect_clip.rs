@@ -0,0 +1,136 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const SIZE: u32 = 128;
+const DEPTH: usize = 1000;
+/// The intersection of the nested clips.
+const CLIP: Rect = Rect::new(40.5, 30.25, 100.75, 110.);
+
+/// Returns the `i`th of the nested clips, which close in on `CLIP` from every side, with
+/// some rectangles larger than their parents in between.
+fn clip(i: usize) -> Rect {
+    if i % 7 == 3 {
+        return Rect::new(-10., -10., SIZE as f64 + 10., SIZE as f64 + 10.);
+    }
+    let t = i as f64 / (DEPTH - 1) as f64;
+    let size = SIZE as f64;
+    Rect::new(
+        CLIP.x0 * t,
+        CLIP.y0 * t,
+        size - (size - CLIP.x1) * t,
+        size - (size - CLIP.y1) * t,
+    )
+}
+
+fn nested_clips(rect_clips: bool) -> Scene {
+    let mut scene = Scene::new();
+    for i in 0..DEPTH {
+        if rect_clips {
+            scene.push_rect_clip(Affine::IDENTITY, &clip(i));
+        } else {
+            scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip(i));
+        }
+    }
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(0., 0., SIZE as f64, SIZE as f64),
+    );
+    for _ in 0..DEPTH {
+        if rect_clips {
+            scene.pop_rect_clip();
+        } else {
+            scene.pop_layer();
+        }
+    }
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn nested_rect_clips_gpu() {
+    nested_rect_clips(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn nested_rect_clips_cpu() {
+    nested_rect_clips(true)
+}
+
+fn nested_rect_clips(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("nested_rect_clips", SIZE, SIZE)
+    };
+    let mut scene = nested_clips(true);
+    // Content drawn after the clips are popped isn't clipped.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::BLUE,
+        None,
+        &Rect::new(0., 0., 8., 8.),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: u32, y: u32| &data[((y * SIZE + x) * 4) as usize..][..4];
+    assert_eq!(pixel(4, 4), [0, 0, 255, 255]);
+    let coverage =
+        |p: u32, min: f64, max: f64| (max.min(p as f64 + 1.) - min.max(p as f64)).max(0.);
+    for y in 8..SIZE {
+        for x in 0..SIZE {
+            let expected = coverage(x, CLIP.x0, CLIP.x1) * coverage(y, CLIP.y0, CLIP.y1) * 255.;
+            let alpha = pixel(x, y)[3] as f64;
+            assert!(
+                (alpha - expected).abs() <= 1.,
+                "pixel ({x}, {y}) has alpha {alpha}, expected {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn rect_clips_use_less_memory_than_layers() {
+    let params = TestParams::new("rect_clip_memory", SIZE, SIZE).render_params();
+    let mut unclipped = Scene::new();
+    unclipped.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(0., 0., SIZE as f64, SIZE as f64),
+    );
+    let base = unclipped.estimate_memory(&params);
+    let rect_clips = nested_clips(true).estimate_memory(&params) - base;
+    let layers = nested_clips(false).estimate_memory(&params) - base;
+    assert!(
+        rect_clips * 100 < layers,
+        "rect clips add {rect_clips} bytes, layers add {layers} bytes"
+    );
+    // Only the intersection of the clips is encoded, and no draw objects.
+    let stats = nested_clips(true).stats();
+    assert_eq!(stats.clip_rects, 1);
+    assert_eq!(stats.draw_tags, unclipped.stats().draw_tags);
+}
+
+#[test]
+fn rotated_rect_clip_is_a_layer() {
+    let mut scene = Scene::new();
+    let rect = Rect::new(0., 0., 10., 10.);
+    scene.push_rect_clip(Affine::scale(2.), &rect);
+    scene.push_rect_clip(Affine::rotate(0.5), &rect);
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::RED, None, &rect);
+    scene.pop_rect_clip();
+    scene.pop_rect_clip();
+    // The rotated clip is encoded as a layer, with a begin and end clip draw object.
+    assert_eq!(scene.stats().draw_tags, 3);
+    assert_eq!(scene.stats().clip_rects, 1);
+}
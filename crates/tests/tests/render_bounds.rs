@@ -0,0 +1,131 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Line, Rect, Stroke},
+    peniko::{Color, Fill, Mix},
+    AaSupport, BinSize, PixelRect, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 32;
+
+fn fill_rect(scene: &mut Scene, rect: Rect) {
+    scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &rect);
+}
+
+/// Returns the smallest rectangle containing every pixel with any coverage.
+fn covered_extent(data: &[u8]) -> Option<PixelRect> {
+    let mut extent: Option<(u32, u32, u32, u32)> = None;
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            if data[((y * WIDTH + x) * 4 + 3) as usize] != 0 {
+                extent = Some(extent.map_or((x, y, x, y), |(x0, y0, x1, y1)| {
+                    (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+                }));
+            }
+        }
+    }
+    extent.map(|(x0, y0, x1, y1)| PixelRect {
+        x: x0,
+        y: y0,
+        width: x1 - x0 + 1,
+        height: y1 - y0 + 1,
+    })
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn render_with_bounds_gpu() {
+    render_with_bounds(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn render_with_bounds_cpu() {
+    render_with_bounds(true)
+}
+
+fn render_with_bounds(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("render_with_bounds", WIDTH, HEIGHT)
+    };
+    // A fill, a stroke, and a fill of the whole target which is clipped by a layer.
+    let mut shapes = Scene::new();
+    fill_rect(&mut shapes, Rect::new(8., 4., 20., 12.));
+    shapes.stroke(
+        &Stroke::new(2.),
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Line::new((10., 16.), (18., 16.)),
+    );
+    let clip = Rect::new(24., 8., 28., 26.);
+    shapes.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+    fill_rect(&mut shapes, Rect::new(0., 0., WIDTH as f64, HEIGHT as f64));
+    shapes.pop_layer();
+    // A fill which hangs over the bottom right corner of the target.
+    let mut overhang = Scene::new();
+    fill_rect(&mut overhang, Rect::new(40., 24., 60., 40.));
+    // A fill which is entirely outside of the target.
+    let mut outside = Scene::new();
+    fill_rect(&mut outside, Rect::new(60., 0., 80., 20.));
+
+    let scenes = [
+        (
+            shapes,
+            Some(PixelRect {
+                x: 8,
+                y: 4,
+                width: 20,
+                height: 22,
+            }),
+        ),
+        (
+            overhang,
+            Some(PixelRect {
+                x: 40,
+                y: 24,
+                width: 8,
+                height: 8,
+            }),
+        ),
+        (outside, None),
+        (Scene::new(), None),
+    ];
+    let render_params = params.render_params();
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            for (i, (scene, expected)) in scenes.iter().enumerate() {
+                let rendered = renderer
+                    .render_with_bounds(device, queue, scene, &render_params)
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                assert_eq!(rendered.bounds, *expected, "scene {i}");
+                let image = vello_tests::read_texture(
+                    device,
+                    queue,
+                    &rendered.texture,
+                    (0, 0),
+                    WIDTH,
+                    HEIGHT,
+                )?;
+                // The bounds are exactly the pixels which were drawn.
+                assert_eq!(covered_extent(image.data.data()), *expected, "scene {i}");
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,15 @@
+use vello::util::RenderContext;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn render_context_from_instance() {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+    let mut context = RenderContext::from_instance(instance);
+    assert!(context.devices.is_empty());
+    let device_id = pollster::block_on(context.device(None)).expect("No compatible device found");
+    assert_eq!(context.devices.len(), 1);
+    // Devices are reused rather than created again for every request.
+    assert_eq!(pollster::block_on(context.device(None)), Some(device_id));
+    let adapters = context.instance.enumerate_adapters(wgpu::Backends::all());
+    assert!(!adapters.is_empty());
+}
@@ -0,0 +1,26 @@
+use vello::{AaSupport, BinSize, RendererOptions};
+
+fn options(antialiasing_support: AaSupport) -> RendererOptions {
+    RendererOptions {
+        surface_format: None,
+        use_cpu: false,
+        antialiasing_support,
+        bin_size: BinSize::default(),
+        max_clip_depth: None,
+    }
+}
+
+#[test]
+fn required_features() {
+    let area = options(AaSupport::area_only()).required_features();
+    let msaa16 = options(AaSupport {
+        area: false,
+        msaa8: false,
+        msaa16: true,
+    })
+    .required_features();
+    // The MSAA fine shaders only use core WGSL, so MSAA16 needs exactly what area does.
+    assert!(msaa16.contains(area));
+    assert_eq!(msaa16 - area, wgpu::Features::empty());
+    assert_eq!(options(AaSupport::all()).required_features(), area);
+}
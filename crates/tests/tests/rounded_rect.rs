@@ -0,0 +1,57 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::Color,
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn rounded_rect_border_gpu() {
+    rounded_rect_border(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn rounded_rect_border_cpu() {
+    rounded_rect_border(true)
+}
+
+fn rounded_rect_border(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("rounded_rect_border", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.rounded_rect(
+        Affine::IDENTITY,
+        Rect::new(8., 8., 56., 56.),
+        8.,
+        Color::RED,
+        4.,
+        Color::BLUE,
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let pixel = |x: usize, y: usize| {
+        let offset = (y * params.width as usize + x) * 4;
+        &data[offset..offset + 4]
+    };
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    assert_eq!(pixel(32, 32), RED);
+    // The border covers the four pixels inside of each straight edge.
+    for x in 0..64 {
+        let expected = match x {
+            0..=7 | 56..=63 => BLACK,
+            8..=11 | 52..=55 => BLUE,
+            _ => RED,
+        };
+        assert_eq!(pixel(x, 32), expected, "x = {x}");
+        assert_eq!(pixel(32, x), expected, "y = {x}");
+    }
+    // Outside of the rounded corners.
+    assert_eq!(pixel(8, 8), BLACK);
+    assert_eq!(pixel(55, 55), BLACK);
+}
@@ -0,0 +1,51 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect, Stroke},
+    peniko::{Color, Fill},
+    Scene, SceneStats,
+};
+
+fn build(scene: &mut Scene) {
+    for i in 0..100 {
+        let offset = i as f64;
+        scene.fill(
+            Fill::NonZero,
+            Affine::translate((offset, 0.)),
+            Color::RED,
+            None,
+            &Circle::new((10., 10.), 5.),
+        );
+        scene.stroke(
+            &Stroke::new(2.),
+            Affine::IDENTITY,
+            Color::BLUE,
+            None,
+            &Rect::new(offset, 0., offset + 10., 10.),
+        );
+    }
+}
+
+fn capacities(scene: &Scene) -> [usize; 6] {
+    let encoding = scene.encoding();
+    [
+        encoding.path_tags.capacity(),
+        encoding.path_data.capacity(),
+        encoding.draw_tags.capacity(),
+        encoding.draw_data.capacity(),
+        encoding.transforms.capacity(),
+        encoding.styles.capacity(),
+    ]
+}
+
+#[test]
+fn with_capacity_avoids_reallocation() {
+    let mut reference = Scene::new();
+    build(&mut reference);
+    let stats = reference.stats();
+    assert_ne!(stats, SceneStats::default());
+
+    let mut scene = Scene::with_capacity(stats);
+    let reserved = capacities(&scene);
+    build(&mut scene);
+    assert_eq!(capacities(&scene), reserved);
+    assert_eq!(scene.stats(), stats);
+}
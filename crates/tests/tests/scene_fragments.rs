@@ -0,0 +1,95 @@
+use std::thread;
+
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+/// Draws the `part`th half of the content, so that the two halves overlap.
+fn draw_part(scene: &mut Scene, part: usize) {
+    for i in 0..16 {
+        let offset = (part * 16 + i) as f64 * 2.;
+        let color = if part == 0 { Color::RED } else { Color::BLUE };
+        scene.fill(
+            Fill::NonZero,
+            Affine::translate((offset, offset)),
+            &Brush::Solid(color.with_alpha_factor(0.5)),
+            None,
+            &Rect::new(0., 0., 24., 24.),
+        );
+    }
+    scene.push_layer(
+        Mix::Multiply,
+        1.0,
+        Affine::IDENTITY,
+        &Circle::new((32., 32.), 20.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(200, 255, 100)),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    scene.pop_layer();
+}
+
+fn serial() -> Scene {
+    let mut scene = Scene::new();
+    for part in 0..2 {
+        draw_part(&mut scene, part);
+    }
+    scene
+}
+
+fn parallel() -> Scene {
+    let fragments = thread::scope(|s| {
+        let workers: Vec<_> = (0..2)
+            .map(|part| {
+                s.spawn(move || {
+                    let mut fragment = Scene::new();
+                    draw_part(&mut fragment, part);
+                    fragment
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    Scene::from_fragments(fragments)
+}
+
+#[test]
+fn fragments_keep_draw_order() {
+    let serial = serial();
+    let parallel = parallel();
+    assert!(serial.encoding().draw_tags == parallel.encoding().draw_tags);
+    assert_eq!(serial.encoding().n_paths, parallel.encoding().n_paths);
+    assert_eq!(serial.encoding().n_clips, parallel.encoding().n_clips);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_fragments_gpu() {
+    scene_fragments(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_fragments_cpu() {
+    scene_fragments(true)
+}
+
+fn scene_fragments(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("scene_fragments", 64, 64)
+    };
+    let expected = vello_tests::render_sync(serial(), &params).unwrap();
+    let image = vello_tests::render_sync(parallel(), &params).unwrap();
+    assert!(image.data.data() == expected.data.data());
+}
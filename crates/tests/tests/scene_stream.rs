@@ -0,0 +1,146 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{BlendMode, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const DRAWS: usize = 1_000_000;
+const DRAWS_PER_GROUP: usize = 1000;
+/// The target is a grid of `CELLS` by `CELLS` squares, each redrawn many times.
+const CELLS: usize = 64;
+const CELL_SIZE: usize = 4;
+const MEMORY_CAP: usize = 1 << 20;
+
+/// Fills the cell of the `i`th draw with an opaque color unique to it.
+fn draw(scene: &mut Scene, i: usize) {
+    let cell = i % (CELLS * CELLS);
+    let x = (cell % CELLS * CELL_SIZE) as f64;
+    let y = (cell / CELLS * CELL_SIZE) as f64;
+    let color = Color::rgb8((i * 37) as u8, (i * 91) as u8, (i >> 12) as u8);
+    let size = CELL_SIZE as f64;
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        color,
+        None,
+        &Rect::new(x, y, x + size, y + size),
+    );
+}
+
+#[test]
+fn scene_stats_size_adds_up() {
+    let mut a = Scene::new();
+    let mut b = Scene::new();
+    for i in 0..10 {
+        draw(&mut a, i);
+        draw(&mut b, i + 10);
+    }
+    let expected = a.stats().size_in_bytes() + b.stats().size_in_bytes();
+    a.append(&b, None);
+    assert_eq!(a.stats().size_in_bytes(), expected);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_stream_gpu() {
+    scene_stream(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_stream_cpu() {
+    scene_stream(true)
+}
+
+fn scene_stream(use_cpu: bool) {
+    let size = (CELLS * CELL_SIZE) as u32;
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("scene_stream", size, size)
+    };
+    let (mut chunks, mut peak) = (0, 0);
+    let streamed = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let render_params = params.render_params();
+            let mut stream =
+                renderer.stream_to_texture(device, queue, target, &render_params, MEMORY_CAP);
+            for group in (0..DRAWS).step_by(DRAWS_PER_GROUP) {
+                stream
+                    .draw(|scene| {
+                        for i in group..group + DRAWS_PER_GROUP {
+                            draw(scene, i);
+                        }
+                    })
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+            }
+            chunks = stream.chunks_rendered();
+            peak = stream.peak_chunk_size();
+            stream.finish().map_err(|e| anyhow::anyhow!("{e}"))
+        },
+    ))
+    .unwrap();
+    assert!(chunks > 1, "the scene fit in {chunks} chunk");
+    assert!(peak <= MEMORY_CAP, "a chunk took {peak} bytes");
+
+    // Every cell is opaque, so only the last draw in each one is visible.
+    let mut last_draws = Scene::new();
+    for i in DRAWS - CELLS * CELLS..DRAWS {
+        draw(&mut last_draws, i);
+    }
+    let expected = vello_tests::render_sync(last_draws, &params).unwrap();
+    assert!(streamed.data.data() == expected.data.data());
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_stream_open_layer_gpu() {
+    scene_stream_open_layer(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scene_stream_open_layer_cpu() {
+    scene_stream_open_layer(true)
+}
+
+/// A group which leaves a layer open is rejected rather than clipping later chunks.
+fn scene_stream_open_layer(use_cpu: bool) {
+    let size = (CELLS * CELL_SIZE) as u32;
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("scene_stream_open_layer", size, size)
+    };
+    let mut rejected = false;
+    let streamed = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let render_params = params.render_params();
+            let mut stream =
+                renderer.stream_to_texture(device, queue, target, &render_params, MEMORY_CAP);
+            rejected = stream
+                .draw(|scene| {
+                    scene.push_layer(
+                        BlendMode::default(),
+                        1.0,
+                        Affine::IDENTITY,
+                        &Rect::new(0., 0., 1., 1.),
+                    );
+                    draw(scene, 0);
+                })
+                .is_err();
+            stream
+                .draw(|scene| draw(scene, 1))
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            stream.finish().map_err(|e| anyhow::anyhow!("{e}"))
+        },
+    ))
+    .unwrap();
+    assert!(rejected, "the group with an open layer was accepted");
+
+    let mut expected = Scene::new();
+    draw(&mut expected, 1);
+    let expected = vello_tests::render_sync(expected, &params).unwrap();
+    assert!(streamed.data.data() == expected.data.data());
+}
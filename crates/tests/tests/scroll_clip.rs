@@ -0,0 +1,92 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+/// The fixed window of the scroll container.
+const WINDOW: Rect = Rect::new(8., 8., 56., 56.);
+
+/// Rows of the list in content coordinates, alternating in color.
+fn draw_rows(scene: &mut Scene, transform: Affine) {
+    for i in 0..10 {
+        let color = if i & 1 == 0 { Color::RED } else { Color::BLUE };
+        let y = 8. + i as f64 * 12.;
+        scene.fill(
+            Fill::NonZero,
+            transform,
+            color,
+            None,
+            &Rect::new(0., y, 64., y + 12.),
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scroll_clip_gpu() {
+    scroll_clip(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn scroll_clip_cpu() {
+    scroll_clip(true)
+}
+
+fn scroll_clip(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("scroll_clip", 64, 64)
+    };
+    // The container is encoded once, and then scrolled by appending it with an offset.
+    let mut list = Scene::new();
+    let clip = list.push_layer_tracked(Mix::Clip, 1.0, Affine::IDENTITY, &WINDOW);
+    draw_rows(&mut list, Affine::IDENTITY);
+    list.pop_layer();
+    for scroll in [0., 18., 30.] {
+        list.set_layer_transform(clip, Affine::translate((0., scroll)));
+        let mut frame = Scene::new();
+        frame.append(&list, Some(Affine::translate((0., -scroll))));
+        let scrolled = vello_tests::render_sync(frame, &params).unwrap();
+
+        let mut expected = Scene::new();
+        expected.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &WINDOW);
+        draw_rows(&mut expected, Affine::translate((0., -scroll)));
+        expected.pop_layer();
+        let expected = vello_tests::render_sync(expected, &params).unwrap();
+        assert!(
+            scrolled.data.data() == expected.data.data(),
+            "scrolled by {scroll}"
+        );
+
+        // Outside of the window stays the black background, whatever the scroll offset.
+        let pixel = |x: usize, y: usize| {
+            let start = (y * params.width as usize + x) * 4;
+            &scrolled.data.data()[start..start + 4]
+        };
+        assert_eq!(pixel(32, 4), [0, 0, 0, 255]);
+        assert_eq!(pixel(32, 60), [0, 0, 0, 255]);
+        // The first row scrolls out of the top of the window.
+        let first_row_visible = pixel(32, 10) == [255, 0, 0, 255];
+        assert_eq!(first_row_visible, scroll < 2., "scrolled by {scroll}");
+    }
+}
+
+#[test]
+fn layer_transform_is_not_shared() {
+    let mut scene = Scene::new();
+    draw_rows(&mut scene, Affine::IDENTITY);
+    let clip = scene.push_layer_tracked(Mix::Clip, 1.0, Affine::IDENTITY, &WINDOW);
+    draw_rows(&mut scene, Affine::IDENTITY);
+    scene.pop_layer();
+    let before = scene.encoding().transforms.clone();
+    // Rows before the layer, the clip, and the rows inside of it.
+    assert_eq!(before.len(), 3);
+    scene.set_layer_transform(clip, Affine::translate((0., 5.)));
+    let after = &scene.encoding().transforms;
+    assert_eq!(after[0], before[0]);
+    assert_ne!(after[1], before[1]);
+    assert_eq!(after[2], before[2]);
+}
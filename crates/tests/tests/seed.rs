@@ -0,0 +1,132 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, Grain, RenderParams, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn seed_gpu() {
+    seed(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn seed_cpu() {
+    seed(true)
+}
+
+fn seed(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("seed", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(128, 128, 128)),
+        None,
+        &Circle::new((32., 32.), 24.),
+    );
+    let render = |seed| {
+        let render_params = RenderParams {
+            grain: Some(Grain { intensity: 0.1 }),
+            seed,
+            ..params.render_params()
+        };
+        vello_tests::render_frames_sync(vec![(scene.clone(), render_params)], &params)
+            .unwrap()
+            .data
+            .data()
+            .to_vec()
+    };
+    let first = render(1);
+    assert_eq!(first, render(1));
+    let second = render(2);
+    assert_ne!(first, second);
+    // The seed only changes the noise: the coverage of the circle is the same.
+    let alpha = |data: &[u8]| data.chunks(4).map(|p| p[3]).collect::<Vec<_>>();
+    assert_eq!(alpha(&first), alpha(&second));
+    assert_eq!(alpha(&first), alpha(&render(0)));
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn seed_dither_gpu() {
+    seed_dither(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn seed_dither_cpu() {
+    seed_dither(true)
+}
+
+fn seed_dither(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("seed_dither", 32, 32)
+    };
+    let mut scene = Scene::new();
+    // Dark enough that three of the 16 entries of the dither matrix are white, so the
+    // pattern changes with any offset of the matrix.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(49, 49, 49)),
+        None,
+        &Rect::new(0., 0., 32., 32.),
+    );
+    let render = |seed| {
+        let render_params = RenderParams {
+            seed,
+            ..params.render_params()
+        };
+        pollster::block_on(vello_tests::with_renderer(
+            |device| {
+                let options = RendererOptions {
+                    surface_format: None,
+                    use_cpu,
+                    antialiasing_support: AaSupport::area_only(),
+                    bin_size: BinSize::default(),
+                    max_clip_depth: None,
+                };
+                Renderer::new(device, options)
+                    .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+            },
+            |device, queue, renderer| {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Dithered target"),
+                    size: wgpu::Extent3d {
+                        width: params.width,
+                        height: params.height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::R8Unorm,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                });
+                renderer
+                    .render_to_texture_dithered(device, queue, &scene, &texture, &render_params)
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                vello_tests::read_mask(device, queue, &texture, params.width, params.height)
+            },
+        ))
+        .unwrap()
+    };
+    let first = render(1);
+    assert_eq!(first, render(1));
+    let second = render(2);
+    assert_ne!(first, second);
+    // Either way, the same fraction of pixels is white.
+    let white = |mask: &[u8]| mask.iter().filter(|&&v| v == 255).count();
+    assert_eq!(white(&first), 3 * 32 * 32 / 16);
+    assert_eq!(white(&first), white(&second));
+}
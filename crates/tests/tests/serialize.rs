@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use vello::{
+    kurbo::{Affine, Circle, Rect, Stroke},
+    peniko::{Blob, Brush, Color, Fill, Format, Gradient, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::GREEN),
+        None,
+        &Rect::new(4., 4., 60., 28.),
+    );
+    let gradient = Gradient::new_linear((0., 0.), (64., 64.)).with_stops([Color::RED, Color::BLUE]);
+    scene.stroke(
+        &Stroke::new(4.),
+        Affine::IDENTITY,
+        &gradient,
+        None,
+        &Circle::new((32., 40.), 16.),
+    );
+    let pixels = [255, 255, 0, 255, 0, 255, 255, 255].repeat(8);
+    let image = Image::new(Blob::new(Arc::new(pixels)), Format::Rgba8, 4, 4);
+    scene.draw_image(&image, Affine::translate((48., 48.)));
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn serialize_gpu() {
+    serialize(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn serialize_cpu() {
+    serialize(true)
+}
+
+fn serialize(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("serialize", 64, 64)
+    };
+    let original = scene();
+    let bytes = original.to_bytes().unwrap();
+    let loaded = Scene::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.to_bytes().unwrap(), bytes);
+    let expected = vello_tests::render_sync(original, &params).unwrap();
+    let image = vello_tests::render_sync(loaded, &params).unwrap();
+    assert!(image.data.data() == expected.data.data());
+}
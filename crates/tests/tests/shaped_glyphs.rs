@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font},
+    skrifa::{
+        instance::{LocationRef, Size},
+        FontRef, MetadataProvider,
+    },
+    PositionedGlyph, Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const FONT_SIZE: f32 = 12.;
+const TEXT: &str = "Shaped runs of text!";
+
+/// A run as a shaper would lay it out, with every third glyph raised as if it were a mark.
+fn shaped_run() -> Vec<PositionedGlyph> {
+    let font_ref = FontRef::new(ROBOTO).unwrap();
+    let charmap = font_ref.charmap();
+    let metrics = font_ref.glyph_metrics(Size::new(FONT_SIZE), LocationRef::default());
+    TEXT.chars()
+        .enumerate()
+        .map(|(i, ch)| {
+            let id = charmap.map(ch).unwrap();
+            PositionedGlyph {
+                id: id.to_u16() as u32,
+                x_advance: metrics.advance_width(id).unwrap(),
+                y_advance: 0.,
+                x_offset: 0.5,
+                y_offset: if i % 3 == 0 { -2. } else { 0. },
+            }
+        })
+        .collect()
+}
+
+/// The glyphs of `run`, placed by summing the advances.
+fn expected_glyphs(run: &[PositionedGlyph]) -> Vec<Glyph> {
+    let mut pen = (0., 0.);
+    run.iter()
+        .map(|glyph| {
+            let placed = Glyph {
+                id: glyph.id,
+                x: pen.0 + glyph.x_offset,
+                y: pen.1 + glyph.y_offset,
+            };
+            pen.0 += glyph.x_advance;
+            pen.1 += glyph.y_advance;
+            placed
+        })
+        .collect()
+}
+
+fn font() -> Font {
+    Font::new(Blob::new(Arc::new(ROBOTO)), 0)
+}
+
+#[test]
+fn shaped_glyph_positions() {
+    let run = shaped_run();
+    assert_eq!(run.len(), 20);
+    let mut scene = Scene::new();
+    scene.draw_glyphs_shaped(&font(), FONT_SIZE, Affine::IDENTITY, &run, Color::WHITE);
+    let encoding = scene.encoding();
+    assert_eq!(encoding.resources.glyph_runs.len(), 1);
+    let glyphs = &encoding.resources.glyphs;
+    let expected = expected_glyphs(&run);
+    assert_eq!(glyphs.len(), expected.len());
+    for (glyph, expected) in glyphs.iter().zip(&expected) {
+        assert_eq!(
+            (glyph.id, glyph.x, glyph.y),
+            (expected.id, expected.x, expected.y)
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn shaped_glyphs_gpu() {
+    shaped_glyphs(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn shaped_glyphs_cpu() {
+    shaped_glyphs(true)
+}
+
+fn shaped_glyphs(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("shaped_glyphs", 128, 24)
+    };
+    let transform = Affine::translate((4., 16.));
+    let run = shaped_run();
+    let mut shaped = Scene::new();
+    shaped.draw_glyphs_shaped(&font(), FONT_SIZE, transform, &run, Color::WHITE);
+    let mut placed = Scene::new();
+    placed
+        .draw_glyphs(&font())
+        .font_size(FONT_SIZE)
+        .transform(transform)
+        .brush(Color::WHITE)
+        .draw(Fill::NonZero, expected_glyphs(&run).into_iter());
+    let image = vello_tests::render_sync(shaped, &params).unwrap();
+    let expected = vello_tests::render_sync(placed, &params).unwrap();
+    assert!(image.data.data().contains(&255));
+    assert!(image.data.data() == expected.data.data());
+}
@@ -0,0 +1,74 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect, Stroke},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+/// Content covering a 256x256 region and reaching far past it on every side.
+fn large_scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::rgb8(40, 80, 160)),
+        None,
+        &Rect::new(-1000., -1000., 1000., 1000.),
+    );
+    for i in 0..8 {
+        let center = (i as f64 * 32. + 12., i as f64 * 24. + 8.);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(Color::rgba8(255, (i * 30) as u8, 0, 200)),
+            None,
+            &Circle::new(center, 20. + i as f64 * 10.),
+        );
+    }
+    scene.stroke(
+        &Stroke::new(6.),
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &Circle::new((128., 128.), 500.),
+    );
+    scene
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn small_target_gpu() {
+    small_target(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn small_target_cpu() {
+    small_target(true)
+}
+
+fn small_target(use_cpu: bool) {
+    let full = TestParams {
+        use_cpu,
+        ..TestParams::new("small_target_full", 256, 256)
+    };
+    let expected = vello_tests::render_sync(large_scene(), &full).unwrap();
+    // Deliberately not a multiple of the tile size, so that the edge tiles are partial.
+    let cropped = TestParams {
+        use_cpu,
+        ..TestParams::new("small_target", 50, 30)
+    };
+    let image = vello_tests::render_sync(large_scene(), &cropped).unwrap();
+    assert_eq!((image.width, image.height), (50, 30));
+    for y in 0..30 {
+        assert!(
+            image_row(image.data.data(), 50, y)
+                == &image_row(expected.data.data(), 256, y)[..50 * 4],
+            "row {y} differs from the full size render"
+        );
+    }
+}
+
+fn image_row(data: &[u8], width: usize, y: usize) -> &[u8] {
+    &data[y * width * 4..(y + 1) * width * 4]
+}
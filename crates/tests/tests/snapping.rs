@@ -0,0 +1,55 @@
+use vello::{
+    kurbo::{Affine, BezPath, Point, Vec2},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn snapped_paths_gpu() {
+    snapped_paths(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn snapped_paths_cpu() {
+    snapped_paths(true)
+}
+
+/// A star whose points are offset by `jitter`, which should be removed by snapping.
+fn star(jitter: f64) -> Scene {
+    let mut scene = Scene::new();
+    scene.set_path_snapping(Some(4));
+    let center = Point::new(32., 32.);
+    let mut path = BezPath::new();
+    for i in 0..10 {
+        let radius = if i % 2 == 0 { 28.3 } else { 11.7 };
+        let angle = i as f64 * std::f64::consts::PI / 5.;
+        let point = center + Vec2::from_angle(angle) * radius + Vec2::new(jitter, -jitter);
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    path.close_path();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::WHITE),
+        None,
+        &path,
+    );
+    scene
+}
+
+fn snapped_paths(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("snapped_paths", 64, 64)
+    };
+    let first = vello_tests::render_sync(star(0.), &params).unwrap();
+    let second = vello_tests::render_sync(star(1e-4), &params).unwrap();
+    assert_eq!(first.data.data(), second.data.data());
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "debug")]
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, Renderer, RendererOptions, Scene, StageInfo,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stage_callback_gpu() {
+    stage_callback(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stage_callback_cpu() {
+    stage_callback(true)
+}
+
+fn stage_callback(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("stage_callback", 32, 32)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(8., 8., 24., 24.),
+    );
+    let stages = Arc::new(Mutex::new(vec![]));
+    let sink = stages.clone();
+    pollster::block_on(vello_tests::render_with_renderer(
+        &params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            let mut renderer = Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
+            renderer.set_stage_callback(Some(Box::new(move |stage: &StageInfo| {
+                assert!(!stage.buffers.is_empty(), "{} has no buffers", stage.label);
+                sink.lock().unwrap().push(stage.label);
+            })));
+            Ok(renderer)
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    // A scene without clips skips the clip stages.
+    assert_eq!(
+        *stages.lock().unwrap(),
+        [
+            "pathtag_reduce",
+            "pathtag_scan",
+            "bbox_clear",
+            "flatten",
+            "draw_reduce",
+            "draw_leaf",
+            "binning",
+            "tile_alloc",
+            "path_count_setup",
+            "path_count",
+            "backdrop",
+            "coarse",
+            "path_tiling_setup",
+            "path_tiling",
+            "fine_area",
+        ]
+    );
+}
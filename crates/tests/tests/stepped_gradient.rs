@@ -0,0 +1,47 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill, Gradient},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stepped_gradient_gpu() {
+    stepped_gradient(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stepped_gradient_cpu() {
+    stepped_gradient(true)
+}
+
+fn stepped_gradient(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("stepped_gradient", 64, 8)
+    };
+    let gradient =
+        Gradient::new_linear((0., 0.), (64., 0.)).with_stops([Color::BLACK, Color::WHITE]);
+    let mut scene = Scene::new();
+    scene.fill_stepped_gradient(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &gradient,
+        4,
+        None,
+        &Rect::new(0., 0., 64., 8.),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let row: Vec<u8> = image.data.data()[..64 * 4]
+        .chunks_exact(4)
+        .map(|pixel| pixel[0])
+        .collect();
+    // Four bands of 16 pixels, from black to white.
+    for (band, expected) in row.chunks(16).zip([0, 85, 170, 255]) {
+        for &value in band {
+            assert!(value.abs_diff(expected) <= 1, "{row:?}");
+        }
+    }
+}
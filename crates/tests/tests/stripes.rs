@@ -0,0 +1,69 @@
+use std::f64::consts::FRAC_PI_4;
+
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Extend, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+fn stripes_gradient() {
+    let gradient = vello::stripes(0., 4., &[Color::RED, Color::BLUE, Color::LIME]);
+    assert_eq!(gradient.extend, Extend::Repeat);
+    let offsets: Vec<f32> = gradient.stops.iter().map(|stop| stop.offset).collect();
+    assert_eq!(offsets, [0., 1. / 3., 1. / 3., 2. / 3., 2. / 3., 1.]);
+    assert!(vello::stripes(0., 4., &[]).stops.is_empty());
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stripes_gpu() {
+    stripes(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn stripes_cpu() {
+    stripes(true)
+}
+
+fn stripes(use_cpu: bool) {
+    const WIDTH: f64 = 8.;
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("stripes", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &vello::stripes(FRAC_PI_4, WIDTH, &[Color::RED, Color::BLUE]),
+        None,
+        &Rect::new(0., 0., 64., 64.),
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let mut checked = 0;
+    for y in 0..params.height as usize {
+        for x in 0..params.width as usize {
+            // Distance of the pixel center along the gradient, in stripes.
+            let d = (x as f64 + 0.5 + y as f64 + 0.5) / 2_f64.sqrt() / WIDTH;
+            let phase = d.fract();
+            if phase.min(1. - phase) < 0.1 {
+                // Too close to an edge between stripes, which depends on where in the pixel the
+                // gradient is sampled.
+                continue;
+            }
+            let expected = if d.floor() as usize & 1 == 0 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 255, 255]
+            };
+            let ix = (y * params.width as usize + x) * 4;
+            assert_eq!(data[ix..ix + 4], expected, "pixel ({x}, {y})");
+            checked += 1;
+        }
+    }
+    assert!(checked > 64 * 64 / 4 * 3);
+}
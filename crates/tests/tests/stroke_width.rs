@@ -0,0 +1,53 @@
+use std::f64::consts::FRAC_PI_3;
+
+use vello::{
+    kurbo::{Affine, Stroke},
+    transformed_stroke_width,
+};
+
+#[test]
+fn uniform_scale() {
+    let stroke = Stroke::new(1.0);
+    assert_eq!(
+        transformed_stroke_width(&stroke, Affine::scale(2.0)),
+        (2.0, 2.0)
+    );
+    // Rotation and translation don't change the width.
+    let transform =
+        Affine::translate((30.0, -4.0)) * Affine::rotate(FRAC_PI_3) * Affine::scale(2.0);
+    let (min, max) = transformed_stroke_width(&stroke, transform);
+    assert!(
+        (min - 2.0).abs() < 1e-9 && (max - 2.0).abs() < 1e-9,
+        "{min} {max}"
+    );
+    // Neither does a reflection.
+    assert_eq!(
+        transformed_stroke_width(&Stroke::new(3.0), Affine::FLIP_X),
+        (3.0, 3.0)
+    );
+}
+
+#[test]
+fn anisotropic_scale() {
+    let stroke = Stroke::new(1.5);
+    assert_eq!(
+        transformed_stroke_width(&stroke, Affine::scale_non_uniform(2.0, 4.0)),
+        (3.0, 6.0)
+    );
+    // The range is the same however the scaled stroke is rotated.
+    let transform = Affine::rotate(FRAC_PI_3) * Affine::scale_non_uniform(4.0, 2.0);
+    let (min, max) = transformed_stroke_width(&stroke, transform);
+    assert!(
+        (min - 3.0).abs() < 1e-9 && (max - 6.0).abs() < 1e-9,
+        "{min} {max}"
+    );
+    // A skew widens the stroke in one direction and narrows it in another, but preserves area.
+    let (min, max) = transformed_stroke_width(&Stroke::new(1.0), Affine::skew(1.0, 0.0));
+    assert!(min < 1.0 && max > 1.0);
+    assert!((min * max - 1.0).abs() < 1e-9);
+    // A degenerate transform collapses the stroke in one direction.
+    assert_eq!(
+        transformed_stroke_width(&Stroke::new(1.0), Affine::scale_non_uniform(5.0, 0.0)),
+        (0.0, 5.0)
+    );
+}
@@ -0,0 +1,79 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::Color,
+    AaSupport, BinSize, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn last_submit_completed_at_gpu() {
+    last_submit_completed_at(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn last_submit_completed_at_cpu() {
+    last_submit_completed_at(true)
+}
+
+fn last_submit_completed_at(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("last_submit_completed_at", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        vello::peniko::Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Circle::new((32., 32.), 24.),
+    );
+    let render_params = params.render_params();
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            assert!(renderer.last_submit_completed_at().is_none());
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Target texture"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut last = None;
+            for frame in 0..3 {
+                renderer
+                    .render_to_texture(device, queue, &scene, &view, &render_params)
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+                device.poll(wgpu::Maintain::Wait);
+                let completed = renderer.last_submit_completed_at();
+                assert!(completed.is_some(), "frame {frame}");
+                assert!(completed > last, "frame {frame}");
+                last = completed;
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
@@ -0,0 +1,92 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    AaSupport, BinSize, RenderParams, Renderer, RendererOptions, Scene, TargetUsage,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn target_usage_gpu() {
+    target_usage(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn target_usage_cpu() {
+    target_usage(true)
+}
+
+fn target_usage(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("target_usage", 16, 16)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::RED,
+        None,
+        &Rect::new(0., 0., 16., 16.),
+    );
+    pollster::block_on(vello_tests::with_renderer(
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer| {
+            let mut render = |target_usage| {
+                let render_params = RenderParams {
+                    target_usage,
+                    ..params.render_params()
+                };
+                renderer
+                    .render_to_fitted_texture(device, queue, &scene, &render_params)
+                    .or_else(|_| bail!("Got non-Send/Sync error from rendering"))
+            };
+            // A texture which is only sampled isn't made a copy source.
+            let sampled = render(TargetUsage::Sample)?;
+            assert!(!sampled
+                .texture
+                .usage()
+                .contains(wgpu::TextureUsages::COPY_SRC));
+            assert!(sampled
+                .texture
+                .usage()
+                .contains(wgpu::TextureUsages::TEXTURE_BINDING));
+            // A texture for readback still is, and reads back the rendered scene.
+            let readback = render(TargetUsage::Readback)?;
+            assert!(readback
+                .texture
+                .usage()
+                .contains(wgpu::TextureUsages::COPY_SRC));
+            let image =
+                vello_tests::read_texture(device, queue, &readback.texture, (0, 0), 16, 16)?;
+            assert_eq!(image.data.data()[..4], [255, 0, 0, 255]);
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
+
+#[test]
+fn target_usage_texture_usages() {
+    assert_eq!(TargetUsage::default(), TargetUsage::Readback);
+    let sample = TargetUsage::Sample.texture_usages();
+    let readback = TargetUsage::Readback.texture_usages();
+    assert!(!sample.contains(wgpu::TextureUsages::COPY_SRC));
+    assert!(readback.contains(wgpu::TextureUsages::COPY_SRC));
+    // Both can still be written by the renderer and sampled.
+    let rendered = wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING;
+    assert_eq!(sample, rendered);
+    assert_eq!(readback, rendered | wgpu::TextureUsages::COPY_SRC);
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font, Image},
+    skrifa::{
+        instance::{LocationRef, Size},
+        metrics::Decoration,
+        FontRef, MetadataProvider,
+    },
+    Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+const FONT_SIZE: f32 = 64.;
+const BASELINE: f32 = 56.;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn text_decoration_gpu() {
+    text_decoration(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn text_decoration_cpu() {
+    text_decoration(true)
+}
+
+fn render(params: &TestParams, glyphs: &[Glyph], decorate: bool) -> Image {
+    let font = Font::new(Blob::new(Arc::new(ROBOTO)), 0);
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font)
+        .font_size(FONT_SIZE)
+        .transform(Affine::translate((0., BASELINE as f64)))
+        .brush(Color::WHITE)
+        .underline(decorate)
+        .strikethrough(decorate)
+        .draw(Fill::NonZero, glyphs.iter().copied());
+    vello_tests::render_sync(scene, params).unwrap()
+}
+
+fn text_decoration(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("text_decoration", 160, 80)
+    };
+    let font_ref = FontRef::new(ROBOTO).unwrap();
+    let charmap = font_ref.charmap();
+    let size = Size::new(FONT_SIZE);
+    let advances = font_ref.glyph_metrics(size, LocationRef::default());
+    let mut pen_x = 8.;
+    let mut glyphs = vec![];
+    for ch in "ace".chars() {
+        let gid = charmap.map(ch).unwrap();
+        glyphs.push(Glyph {
+            id: gid.to_u16() as u32,
+            x: pen_x,
+            y: 0.,
+        });
+        pen_x += advances.advance_width(gid).unwrap();
+    }
+    let metrics = font_ref.metrics(size, LocationRef::default());
+    // The pixel row through the middle of each decoration.
+    let row = |decoration: Decoration| {
+        (BASELINE - decoration.offset + decoration.thickness / 2.) as usize
+    };
+    let underline = row(metrics.underline.unwrap());
+    let strikethrough = row(metrics.strikeout.unwrap());
+    let covered = |image: &Image, y: usize| {
+        let data = image.data.data();
+        (9..pen_x as usize - 1).all(|x| data[(y * params.width as usize + x) * 4] == 255)
+    };
+
+    let plain = render(&params, &glyphs, false);
+    assert!(!covered(&plain, underline));
+    assert!(!covered(&plain, strikethrough));
+
+    let decorated = render(&params, &glyphs, true);
+    assert!(
+        covered(&decorated, underline),
+        "no underline at row {underline}"
+    );
+    assert!(
+        covered(&decorated, strikethrough),
+        "no strikethrough at row {strikethrough}"
+    );
+    // Nothing is drawn past the advance of the last glyph.
+    let data = decorated.data.data();
+    let x = pen_x as usize + 2;
+    assert_eq!(data[(underline * params.width as usize + x) * 4], 0);
+}
@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use vello::{
+    glyph::Glyph,
+    kurbo::Affine,
+    peniko::{Blob, Color, Fill, Font},
+    skrifa::{FontRef, MetadataProvider},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn text_outline_gpu() {
+    text_outline(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn text_outline_cpu() {
+    text_outline(true)
+}
+
+fn text_outline(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::rgb8(128, 128, 128),
+        ..TestParams::new("text_outline", 48, 64)
+    };
+    let font = Font::new(Blob::new(Arc::new(ROBOTO)), 0);
+    let gid = FontRef::new(ROBOTO).unwrap().charmap().map('I').unwrap();
+    let glyph = Glyph {
+        id: gid.to_u16() as u32,
+        x: 16.,
+        y: 0.,
+    };
+    let mut scene = Scene::new();
+    scene
+        .draw_glyphs(&font)
+        .font_size(48.)
+        .transform(Affine::translate((0., 52.)))
+        .brush(Color::WHITE)
+        .outline(2., Color::BLACK)
+        .draw(Fill::NonZero, std::iter::once(glyph));
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    // A row through the middle of the stem of the "I".
+    let row = 34;
+    let pixel = |x: usize| &data[(row * params.width as usize + x) * 4..][..3];
+    let white: Vec<usize> = (0..params.width as usize)
+        .filter(|&x| pixel(x).iter().all(|&c| c == 255))
+        .collect();
+    let (&left, &right) = (white.first().unwrap(), white.last().unwrap());
+    assert!(right - left >= 2, "stem is too narrow: {left}..={right}");
+    for x in [left - 2, right + 2] {
+        assert_eq!(pixel(x), [0, 0, 0], "outline at x = {x}");
+    }
+    for x in [left - 6, right + 6] {
+        assert_eq!(pixel(x), [128, 128, 128], "background at x = {x}");
+    }
+}
@@ -0,0 +1,51 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn thin_fill_gpu() {
+    thin_fill(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn thin_fill_cpu() {
+    thin_fill(true)
+}
+
+fn thin_fill(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("thin_fill", 64, 64)
+    };
+    // Slivers at several offsets within their pixel, including ones near the right edge of
+    // a tile, where the tile relative coordinates are largest.
+    let columns = [(4, 0.1), (9, 0.35), (15, 0.65), (31, 0.6), (46, 0.)];
+    let mut scene = Scene::new();
+    for (x, offset) in columns {
+        let x0 = x as f64 + offset;
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            &Brush::Solid(Color::WHITE),
+            None,
+            &Rect::new(x0, 8., x0 + 0.3, 56.),
+        );
+    }
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    let coverage = |x: usize, y: usize| data[(y * params.width as usize + x) * 4];
+    for (x, _) in columns {
+        for y in 8..56 {
+            // 30% of 255.
+            let value = coverage(x, y);
+            assert!(value.abs_diff(77) <= 2, "({x}, {y}) has coverage {value}");
+            assert_eq!(coverage(x - 1, y), 0);
+            assert_eq!(coverage(x + 1, y), 0);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use vello::{
+    kurbo::{Affine, Line, Stroke},
+    peniko::Color,
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn thin_line_seams_gpu() {
+    thin_line_seams(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn thin_line_seams_cpu() {
+    thin_line_seams(true)
+}
+
+fn thin_line_seams(use_cpu: bool) {
+    const WIDTH: u32 = 256;
+    const HEIGHT: u32 = 160;
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("thin_line_seams", WIDTH, HEIGHT)
+    };
+    let line = Line::new((0., 0.), (WIDTH as f64, HEIGHT as f64));
+    let mut scene = Scene::new();
+    scene.stroke(
+        &Stroke::new(1.),
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &line,
+    );
+    let image = vello_tests::render_sync(scene, &params).unwrap();
+    let data = image.data.data();
+    // Each row crosses the line over the same length, so the coverage in each row adds up to
+    // the same total. A gap where the line crosses the edge of a tile would show up as a row
+    // with less coverage than the others.
+    let expected = 255. * line.length() / HEIGHT as f64;
+    for y in 4..HEIGHT as usize - 4 {
+        let row = &data[y * WIDTH as usize * 4..(y + 1) * WIDTH as usize * 4];
+        let total: u32 = row.chunks_exact(4).map(|pixel| pixel[3] as u32).sum();
+        let error = (total as f64 - expected).abs() / expected;
+        assert!(
+            error < 0.01,
+            "row {y} has coverage {total}, expected {expected}"
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    AaSupport, BinSize, RenderParams, Renderer, RendererOptions, Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn tiled_canvas_gpu() {
+    tiled_canvas(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn tiled_canvas_cpu() {
+    tiled_canvas(true)
+}
+
+fn tiled_canvas(use_cpu: bool) {
+    const SIZE: u32 = 10000;
+    const SEAM: u32 = SIZE / 2;
+    let mut scene = Scene::new();
+    // A square straddling the point where all four tiles meet.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(4990., 4990., 5010., 5010.),
+    );
+    let params = RenderParams {
+        width: SIZE,
+        height: SIZE,
+        ..Default::default()
+    };
+    let new_renderer = |device: &wgpu::Device| {
+        let options = RendererOptions {
+            surface_format: None,
+            use_cpu,
+            antialiasing_support: AaSupport::area_only(),
+            bin_size: BinSize::default(),
+            max_clip_depth: None,
+        };
+        Renderer::new(device, options)
+            .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+    };
+    pollster::block_on(vello_tests::with_renderer(
+        new_renderer,
+        |device, queue, renderer| {
+            let tiles = renderer
+                .render_to_textures_tiled(device, queue, &scene, &params, Some(8192))
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            let layout: Vec<_> = tiles
+                .iter()
+                .map(|tile| (tile.x, tile.y, tile.width, tile.height))
+                .collect();
+            assert_eq!(
+                layout,
+                [
+                    (0, 0, SEAM, SEAM),
+                    (SEAM, 0, SEAM, SEAM),
+                    (0, SEAM, SEAM, SEAM),
+                    (SEAM, SEAM, SEAM, SEAM),
+                ]
+            );
+            // Check a 32x32 window on each side of the seams, in canvas coordinates.
+            for tile in &tiles {
+                let x0 = if tile.x == 0 { SEAM - 32 } else { 0 };
+                let y0 = if tile.y == 0 { SEAM - 32 } else { 0 };
+                let image =
+                    vello_tests::read_texture(device, queue, &tile.texture, (x0, y0), 32, 32)?;
+                for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+                    let x = tile.x + x0 + i as u32 % 32;
+                    let y = tile.y + y0 + i as u32 / 32;
+                    let inside = (4990..5010).contains(&x) && (4990..5010).contains(&y);
+                    let expected = if inside {
+                        [255, 0, 0, 255]
+                    } else {
+                        [0, 0, 0, 255]
+                    };
+                    assert_eq!(pixel, expected, "canvas pixel ({x}, {y})");
+                }
+            }
+            Ok(())
+        },
+    ))
+    .unwrap();
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn flipped_tiled_canvas_gpu() {
+    flipped_tiled_canvas(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn flipped_tiled_canvas_cpu() {
+    flipped_tiled_canvas(true)
+}
+
+fn flipped_tiled_canvas(use_cpu: bool) {
+    const WIDTH: u32 = 96;
+    const HEIGHT: u32 = 64;
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("flipped_tiled_canvas", WIDTH, HEIGHT)
+    };
+    let render_params = RenderParams {
+        flip_x: true,
+        flip_y: true,
+        ..params.render_params()
+    };
+    let mut scene = Scene::new();
+    // Squares of different colors in the top left corner and across the middle of the
+    // canvas, so that flipping within each tile would move them.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(4., 4., 20., 12.),
+    );
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::BLUE),
+        None,
+        &Rect::new(40., 24., 60., 50.),
+    );
+    let mut tiled = vec![0; (WIDTH * HEIGHT * 4) as usize];
+    let single = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let tiles = renderer
+                .render_to_textures_tiled(device, queue, &scene, &render_params, Some(40))
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            assert_eq!(tiles.len(), 6);
+            for tile in &tiles {
+                let image = vello_tests::read_texture(
+                    device,
+                    queue,
+                    &tile.texture,
+                    (0, 0),
+                    tile.width,
+                    tile.height,
+                )?;
+                for (i, pixel) in image.data.data().chunks_exact(4).enumerate() {
+                    let x = tile.x + i as u32 % tile.width;
+                    let y = tile.y + i as u32 / tile.width;
+                    let ix = ((y * WIDTH + x) * 4) as usize;
+                    tiled[ix..ix + 4].copy_from_slice(pixel);
+                }
+            }
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &render_params)
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            Ok(())
+        },
+    ))
+    .unwrap();
+    let single = single.data.data();
+    for (i, (tiled, single)) in tiled
+        .chunks_exact(4)
+        .zip(single.chunks_exact(4))
+        .enumerate()
+    {
+        let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+        assert_eq!(tiled, single, "canvas pixel ({x}, {y})");
+    }
+    // The red square ends up in the bottom right corner.
+    let ix = (((HEIGHT - 8) * WIDTH + WIDTH - 8) * 4) as usize;
+    assert_eq!(single[ix..ix + 4], [255, 0, 0, 255]);
+}
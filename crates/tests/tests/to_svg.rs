@@ -0,0 +1,91 @@
+use vello::{
+    kurbo::{Affine, Circle, Rect, Stroke},
+    peniko::{Color, Fill, Gradient, Mix},
+    Scene,
+};
+
+#[test]
+fn rect_and_gradient_circle() {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::translate((5., 5.)),
+        Color::rgb8(255, 0, 0),
+        None,
+        &Rect::new(0., 0., 20., 10.),
+    );
+    let gradient = Gradient::new_linear((30., 0.), (50., 0.))
+        .with_stops([Color::rgb8(0, 0, 255), Color::rgb8(0, 255, 0)]);
+    scene.fill(
+        Fill::EvenOdd,
+        Affine::IDENTITY,
+        &gradient,
+        None,
+        &Circle::new((40., 20.), 10.),
+    );
+    let svg = scene.to_svg();
+    assert!(svg.starts_with("<svg"), "{svg}");
+    assert!(svg.ends_with("</svg>"), "{svg}");
+    assert!(
+        svg.contains(r##"<rect x="0" y="0" width="20" height="10" transform="matrix(1 0 0 1 5 5)" fill="#ff0000"/>"##),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r##"<linearGradient id="gradient0" gradientUnits="userSpaceOnUse" x1="30" y1="0" x2="50" y2="0""##),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r##"<stop offset="0" stop-color="#0000ff"/>"##),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r##"<stop offset="1" stop-color="#00ff00"/>"##),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r##"fill="url(#gradient0)" fill-rule="evenodd"/>"##),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r#"<path d="M50 20 C50 25.51915 45.51915 30 40 30 C"#),
+        "{svg}"
+    );
+}
+
+#[test]
+fn strokes_and_clips() {
+    let mut scene = Scene::new();
+    scene.push_layer(
+        Mix::Multiply,
+        0.5,
+        Affine::IDENTITY,
+        &Rect::new(0., 0., 8., 8.),
+    );
+    scene.stroke(
+        &Stroke::new(2.),
+        Affine::IDENTITY,
+        Color::rgba8(0, 0, 0, 128),
+        None,
+        &Rect::new(1., 1., 7., 7.),
+    );
+    scene.pop_layer();
+    let svg = scene.to_svg();
+    assert!(
+        svg.contains(
+            r##"<clipPath id="clip0"><rect x="0" y="0" width="8" height="8"/></clipPath>"##
+        ),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(
+            r##"<g clip-path="url(#clip0)" opacity="0.5" style="mix-blend-mode:multiply">"##
+        ),
+        "{svg}"
+    );
+    // Strokes are expanded into fills when they are encoded.
+    assert!(svg.contains(r##"<path d="M1 0 L7 0 C"##), "{svg}");
+    assert!(
+        svg.ends_with(r##"Z" fill="#000000" fill-opacity="0.5019608"/></g></svg>"##),
+        "{svg}"
+    );
+}
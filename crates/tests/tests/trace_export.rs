@@ -0,0 +1,90 @@
+#![cfg(feature = "wgpu-profiler")]
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn export_trace_gpu() {
+    export_trace(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn export_trace_cpu() {
+    export_trace(true)
+}
+
+fn export_trace(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("export_trace", 64, 64)
+    };
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(8., 8., 56., 56.),
+    );
+    let path = std::env::temp_dir().join(format!("vello_export_trace_{use_cpu}.json"));
+    let mut timed = false;
+    pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            renderer
+                .render_to_texture(device, queue, &scene, &view, &params.render_params())
+                .or_else(|_| bail!("Got non-Send/Sync error from rendering"))?;
+            renderer
+                .end_profiler_frame(device, queue)
+                .or_else(|_| bail!("Got non-Send/Sync error from profiling"))?;
+            renderer
+                .export_trace(&path)
+                .or_else(|_| bail!("Got non-Send/Sync error from exporting"))?;
+            timed = device.features().contains(wgpu::Features::TIMESTAMP_QUERY);
+            Ok(())
+        },
+    ))
+    .unwrap();
+    let trace: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let events = trace["traceEvents"].as_array().unwrap();
+    if !timed {
+        assert!(events.is_empty());
+        return;
+    }
+    let names: Vec<_> = events
+        .iter()
+        .map(|event| event["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"render_to_texture"));
+    // Stages run on the CPU aren't timed.
+    if !use_cpu {
+        for stage in [
+            "pathtag_reduce",
+            "flatten",
+            "draw_leaf",
+            "binning",
+            "tile_alloc",
+            "path_count",
+            "backdrop_dyn",
+            "coarse",
+            "path_tiling",
+            "fine_area",
+        ] {
+            assert!(names.contains(&stage), "no entry for {stage} in {names:?}");
+        }
+    }
+    for event in events {
+        assert_eq!(event["ph"], "X");
+        assert!(event["dur"].as_f64().unwrap() >= 0.);
+    }
+}
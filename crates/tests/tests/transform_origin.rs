@@ -0,0 +1,70 @@
+use vello::{
+    kurbo::{Affine, Circle, Point, Rect, Vec2},
+    peniko::{Brush, Color, Fill},
+    Scene,
+};
+use vello_tests::TestParams;
+
+const FAR: f64 = 1e7;
+
+/// Draws shapes with sub-pixel edges, placed at `offset` in world space.
+fn draw(scene: &mut Scene, offset: f64) {
+    let view = Affine::translate((-offset, -offset));
+    scene.fill(
+        Fill::NonZero,
+        view,
+        &Brush::Solid(Color::RED),
+        None,
+        &(Rect::new(4.25, 6.5, 29.75, 40.375) + Vec2::new(offset, offset)),
+    );
+    scene.fill(
+        Fill::NonZero,
+        view,
+        &Brush::Solid(Color::BLUE),
+        None,
+        &Circle::new((offset + 44.5, offset + 40.25), 15.3),
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn transform_origin_gpu() {
+    transform_origin(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn transform_origin_cpu() {
+    transform_origin(true)
+}
+
+fn transform_origin(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("transform_origin", 64, 64)
+    };
+    let mut near = Scene::new();
+    draw(&mut near, 0.);
+    let expected = vello_tests::render_sync(near, &params).unwrap();
+
+    let mut far = Scene::new();
+    far.set_transform_origin(Some(Point::new(FAR, FAR)));
+    draw(&mut far, FAR);
+    let image = vello_tests::render_sync(far, &params).unwrap();
+    // Curves are flattened from slightly different control points, so allow for rounding.
+    for (i, (a, b)) in image
+        .data
+        .data()
+        .iter()
+        .zip(expected.data.data())
+        .enumerate()
+    {
+        assert!(a.abs_diff(*b) <= 1, "byte {i}: {a} != {b}");
+    }
+
+    // Without rebasing, the edges are rounded to whole units before they are transformed.
+    let mut unrebased = Scene::new();
+    draw(&mut unrebased, FAR);
+    let image = vello_tests::render_sync(unrebased, &params).unwrap();
+    assert!(image.data.data() != expected.data.data());
+}
@@ -0,0 +1,103 @@
+use vello::{
+    kurbo::{Affine, Circle},
+    peniko::{Brush, Color, Fill, Image},
+    Scene,
+};
+use vello_tests::TestParams;
+
+fn red_circle() -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Circle::new((32., 32.), 20.5),
+    );
+    scene
+}
+
+/// Returns the pixels whose centers are within a pixel of the edge of the circle, as
+/// premultiplied values in `[0, 1]`.
+fn edge_pixels(image: &Image) -> Vec<[f64; 4]> {
+    let data = image.data.data();
+    let mut edges = vec![];
+    for y in 0..image.height as usize {
+        for x in 0..image.width as usize {
+            let distance = (x as f64 + 0.5 - 32.).hypot(y as f64 + 0.5 - 32.);
+            if (distance - 20.5).abs() > 0.5 {
+                continue;
+            }
+            let pixel = &data[(y * image.width as usize + x) * 4..][..4];
+            let alpha = pixel[3] as f64 / 255.;
+            let [r, g, b] = [0, 1, 2].map(|c| pixel[c] as f64 / 255. * alpha);
+            edges.push([r, g, b, alpha]);
+        }
+    }
+    assert!(!edges.is_empty());
+    edges
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn transparent_edges_gpu() {
+    transparent_edges(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn transparent_edges_cpu() {
+    transparent_edges(true)
+}
+
+fn transparent_edges(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::TRANSPARENT,
+        ..TestParams::new("transparent_edges", 64, 64)
+    };
+    let image = vello_tests::render_sync(red_circle(), &params).unwrap();
+    for [r, g, b, a] in edge_pixels(&image) {
+        // With a transparent background, the premultiplied color is the coverage of the red.
+        assert!((r - a).abs() < 0.01, "dark fringe: red {r} at alpha {a}");
+        assert_eq!((g, b), (0., 0.));
+    }
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn translucent_background_edges_gpu() {
+    translucent_background_edges(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn translucent_background_edges_cpu() {
+    translucent_background_edges(true)
+}
+
+fn translucent_background_edges(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        base_colour: Color::rgba8(0, 0, 255, 128),
+        ..TestParams::new("translucent_background_edges", 64, 64)
+    };
+    let background_alpha = 128. / 255.;
+    let image = vello_tests::render_sync(red_circle(), &params).unwrap();
+    for [r, g, b, a] in edge_pixels(&image) {
+        // Each edge pixel is the red circle drawn over the premultiplied blue background with
+        // coverage `r`.
+        let coverage = r;
+        let expected_b = background_alpha * (1. - coverage);
+        let expected_a = coverage + expected_b;
+        assert!(
+            (b - expected_b).abs() < 0.02,
+            "blue {b}, expected {expected_b}"
+        );
+        assert!(
+            (a - expected_a).abs() < 0.02,
+            "alpha {a}, expected {expected_a}"
+        );
+        assert_eq!(g, 0.);
+    }
+}
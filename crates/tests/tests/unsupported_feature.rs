@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Blob, Brush, Color, Fill, Format, Image},
+    AaConfig, AaSupport, BinSize, Renderer, RendererOptions, Scene, UnsupportedFeature,
+};
+use vello_tests::TestParams;
+
+/// Renders `scene` with a renderer supporting only area antialiasing, and returns the
+/// unsupported feature reported by the render, if any.
+fn unsupported_feature(
+    scene: &Scene,
+    params: &TestParams,
+    antialiasing_method: AaConfig,
+) -> Option<UnsupportedFeature> {
+    let mut feature = None;
+    let image = pollster::block_on(vello_tests::render_with_renderer(
+        params,
+        |device| {
+            let options = RendererOptions {
+                surface_format: None,
+                use_cpu: params.use_cpu,
+                antialiasing_support: AaSupport::area_only(),
+                bin_size: BinSize::default(),
+                max_clip_depth: None,
+            };
+            Renderer::new(device, options)
+                .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))
+        },
+        |device, queue, renderer, target| {
+            let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+            let render_params = vello::RenderParams {
+                antialiasing_method,
+                ..params.render_params()
+            };
+            if let Err(error) =
+                renderer.render_to_texture(device, queue, scene, &view, &render_params)
+            {
+                feature = error.downcast_ref::<UnsupportedFeature>().cloned();
+            }
+            Ok(())
+        },
+    ));
+    image.unwrap();
+    feature
+}
+
+fn red_square() -> Scene {
+    let mut scene = Scene::new();
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(Color::RED),
+        None,
+        &Rect::new(8., 8., 24., 24.),
+    );
+    scene
+}
+
+#[test]
+fn unsupported_feature_display() {
+    let error = UnsupportedFeature::ImageSize {
+        width: 9000,
+        height: 4,
+    };
+    assert_eq!(
+        error.to_string(),
+        "9000x4 image is larger than the image atlas"
+    );
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn unsupported_feature_gpu() {
+    unsupported_features(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn unsupported_feature_cpu() {
+    unsupported_features(true)
+}
+
+fn unsupported_features(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("unsupported_feature", 32, 32)
+    };
+    let scene = red_square();
+    assert_eq!(unsupported_feature(&scene, &params, AaConfig::Area), None);
+    assert_eq!(
+        unsupported_feature(&scene, &params, AaConfig::Msaa16),
+        Some(UnsupportedFeature::AntialiasingMethod(AaConfig::Msaa16))
+    );
+
+    // Wider than the largest image atlas of 8192 pixels, so it can't be drawn on any device.
+    let (width, height) = (8193, 4);
+    let data = vec![255u8; (width * height * 4) as usize];
+    let image = Image::new(Blob::new(Arc::new(data)), Format::Rgba8, width, height);
+    let mut scene = red_square();
+    scene.draw_image(&image, Affine::IDENTITY);
+    assert_eq!(
+        unsupported_feature(&scene, &params, AaConfig::Area),
+        Some(UnsupportedFeature::ImageSize { width, height })
+    );
+}
@@ -0,0 +1,83 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    Scene, UpscaleFilter,
+};
+use vello_tests::TestParams;
+
+/// Renders a white bar on black at half resolution, and returns the red channel of the
+/// middle row of the upscaled result.
+fn upscaled_row(use_cpu: bool, filter: UpscaleFilter) -> Vec<u8> {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("upscale_filter", 64, 16)
+    };
+    let mut scene = Scene::new();
+    // The edges fall between pixels of the half resolution render.
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::WHITE,
+        None,
+        &Rect::new(16., 0., 48., 16.),
+    );
+    let image = pollster::block_on(vello_tests::render_with(
+        &params,
+        |device, queue, renderer, target| {
+            renderer
+                .render_to_texture_scaled(
+                    device,
+                    queue,
+                    &scene,
+                    target,
+                    &params.render_params(),
+                    0.5,
+                    filter,
+                )
+                .or_else(|_| anyhow::bail!("Got non-Send/Sync error from rendering"))
+        },
+    ))
+    .unwrap();
+    let width = params.width as usize;
+    let row = 8 * width * 4;
+    image.data.data()[row..row + width * 4]
+        .chunks_exact(4)
+        .map(|pixel| pixel[0])
+        .collect()
+}
+
+/// The number of pixels which are neither fully black nor fully white.
+fn soft_pixels(row: &[u8]) -> usize {
+    row.iter().filter(|v| **v != 0 && **v != 255).count()
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn upscale_filter_gpu() {
+    upscale_filter(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn upscale_filter_cpu() {
+    upscale_filter(true)
+}
+
+fn upscale_filter(use_cpu: bool) {
+    let nearest = upscaled_row(use_cpu, UpscaleFilter::Nearest);
+    let expected: Vec<u8> = (0..64)
+        .map(|x| if (16..48).contains(&x) { 255 } else { 0 })
+        .collect();
+    assert_eq!(nearest, expected);
+    let bilinear = upscaled_row(use_cpu, UpscaleFilter::Bilinear);
+    let bicubic = upscaled_row(use_cpu, UpscaleFilter::Bicubic);
+    for row in [&bilinear, &bicubic] {
+        // Each edge blends across the two output pixels on either side of it.
+        assert_eq!(soft_pixels(row), 4, "{row:?}");
+        assert_eq!(row[0], 0);
+        assert_eq!(row[32], 255);
+    }
+    // The bicubic kernel keeps more contrast next to the edge.
+    assert!(bicubic[16] > bilinear[16], "{bicubic:?} {bilinear:?}");
+    assert!(bicubic[15] < bilinear[15], "{bicubic:?} {bilinear:?}");
+}
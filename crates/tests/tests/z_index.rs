@@ -0,0 +1,104 @@
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Brush, Color, Fill, Mix},
+    Scene,
+};
+use vello_tests::TestParams;
+
+fn fill_rect(scene: &mut Scene, color: Color, rect: Rect) {
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        &Brush::Solid(color),
+        None,
+        &rect,
+    );
+}
+
+/// A red square at z index 1 drawn first, then a blue square covering the whole scene at z
+/// index -1, which should end up behind it.
+fn reordered() -> Scene {
+    let mut scene = Scene::new();
+    scene.set_z_index(1);
+    fill_rect(&mut scene, Color::RED, Rect::new(16., 16., 48., 48.));
+    scene.set_z_index(-1);
+    fill_rect(&mut scene, Color::BLUE, Rect::new(0., 0., 64., 64.));
+    scene
+}
+
+#[test]
+fn z_index_matches_insertion_in_order() {
+    let mut in_order = Scene::new();
+    fill_rect(&mut in_order, Color::BLUE, Rect::new(0., 0., 64., 64.));
+    fill_rect(&mut in_order, Color::RED, Rect::new(16., 16., 48., 48.));
+    let reordered = reordered();
+    assert_eq!(reordered.z_index(), -1);
+    // The transforms and styles are encoded again for each z index, but the draws are the same.
+    assert!(reordered.encoding().draw_tags == in_order.encoding().draw_tags);
+    assert_eq!(
+        reordered.encoding().draw_data,
+        in_order.encoding().draw_data
+    );
+}
+
+#[test]
+fn z_index_unchanged_in_layer() {
+    let layer = |z_index: Option<i32>| {
+        let mut scene = Scene::new();
+        fill_rect(&mut scene, Color::BLUE, Rect::new(0., 0., 64., 64.));
+        let clip = Rect::new(8., 8., 56., 56.);
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &clip);
+        if let Some(z_index) = z_index {
+            scene.set_z_index(z_index);
+        }
+        fill_rect(&mut scene, Color::RED, Rect::new(16., 16., 48., 48.));
+        scene.pop_layer();
+        scene
+    };
+    let scene = layer(Some(-1));
+    assert_eq!(scene.z_index(), 0);
+    let expected = layer(None);
+    assert!(scene.encoding().draw_tags == expected.encoding().draw_tags);
+    assert_eq!(scene.encoding().draw_data, expected.encoding().draw_data);
+}
+
+#[test]
+fn z_index_merged_encoding_is_kept() {
+    let mut scene = reordered();
+    // The content at each z index is only merged once.
+    let merged: *const _ = scene.encoding();
+    assert!(std::ptr::eq(merged, scene.encoding()));
+    // Drawing more content merges it again.
+    let draws = scene.encoding().draw_tags.len();
+    fill_rect(&mut scene, Color::GREEN, Rect::new(0., 0., 8., 8.));
+    assert_eq!(scene.encoding().draw_tags.len(), draws + 1);
+    scene.set_z_index(1);
+    fill_rect(&mut scene, Color::GREEN, Rect::new(0., 0., 8., 8.));
+    assert_eq!(scene.encoding().draw_tags.len(), draws + 2);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn z_index_gpu() {
+    z_index(false)
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn z_index_cpu() {
+    z_index(true)
+}
+
+fn z_index(use_cpu: bool) {
+    let params = TestParams {
+        use_cpu,
+        ..TestParams::new("z_index", 64, 64)
+    };
+    let image = vello_tests::render_sync(reordered(), &params).unwrap();
+    let pixel = |x: usize, y: usize| {
+        let index = (y * params.width as usize + x) * 4;
+        &image.data.data()[index..index + 4]
+    };
+    assert_eq!(pixel(32, 32), &[255, 0, 0, 255]);
+    assert_eq!(pixel(4, 4), &[0, 0, 255, 255]);
+}
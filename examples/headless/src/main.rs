@@ -91,6 +91,8 @@ async fn render(mut scenes: SceneSet, index: usize, args: &Args) -> Result<()> {
             surface_format: None,
             use_cpu: args.use_cpu,
             antialiasing_support: vello::AaSupport::area_only(),
+            bin_size: vello::BinSize::default(),
+            max_clip_depth: None,
         },
     )
     .or_else(|_| bail!("Got non-Send/Sync error from creating renderer"))?;
@@ -139,7 +141,7 @@ async fn render(mut scenes: SceneSet, index: usize, args: &Args) -> Result<()> {
             .unwrap_or(vello::peniko::Color::BLACK),
         width,
         height,
-        antialiasing_method: vello::AaConfig::Area,
+        ..Default::default()
     };
     let mut scene = Scene::new();
     scene.append(&fragment, Some(transform));
@@ -30,6 +30,8 @@ impl FromWorld for VelloRenderer {
                 RendererOptions {
                     surface_format: None,
                     antialiasing_support: vello::AaSupport::area_only(),
+                    bin_size: vello::BinSize::default(),
+                    max_clip_depth: None,
                     use_cpu: false,
                 },
             )
@@ -70,7 +72,7 @@ fn render_scenes(
             base_color: vello::peniko::Color::AQUAMARINE,
             width: gpu_image.size.x as u32,
             height: gpu_image.size.y as u32,
-            antialiasing_method: vello::AaConfig::Area,
+            ..Default::default()
         };
         renderer
             .0
@@ -89,6 +89,8 @@ fn run(
                     surface_format: Some(render_state.surface.format),
                     use_cpu: use_cpu,
                     antialiasing_support: vello::AaSupport::all(),
+                    bin_size: vello::BinSize::default(),
+                    max_clip_depth: None,
                 },
             )
             .expect("Could create renderer"),
@@ -374,6 +376,7 @@ fn run(
                             width,
                             height,
                             antialiasing_method,
+                            ..Default::default()
                         };
                         scene.reset();
                         let mut transform = transform;
@@ -538,6 +541,8 @@ fn run(
                                     surface_format: Some(render_state.surface.format),
                                     use_cpu,
                                     antialiasing_support: vello::AaSupport::all(),
+                                    bin_size: vello::BinSize::default(),
+                                    max_clip_depth: None,
                                 },
                             )
                             .expect("Could create renderer")
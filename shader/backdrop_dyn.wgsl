@@ -20,6 +20,15 @@ var<workgroup> sh_row_width: array<u32, WG_SIZE>;
 var<workgroup> sh_row_count: array<u32, WG_SIZE>;
 var<workgroup> sh_offset: array<u32, WG_SIZE>;
 
+// Adds two winding numbers, clamping instead of wrapping around on overflow, so that an
+// extreme amount of overlap can't flip the sign of the winding or wrap it back to zero.
+fn add_saturating(a: i32, b: i32) -> i32 {
+    let sum = a + b;
+    // Overflow happened if both operands have a different sign from the result.
+    let overflow = ((a ^ sum) & (b ^ sum)) < 0;
+    return select(sum, select(0x7fffffff, -0x7fffffff - 1, a < 0), overflow);
+}
+
 @compute @workgroup_size(256)
 fn main(
     @builtin(global_invocation_id) global_id: vec3<u32>,
@@ -62,7 +71,7 @@ fn main(
             var sum = tiles[tile_ix].backdrop;
             for (var x = 1u; x < width; x += 1u) {
                 tile_ix += 1u;
-                sum += tiles[tile_ix].backdrop;
+                sum = add_saturating(sum, tiles[tile_ix].backdrop);
                 tiles[tile_ix].backdrop = sum;
             }
         }
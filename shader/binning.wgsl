@@ -32,17 +32,12 @@ var<storage, read_write> bin_data: array<u32>;
 struct BinHeader {
     element_count: u32,
     chunk_offset: u32,
+    overflow_offset: u32,
 }
 
 @group(0) @binding(7)
 var<storage, read_write> bin_header: array<BinHeader>;
 
-// conversion factors from coordinates to bin
-let SX = 0.00390625;
-let SY = 0.00390625;
-//let SX = 1.0 / f32(N_TILE_X * TILE_WIDTH);
-//let SY = 1.0 / f32(N_TILE_Y * TILE_HEIGHT);
-
 let WG_SIZE = 256u;
 let N_SLICE = 8u;
 //let N_SLICE = WG_SIZE / 32u;
@@ -52,6 +47,7 @@ var<workgroup> sh_bitmaps: array<array<atomic<u32>, N_TILE>, N_SLICE>;
 // store count values packed two u16's to a u32
 var<workgroup> sh_count: array<array<u32, N_TILE>, N_SUBSLICE>;
 var<workgroup> sh_chunk_offset: array<u32, N_TILE>;
+var<workgroup> sh_overflow_offset: array<u32, N_TILE>;
 
 @compute @workgroup_size(256)
 fn main(
@@ -65,6 +61,11 @@ fn main(
     workgroupBarrier();
 
     // Read inputs and determine coverage of bins
+    let n_tile_x = config.bin_width;
+    let n_tile_y = N_TILE / n_tile_x;
+    // conversion factors from coordinates to bin
+    let sx = 1.0 / f32(n_tile_x * TILE_WIDTH);
+    let sy = 1.0 / f32(n_tile_y * TILE_HEIGHT);
     let element_ix = global_id.x;
     var x0 = 0;
     var y0 = 0;
@@ -95,14 +96,14 @@ fn main(
         // and leave the coordinates at 0. This way the path will get clipped out and won't get
         // assigned to a bin.
         if bbox.x < bbox.z && bbox.y < bbox.w {
-            x0 = i32(floor(bbox.x * SX));
-            y0 = i32(floor(bbox.y * SY));
-            x1 = i32(ceil(bbox.z * SX));
-            y1 = i32(ceil(bbox.w * SY));
+            x0 = i32(floor(bbox.x * sx));
+            y0 = i32(floor(bbox.y * sy));
+            x1 = i32(ceil(bbox.z * sx));
+            y1 = i32(ceil(bbox.w * sy));
         }
     }
-    let width_in_bins = i32((config.width_in_tiles + N_TILE_X - 1u) / N_TILE_X);
-    let height_in_bins = i32((config.height_in_tiles + N_TILE_Y - 1u) / N_TILE_Y);
+    let width_in_bins = i32((config.width_in_tiles + n_tile_x - 1u) / n_tile_x);
+    let height_in_bins = i32((config.height_in_tiles + n_tile_y - 1u) / n_tile_y);
     x0 = clamp(x0, 0, width_in_bins);
     y0 = clamp(y0, 0, height_in_bins);
     x1 = clamp(x1, 0, width_in_bins);
@@ -134,15 +135,26 @@ fn main(
         let element_count_packed = element_count_lo | (element_count_hi << 16u);
         sh_count[i][local_id.x] = element_count_packed;
     }
-    // element_count is the number of draw objects covering this thread's bin
-    var chunk_offset = atomicAdd(&bump.binning, element_count);
-    if chunk_offset + element_count > config.binning_size {
+    // element_count is the number of draw objects covering this thread's bin. The first
+    // bin_capacity of them go in the chunk of the bin and the rest spill into an overflow chunk.
+    let chunk_count = min(element_count, config.bin_capacity);
+    let overflow_count = element_count - chunk_count;
+    var chunk_offset = atomicAdd(&bump.binning, chunk_count);
+    var overflow_offset = 0u;
+    if overflow_count > 0u {
+        overflow_offset = atomicAdd(&bump.binning, overflow_count);
+    }
+    if chunk_offset + chunk_count > config.binning_size
+        || overflow_offset + overflow_count > config.binning_size {
         chunk_offset = 0u;
+        overflow_offset = 0u;
         atomicOr(&bump.failed, STAGE_BINNING);
-    }    
+    }
     sh_chunk_offset[local_id.x] = chunk_offset;
+    sh_overflow_offset[local_id.x] = overflow_offset;
     bin_header[global_id.x].element_count = element_count;
     bin_header[global_id.x].chunk_offset = chunk_offset;
+    bin_header[global_id.x].overflow_offset = overflow_offset;
     workgroupBarrier();
 
     // loop over bbox of bins touched by this draw object
@@ -159,8 +171,12 @@ fn main(
                 let count_packed = sh_count[count_ix / 2u][bin_ix];
                 idx += (count_packed >> (16u * (count_ix & 1u))) & 0xffffu;
             }
-            let offset = config.bin_data_start + sh_chunk_offset[bin_ix];
-            bin_data[offset + idx] = element_ix;
+            var offset = sh_chunk_offset[bin_ix];
+            if idx >= config.bin_capacity {
+                offset = sh_overflow_offset[bin_ix];
+                idx -= config.bin_capacity;
+            }
+            bin_data[config.bin_data_start + offset + idx] = element_ix;
         }
         x += 1;
         if x == x1 {
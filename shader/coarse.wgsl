@@ -21,6 +21,7 @@ var<storage> draw_monoids: array<DrawMonoid>;
 struct BinHeader {
     element_count: u32,
     chunk_offset: u32,
+    overflow_offset: u32,
 }
 
 @group(0) @binding(3)
@@ -41,6 +42,15 @@ var<storage, read_write> bump: BumpAllocators;
 @group(0) @binding(8)
 var<storage, read_write> ptcl: array<u32>;
 
+// One bit per tile, in row-major order. Clean tiles get no commands.
+@group(0) @binding(9)
+var<storage> dirty_tiles: array<u32>;
+
+// The number of draw objects which wrote commands to each tile, in row-major order. Only
+// written when it has an entry for each tile, as it is a single word otherwise.
+@group(0) @binding(10)
+var<storage, read_write> overdraw: array<u32>;
+
 
 
 // Much of this code assumes WG_SIZE == N_TILE. If these diverge, then
@@ -52,6 +62,7 @@ let N_SLICE = 8u;
 var<workgroup> sh_bitmaps: array<array<atomic<u32>, N_TILE>, N_SLICE>;
 var<workgroup> sh_part_count: array<u32, WG_SIZE>;
 var<workgroup> sh_part_offsets: array<u32, WG_SIZE>;
+var<workgroup> sh_part_overflow_offsets: array<u32, WG_SIZE>;
 var<workgroup> sh_drawobj_ix: array<u32, WG_SIZE>;
 var<workgroup> sh_tile_stride: array<u32, WG_SIZE>;
 var<workgroup> sh_tile_width: array<u32, WG_SIZE>;
@@ -59,10 +70,24 @@ var<workgroup> sh_tile_x0y0: array<u32, WG_SIZE>;
 var<workgroup> sh_tile_count: array<u32, WG_SIZE>;
 var<workgroup> sh_tile_base: array<u32, WG_SIZE>;
 
+fn is_dirty(tile_x: u32, tile_y: u32) -> bool {
+    let ix = tile_y * config.width_in_tiles + tile_x;
+    return (dirty_tiles[ix / 32u] & (1u << (ix & 31u))) != 0u;
+}
+
 // helper functions for writing ptcl
 
 var<private> cmd_offset: u32;
 var<private> cmd_limit: u32;
+var<private> draw_count: u32;
+// Numbers of fills written as solid and with coverage, for the frame statistics.
+var<private> solid_count: u32;
+var<private> coverage_count: u32;
+// Top left corner of this tile, in pixels.
+var<private> tile_origin: vec2<f32>;
+// The clip rect that fine currently clips fills to in this tile, in the form of the
+// draw info flags, or 0 for none.
+var<private> clip_rect: u32;
 
 // Make sure there is space for a command of given size, plus a jump if needed
 fn alloc_cmd(size: u32) {
@@ -82,7 +107,35 @@ fn alloc_cmd(size: u32) {
     }
 }
 
+// Sets the clip rect of the fill that is about to be written, if it differs from the current
+// one. Tiles lying completely inside of the rect don't need it.
+fn write_clip_rect(draw_flags: u32) {
+    var rect_ix = draw_flags >> DRAW_INFO_FLAGS_CLIP_RECT_SHIFT;
+    var rect = vec4(0.0);
+    if rect_ix != 0u {
+        let base = config.clip_rect_base + (rect_ix - 1u) * 4u;
+        rect = bitcast<vec4f>(vec4(scene[base], scene[base + 1u], scene[base + 2u], scene[base + 3u]));
+        let tile_end = tile_origin + vec2(f32(TILE_WIDTH), f32(TILE_HEIGHT));
+        if all(rect.xy <= tile_origin) && all(tile_end <= rect.zw) {
+            rect_ix = 0u;
+            rect = vec4(0.0);
+        }
+    }
+    if rect_ix != clip_rect {
+        alloc_cmd(5u);
+        ptcl[cmd_offset] = CMD_CLIP_RECT;
+        let clip = CmdClipRect(rect);
+        ptcl[cmd_offset + 1u] = bitcast<u32>(clip.rect.x);
+        ptcl[cmd_offset + 2u] = bitcast<u32>(clip.rect.y);
+        ptcl[cmd_offset + 3u] = bitcast<u32>(clip.rect.z);
+        ptcl[cmd_offset + 4u] = bitcast<u32>(clip.rect.w);
+        cmd_offset += 5u;
+        clip_rect = rect_ix;
+    }
+}
+
 fn write_path(tile: Tile, tile_ix: u32, draw_flags: u32) {
+    write_clip_rect(draw_flags);
     // We overload the "segments" field to store both count (written by
     // path_count stage) and segment allocation (used by path_tiling and
     // fine).
@@ -93,16 +146,21 @@ fn write_path(tile: Tile, tile_ix: u32, draw_flags: u32) {
         alloc_cmd(4u);
         ptcl[cmd_offset] = CMD_FILL;
         let even_odd = (draw_flags & DRAW_INFO_FLAGS_FILL_RULE_BIT) != 0u;
-        let size_and_rule = (n_segs << 1u) | u32(even_odd);
+        let is_glyph = (draw_flags & DRAW_INFO_FLAGS_GLYPH_BIT) != 0u;
+        let aliased = (draw_flags & DRAW_INFO_FLAGS_ALIASED_BIT) != 0u;
+        let size_and_rule = (n_segs << 3u) | (u32(aliased) << 2u) | (u32(is_glyph) << 1u)
+            | u32(even_odd);
         let fill = CmdFill(size_and_rule, seg_ix, tile.backdrop);
         ptcl[cmd_offset + 1u] = fill.size_and_rule;
         ptcl[cmd_offset + 2u] = fill.seg_data;
         ptcl[cmd_offset + 3u] = u32(fill.backdrop);
         cmd_offset += 4u;
+        coverage_count += 1u;
     } else {
         alloc_cmd(1u);
         ptcl[cmd_offset] = CMD_SOLID;
         cmd_offset += 1u;
+        solid_count += 1u;
     }
 }
 
@@ -128,10 +186,38 @@ fn write_image(info_offset: u32) {
     cmd_offset += 2u;
 }
 
-fn write_begin_clip() {
-    alloc_cmd(1u);
+fn write_bilinear_grad(info_offset: u32) {
+    alloc_cmd(2u);
+    ptcl[cmd_offset] = CMD_BILINEAR_GRAD;
+    ptcl[cmd_offset + 1u] = info_offset;
+    cmd_offset += 2u;
+}
+
+fn write_begin_clip(begin_clip: CmdBeginClip) {
+    alloc_cmd(2u);
     ptcl[cmd_offset] = CMD_BEGIN_CLIP;
-    cmd_offset += 1u;
+    ptcl[cmd_offset + 1u] = begin_clip.flags;
+    cmd_offset += 2u;
+}
+
+fn write_feather(feather: CmdFeather) {
+    alloc_cmd(6u);
+    ptcl[cmd_offset] = CMD_FEATHER;
+    ptcl[cmd_offset + 1u] = bitcast<u32>(feather.rect.x);
+    ptcl[cmd_offset + 2u] = bitcast<u32>(feather.rect.y);
+    ptcl[cmd_offset + 3u] = bitcast<u32>(feather.rect.z);
+    ptcl[cmd_offset + 4u] = bitcast<u32>(feather.rect.w);
+    ptcl[cmd_offset + 5u] = bitcast<u32>(feather.feather);
+    cmd_offset += 6u;
+}
+
+fn write_color_matrix(dd: u32) {
+    alloc_cmd(21u);
+    ptcl[cmd_offset] = CMD_COLOR_MATRIX;
+    for (var i = 0u; i < 20u; i += 1u) {
+        ptcl[cmd_offset + 1u + i] = scene[dd + i];
+    }
+    cmd_offset += 21u;
 }
 
 fn write_end_clip(end_clip: CmdEndClip) {
@@ -158,19 +244,22 @@ fn main(
     if (failed & (STAGE_BINNING | STAGE_TILE_ALLOC | STAGE_PATH_COARSE)) != 0u {
         return;
     }
-    let width_in_bins = (config.width_in_tiles + N_TILE_X - 1u) / N_TILE_X;
+    let n_tile_x = config.bin_width;
+    let n_tile_y = N_TILE / n_tile_x;
+    let width_in_bins = (config.width_in_tiles + n_tile_x - 1u) / n_tile_x;
     let bin_ix = width_in_bins * wg_id.y + wg_id.x;
     let n_partitions = (config.n_drawobj + N_TILE - 1u) / N_TILE;
 
     // Coordinates of the top left of this bin, in tiles.
-    let bin_tile_x = N_TILE_X * wg_id.x;
-    let bin_tile_y = N_TILE_Y * wg_id.y;
+    let bin_tile_x = n_tile_x * wg_id.x;
+    let bin_tile_y = n_tile_y * wg_id.y;
 
-    let tile_x = local_id.x % N_TILE_X;
-    let tile_y = local_id.x / N_TILE_X;
+    let tile_x = local_id.x % n_tile_x;
+    let tile_y = local_id.x / n_tile_x;
     let this_tile_ix = (bin_tile_y + tile_y) * config.width_in_tiles + bin_tile_x + tile_x;
     cmd_offset = this_tile_ix * PTCL_INITIAL_ALLOC;
     cmd_limit = cmd_offset + (PTCL_INITIAL_ALLOC - PTCL_HEADROOM);
+    tile_origin = vec2(f32((bin_tile_x + tile_x) * TILE_WIDTH), f32((bin_tile_y + tile_y) * TILE_HEIGHT));
 
     // clip state
     var clip_zero_depth = 0u;
@@ -203,6 +292,7 @@ fn main(
                     let bin_header = bin_headers[in_ix];
                     count = bin_header.element_count;
                     sh_part_offsets[local_id.x] = bin_header.chunk_offset;
+                    sh_part_overflow_offsets[local_id.x] = bin_header.overflow_offset;
                 }
                 // prefix sum the element counts
                 for (var i = 0u; i < firstTrailingBit(WG_SIZE); i += 1u) {
@@ -228,8 +318,12 @@ fn main(
                     }
                 }
                 ix -= select(part_start_ix, sh_part_count[part_ix - 1u], part_ix > 0u);
-                let offset = config.bin_data_start + sh_part_offsets[part_ix];
-                sh_drawobj_ix[local_id.x] = info_bin_data[offset + ix];
+                var offset = sh_part_offsets[part_ix];
+                if ix >= config.bin_capacity {
+                    offset = sh_part_overflow_offsets[part_ix];
+                    ix -= config.bin_capacity;
+                }
+                sh_drawobj_ix[local_id.x] = info_bin_data[config.bin_data_start + offset + ix];
             }
             wr_ix = min(rd_ix + N_TILE, ready_ix);
             if wr_ix - rd_ix >= N_TILE || (wr_ix >= ready_ix && partition_ix >= n_partitions) {
@@ -253,10 +347,10 @@ fn main(
             sh_tile_stride[local_id.x] = stride;
             let dx = i32(path.bbox.x) - i32(bin_tile_x);
             let dy = i32(path.bbox.y) - i32(bin_tile_y);
-            let x0 = clamp(dx, 0, i32(N_TILE_X));
-            let y0 = clamp(dy, 0, i32(N_TILE_Y));
-            let x1 = clamp(i32(path.bbox.z) - i32(bin_tile_x), 0, i32(N_TILE_X));
-            let y1 = clamp(i32(path.bbox.w) - i32(bin_tile_y), 0, i32(N_TILE_Y));
+            let x0 = clamp(dx, 0, i32(n_tile_x));
+            let y0 = clamp(dy, 0, i32(n_tile_y));
+            let x1 = clamp(i32(path.bbox.z) - i32(bin_tile_x), 0, i32(n_tile_x));
+            let y1 = clamp(i32(path.bbox.w) - i32(bin_tile_y), 0, i32(n_tile_y));
             sh_tile_width[local_id.x] = u32(x1 - x0);
             sh_tile_x0y0[local_id.x] = u32(x0) | u32(y0 << 16u);
             tile_count = u32(x1 - x0) * u32(y1 - y0);
@@ -317,14 +411,17 @@ fn main(
             // backdrop (i.e. the winding number of its top-left corner) is even.
             let backdrop_clear = select(tile.backdrop, abs(tile.backdrop) & 1, even_odd) == 0;
             let include_tile = n_segs != 0u || (backdrop_clear == is_clip) || is_blend;
-            if include_tile {
+            // Tiles outside of the target are never visited here, as tile allocation clamps
+            // path bounding boxes to the target, so the threads for those tiles in edge bins
+            // find empty bitmaps and write no commands.
+            if include_tile && is_dirty(bin_tile_x + x, bin_tile_y + y) {
                 let el_slice = el_ix / 32u;
                 let el_mask = 1u << (el_ix & 31u);
-                atomicOr(&sh_bitmaps[el_slice][y * N_TILE_X + x], el_mask);
+                atomicOr(&sh_bitmaps[el_slice][y * n_tile_x + x], el_mask);
             }
         }
         workgroupBarrier();
-        // At this point bit drawobj % 32 is set in sh_bitmaps[drawobj / 32][y * N_TILE_X + x]
+        // At this point bit drawobj % 32 is set in sh_bitmaps[drawobj / 32][y * n_tile_x + x]
         // if drawobj touches tile (x, y).
 
         // Write per-tile command list for this tile
@@ -359,12 +456,14 @@ fn main(
                     // DRAWTAG_FILL_COLOR
                     case 0x44u: {
                         write_path(tile, tile_ix, draw_flags);
+                        draw_count += 1u;
                         let rgba_color = scene[dd];
                         write_color(CmdColor(rgba_color));
                     }
                     // DRAWTAG_FILL_LIN_GRADIENT
                     case 0x114u: {
                         write_path(tile, tile_ix, draw_flags);
+                        draw_count += 1u;
                         let index = scene[dd];
                         let info_offset = di + 1u;
                         write_grad(CMD_LIN_GRAD, index, info_offset);
@@ -372,21 +471,44 @@ fn main(
                     // DRAWTAG_FILL_RAD_GRADIENT
                     case 0x29cu: {
                         write_path(tile, tile_ix, draw_flags);
+                        draw_count += 1u;
                         let index = scene[dd];
                         let info_offset = di + 1u;
                         write_grad(CMD_RAD_GRAD, index, info_offset);
                     }
                     // DRAWTAG_FILL_IMAGE
-                    case 0x248u: {
+                    case 0x2d0u: {
                         write_path(tile, tile_ix, draw_flags);
+                        draw_count += 1u;
                         write_image(di + 1u);
                     }
-                    // DRAWTAG_BEGIN_CLIP
-                    case 0x9u: {
+                    // DRAWTAG_FILL_BILINEAR_GRADIENT
+                    case 0x6ccu: {
+                        write_path(tile, tile_ix, draw_flags);
+                        draw_count += 1u;
+                        write_bilinear_grad(di + 1u);
+                    }
+                    // DRAWTAG_BEGIN_CLIP, DRAWTAG_BEGIN_CLIP_COLOR_MATRIX
+                    case 0x1du, 0x501du: {
                         if tile.segment_count_or_ix == 0u && tile.backdrop == 0 {
                             clip_zero_depth = clip_depth + 1u;
                         } else {
-                            write_begin_clip();
+                            // The clip coverage is computed here rather than at the end of the
+                            // clip, so that fine can use it to combine nested clip edges.
+                            // A clip shape is always a non-zero fill (draw_flags=0).
+                            write_path(tile, tile_ix, /*draw_flags=*/0u);
+                            let feather = bitcast<f32>(scene[dd + 2u]);
+                            if feather > 0.0 {
+                                let rect = vec4(
+                                    bitcast<f32>(scene[dd + 3u]),
+                                    bitcast<f32>(scene[dd + 4u]),
+                                    bitcast<f32>(scene[dd + 5u]),
+                                    bitcast<f32>(scene[dd + 6u]),
+                                );
+                                write_feather(CmdFeather(rect, feather));
+                            }
+                            // The non-isolated and knockout flags follow the color matrix flag.
+                            write_begin_clip(CmdBeginClip((scene[dd] >> 17u) & 3u));
                             render_blend_depth += 1u;
                             max_blend_depth = max(max_blend_depth, render_blend_depth);
                         }
@@ -395,11 +517,14 @@ fn main(
                     // DRAWTAG_END_CLIP
                     case 0x21u: {
                         clip_depth -= 1u;
-                        // A clip shape is always a non-zero fill (draw_flags=0).
-                        write_path(tile, tile_ix, /*draw_flags=*/0u);
                         let blend = scene[dd];
                         let alpha = bitcast<f32>(scene[dd + 1u]);
-                        write_end_clip(CmdEndClip(blend, alpha));
+                        let COLOR_MATRIX_FLAG = 1u << 16u;
+                        if (blend & COLOR_MATRIX_FLAG) != 0u {
+                            // The matrix follows the 7 words of clip data.
+                            write_color_matrix(dd + 7u);
+                        }
+                        write_end_clip(CmdEndClip(blend & 0xffffu, alpha));
                         render_blend_depth -= 1u;
                     }
                     default: {}
@@ -407,8 +532,8 @@ fn main(
             } else {
                 // In "clip zero" state, suppress all drawing
                 switch drawtag {
-                    // DRAWTAG_BEGIN_CLIP
-                    case 0x9u: {
+                    // DRAWTAG_BEGIN_CLIP, DRAWTAG_BEGIN_CLIP_COLOR_MATRIX
+                    case 0x1du, 0x501du: {
                         clip_depth += 1u;
                     }
                     // DRAWTAG_END_CLIP
@@ -430,10 +555,19 @@ fn main(
         workgroupBarrier();
     }
     if bin_tile_x + tile_x < config.width_in_tiles && bin_tile_y + tile_y < config.height_in_tiles {
-        ptcl[cmd_offset] = CMD_END;
+        ptcl[cmd_offset] = select(CMD_SKIP, CMD_END, is_dirty(bin_tile_x + tile_x, bin_tile_y + tile_y));
+        if arrayLength(&overdraw) > this_tile_ix {
+            overdraw[this_tile_ix] = draw_count;
+        }
         if max_blend_depth > BLEND_STACK_SPLIT {
             let scratch_size = max_blend_depth * TILE_WIDTH * TILE_HEIGHT;
             ptcl[blend_offset] = atomicAdd(&bump.blend, scratch_size);
         }
+        if solid_count != 0u {
+            atomicAdd(&bump.solid_tiles, solid_count);
+        }
+        if coverage_count != 0u {
+            atomicAdd(&bump.coverage_tiles, coverage_count);
+        }
     }
 }
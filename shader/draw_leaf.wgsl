@@ -99,7 +99,7 @@ fn main(
     let di = m.info_offset;
     if tag_word == DRAWTAG_FILL_COLOR || tag_word == DRAWTAG_FILL_LIN_GRADIENT ||
         tag_word == DRAWTAG_FILL_RAD_GRADIENT || tag_word == DRAWTAG_FILL_IMAGE ||
-        tag_word == DRAWTAG_BEGIN_CLIP
+        tag_word == DRAWTAG_FILL_BILINEAR_GRADIENT || tag_word == DRAWTAG_BEGIN_CLIP || tag_word == DRAWTAG_BEGIN_CLIP_COLOR_MATRIX
     {
         let bbox = path_bbox[m.path_ix];
         // TODO: bbox is mostly yagni here, sort that out. Maybe clips?
@@ -111,7 +111,7 @@ fn main(
         var transform = Transform();
         let draw_flags = bbox.draw_flags;
         if tag_word == DRAWTAG_FILL_LIN_GRADIENT || tag_word == DRAWTAG_FILL_RAD_GRADIENT ||
-            tag_word == DRAWTAG_FILL_IMAGE
+            tag_word == DRAWTAG_FILL_IMAGE || tag_word == DRAWTAG_FILL_BILINEAR_GRADIENT
         {
             transform = read_transform(config.transform_base, bbox.trans_ix);
         }
@@ -220,7 +220,7 @@ fn main(
                 info[di + 9u] = bitcast<u32>((flags << 3u) | kind);
             }
             // DRAWTAG_FILL_IMAGE
-            case 0x248u: {
+            case 0x2d0u: {
                 info[di] = draw_flags;
                 let inv = transform_inverse(transform);
                 info[di + 1u] = bitcast<u32>(inv.matrx.x);
@@ -231,13 +231,32 @@ fn main(
                 info[di + 6u] = bitcast<u32>(inv.translate.y);
                 info[di + 7u] = scene[dd];
                 info[di + 8u] = scene[dd + 1u];
+                info[di + 9u] = scene[dd + 2u];
+                info[di + 10u] = scene[dd + 3u];
+            }
+            // DRAWTAG_FILL_BILINEAR_GRADIENT
+            case 0x6ccu: {
+                info[di] = draw_flags;
+                // Maps device space onto the unit square of the patch.
+                let inv = transform_inverse(transform);
+                info[di + 1u] = bitcast<u32>(inv.matrx.x);
+                info[di + 2u] = bitcast<u32>(inv.matrx.y);
+                info[di + 3u] = bitcast<u32>(inv.matrx.z);
+                info[di + 4u] = bitcast<u32>(inv.matrx.w);
+                info[di + 5u] = bitcast<u32>(inv.translate.x);
+                info[di + 6u] = bitcast<u32>(inv.translate.y);
+                info[di + 7u] = scene[dd];
+                info[di + 8u] = scene[dd + 1u];
+                info[di + 9u] = scene[dd + 2u];
+                info[di + 10u] = scene[dd + 3u];
             }
             default: {}
         }
     }
-    if tag_word == DRAWTAG_BEGIN_CLIP || tag_word == DRAWTAG_END_CLIP {
+    let is_begin_clip = tag_word == DRAWTAG_BEGIN_CLIP || tag_word == DRAWTAG_BEGIN_CLIP_COLOR_MATRIX;
+    if is_begin_clip || tag_word == DRAWTAG_END_CLIP {
         var path_ix = ~ix;
-        if tag_word == DRAWTAG_BEGIN_CLIP {
+        if is_begin_clip {
             path_ix = m.path_ix;
         }
         clip_inp[m.clip_ix] = ClipInp(ix, i32(path_ix));
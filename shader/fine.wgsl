@@ -40,13 +40,18 @@ var gradients: texture_2d<f32>;
 @group(0) @binding(6)
 var image_atlas: texture_2d<f32>;
 
+// Table from linear intensities to output values, which replaces the sRGB encoding of the
+// output if it has at least two entries.
+@group(0) @binding(7)
+var<storage> transfer_lut: array<f32>;
+
 #ifdef msaa8
 let MASK_WIDTH = 32u;
 let MASK_HEIGHT = 32u;
 let SH_SAMPLES_SIZE = 512u;
 let SAMPLE_WORDS_PER_PIXEL = 2u;
 // This might be better in uniform, but that has 16 byte alignment
-@group(0) @binding(7)
+@group(0) @binding(8)
 var<storage> mask_lut: array<u32, 256u>;
 #endif
 
@@ -55,7 +60,7 @@ let MASK_WIDTH = 64u;
 let MASK_HEIGHT = 64u;
 let SH_SAMPLES_SIZE = 1024u;
 let SAMPLE_WORDS_PER_PIXEL = 4u;
-@group(0) @binding(7)
+@group(0) @binding(8)
 var<storage> mask_lut: array<u32, 2048u>;
 #endif
 
@@ -138,7 +143,7 @@ fn fill_path_ms(fill: CmdFill, local_id: vec2<u32>, result: ptr<function, array<
         fill_path_ms_evenodd(fill, local_id, result);
         return;
     }
-    let n_segs = fill.size_and_rule >> 1u;
+    let n_segs = fill.size_and_rule >> 3u;
     let th_ix = local_id.y * (TILE_WIDTH / PIXELS_PER_THREAD) + local_id.x;
     // Initialize winding number arrays to a winding number of 0, which is 0x80 in an
     // 8 bit biased signed integer encoding.
@@ -496,7 +501,7 @@ fn fill_path_ms(fill: CmdFill, local_id: vec2<u32>, result: ptr<function, array<
 //
 // TODO: factor some logic out to reduce code duplication.
 fn fill_path_ms_evenodd(fill: CmdFill, local_id: vec2<u32>, result: ptr<function, array<f32, PIXELS_PER_THREAD>>) {
-    let n_segs = fill.size_and_rule >> 1u;
+    let n_segs = fill.size_and_rule >> 3u;
     let th_ix = local_id.y * (TILE_WIDTH / PIXELS_PER_THREAD) + local_id.x;
     if th_ix < TILE_HEIGHT {
         if th_ix == 0u {
@@ -753,7 +758,28 @@ fn read_image(cmd_ix: u32) -> CmdImage {
     let y = f32(xy & 0xffffu);
     let width = f32(width_height >> 16u);
     let height = f32(width_height & 0xffffu);
-    return CmdImage(matrx, xlat, vec2(x, y), vec2(width, height));
+    let sampling = info[info_offset + 8u];
+    let tint = unpack4x8unorm(info[info_offset + 9u]).wzyx;
+    return CmdImage(matrx, xlat, vec2(x, y), vec2(width, height), sampling, tint);
+}
+
+fn read_bilinear_grad(cmd_ix: u32) -> CmdBilinearGrad {
+    let info_offset = ptcl[cmd_ix + 1u];
+    let m0 = bitcast<f32>(info[info_offset]);
+    let m1 = bitcast<f32>(info[info_offset + 1u]);
+    let m2 = bitcast<f32>(info[info_offset + 2u]);
+    let m3 = bitcast<f32>(info[info_offset + 3u]);
+    let matrx = vec4(m0, m1, m2, m3);
+    let xlat = vec2(bitcast<f32>(info[info_offset + 4u]), bitcast<f32>(info[info_offset + 5u]));
+    let c00 = unpack4x8unorm(info[info_offset + 6u]).wzyx;
+    let c10 = unpack4x8unorm(info[info_offset + 7u]).wzyx;
+    let c01 = unpack4x8unorm(info[info_offset + 8u]).wzyx;
+    let c11 = unpack4x8unorm(info[info_offset + 9u]).wzyx;
+    return CmdBilinearGrad(matrx, xlat, c00, c10, c01, c11);
+}
+
+fn read_begin_clip(cmd_ix: u32) -> CmdBeginClip {
+    return CmdBeginClip(ptcl[cmd_ix + 1u]);
 }
 
 fn read_end_clip(cmd_ix: u32) -> CmdEndClip {
@@ -762,6 +788,85 @@ fn read_end_clip(cmd_ix: u32) -> CmdEndClip {
     return CmdEndClip(blend, alpha);
 }
 
+fn read_clip_rect(cmd_ix: u32) -> CmdClipRect {
+    let rect = vec4(
+        bitcast<f32>(ptcl[cmd_ix + 1u]),
+        bitcast<f32>(ptcl[cmd_ix + 2u]),
+        bitcast<f32>(ptcl[cmd_ix + 3u]),
+        bitcast<f32>(ptcl[cmd_ix + 4u]),
+    );
+    return CmdClipRect(rect);
+}
+
+// Scales the coverage of a fill by the part of each pixel inside of its clip rect.
+fn apply_clip_rect(xy: vec2<f32>, rect: vec4<f32>, area: ptr<function, array<f32, PIXELS_PER_THREAD>>) {
+    let y_coverage = clamp(min(xy.y + 1.0, rect.w) - max(xy.y, rect.y), 0.0, 1.0);
+    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+        let x = xy.x + f32(i);
+        let x_coverage = clamp(min(x + 1.0, rect.z) - max(x, rect.x), 0.0, 1.0);
+        (*area)[i] *= x_coverage * y_coverage;
+    }
+}
+
+fn read_feather(cmd_ix: u32) -> CmdFeather {
+    let rect = vec4(
+        bitcast<f32>(ptcl[cmd_ix + 1u]),
+        bitcast<f32>(ptcl[cmd_ix + 2u]),
+        bitcast<f32>(ptcl[cmd_ix + 3u]),
+        bitcast<f32>(ptcl[cmd_ix + 4u]),
+    );
+    let feather = bitcast<f32>(ptcl[cmd_ix + 5u]);
+    return CmdFeather(rect, feather);
+}
+
+fn read_color_matrix(cmd_ix: u32) -> CmdColorMatrix {
+    var m: array<f32, 20>;
+    for (var i = 0u; i < 20u; i += 1u) {
+        m[i] = bitcast<f32>(ptcl[cmd_ix + 1u + i]);
+    }
+    // The matrix is stored by rows, while WGSL matrices are built from columns.
+    let matrx = mat4x4(
+        vec4(m[0], m[5], m[10], m[15]),
+        vec4(m[1], m[6], m[11], m[16]),
+        vec4(m[2], m[7], m[12], m[17]),
+        vec4(m[3], m[8], m[13], m[18]),
+    );
+    let offset = vec4(m[4], m[9], m[14], m[19]);
+    return CmdColorMatrix(matrx, offset);
+}
+
+// Composites the premultiplied color `fg` covering `coverage` of a pixel onto `dst`. In a
+// knockout group, it is instead composited with the group's initial backdrop, and replaces
+// `dst` in proportion to its coverage.
+fn draw_over(dst: vec4<f32>, fg: vec4<f32>, coverage: f32, knockout: bool, backdrop: vec4<f32>) -> vec4<f32> {
+    if knockout {
+        return mix(dst, fg + backdrop * (1.0 - fg.a), coverage);
+    }
+    let fg_i = fg * coverage;
+    return dst * (1.0 - fg_i.a) + fg_i;
+}
+
+// Adjusts the coverage of a glyph drawn with the premultiplied color `fg`, so that blending
+// in sRGB space approximates blending in linear light with the configured glyph gamma.
+// Blending in sRGB space makes dark text on a light background look too bold and light text
+// on a dark background too thin, so dark glyphs are thinned and light glyphs thickened.
+fn glyph_coverage(area: f32, fg: vec4<f32>) -> f32 {
+    let inv_gamma = 1.0 / config.glyph_gamma;
+    let luma = dot(fg.rgb / max(fg.a, 1e-6), vec3(0.2126, 0.7152, 0.0722));
+    let dark = 1.0 - pow(1.0 - area, inv_gamma);
+    let light = pow(area, inv_gamma);
+    return mix(dark, light, luma);
+}
+
+// Only the clips nested less than this deep can be non-isolated or knockout groups, as their
+// flags are kept in the bits of a u32. Deeper clips are past the blend stack in any case.
+let MAX_FLAGGED_CLIP_DEPTH = 32u;
+
+// Returns whether the bit for the clip at `depth` is set in `flags`.
+fn clip_flag(flags: u32, depth: u32) -> bool {
+    return depth < MAX_FLAGGED_CLIP_DEPTH && ((flags >> depth) & 1u) != 0u;
+}
+
 fn extend_mode(t: f32, mode: u32) -> f32 {
     let EXTEND_PAD = 0u;
     let EXTEND_REPEAT = 1u;
@@ -791,7 +896,7 @@ let PIXELS_PER_THREAD = 4u;
 //
 // FIXME: This should return an array when https://github.com/gfx-rs/naga/issues/1930 is fixed.
 fn fill_path(fill: CmdFill, xy: vec2<f32>, result: ptr<function, array<f32, PIXELS_PER_THREAD>>) {
-    let n_segs = fill.size_and_rule >> 1u;
+    let n_segs = fill.size_and_rule >> 3u;
     let even_odd = (fill.size_and_rule & 1u) != 0u;
     var area: array<f32, PIXELS_PER_THREAD>;
     let backdrop_f = f32(fill.backdrop);
@@ -822,7 +927,9 @@ fn fill_path(fill: CmdFill, xy: vec2<f32>, result: ptr<function, array<f32, PIXE
                 let b = min(xmax, 1.0);
                 let c = max(b, 0.0);
                 let d = max(xmin, 0.0);
-                let a = (b + 0.5 * (d * d - c * c) - xmin) / (xmax - xmin);
+                // Factored so that the cancellation in `d * d - c * c` doesn't swamp the coverage of
+                // near vertical edges, where `xmax - xmin` is tiny. Thin slivers would otherwise drop out.
+                let a = (b - xmin + 0.5 * (d - c) * (d + c)) / (xmax - xmin);
                 area[i] += a * dy;
             }
         }
@@ -846,6 +953,79 @@ fn fill_path(fill: CmdFill, xy: vec2<f32>, result: ptr<function, array<f32, PIXE
     *result = area;
 }
 
+// Computes the coverage of each quarter of the pixels covered by `fill_path`, in the order top
+// left, top right, bottom left, bottom right, by rasterizing the path in half pixel cells.
+fn fill_path_quarters(fill: CmdFill, xy: vec2<f32>, result: ptr<function, array<vec4<f32>, PIXELS_PER_THREAD>>) {
+    let n_segs = fill.size_and_rule >> 3u;
+    let even_odd = (fill.size_and_rule & 1u) != 0u;
+    var area: array<vec4<f32>, PIXELS_PER_THREAD>;
+    let backdrop_f = f32(fill.backdrop);
+    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+        area[i] = vec4(backdrop_f);
+    }
+    // Coordinates are in units of cells from here on.
+    let origin = 2.0 * xy;
+    for (var i = 0u; i < n_segs; i++) {
+        let seg_off = fill.seg_data + i;
+        let segment = segments[seg_off];
+        let point0 = 2.0 * segment.point0;
+        let delta = 2.0 * (segment.point1 - segment.point0);
+        for (var row = 0u; row < 2u; row += 1u) {
+            let row_y = origin.y + f32(row);
+            let y = point0.y - row_y;
+            let y0 = clamp(y, 0.0, 1.0);
+            let y1 = clamp(y + delta.y, 0.0, 1.0);
+            let dy = y0 - y1;
+            if dy != 0.0 {
+                let vec_y_recip = 1.0 / delta.y;
+                let t0 = (y0 - y) * vec_y_recip;
+                let t1 = (y1 - y) * vec_y_recip;
+                let startx = point0.x - origin.x;
+                let x0 = startx + t0 * delta.x;
+                let x1 = startx + t1 * delta.x;
+                let xmin0 = min(x0, x1);
+                let xmax0 = max(x0, x1);
+                for (var cell = 0u; cell < 2u * PIXELS_PER_THREAD; cell += 1u) {
+                    let i_f = f32(cell);
+                    let xmin = min(xmin0 - i_f, 1.0) - 1.0e-6;
+                    let xmax = xmax0 - i_f;
+                    let b = min(xmax, 1.0);
+                    let c = max(b, 0.0);
+                    let d = max(xmin, 0.0);
+                    let a = (b - xmin + 0.5 * (d - c) * (d + c)) / (xmax - xmin);
+                    area[cell / 2u][row * 2u + cell % 2u] += a * dy;
+                }
+            }
+            let y_edge = sign(delta.x) * clamp(row_y - 2.0 * segment.y_edge + 1.0, 0.0, 1.0);
+            for (var j = 0u; j < PIXELS_PER_THREAD; j += 1u) {
+                area[j][row * 2u] += y_edge;
+                area[j][row * 2u + 1u] += y_edge;
+            }
+        }
+    }
+    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+        let a = area[i];
+        if even_odd {
+            area[i] = abs(a - 2.0 * round(0.5 * a));
+        } else {
+            area[i] = min(abs(a), vec4(1.0));
+        }
+    }
+    *result = area;
+}
+
+// Scales the quarters of a pixel's coverage so that their mean is `coverage`, which the
+// aliasing, feathering and multisampling of a fill can make differ from the quarters.
+fn match_coverage(quarters: vec4<f32>, coverage: f32) -> vec4<f32> {
+    let mean = dot(quarters, vec4(0.25));
+    if coverage >= mean {
+        // Scale the uncovered parts, which keeps every quarter at or below full coverage.
+        let scale = select(0.0, (1.0 - coverage) / (1.0 - mean), mean < 1.0);
+        return 1.0 - (1.0 - quarters) * scale;
+    }
+    return quarters * (coverage / mean);
+}
+
 // The X size should be 16 / PIXELS_PER_THREAD
 @compute @workgroup_size(4, 16)
 fn main(
@@ -862,11 +1042,35 @@ fn main(
         rgba[i] = unpack4x8unorm(config.base_color).wzyx;
     }
     var blend_stack: array<array<u32, PIXELS_PER_THREAD>, BLEND_STACK_SPLIT>;
+    // Coverage of the quarters of each pixel by the intersection of the clip shapes of each
+    // open clip and the clips enclosing it. Combining nested clips a quarter at a time keeps
+    // both shared and abutting edges close to their exact coverage.
+    var clip_area: array<array<vec4<f32>, PIXELS_PER_THREAD>, BLEND_STACK_SPLIT>;
     var clip_depth = 0u;
+    // Bits set for each open clip which is a non-isolated or knockout group.
+    var non_isolated_clips = 0u;
+    var knockout_clips = 0u;
+    // Whether the innermost open clip is a knockout group, and its initial backdrop.
+    var knockout = false;
+    var knockout_backdrop: array<vec4<f32>, PIXELS_PER_THREAD>;
     var area: array<f32, PIXELS_PER_THREAD>;
+    // The fill which area was last computed from, if it wasn't a solid tile.
+    var last_fill: CmdFill;
+    var last_fill_set = false;
+    // Whether area holds the coverage of a glyph.
+    var glyph_fill = false;
+    // Whether area covers every pixel of an opaque scene, so colors can replace rgba.
+    var solid = false;
+    // Whether the coverage of fills is clipped to clip_rect.
+    var clip_rect_set = false;
+    var clip_rect = vec4(0.0);
     var cmd_ix = tile_ix * PTCL_INITIAL_ALLOC;
     let blend_offset = ptcl[cmd_ix];
     cmd_ix += 1u;
+    // Leave the existing contents of the target in place for clean tiles
+    if ptcl[cmd_ix] == CMD_SKIP {
+        return;
+    }
     // main interpretation loop
     while true {
         let tag = ptcl[cmd_ix];
@@ -882,6 +1086,25 @@ fn main(
 #else
                 fill_path(fill, local_xy, &area);
 #endif
+                if config.conservative_coverage != 0u {
+                    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                        // The threshold absorbs rounding error, which can leave a tiny
+                        // amount of coverage in pixels the path doesn't touch.
+                        area[i] = select(0.0, 1.0, area[i] > 1e-4);
+                    }
+                } else if (fill.size_and_rule & 4u) != 0u {
+                    // Aliased draws cover the pixels whose centers they cover, roughly.
+                    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                        area[i] = select(0.0, 1.0, area[i] >= 0.5);
+                    }
+                }
+                if clip_rect_set {
+                    apply_clip_rect(xy, clip_rect, &area);
+                }
+                glyph_fill = (fill.size_and_rule & 2u) != 0u;
+                solid = false;
+                last_fill = fill;
+                last_fill_set = true;
                 cmd_ix += 4u;
             }
             // CMD_STROKE
@@ -899,15 +1122,30 @@ fn main(
                 for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
                     area[i] = 1.0;
                 }
+                if clip_rect_set {
+                    apply_clip_rect(xy, clip_rect, &area);
+                }
+                solid = config.opaque != 0u && !clip_rect_set;
+                last_fill_set = false;
                 cmd_ix += 1u;
             }
             // CMD_COLOR
             case 5u: {
                 let color = read_color(cmd_ix);
                 let fg = unpack4x8unorm(color.rgba_color).wzyx;
-                for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
-                    let fg_i = fg * area[i];
-                    rgba[i] = rgba[i] * (1.0 - fg_i.a) + fg_i;
+                if solid {
+                    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                        rgba[i] = fg;
+                    }
+                } else {
+                    let correct_glyph = glyph_fill && config.glyph_gamma != 1.0;
+                    for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                        var coverage = area[i];
+                        if correct_glyph {
+                            coverage = glyph_coverage(coverage, fg);
+                        }
+                        rgba[i] = draw_over(rgba[i], fg, coverage, knockout, knockout_backdrop[i]);
+                    }
                 }
                 cmd_ix += 2u;
             }
@@ -919,8 +1157,7 @@ fn main(
                     let my_d = d + lin.line_x * f32(i);
                     let x = i32(round(extend_mode(my_d, lin.extend_mode) * f32(GRADIENT_WIDTH - 1)));
                     let fg_rgba = textureLoad(gradients, vec2(x, i32(lin.index)), 0);
-                    let fg_i = fg_rgba * area[i];
-                    rgba[i] = rgba[i] * (1.0 - fg_i.a) + fg_i;
+                    rgba[i] = draw_over(rgba[i], fg_rgba, area[i], knockout, knockout_backdrop[i]);
                 }
                 cmd_ix += 3u;
             }
@@ -964,8 +1201,7 @@ fn main(
                         t = select(t, 1.0 - t, is_swapped);
                         let x = i32(round(t * f32(GRADIENT_WIDTH - 1)));
                         let fg_rgba = textureLoad(gradients, vec2(x, i32(rad.index)), 0);
-                        let fg_i = fg_rgba * area[i];
-                        rgba[i] = rgba[i] * (1.0 - fg_i.a) + fg_i;
+                        rgba[i] = draw_over(rgba[i], fg_rgba, area[i], knockout, knockout_backdrop[i]);
                     }
                 }
                 cmd_ix += 3u;
@@ -973,59 +1209,169 @@ fn main(
             // CMD_IMAGE
             case 8u: {
                 let image = read_image(cmd_ix);
-                let atlas_extents = image.atlas_offset + image.extents;
+                let mode = vec2(image.sampling & 3u, (image.sampling >> 2u) & 3u);
+                let nearest = (image.sampling & IMAGE_FILTER_NEAREST) != 0u;
+                let premultiplied = (image.sampling & IMAGE_PREMULTIPLIED) != 0u;
+                // Clamp to border only draws inside of the image, along the axes using it.
+                let clipped = mode == vec2(IMAGE_ADDRESS_CLAMP_TO_BORDER);
                 for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
-                    let my_xy = vec2(xy.x + f32(i), xy.y);
-                    let atlas_uv = image.matrx.xy * my_xy.x + image.matrx.zw * my_xy.y + image.xlat + image.atlas_offset;
-                    // This currently clips to the image bounds. TODO: extend modes
-                    if all(atlas_uv < atlas_extents) && area[i] != 0.0 {
-                        let uv_quad = vec4(max(floor(atlas_uv), image.atlas_offset), min(ceil(atlas_uv), atlas_extents));
-                        let uv_frac = fract(atlas_uv);
-                        let a = premul_alpha(textureLoad(image_atlas, vec2<i32>(uv_quad.xy), 0));
-                        let b = premul_alpha(textureLoad(image_atlas, vec2<i32>(uv_quad.xw), 0));
-                        let c = premul_alpha(textureLoad(image_atlas, vec2<i32>(uv_quad.zy), 0));
-                        let d = premul_alpha(textureLoad(image_atlas, vec2<i32>(uv_quad.zw), 0));
-                        let fg_rgba = mix(mix(a, b, uv_frac.y), mix(c, d, uv_frac.y), uv_frac.x);
-                        let fg_i = fg_rgba * area[i];
-                        rgba[i] = rgba[i] * (1.0 - fg_i.a) + fg_i;
+                    let my_xy = vec2(xy.x + f32(i) + 0.5, xy.y + 0.5);
+                    let uv = image.matrx.xy * my_xy.x + image.matrx.zw * my_xy.y + image.xlat;
+                    let inside = uv >= vec2(0.0) && uv < image.extents;
+                    if all(inside || !clipped) && all(image.extents > vec2(0.0)) && area[i] != 0.0 {
+                        var fg_rgba: vec4<f32>;
+                        if nearest {
+                            let texel = image_address(floor(uv), image.extents, mode);
+                            fg_rgba = load_image_texel(image.atlas_offset + texel, premultiplied);
+                        } else {
+                            // Interpolate between the centers of the neighboring texels, with
+                            // each one mapped onto the image so that texels outside of this
+                            // image in the atlas are never read.
+                            let sample_uv = uv - vec2(0.5);
+                            let uv_frac = fract(sample_uv);
+                            let lo = image.atlas_offset + image_address(floor(sample_uv), image.extents, mode);
+                            let hi = image.atlas_offset + image_address(floor(sample_uv) + 1.0, image.extents, mode);
+                            // Texels are premultiplied before filtering, so that the color of fully
+                            // transparent texels doesn't bleed into their neighbors.
+                            let a = load_image_texel(lo, premultiplied);
+                            let b = load_image_texel(vec2(lo.x, hi.y), premultiplied);
+                            let c = load_image_texel(vec2(hi.x, lo.y), premultiplied);
+                            let d = load_image_texel(hi, premultiplied);
+                            fg_rgba = mix(mix(a, b, uv_frac.y), mix(c, d, uv_frac.y), uv_frac.x);
+                        }
+                        rgba[i] = draw_over(rgba[i], fg_rgba * image.tint, area[i], knockout, knockout_backdrop[i]);
                     }
                 }
                 cmd_ix += 2u;
             }
+            // CMD_BILINEAR_GRAD
+            case 15u: {
+                let bilinear = read_bilinear_grad(cmd_ix);
+                for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                    let my_xy = vec2(xy.x + f32(i) + 0.5, xy.y + 0.5);
+                    let uv = clamp(bilinear.matrx.xy * my_xy.x + bilinear.matrx.zw * my_xy.y + bilinear.xlat, vec2(0.0), vec2(1.0));
+                    let fg_rgba = mix(mix(bilinear.c00, bilinear.c10, uv.x), mix(bilinear.c01, bilinear.c11, uv.x), uv.y);
+                    rgba[i] = draw_over(rgba[i], fg_rgba, area[i], knockout, knockout_backdrop[i]);
+                }
+                cmd_ix += 2u;
+            }
             // CMD_BEGIN_CLIP
             case 9u: {
+                let begin_clip = read_begin_clip(cmd_ix);
+                let isolated = (begin_clip.flags & CLIP_FLAG_NON_ISOLATED) == 0u;
+                knockout = (begin_clip.flags & CLIP_FLAG_KNOCKOUT) != 0u;
+                let bit = select(0u, 1u << clip_depth, clip_depth < MAX_FLAGGED_CLIP_DEPTH);
+                non_isolated_clips = select(non_isolated_clips | bit, non_isolated_clips & ~bit, isolated);
+                knockout_clips = select(knockout_clips & ~bit, knockout_clips | bit, knockout);
                 if clip_depth < BLEND_STACK_SPLIT {
+                    // The clip shape was the last fill.
+                    var quarters: array<vec4<f32>, PIXELS_PER_THREAD>;
+                    if last_fill_set {
+                        fill_path_quarters(last_fill, local_xy, &quarters);
+                    } else {
+                        for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                            quarters[i] = vec4(1.0);
+                        }
+                    }
                     for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                        var clip_quarters = match_coverage(quarters[i], area[i]);
+                        if clip_depth > 0u {
+                            clip_quarters = min(clip_quarters, clip_area[clip_depth - 1u][i]);
+                        }
                         blend_stack[clip_depth][i] = pack4x8unorm(rgba[i]);
-                        rgba[i] = vec4(0.0);
+                        clip_area[clip_depth][i] = clip_quarters;
+                        // A non-isolated group starts out with a copy of its backdrop.
+                        if isolated {
+                            rgba[i] = vec4(0.0);
+                        }
+                        knockout_backdrop[i] = rgba[i];
                     }
                 } else {
                     // TODO: spill to memory
                 }
                 clip_depth += 1u;
-                cmd_ix += 1u;
+                cmd_ix += 2u;
             }
             // CMD_END_CLIP
             case 10u: {
                 let end_clip = read_end_clip(cmd_ix);
                 clip_depth -= 1u;
+                let isolated = !clip_flag(non_isolated_clips, clip_depth);
+                // The group is itself drawn into the enclosing group, which may knock it out.
+                let parent = clip_depth - 1u;
+                knockout = clip_depth > 0u && clip_flag(knockout_clips, parent);
                 for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
-                    var bg_rgba: u32;
+                    if knockout && parent < BLEND_STACK_SPLIT {
+                        let parent_isolated = !clip_flag(non_isolated_clips, parent);
+                        knockout_backdrop[i] = select(unpack4x8unorm(blend_stack[parent][i]), vec4(0.0), parent_isolated);
+                    }
+                }
+                for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                    // Clips past the blend stack aren't spilled to memory yet, so their
+                    // content is dropped.
+                    var bg_rgba = 0u;
+                    var coverage = 0.0;
                     if clip_depth < BLEND_STACK_SPLIT {
                         bg_rgba = blend_stack[clip_depth][i];
-                    } else {
-                        // load from memory
+                        coverage = dot(clip_area[clip_depth][i], vec4(0.25));
+                        // The enclosing clips apply their own coverage as they end, so only the
+                        // fraction of theirs which this clip keeps is applied here. The product
+                        // over all of them is the coverage of the intersection.
+                        if clip_depth > 0u {
+                            let parent = dot(clip_area[clip_depth - 1u][i], vec4(0.25));
+                            coverage = select(0.0, coverage / parent, parent > 0.0);
+                        }
                     }
                     let bg = unpack4x8unorm(bg_rgba);
-                    let fg = rgba[i] * area[i] * end_clip.alpha;
-                    rgba[i] = blend_mix_compose(bg, fg, end_clip.blend);
+                    if !isolated {
+                        // The content already includes the backdrop it was drawn onto.
+                        rgba[i] = mix(bg, rgba[i], coverage * end_clip.alpha);
+                    } else if knockout {
+                        let fg = rgba[i] * end_clip.alpha;
+                        let group = blend_mix_compose(knockout_backdrop[i], fg, end_clip.blend);
+                        rgba[i] = mix(bg, group, coverage);
+                    } else {
+                        let fg = rgba[i] * coverage * end_clip.alpha;
+                        rgba[i] = blend_mix_compose(bg, fg, end_clip.blend);
+                    }
                 }
                 cmd_ix += 3u;
             }
+            // CMD_FEATHER
+            case 13u: {
+                let feather = read_feather(cmd_ix);
+                for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                    let p = vec2(xy.x + f32(i), xy.y) + 0.5;
+                    let d = min(p - feather.rect.xy, feather.rect.zw - p);
+                    let ramp = clamp(d / feather.feather + 0.5, vec2(0.0), vec2(1.0));
+                    area[i] *= ramp.x * ramp.y;
+                }
+                cmd_ix += 6u;
+            }
+            // CMD_COLOR_MATRIX
+            case 14u: {
+                let color_matrix = read_color_matrix(cmd_ix);
+                for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
+                    let fg = rgba[i];
+                    let a_inv = 1.0 / max(fg.a, 1e-6);
+                    let color = vec4(fg.rgb * a_inv, fg.a);
+                    let result = clamp(color_matrix.matrx * color + color_matrix.offset, vec4(0.0), vec4(1.0));
+                    rgba[i] = vec4(result.rgb * result.a, result.a);
+                }
+                cmd_ix += 21u;
+            }
             // CMD_JUMP
             case 11u: {
                 cmd_ix = ptcl[cmd_ix + 1u];
             }
+            // CMD_CLIP_RECT
+            case 16u: {
+                clip_rect = read_clip_rect(cmd_ix).rect;
+                // Coarse clears the rect by writing an empty one, as draws whose clip rect
+                // is empty are culled before they reach coarse.
+                clip_rect_set = clip_rect.x < clip_rect.z;
+                cmd_ix += 5u;
+            }
             default: {}
         }
     }
@@ -1033,10 +1379,18 @@ fn main(
     for (var i = 0u; i < PIXELS_PER_THREAD; i += 1u) {
         let coords = xy_uint + vec2(i, 0u);
         if coords.x < config.target_width && coords.y < config.target_height {
-            let fg = rgba[i];
+            var fg = rgba[i];
+            if config.grain_intensity != 0.0 {
+                // Grain is added to the color in proportion to alpha, so it stays premultiplied.
+                let noise = grain_noise(coords, config.seed) * config.grain_intensity;
+                fg = vec4(clamp(fg.rgb + noise * fg.a, vec3(0.0), vec3(fg.a)), fg.a);
+            }
             // Max with a small epsilon to avoid NaNs
             let a_inv = 1.0 / max(fg.a, 1e-6);
-            let rgba_sep = vec4(fg.rgb * a_inv, fg.a);
+            var rgba_sep = vec4(fg.rgb * a_inv, fg.a);
+            if arrayLength(&transfer_lut) >= 2u {
+                rgba_sep = vec4(apply_transfer_lut(srgb_to_linear(rgba_sep.rgb)), rgba_sep.a);
+            }
             textureStore(output, vec2<i32>(coords), rgba_sep);
         }
     } 
@@ -1055,6 +1409,60 @@ fn main(
 #endif
 }
 
+// Decodes sRGB encoded color values to linear light.
+fn srgb_to_linear(srgb: vec3<f32>) -> vec3<f32> {
+    let low = srgb / 12.92;
+    let high = pow((srgb + 0.055) / 1.055, vec3(2.4));
+    return select(high, low, srgb <= vec3(0.04045));
+}
+
+// Encodes linear intensities with the output transfer function, interpolating between the
+// entries of the table.
+fn apply_transfer_lut(linear: vec3<f32>) -> vec3<f32> {
+    let last = arrayLength(&transfer_lut) - 1u;
+    let pos = clamp(linear, vec3(0.0), vec3(1.0)) * f32(last);
+    var result: vec3<f32>;
+    for (var i = 0u; i < 3u; i += 1u) {
+        let lo = min(u32(pos[i]), last - 1u);
+        let t = pos[i] - f32(lo);
+        result[i] = mix(transfer_lut[lo], transfer_lut[lo + 1u], t);
+    }
+    return result;
+}
+
+// Returns noise in [-1, 1] for film grain, from a hash of the pixel coordinates and seed.
+fn grain_noise(xy: vec2<u32>, seed: u32) -> f32 {
+    var h = (xy.x * 0x8da6b343u) ^ (xy.y * 0xd8163841u) ^ (seed * 0xcb1ab31fu);
+    h = (h ^ (h >> 16u)) * 0x7feb352du;
+    h = (h ^ (h >> 15u)) * 0x846ca68bu;
+    h = h ^ (h >> 16u);
+    return f32(h >> 8u) * (2.0 / 16777215.0) - 1.0;
+}
+
+let IMAGE_ADDRESS_CLAMP_TO_BORDER = 0u;
+let IMAGE_ADDRESS_REPEAT = 2u;
+let IMAGE_ADDRESS_MIRROR_REPEAT = 3u;
+let IMAGE_FILTER_NEAREST = 16u;
+let IMAGE_PREMULTIPLIED = 32u;
+
+// Maps whole texel coordinates onto an image of the given size, using the address mode of
+// each axis. Both clamp modes clamp to the edge texels here.
+fn image_address(texel: vec2<f32>, size: vec2<f32>, mode: vec2<u32>) -> vec2<f32> {
+    var result = clamp(texel, vec2(0.0), size - vec2(1.0));
+    let repeated = texel - size * floor(texel / size);
+    let period = 2.0 * size;
+    let mirrored_period = texel - period * floor(texel / period);
+    let mirrored = select(mirrored_period, period - vec2(1.0) - mirrored_period, mirrored_period >= size);
+    result = select(result, repeated, mode == vec2(IMAGE_ADDRESS_REPEAT));
+    return select(result, mirrored, mode == vec2(IMAGE_ADDRESS_MIRROR_REPEAT));
+}
+
 fn premul_alpha(rgba: vec4<f32>) -> vec4<f32> {
     return vec4(rgba.rgb * rgba.a, rgba.a);
 }
+
+// Loads a texel of the image atlas, premultiplied.
+fn load_image_texel(xy: vec2<f32>, premultiplied: bool) -> vec4<f32> {
+    let rgba = textureLoad(image_atlas, vec2<i32>(xy), 0);
+    return select(premul_alpha(rgba), rgba, premultiplied);
+}
@@ -219,7 +219,10 @@ fn flatten_cubic(cubic: CubicPoints, path_ix: u32, local_to_device: Transform, o
     // Normal vector to calculate the start point of the offset curve.
     var n0 = offset * cubic_start_normal(p0, p1, p2, p3);
 
-    let n = max(u32(ceil(val * (0.5 / scaled_sqrt_tol))), 1u);
+    var n = max(u32(ceil(val * (0.5 / scaled_sqrt_tol))), 1u);
+    if config.max_curve_segments != 0u {
+        n = min(n, config.max_curve_segments);
+    }
     var lp0 = p0;
     qp0 = p0;
     let v_step = val / f32(n);
@@ -280,6 +283,41 @@ fn flatten_cubic(cubic: CubicPoints, path_ix: u32, local_to_device: Transform, o
     }
 }
 
+// Returns true if the device-space control points of a curve all lie past the same edge of the
+// target. The curve is then inside their convex hull, as is the line between its end points,
+// so a fill of either one covers exactly the same pixels of the target.
+fn hull_outside_target(p0: vec2f, p1: vec2f, p2: vec2f, p3: vec2f) -> bool {
+    let lo = min(min(p0, p1), min(p2, p3));
+    let hi = max(max(p0, p1), max(p2, p3));
+    let size = vec2(f32(config.target_width), f32(config.target_height));
+    return any(hi < vec2(0.0)) || any(lo > size);
+}
+
+// Flattens a filled quadratic Bézier with device-space control points. Unlike `flatten_cubic`,
+// there is no approximation by quadratics, so the whole error budget is spent on flattening and
+// the subdivision doesn't need to be estimated in two passes.
+fn flatten_quad(p0: vec2f, p1: vec2f, p2: vec2f, path_ix: u32) {
+    let ACCURACY = 0.25;
+    let sqrt_tol = sqrt(ACCURACY);
+    let params = estimate_subdiv(p0, p1, p2, sqrt_tol);
+    var n = max(u32(ceil(params.val * (0.5 / sqrt_tol))), 1u);
+    if config.max_curve_segments != 0u {
+        n = min(n, config.max_curve_segments);
+    }
+    let u0 = approx_parabola_inv_integral(params.a0);
+    let u2 = approx_parabola_inv_integral(params.a2);
+    let uscale = 1.0 / (u2 - u0);
+    var lp0 = p0;
+    for (var i = 1u; i < n; i += 1u) {
+        let a = mix(params.a0, params.a2, f32(i) / f32(n));
+        let t = (approx_parabola_inv_integral(a) - u0) * uscale;
+        let lp1 = eval_quad(p0, p1, p2, t);
+        output_line(path_ix, lp0, lp1);
+        lp0 = lp1;
+    }
+    output_line(path_ix, lp0, p2);
+}
+
 // Flattens the circular arc that subtends the angle begin-center-end. It is assumed that
 // ||begin - center|| == ||end - center||. `begin`, `end`, and `center` are defined in the path's
 // local coordinate space.
@@ -550,8 +588,19 @@ fn read_path_segment(tag: PathTagData, is_stroke: bool) -> CubicPoints {
     return CubicPoints(p0, p1, p2, p3);
 }
 
+// Moves a point within `config.horizontal_epsilon` of a pixel row boundary onto it, so that
+// nearly horizontal lines don't cross the boundary by a sliver which the winding and area
+// computations can't resolve precisely. Every line sharing the point moves with it, so the
+// outline stays closed.
+fn snap_to_row(p: vec2f) -> vec2f {
+    let row = round(p.y);
+    return vec2(p.x, select(p.y, row, abs(p.y - row) < config.horizontal_epsilon));
+}
+
 // Writes a line into a the `lines` buffer at a pre-allocated location designated by `line_ix`.
-fn write_line(line_ix: u32, path_ix: u32, p0: vec2f, p1: vec2f) {
+fn write_line(line_ix: u32, path_ix: u32, p0_in: vec2f, p1_in: vec2f) {
+    let p0 = snap_to_row(p0_in);
+    let p1 = snap_to_row(p1_in);
     bbox = vec4(min(bbox.xy, min(p0, p1)), max(bbox.zw, max(p0, p1)));
     lines[line_ix] = LineSoup(path_ix, p0, p1);
 }
@@ -624,8 +673,12 @@ fn main(
 
     let out = &path_bboxes[path_ix];
     let style_flags = scene[config.style_base + style_ix];
+    let clip_rect = scene[config.style_base + style_ix + 2u];
     // The fill bit is always set to 0 for strokes which represents a non-zero fill.
-    let draw_flags = select(DRAW_INFO_FLAGS_FILL_RULE_BIT, 0u, (style_flags & STYLE_FLAGS_FILL) == 0u);
+    let draw_flags = select(DRAW_INFO_FLAGS_FILL_RULE_BIT, 0u, (style_flags & STYLE_FLAGS_FILL) == 0u)
+        | select(0u, DRAW_INFO_FLAGS_GLYPH_BIT, (style_flags & STYLE_FLAGS_GLYPH) != 0u)
+        | select(0u, DRAW_INFO_FLAGS_ALIASED_BIT, (style_flags & STYLE_FLAGS_ALIASED) != 0u)
+        | (clip_rect << DRAW_INFO_FLAGS_CLIP_RECT_SHIFT);
     if (tag.tag_byte & PATH_TAG_PATH) != 0u {
         (*out).draw_flags = draw_flags;
         (*out).trans_ix = trans_ix;
@@ -634,13 +687,27 @@ fn main(
     let seg_type = tag.tag_byte & PATH_TAG_SEG_TYPE;
     if seg_type != 0u {
         let is_stroke = (style_flags & STYLE_FLAGS_STYLE) != 0u;
-        let transform = read_transform(config.transform_base, trans_ix);
-        let pts = read_path_segment(tag, is_stroke);
+        var transform = read_transform(config.transform_base, trans_ix);
+        var pts = read_path_segment(tag, is_stroke);
 
         if is_stroke {
             let linewidth = bitcast<f32>(scene[config.style_base + style_ix + 1u]);
             let offset = 0.5 * linewidth;
 
+            // A non-scaling stroke is offset in device space, so the segment is transformed up
+            // front and the offset curves aren't transformed again.
+            let local_to_device = transform;
+            let non_scaling = (style_flags & STYLE_FLAGS_NON_SCALING) != 0u;
+            if non_scaling {
+                pts = CubicPoints(
+                    transform_apply(transform, pts.p0),
+                    transform_apply(transform, pts.p1),
+                    transform_apply(transform, pts.p2),
+                    transform_apply(transform, pts.p3)
+                );
+                transform = transform_identity();
+            }
+
             let is_open = (tag.tag_byte & PATH_TAG_SEG_TYPE) != PATH_TAG_LINETO;
             let is_stroke_cap_marker = (tag.tag_byte & PATH_TAG_SUBPATH_END) != 0u;
             if is_stroke_cap_marker {
@@ -661,7 +728,10 @@ fn main(
                 // Read the neighboring segment.
                 let neighbor = read_neighboring_segment(ix + 1u);
                 let tan_prev = cubic_end_tangent(pts.p0, pts.p1, pts.p2, pts.p3);
-                let tan_next = neighbor.tangent;
+                var tan_next = neighbor.tangent;
+                if non_scaling {
+                    tan_next = local_to_device.mat.xy * tan_next.x + local_to_device.mat.zw * tan_next.y;
+                }
                 let offset_tangent = offset * normalize(tan_prev);
                 let n_prev = offset_tangent.yx * vec2f(-1., 1.);
                 let n_next = offset * normalize(tan_next).yx * vec2f(-1., 1.);
@@ -674,8 +744,35 @@ fn main(
                              pts.p3, pts.p3 + n_prev, pts.p3 - n_prev, offset_tangent, transform);
                 }
             }
+        } else if seg_type == PATH_TAG_QUADTO {
+            // Undo the degree raise in `read_path_segment` to recover the quadratic.
+            let p0 = transform_apply(transform, pts.p0);
+            let p1 = transform_apply(transform, 1.5 * pts.p1 - 0.5 * pts.p0);
+            let p2 = transform_apply(transform, pts.p3);
+            if config.cull_offscreen_curves != 0u && hull_outside_target(p0, p1, p1, p2) {
+                output_line(path_ix, p0, p2);
+            } else {
+                flatten_quad(p0, p1, p2, path_ix);
+            }
         } else {
-            flatten_cubic(pts, path_ix, transform, /*offset*/ 0.);
+            let p0 = transform_apply(transform, pts.p0);
+            let p3 = transform_apply(transform, pts.p3);
+            if config.cull_offscreen_curves != 0u && hull_outside_target(
+                p0, transform_apply(transform, pts.p1), transform_apply(transform, pts.p2), p3
+            ) {
+                output_line(path_ix, p0, p3);
+            } else {
+                flatten_cubic(pts, path_ix, transform, /*offset*/ 0.);
+            }
+        }
+        // A draw clipped to a rectangle is bounded by it, so that the tiles outside of the
+        // rectangle are culled by binning and tile allocation.
+        if clip_rect != 0u {
+            let rect_ix = config.clip_rect_base + (clip_rect - 1u) * 4u;
+            let rect = bitcast<vec4f>(vec4(
+                scene[rect_ix], scene[rect_ix + 1u], scene[rect_ix + 2u], scene[rect_ix + 3u]
+            ));
+            bbox = vec4(max(bbox.xy, rect.xy), min(bbox.zw, rect.zw));
         }
         // Update bounding box using atomics only. Computing a monoid is a
         // potential future optimization.
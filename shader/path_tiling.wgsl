@@ -89,18 +89,23 @@ fn main(
         let tile_xy = vec2(f32(x) * f32(TILE_WIDTH), f32(y) * f32(TILE_HEIGHT));
         let tile_xy1 = tile_xy + vec2(f32(TILE_WIDTH), f32(TILE_HEIGHT));
 
+        // The crossings with the tile edges are computed from the whole line, rather than from
+        // the part left after clipping the other end, so that the tiles on either side of an
+        // edge agree exactly on the crossing and thin lines are continuous across seams.
+        let line_xy0 = xy0;
+        let line_xy1 = xy1;
         if seg_within_line > 0u {
             let z_prev = floor(a * (f32(seg_within_line) - 1.0) + b);
             if z == z_prev {
                 // Top edge is clipped
-                var xt = xy0.x + (xy1.x - xy0.x) * (tile_xy.y - xy0.y) / (xy1.y - xy0.y);
+                var xt = line_xy0.x + (line_xy1.x - line_xy0.x) * (tile_xy.y - line_xy0.y) / (line_xy1.y - line_xy0.y);
                 // TODO: we want to switch to tile-relative coordinates
                 xt = clamp(xt, tile_xy.x + 1e-3, tile_xy1.x);
                 xy0 = vec2(xt, tile_xy.y);
             } else {
                 // If is_positive_slope, left edge is clipped, otherwise right
                 let x_clip = select(tile_xy1.x, tile_xy.x, is_positive_slope);
-                var yt = xy0.y + (xy1.y - xy0.y) * (x_clip - xy0.x) / (xy1.x - xy0.x);
+                var yt = line_xy0.y + (line_xy1.y - line_xy0.y) * (x_clip - line_xy0.x) / (line_xy1.x - line_xy0.x);
                 yt = clamp(yt, tile_xy.y + 1e-3, tile_xy1.y);
                 xy0 = vec2(x_clip, yt);
             }
@@ -109,13 +114,13 @@ fn main(
             let z_next = floor(a * (f32(seg_within_line) + 1.0) + b);
             if z == z_next {
                 // Bottom edge is clipped
-                var xt = xy0.x + (xy1.x - xy0.x) * (tile_xy1.y - xy0.y) / (xy1.y - xy0.y);
+                var xt = line_xy0.x + (line_xy1.x - line_xy0.x) * (tile_xy1.y - line_xy0.y) / (line_xy1.y - line_xy0.y);
                 xt = clamp(xt, tile_xy.x + 1e-3, tile_xy1.x);
                 xy1 = vec2(xt, tile_xy1.y);
             } else {
                 // If is_positive_slope, right edge is clipped, otherwise left
                 let x_clip = select(tile_xy.x, tile_xy1.x, is_positive_slope);
-                var yt = xy0.y + (xy1.y - xy0.y) * (x_clip - xy0.x) / (xy1.x - xy0.x);
+                var yt = line_xy0.y + (line_xy1.y - line_xy0.y) * (x_clip - line_xy0.x) / (line_xy1.x - line_xy0.x);
                 yt = clamp(yt, tile_xy.y + 1e-3, tile_xy1.y);
                 xy1 = vec2(x_clip, yt);
             }
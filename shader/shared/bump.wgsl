@@ -17,6 +17,10 @@ struct BumpAllocators {
     segments: atomic<u32>,
     blend: atomic<u32>,
     lines: atomic<u32>,
+    // Numbers of tiles of draw objects that coarse rasterization wrote as solid fills and as
+    // fills with coverage from segments. These are only used for statistics.
+    solid_tiles: atomic<u32>,
+    coverage_tiles: atomic<u32>,
 }
 
 struct IndirectCount {
@@ -30,22 +30,43 @@ struct Config {
 
     transform_base: u32,
     style_base: u32,
+    clip_rect_base: u32,
 
     // Sizes of bump allocated buffers (in element size units)
     binning_size: u32,
     tiles_size: u32,
     segments_size: u32,
     ptcl_size: u32,
+
+    // Width of a bin in tiles. Bins always contain N_TILE tiles.
+    bin_width: u32,
+    // Number of draw objects stored in the chunk of each bin in a partition before the rest
+    // spill into an overflow chunk.
+    bin_capacity: u32,
+    // Gamma used to correct the coverage of glyphs drawn with solid colors, 1.0 to disable.
+    glyph_gamma: f32,
+    // Non-zero to treat every partially covered pixel of a fill as fully covered.
+    conservative_coverage: u32,
+    // Maximum number of lines a single curve is flattened into, whatever the tolerance, or 0
+    // for no limit.
+    max_curve_segments: u32,
+    // Largest change made to each color channel by film grain noise, 0.0 to disable.
+    grain_intensity: f32,
+    // Seed for stochastic effects, such as the film grain.
+    seed: u32,
+    // Non-zero if the scene and base color are opaque, with no layers.
+    opaque: u32,
+    // Distance from a pixel row boundary within which flattened points are moved onto it.
+    horizontal_epsilon: f32,
+    // Non-zero to flatten filled curves lying entirely past an edge of the target into a line.
+    cull_offscreen_curves: u32,
 }
 
 // Geometry of tiles and bins
 
 let TILE_WIDTH = 16u;
 let TILE_HEIGHT = 16u;
-// Number of tiles per bin
-let N_TILE_X = 16u;
-let N_TILE_Y = 16u;
-//let N_TILE = N_TILE_X * N_TILE_Y;
+// Number of tiles per bin. The shape of a bin is given by config.bin_width.
 let N_TILE = 256u;
 
 // Not currently supporting non-square tiles
@@ -19,14 +19,22 @@ let DRAWTAG_NOP = 0u;
 let DRAWTAG_FILL_COLOR = 0x44u;
 let DRAWTAG_FILL_LIN_GRADIENT = 0x114u;
 let DRAWTAG_FILL_RAD_GRADIENT = 0x29cu;
-let DRAWTAG_FILL_IMAGE = 0x248u;
-let DRAWTAG_BEGIN_CLIP = 0x9u;
+let DRAWTAG_FILL_IMAGE = 0x2d0u;
+let DRAWTAG_FILL_BILINEAR_GRADIENT = 0x6ccu;
+let DRAWTAG_BEGIN_CLIP = 0x1du;
+let DRAWTAG_BEGIN_CLIP_COLOR_MATRIX = 0x501du;
 let DRAWTAG_END_CLIP = 0x21u;
 
 /// The first word of each draw info stream entry contains the flags. This is not a part of the
 /// draw object stream but get used after the draw objects have been reduced on the GPU.
 /// 0 represents a non-zero fill. 1 represents an even-odd fill.
 let DRAW_INFO_FLAGS_FILL_RULE_BIT = 1u;
+// Set for glyph outlines.
+let DRAW_INFO_FLAGS_GLYPH_BIT = 2u;
+// Set for draws with hard coverage instead of antialiasing.
+let DRAW_INFO_FLAGS_ALIASED_BIT = 4u;
+// The bits above this hold one more than the index of the clip rect of the draw, or 0.
+let DRAW_INFO_FLAGS_CLIP_RECT_SHIFT = 8u;
 
 fn draw_monoid_identity() -> DrawMonoid {
     return DrawMonoid();
@@ -45,7 +53,8 @@ fn map_draw_tag(tag_word: u32) -> DrawMonoid {
     var c: DrawMonoid;
     c.path_ix = u32(tag_word != DRAWTAG_NOP);
     c.clip_ix = tag_word & 1u;
-    c.scene_offset = (tag_word >> 2u) & 0x07u;
+    // Tags with more than 7 words of draw data keep the remainder in bits 10-15.
+    c.scene_offset = ((tag_word >> 2u) & 0x07u) + ((tag_word >> 10u) & 0x3fu);
     c.info_offset = (tag_word >> 6u) & 0x0fu;
     return c;
 }
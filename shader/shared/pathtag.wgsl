@@ -24,10 +24,13 @@ let PATH_TAG_STYLE = 0x40u;
 let PATH_TAG_SUBPATH_END = 4u;
 
 // Size of the `Style` data structure in words
-let STYLE_SIZE_IN_WORDS: u32 = 2u;
+let STYLE_SIZE_IN_WORDS: u32 = 3u;
 
 let STYLE_FLAGS_STYLE: u32 = 0x80000000u;
 let STYLE_FLAGS_FILL: u32 = 0x40000000u;
+let STYLE_FLAGS_GLYPH: u32 = 0x00800000u;
+let STYLE_FLAGS_ALIASED: u32 = 0x00400000u;
+let STYLE_FLAGS_NON_SCALING: u32 = 0x00200000u;
 let STYLE_MITER_LIMIT_MASK: u32 = 0xFFFFu;
 
 let STYLE_FLAGS_START_CAP_MASK: u32 = 0x0C000000u;
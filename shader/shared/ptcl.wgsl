@@ -20,10 +20,19 @@ let CMD_IMAGE = 8u;
 let CMD_BEGIN_CLIP = 9u;
 let CMD_END_CLIP = 10u;
 let CMD_JUMP = 11u;
+// Written in place of the command list for tiles that are not dirty, these
+// tiles are left untouched by fine rasterization.
+let CMD_SKIP = 12u;
+let CMD_FEATHER = 13u;
+let CMD_COLOR_MATRIX = 14u;
+let CMD_BILINEAR_GRAD = 15u;
+let CMD_CLIP_RECT = 16u;
 
 // The individual PTCL structs are written here, but read/write is by
 // hand in the relevant shaders
 
+// size_and_rule holds the segment count in the upper 29 bits, then a bit which is set for
+// aliased draws, a bit which is set for glyphs and a bit which is set for even-odd fills.
 struct CmdFill {
     size_and_rule: u32,
     seg_data: u32,
@@ -67,9 +76,47 @@ struct CmdImage {
     xlat: vec2<f32>,
     atlas_offset: vec2<f32>,
     extents: vec2<f32>,
+    sampling: u32,
+    tint: vec4<f32>,
 }
 
+// Corner colors of the patch, in the order (0, 0), (1, 0), (0, 1), (1, 1)
+struct CmdBilinearGrad {
+    matrx: vec4<f32>,
+    xlat: vec2<f32>,
+    c00: vec4<f32>,
+    c10: vec4<f32>,
+    c01: vec4<f32>,
+    c11: vec4<f32>,
+}
+
+// Transparency group flags of a clip, taken from its blend mode
+struct CmdBeginClip {
+    flags: u32,
+}
+
+let CLIP_FLAG_NON_ISOLATED = 1u;
+let CLIP_FLAG_KNOCKOUT = 2u;
+
 struct CmdEndClip {
     blend: u32,
     alpha: f32,
 }
+
+// Sets the rectangle that the coverage of the following fills is clipped to
+struct CmdClipRect {
+    rect: vec4<f32>,
+}
+
+// Scales the clip coverage by a ramp across the edges of a rectangle
+struct CmdFeather {
+    rect: vec4<f32>,
+    feather: f32,
+}
+
+// Transforms the content of a layer before it is composited. The rows of the
+// 4x5 matrix are split into their linear part and offset.
+struct CmdColorMatrix {
+    matrx: mat4x4<f32>,
+    offset: vec4<f32>,
+}
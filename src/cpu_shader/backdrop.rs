@@ -15,7 +15,8 @@ fn backdrop_main(config: &ConfigUniform, paths: &[Path], tiles: &mut [Tile]) {
             let mut sum = 0;
             for x in 0..width {
                 let tile = &mut tiles[(base + y * width + x) as usize];
-                sum += tile.backdrop;
+                // Saturate rather than wrap, matching the shader.
+                sum = tile.backdrop.saturating_add(sum);
                 tile.backdrop = sum;
             }
         }
@@ -28,3 +29,25 @@ pub fn backdrop(_n_wg: u32, resources: &[CpuBinding]) {
     let mut tiles = resources[2].as_slice_mut();
     backdrop_main(&config, &paths, &mut tiles);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backdrop_saturates() {
+        let mut config = ConfigUniform::default();
+        config.layout.n_draw_objects = 1;
+        let mut path = Path::default();
+        path.bbox = [0, 0, 4, 1];
+        let backdrops = [i32::MAX, i32::MAX, 2, -1];
+        let mut tiles = backdrops.map(|backdrop| Tile {
+            backdrop,
+            ..Default::default()
+        });
+        backdrop_main(&config, &[path], &mut tiles);
+        // Wrapping around would have left the third tile with a winding number of zero.
+        let windings = tiles.map(|tile| tile.backdrop);
+        assert_eq!(windings, [i32::MAX, i32::MAX, i32::MAX, i32::MAX - 1]);
+    }
+}
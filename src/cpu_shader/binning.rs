@@ -8,10 +8,7 @@ use crate::cpu_dispatch::CpuBinding;
 const WG_SIZE: usize = 256;
 const TILE_WIDTH: usize = 16;
 const TILE_HEIGHT: usize = 16;
-const N_TILE_X: usize = 16;
-const N_TILE_Y: usize = 16;
-const SX: f32 = 1.0 / ((N_TILE_X * TILE_WIDTH) as f32);
-const SY: f32 = 1.0 / ((N_TILE_Y * TILE_HEIGHT) as f32);
+const N_TILE: usize = 256;
 
 fn bbox_intersect(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
     [
@@ -33,13 +30,17 @@ fn binning_main(
     bin_data: &mut [u32],
     bin_header: &mut [BinHeader],
 ) {
+    let n_tile_x = config.bin_width as usize;
+    let n_tile_y = N_TILE / n_tile_x;
+    let sx = 1.0 / ((n_tile_x * TILE_WIDTH) as f32);
+    let sy = 1.0 / ((n_tile_y * TILE_HEIGHT) as f32);
     for wg in 0..n_wg as usize {
         let mut counts = [0; WG_SIZE];
         let mut bboxes = [[0, 0, 0, 0]; WG_SIZE];
         let width_in_bins =
-            ((config.width_in_tiles + N_TILE_X as u32 - 1) / N_TILE_X as u32) as i32;
+            ((config.width_in_tiles + n_tile_x as u32 - 1) / n_tile_x as u32) as i32;
         let height_in_bins =
-            ((config.height_in_tiles + N_TILE_Y as u32 - 1) / N_TILE_Y as u32) as i32;
+            ((config.height_in_tiles + n_tile_y as u32 - 1) / n_tile_y as u32) as i32;
         for local_ix in 0..WG_SIZE {
             let element_ix = wg * WG_SIZE + local_ix;
             let mut x0 = 0;
@@ -63,10 +64,10 @@ fn binning_main(
                 let bbox = bbox_intersect(clip_bbox, pb);
                 intersected_bbox[element_ix] = bbox;
                 if bbox[0] < bbox[2] && bbox[1] < bbox[3] {
-                    x0 = (bbox[0] * SX).floor() as i32;
-                    y0 = (bbox[1] * SY).floor() as i32;
-                    x1 = (bbox[2] * SX).ceil() as i32;
-                    y1 = (bbox[3] * SY).ceil() as i32;
+                    x0 = (bbox[0] * sx).floor() as i32;
+                    y0 = (bbox[1] * sy).floor() as i32;
+                    x1 = (bbox[2] * sx).ceil() as i32;
+                    y1 = (bbox[3] * sy).ceil() as i32;
                 }
             }
             x0 = x0.clamp(0, width_in_bins);
@@ -81,24 +82,37 @@ fn binning_main(
             bboxes[local_ix] = [x0, y0, x1, y1];
         }
         let mut chunk_offset = [0; WG_SIZE];
+        let mut overflow_offset = [0; WG_SIZE];
         for local_ix in 0..WG_SIZE {
             let global_ix = wg * WG_SIZE + local_ix;
+            let chunk_count = counts[local_ix].min(config.bin_capacity);
             chunk_offset[local_ix] = bump.binning;
-            bump.binning += counts[local_ix];
+            bump.binning += chunk_count;
+            if counts[local_ix] > chunk_count {
+                overflow_offset[local_ix] = bump.binning;
+                bump.binning += counts[local_ix] - chunk_count;
+            }
             bin_header[global_ix] = BinHeader {
                 element_count: counts[local_ix],
                 chunk_offset: chunk_offset[local_ix],
+                overflow_offset: overflow_offset[local_ix],
             };
         }
+        let mut written = [0; WG_SIZE];
         for local_ix in 0..WG_SIZE {
             let element_ix = wg * WG_SIZE + local_ix;
             let bbox = bboxes[local_ix];
             for y in bbox[1]..bbox[3] {
                 for x in bbox[0]..bbox[2] {
                     let bin_ix = (y * width_in_bins + x) as usize;
-                    let ix = config.layout.bin_data_start + chunk_offset[bin_ix];
-                    bin_data[ix as usize] = element_ix as u32;
-                    chunk_offset[bin_ix] += 1;
+                    let idx = written[bin_ix];
+                    let ix = if idx < config.bin_capacity {
+                        chunk_offset[bin_ix] + idx
+                    } else {
+                        overflow_offset[bin_ix] + idx - config.bin_capacity
+                    };
+                    bin_data[(config.layout.bin_data_start + ix) as usize] = element_ix as u32;
+                    written[bin_ix] += 1;
                 }
             }
         }
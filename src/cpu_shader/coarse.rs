@@ -2,20 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT OR Unlicense
 
 use vello_encoding::{
-    BinHeader, BumpAllocators, ConfigUniform, DrawMonoid, DrawTag, Path, Tile,
-    DRAW_INFO_FLAGS_FILL_RULE_BIT,
+    BinHeader, BumpAllocators, ConfigUniform, DrawBeginClip, DrawMonoid, DrawTag, Path, Tile,
+    DRAW_INFO_FLAGS_ALIASED_BIT, DRAW_INFO_FLAGS_CLIP_RECT_SHIFT, DRAW_INFO_FLAGS_FILL_RULE_BIT,
+    DRAW_INFO_FLAGS_GLYPH_BIT,
 };
 
 use crate::cpu_dispatch::CpuBinding;
 
 use super::{
-    CMD_BEGIN_CLIP, CMD_COLOR, CMD_END, CMD_END_CLIP, CMD_FILL, CMD_IMAGE, CMD_JUMP, CMD_LIN_GRAD,
-    CMD_RAD_GRAD, CMD_SOLID, PTCL_INITIAL_ALLOC,
+    CMD_BEGIN_CLIP, CMD_BILINEAR_GRAD, CMD_CLIP_RECT, CMD_COLOR, CMD_COLOR_MATRIX, CMD_END,
+    CMD_END_CLIP, CMD_FEATHER, CMD_FILL, CMD_IMAGE, CMD_JUMP, CMD_LIN_GRAD, CMD_RAD_GRAD, CMD_SKIP,
+    CMD_SOLID, PTCL_INITIAL_ALLOC,
 };
 
-const N_TILE_X: usize = 16;
-const N_TILE_Y: usize = 16;
-const N_TILE: usize = N_TILE_X * N_TILE_Y;
+const N_TILE: usize = 256;
+const TILE_WIDTH: u32 = 16;
+const TILE_HEIGHT: u32 = 16;
 
 const PTCL_INCREMENT: u32 = 256;
 const PTCL_HEADROOM: u32 = 2;
@@ -24,15 +26,22 @@ const PTCL_HEADROOM: u32 = 2;
 struct TileState {
     cmd_offset: u32,
     cmd_limit: u32,
+    /// Top left corner of the tile, in pixels.
+    origin: [f32; 2],
+    /// The clip rect that fine currently clips fills to, in the form of the draw info flags,
+    /// or 0 for none.
+    clip_rect: u32,
 }
 
 impl TileState {
-    fn new(tile_ix: u32) -> TileState {
+    fn new(tile_ix: u32, origin: [f32; 2]) -> TileState {
         let cmd_offset = tile_ix * PTCL_INITIAL_ALLOC;
         let cmd_limit = cmd_offset + (PTCL_INITIAL_ALLOC - PTCL_HEADROOM);
         TileState {
             cmd_offset,
             cmd_limit,
+            origin,
+            clip_rect: 0,
         }
     }
 
@@ -60,14 +69,50 @@ impl TileState {
         ptcl[(self.cmd_offset + offset) as usize] = value;
     }
 
+    /// Sets the clip rect of the fill that is about to be written, if it differs from the
+    /// current one. Tiles lying completely inside of the rect don't need it.
+    fn write_clip_rect(
+        &mut self,
+        config: &ConfigUniform,
+        scene: &[u32],
+        bump: &mut BumpAllocators,
+        ptcl: &mut [u32],
+        draw_flags: u32,
+    ) {
+        let mut rect_ix = draw_flags >> DRAW_INFO_FLAGS_CLIP_RECT_SHIFT;
+        let mut rect = [0; 4];
+        if rect_ix != 0 {
+            let base = (config.layout.clip_rect_base + (rect_ix - 1) * 4) as usize;
+            rect.copy_from_slice(&scene[base..base + 4]);
+            let [x0, y0, x1, y1] = rect.map(f32::from_bits);
+            let [x, y] = self.origin;
+            if x0 <= x && y0 <= y && x + TILE_WIDTH as f32 <= x1 && y + TILE_HEIGHT as f32 <= y1 {
+                rect_ix = 0;
+                rect = [0; 4];
+            }
+        }
+        if rect_ix != self.clip_rect {
+            self.alloc_cmd(5, config, bump, ptcl);
+            self.write(ptcl, 0, CMD_CLIP_RECT);
+            for (i, value) in rect.into_iter().enumerate() {
+                self.write(ptcl, 1 + i as u32, value);
+            }
+            self.cmd_offset += 5;
+            self.clip_rect = rect_ix;
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn write_path(
         &mut self,
         config: &ConfigUniform,
+        scene: &[u32],
         bump: &mut BumpAllocators,
         ptcl: &mut [u32],
         tile: &mut Tile,
         draw_flags: u32,
     ) {
+        self.write_clip_rect(config, scene, bump, ptcl, draw_flags);
         let n_segs = tile.segment_count_or_ix;
         if n_segs != 0 {
             let seg_ix = bump.segments;
@@ -76,15 +121,22 @@ impl TileState {
             self.alloc_cmd(4, config, bump, ptcl);
             self.write(ptcl, 0, CMD_FILL);
             let even_odd = (draw_flags & DRAW_INFO_FLAGS_FILL_RULE_BIT) != 0;
-            let size_and_rule = (n_segs << 1) | (even_odd as u32);
+            let is_glyph = (draw_flags & DRAW_INFO_FLAGS_GLYPH_BIT) != 0;
+            let aliased = (draw_flags & DRAW_INFO_FLAGS_ALIASED_BIT) != 0;
+            let size_and_rule = (n_segs << 3)
+                | ((aliased as u32) << 2)
+                | ((is_glyph as u32) << 1)
+                | (even_odd as u32);
             self.write(ptcl, 1, size_and_rule);
             self.write(ptcl, 2, seg_ix);
             self.write(ptcl, 3, tile.backdrop as u32);
             self.cmd_offset += 4;
+            bump.coverage_tiles += 1;
         } else {
             self.alloc_cmd(1, config, bump, ptcl);
             self.write(ptcl, 0, CMD_SOLID);
             self.cmd_offset += 1;
+            bump.solid_tiles += 1;
         }
     }
 
@@ -114,6 +166,19 @@ impl TileState {
         self.cmd_offset += 2;
     }
 
+    fn write_bilinear_grad(
+        &mut self,
+        config: &ConfigUniform,
+        bump: &mut BumpAllocators,
+        ptcl: &mut [u32],
+        info_offset: u32,
+    ) {
+        self.alloc_cmd(2, config, bump, ptcl);
+        self.write(ptcl, 0, CMD_BILINEAR_GRAD);
+        self.write(ptcl, 1, info_offset);
+        self.cmd_offset += 2;
+    }
+
     fn write_grad(
         &mut self,
         config: &ConfigUniform,
@@ -135,10 +200,44 @@ impl TileState {
         config: &ConfigUniform,
         bump: &mut BumpAllocators,
         ptcl: &mut [u32],
+        flags: u32,
     ) {
-        self.alloc_cmd(1, config, bump, ptcl);
+        self.alloc_cmd(2, config, bump, ptcl);
         self.write(ptcl, 0, CMD_BEGIN_CLIP);
-        self.cmd_offset += 1;
+        self.write(ptcl, 1, flags);
+        self.cmd_offset += 2;
+    }
+
+    fn write_feather(
+        &mut self,
+        config: &ConfigUniform,
+        bump: &mut BumpAllocators,
+        ptcl: &mut [u32],
+        rect: [u32; 4],
+        feather: u32,
+    ) {
+        self.alloc_cmd(6, config, bump, ptcl);
+        self.write(ptcl, 0, CMD_FEATHER);
+        for (i, value) in rect.into_iter().enumerate() {
+            self.write(ptcl, 1 + i as u32, value);
+        }
+        self.write(ptcl, 5, feather);
+        self.cmd_offset += 6;
+    }
+
+    fn write_color_matrix(
+        &mut self,
+        config: &ConfigUniform,
+        bump: &mut BumpAllocators,
+        ptcl: &mut [u32],
+        matrix: &[u32],
+    ) {
+        self.alloc_cmd(21, config, bump, ptcl);
+        self.write(ptcl, 0, CMD_COLOR_MATRIX);
+        for (i, value) in matrix.iter().enumerate() {
+            self.write(ptcl, 1 + i as u32, *value);
+        }
+        self.cmd_offset += 21;
     }
 
     fn write_end_clip(
@@ -167,11 +266,16 @@ fn coarse_main(
     tiles: &mut [Tile],
     bump: &mut BumpAllocators,
     ptcl: &mut [u32],
+    dirty_tiles: &[u32],
+    overdraw: &mut [u32],
 ) {
     let width_in_tiles = config.width_in_tiles;
     let height_in_tiles = config.height_in_tiles;
-    let width_in_bins = (width_in_tiles + N_TILE_X as u32 - 1) / N_TILE_X as u32;
-    let height_in_bins = (height_in_tiles + N_TILE_Y as u32 - 1) / N_TILE_Y as u32;
+    // Bins always contain `N_TILE` tiles, but their shape is configurable.
+    let n_tile_x = config.bin_width as usize;
+    let n_tile_y = N_TILE / n_tile_x;
+    let width_in_bins = (width_in_tiles + n_tile_x as u32 - 1) / n_tile_x as u32;
+    let height_in_bins = (height_in_tiles + n_tile_y as u32 - 1) / n_tile_y as u32;
     let n_bins = width_in_bins * height_in_bins;
     let bin_data_start = config.layout.bin_data_start;
     let drawtag_base = config.layout.draw_tag_base;
@@ -183,14 +287,18 @@ fn coarse_main(
         }
         let bin_x = bin % width_in_bins;
         let bin_y = bin / width_in_bins;
-        let bin_tile_x = N_TILE_X as u32 * bin_x;
-        let bin_tile_y = N_TILE_Y as u32 * bin_y;
+        let bin_tile_x = n_tile_x as u32 * bin_x;
+        let bin_tile_y = n_tile_y as u32 * bin_y;
         for part in 0..n_partitions {
             let in_ix = part * N_TILE as u32 + bin;
             let bin_header = bin_headers[in_ix as usize];
-            let start = bin_data_start + bin_header.chunk_offset;
             for i in 0..bin_header.element_count {
-                let drawobj_ix = info_bin_data[(start + i) as usize];
+                let ix = if i < config.bin_capacity {
+                    bin_header.chunk_offset + i
+                } else {
+                    bin_header.overflow_offset + i - config.bin_capacity
+                };
+                let drawobj_ix = info_bin_data[(bin_data_start + ix) as usize];
                 let tag = scene[(drawtag_base + drawobj_ix) as usize];
                 if DrawTag(tag) != DrawTag::NOP {
                     let draw_monoid = draw_monoids[drawobj_ix as usize];
@@ -198,13 +306,13 @@ fn coarse_main(
                     let path = paths[path_ix as usize];
                     let dx = path.bbox[0] as i32 - bin_tile_x as i32;
                     let dy = path.bbox[1] as i32 - bin_tile_y as i32;
-                    let x0 = dx.clamp(0, N_TILE_X as i32);
-                    let y0 = dy.clamp(0, N_TILE_Y as i32);
-                    let x1 = (path.bbox[2] as i32 - bin_tile_x as i32).clamp(0, N_TILE_X as i32);
-                    let y1 = (path.bbox[3] as i32 - bin_tile_y as i32).clamp(0, N_TILE_Y as i32);
+                    let x0 = dx.clamp(0, n_tile_x as i32);
+                    let y0 = dy.clamp(0, n_tile_y as i32);
+                    let x1 = (path.bbox[2] as i32 - bin_tile_x as i32).clamp(0, n_tile_x as i32);
+                    let y1 = (path.bbox[3] as i32 - bin_tile_y as i32).clamp(0, n_tile_y as i32);
                     for y in y0..y1 {
                         for x in x0..x1 {
-                            compacted[(y * N_TILE_X as i32 + x) as usize].push(drawobj_ix);
+                            compacted[(y * n_tile_x as i32 + x) as usize].push(drawobj_ix);
                         }
                     }
                 }
@@ -214,12 +322,31 @@ fn coarse_main(
         // While the WGSL source does at most 256 draw objects at a time,
         // this version does all the draw objects in a tile.
         for tile_ix in 0..N_TILE {
-            let tile_x = (tile_ix % N_TILE_X) as u32;
-            let tile_y = (tile_ix / N_TILE_X) as u32;
+            let tile_x = (tile_ix % n_tile_x) as u32;
+            let tile_y = (tile_ix / n_tile_x) as u32;
+            // Bins on the right and bottom edges extend past the target. Their tiles outside
+            // of it have no draw objects, as path bounding boxes are clamped to the target by
+            // tile allocation, and no command list of their own.
+            if bin_tile_x + tile_x >= width_in_tiles || bin_tile_y + tile_y >= height_in_tiles {
+                continue;
+            }
             let this_tile_ix = (bin_tile_y + tile_y) * width_in_tiles + bin_tile_x + tile_x;
-            let mut tile_state = TileState::new(this_tile_ix);
+            let origin = [
+                ((bin_tile_x + tile_x) * TILE_WIDTH) as f32,
+                ((bin_tile_y + tile_y) * TILE_HEIGHT) as f32,
+            ];
+            let mut tile_state = TileState::new(this_tile_ix, origin);
             let blend_offset = tile_state.cmd_offset;
             tile_state.cmd_offset += 1;
+            // Only written when there is an entry for each tile, as in the WGSL source.
+            let mut draw_count = 0;
+            if dirty_tiles[(this_tile_ix / 32) as usize] & (1 << (this_tile_ix & 31)) == 0 {
+                ptcl[tile_state.cmd_offset as usize] = CMD_SKIP;
+                if let Some(count) = overdraw.get_mut(this_tile_ix as usize) {
+                    *count = 0;
+                }
+                continue;
+            }
             let mut clip_depth = 0;
             let mut clip_zero_depth = 0;
             for drawobj_ix in &compacted[tile_ix] {
@@ -257,18 +384,25 @@ fn coarse_main(
                     } == 0;
                     let include_tile = n_segs != 0 || (backdrop_clear == is_clip) || is_blend;
                     if include_tile {
+                        if !is_clip {
+                            draw_count += 1;
+                        }
                         match DrawTag(drawtag) {
                             DrawTag::COLOR => {
-                                tile_state.write_path(config, bump, ptcl, tile, draw_flags);
+                                tile_state.write_path(config, scene, bump, ptcl, tile, draw_flags);
                                 let rgba_color = scene[dd as usize];
                                 tile_state.write_color(config, bump, ptcl, rgba_color);
                             }
                             DrawTag::IMAGE => {
-                                tile_state.write_path(config, bump, ptcl, tile, draw_flags);
+                                tile_state.write_path(config, scene, bump, ptcl, tile, draw_flags);
                                 tile_state.write_image(config, bump, ptcl, di + 1);
                             }
+                            DrawTag::BILINEAR_GRADIENT => {
+                                tile_state.write_path(config, scene, bump, ptcl, tile, draw_flags);
+                                tile_state.write_bilinear_grad(config, bump, ptcl, di + 1);
+                            }
                             DrawTag::LINEAR_GRADIENT => {
-                                tile_state.write_path(config, bump, ptcl, tile, draw_flags);
+                                tile_state.write_path(config, scene, bump, ptcl, tile, draw_flags);
                                 let index = scene[dd as usize];
                                 tile_state.write_grad(
                                     config,
@@ -280,7 +414,7 @@ fn coarse_main(
                                 );
                             }
                             DrawTag::RADIAL_GRADIENT => {
-                                tile_state.write_path(config, bump, ptcl, tile, draw_flags);
+                                tile_state.write_path(config, scene, bump, ptcl, tile, draw_flags);
                                 let index = scene[dd as usize];
                                 tile_state.write_grad(
                                     config,
@@ -291,22 +425,44 @@ fn coarse_main(
                                     di + 1,
                                 );
                             }
-                            DrawTag::BEGIN_CLIP => {
+                            DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => {
                                 if tile.segment_count_or_ix == 0 && tile.backdrop == 0 {
                                     clip_zero_depth = clip_depth + 1;
                                 } else {
-                                    tile_state.write_begin_clip(config, bump, ptcl);
+                                    // A clip shape is always a non-zero fill (draw_flags=0).
+                                    tile_state.write_path(config, scene, bump, ptcl, tile, 0);
+                                    let feather = scene[dd as usize + 2];
+                                    if f32::from_bits(feather) > 0.0 {
+                                        let rect_start = dd as usize + 3;
+                                        let rect =
+                                            scene[rect_start..rect_start + 4].try_into().unwrap();
+                                        tile_state.write_feather(config, bump, ptcl, rect, feather);
+                                    }
+                                    // The non-isolated and knockout flags follow the color
+                                    // matrix flag.
+                                    let flags = (scene[dd as usize] >> 17) & 3;
+                                    tile_state.write_begin_clip(config, bump, ptcl, flags);
                                     // TODO: update blend depth
                                 }
                                 clip_depth += 1;
                             }
                             DrawTag::END_CLIP => {
                                 clip_depth -= 1;
-                                // A clip shape is always a non-zero fill (draw_flags=0).
-                                tile_state.write_path(config, bump, ptcl, tile, 0);
                                 let blend = scene[dd as usize];
                                 let alpha = f32::from_bits(scene[dd as usize + 1]);
-                                tile_state.write_end_clip(config, bump, ptcl, blend, alpha);
+                                if blend & DrawBeginClip::COLOR_MATRIX_FLAG != 0 {
+                                    // The matrix follows the 7 words of clip data.
+                                    let matrix_start = dd as usize + 7;
+                                    let matrix = &scene[matrix_start..matrix_start + 20];
+                                    tile_state.write_color_matrix(config, bump, ptcl, matrix);
+                                }
+                                tile_state.write_end_clip(
+                                    config,
+                                    bump,
+                                    ptcl,
+                                    blend & 0xffff,
+                                    alpha,
+                                );
                             }
                             _ => todo!(),
                         }
@@ -314,7 +470,7 @@ fn coarse_main(
                 } else {
                     // In "clip zero" state, suppress all drawing
                     match DrawTag(drawtag) {
-                        DrawTag::BEGIN_CLIP => clip_depth += 1,
+                        DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => clip_depth += 1,
                         DrawTag::END_CLIP => {
                             if clip_depth == clip_zero_depth {
                                 clip_zero_depth = 0;
@@ -326,12 +482,13 @@ fn coarse_main(
                 }
             }
 
-            if bin_tile_x + tile_x < width_in_tiles && bin_tile_y + tile_y < height_in_tiles {
-                ptcl[tile_state.cmd_offset as usize] = CMD_END;
-                let scratch_size = 0; // TODO: actually compute blend depth
-                ptcl[blend_offset as usize] = bump.blend;
-                bump.blend += scratch_size;
+            ptcl[tile_state.cmd_offset as usize] = CMD_END;
+            if let Some(count) = overdraw.get_mut(this_tile_ix as usize) {
+                *count = draw_count;
             }
+            let scratch_size = 0; // TODO: actually compute blend depth
+            ptcl[blend_offset as usize] = bump.blend;
+            bump.blend += scratch_size;
         }
     }
 }
@@ -346,6 +503,8 @@ pub fn coarse(_n_wg: u32, resources: &[CpuBinding]) {
     let mut tiles = resources[6].as_slice_mut();
     let mut bump = resources[7].as_typed_mut();
     let mut ptcl = resources[8].as_slice_mut();
+    let dirty_tiles = resources[9].as_slice();
+    let mut overdraw = resources[10].as_slice_mut();
     coarse_main(
         &config,
         &scene,
@@ -356,5 +515,7 @@ pub fn coarse(_n_wg: u32, resources: &[CpuBinding]) {
         &mut tiles,
         &mut bump,
         &mut ptcl,
+        &dirty_tiles,
+        &mut overdraw,
     );
 }
@@ -37,7 +37,9 @@ fn draw_leaf_main(
                 || tag_word == DrawTag::LINEAR_GRADIENT
                 || tag_word == DrawTag::RADIAL_GRADIENT
                 || tag_word == DrawTag::IMAGE
+                || tag_word == DrawTag::BILINEAR_GRADIENT
                 || tag_word == DrawTag::BEGIN_CLIP
+                || tag_word == DrawTag::BEGIN_CLIP_COLOR_MATRIX
             {
                 let bbox = path_bbox[m.path_ix as usize];
                 let transform = Transform::read(config.layout.transform_base, bbox.trans_ix, scene);
@@ -107,7 +109,7 @@ fn draw_leaf_main(
                         info[di + 9] = f32::to_bits(ra);
                         info[di + 19] = f32::to_bits(roff);
                     }
-                    DrawTag::IMAGE => {
+                    DrawTag::IMAGE | DrawTag::BILINEAR_GRADIENT => {
                         info[di] = draw_flags;
                         let z = transform.0;
                         let inv_det = (z[0] * z[3] - z[1] * z[2]).recip();
@@ -129,12 +131,14 @@ fn draw_leaf_main(
                         info[di + 6] = f32::to_bits(inv_tr[1]);
                         info[di + 7] = scene[dd as usize];
                         info[di + 8] = scene[dd as usize + 1];
+                        info[di + 9] = scene[dd as usize + 2];
+                        info[di + 10] = scene[dd as usize + 3];
                     }
-                    DrawTag::BEGIN_CLIP => (),
+                    DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => (),
                     _ => todo!("unhandled draw tag {:x}", tag_word.0),
                 }
             }
-            if tag_word == DrawTag::BEGIN_CLIP {
+            if tag_word == DrawTag::BEGIN_CLIP || tag_word == DrawTag::BEGIN_CLIP_COLOR_MATRIX {
                 let path_ix = m.path_ix as i32;
                 clip_inp[m.clip_ix as usize] = Clip { ix, path_ix };
             } else if tag_word == DrawTag::END_CLIP {
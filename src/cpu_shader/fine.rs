@@ -5,7 +5,9 @@ use vello_encoding::{ConfigUniform, PathSegment, Tile};
 
 use crate::cpu_dispatch::CpuTexture;
 
-use super::{CMD_COLOR, CMD_END, CMD_FILL, CMD_JUMP, CMD_SOLID, PTCL_INITIAL_ALLOC};
+use super::{
+    CMD_CLIP_RECT, CMD_COLOR, CMD_END, CMD_FILL, CMD_JUMP, CMD_SKIP, CMD_SOLID, PTCL_INITIAL_ALLOC,
+};
 
 // These should also move into a common area
 const TILE_WIDTH: usize = 16;
@@ -33,6 +35,22 @@ fn read_fill(ptcl: &[u32], offset: u32) -> CmdFill {
     }
 }
 
+fn read_clip_rect(ptcl: &[u32], offset: u32) -> [f32; 4] {
+    let rect = &ptcl[(offset + 1) as usize..][..4];
+    [rect[0], rect[1], rect[2], rect[3]].map(f32::from_bits)
+}
+
+/// Scales the coverage of a fill by the part of each pixel inside of its clip rect.
+fn apply_clip_rect(area: &mut [f32], rect: &[f32; 4], x_tile: f32, y_tile: f32) {
+    for (i, a) in area.iter_mut().enumerate() {
+        let x = x_tile + (i % TILE_WIDTH) as f32;
+        let y = y_tile + (i / TILE_WIDTH) as f32;
+        let x_coverage = ((x + 1.0).min(rect[2]) - x.max(rect[0])).clamp(0.0, 1.0);
+        let y_coverage = ((y + 1.0).min(rect[3]) - y.max(rect[1])).clamp(0.0, 1.0);
+        *a *= x_coverage * y_coverage;
+    }
+}
+
 fn unpack4x8unorm(x: u32) -> [f32; 4] {
     let mut result = [0.0; 4];
     for i in 0..4 {
@@ -51,7 +69,7 @@ fn pack4x8unorm(x: [f32; 4]) -> u32 {
 }
 
 fn fill_path(area: &mut [f32], segments: &[PathSegment], fill: &CmdFill, x_tile: f32, y_tile: f32) {
-    let n_segs = fill.size_and_rule >> 1;
+    let n_segs = fill.size_and_rule >> 3;
     let even_odd = (fill.size_and_rule & 1) != 0;
     let backdrop_f = fill.backdrop as f32;
     for a in area.iter_mut() {
@@ -85,7 +103,7 @@ fn fill_path(area: &mut [f32], segments: &[PathSegment], fill: &CmdFill, x_tile:
                     let b = xmax.min(1.0);
                     let c = b.max(0.0);
                     let d = xmin.max(0.0);
-                    let a = (b + 0.5 * (d * d - c * c) - xmin) / (xmax - xmin);
+                    let a = (b - xmin + 0.5 * (d - c) * (d + c)) / (xmax - xmin);
                     area[yi * TILE_WIDTH + i] += y_edge + a * dy;
                 }
             } else if y_edge != 0.0 {
@@ -110,6 +128,17 @@ fn fill_path(area: &mut [f32], segments: &[PathSegment], fill: &CmdFill, x_tile:
     }
 }
 
+/// Returns noise in [-1, 1] for film grain, from a hash of the pixel coordinates and seed.
+fn grain_noise(xy: [u32; 2], seed: u32) -> f32 {
+    let mut h = xy[0].wrapping_mul(0x8da6b343)
+        ^ xy[1].wrapping_mul(0xd8163841)
+        ^ seed.wrapping_mul(0xcb1ab31f);
+    h = (h ^ (h >> 16)).wrapping_mul(0x7feb352d);
+    h = (h ^ (h >> 15)).wrapping_mul(0x846ca68b);
+    h ^= h >> 16;
+    (h >> 8) as f32 * (2.0 / 16777215.0) - 1.0
+}
+
 // Note: this is a draft. Texture resources are not yet wired up, so it
 // has not yet been tested.
 #[allow(unused)]
@@ -135,11 +164,20 @@ fn fine_main(
         for a in &mut area {
             *a = 0.0;
         }
+        // Whether area covers the whole tile of an opaque scene, so colors can replace rgba.
+        let mut solid = false;
+        // The rectangle that the coverage of fills is clipped to, if any.
+        let mut clip_rect = None;
         let tile_x = tile_ix % width_in_tiles;
         let tile_y = tile_ix / width_in_tiles;
+        let x0 = (tile_x as usize * TILE_WIDTH) as f32;
+        let y0 = (tile_y as usize * TILE_HEIGHT) as f32;
         let mut cmd_ix = tile_ix * PTCL_INITIAL_ALLOC;
         // skip over blend stack allocation
         cmd_ix += 1;
+        if ptcl[cmd_ix as usize] == CMD_SKIP {
+            continue;
+        }
         loop {
             let tag = ptcl[cmd_ix as usize];
             if tag == CMD_END {
@@ -149,26 +187,45 @@ fn fine_main(
                 CMD_FILL => {
                     let fill = read_fill(ptcl, cmd_ix);
                     // x0 and y0 will go away when we do tile-relative coords
-                    let x0 = (tile_x as usize * TILE_WIDTH) as f32;
-                    let y0 = (tile_y as usize * TILE_HEIGHT) as f32;
                     fill_path(&mut area, segments, &fill, x0, y0);
+                    if config.conservative_coverage != 0 {
+                        for a in &mut area {
+                            *a = if *a > 1e-4 { 1.0 } else { 0.0 };
+                        }
+                    } else if (fill.size_and_rule & 4) != 0 {
+                        for a in &mut area {
+                            *a = if *a >= 0.5 { 1.0 } else { 0.0 };
+                        }
+                    }
+                    if let Some(rect) = &clip_rect {
+                        apply_clip_rect(&mut area, rect, x0, y0);
+                    }
+                    solid = false;
                     cmd_ix += 4;
                 }
                 CMD_SOLID => {
                     for a in &mut area {
                         *a = 1.0;
                     }
+                    if let Some(rect) = &clip_rect {
+                        apply_clip_rect(&mut area, rect, x0, y0);
+                    }
+                    solid = config.opaque != 0 && clip_rect.is_none();
                     cmd_ix += 2;
                 }
                 CMD_COLOR => {
                     let color = read_color(ptcl, cmd_ix);
                     let fg = unpack4x8unorm(color);
                     let fg = [fg[3], fg[2], fg[1], fg[0]];
-                    for i in 0..TILE_SIZE {
-                        let ai = area[i];
-                        let fg_i = [fg[0] * ai, fg[1] * ai, fg[2] * ai, fg[3] * ai];
-                        for j in 0..4 {
-                            rgba[i][j] = rgba[i][j] * (1.0 - fg_i[3]) + fg_i[j];
+                    if solid {
+                        rgba.fill(fg);
+                    } else {
+                        for i in 0..TILE_SIZE {
+                            let ai = area[i];
+                            let fg_i = [fg[0] * ai, fg[1] * ai, fg[2] * ai, fg[3] * ai];
+                            for j in 0..4 {
+                                rgba[i][j] = rgba[i][j] * (1.0 - fg_i[3]) + fg_i[j];
+                            }
                         }
                     }
                     cmd_ix += 2;
@@ -176,6 +233,12 @@ fn fine_main(
                 CMD_JUMP => {
                     cmd_ix = ptcl[(cmd_ix + 1) as usize];
                 }
+                CMD_CLIP_RECT => {
+                    // Coarse clears the rect by writing an empty one.
+                    let rect = read_clip_rect(ptcl, cmd_ix);
+                    clip_rect = (rect[0] < rect[2]).then_some(rect);
+                    cmd_ix += 5;
+                }
                 _ => todo!("unhandled ptcl command {tag}"),
             }
         }
@@ -184,7 +247,19 @@ fn fine_main(
             let base =
                 output.width * (tile_y as usize * TILE_HEIGHT + y) + tile_x as usize * TILE_WIDTH;
             for x in 0..TILE_WIDTH {
-                let rgba32 = pack4x8unorm(rgba[y * TILE_WIDTH + x]);
+                let mut fg = rgba[y * TILE_WIDTH + x];
+                if config.grain_intensity != 0.0 {
+                    let xy = [
+                        tile_x * TILE_WIDTH as u32 + x as u32,
+                        tile_y * TILE_HEIGHT as u32 + y as u32,
+                    ];
+                    let noise = grain_noise(xy, config.seed) * config.grain_intensity;
+                    let a = fg[3];
+                    for c in &mut fg[..3] {
+                        *c = (*c + noise * a).clamp(0.0, a);
+                    }
+                }
+                let rgba32 = pack4x8unorm(fg);
                 output.pixels[base + x] = rgba32;
             }
         }
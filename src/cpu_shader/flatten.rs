@@ -6,7 +6,8 @@ use crate::cpu_dispatch::CpuBinding;
 use super::util::{Transform, Vec2, ROBUST_EPSILON};
 use vello_encoding::{
     math::f16_to_f32, BumpAllocators, ConfigUniform, LineSoup, Monoid, PathBbox, PathMonoid,
-    PathTag, Style, DRAW_INFO_FLAGS_FILL_RULE_BIT,
+    PathTag, Style, DRAW_INFO_FLAGS_ALIASED_BIT, DRAW_INFO_FLAGS_CLIP_RECT_SHIFT,
+    DRAW_INFO_FLAGS_FILL_RULE_BIT, DRAW_INFO_FLAGS_GLYPH_BIT,
 };
 
 fn to_minus_one_quarter(x: f32) -> f32 {
@@ -118,6 +119,16 @@ fn cubic_end_normal(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Vec2 {
     Vec2::new(-tangent.y, tangent.x)
 }
 
+/// Moves `y` onto the nearest pixel row boundary if it is within `epsilon` of it.
+fn snap_to_row(y: f32, epsilon: f32) -> f32 {
+    let row = y.round();
+    if (y - row).abs() < epsilon {
+        row
+    } else {
+        y
+    }
+}
+
 fn write_line(
     line_ix: usize,
     path_ix: u32,
@@ -209,6 +220,7 @@ fn flatten_cubic(
     path_ix: u32,
     local_to_device: &Transform,
     offset: f32,
+    max_segments: u32,
     line_ix: &mut usize,
     lines: &mut [LineSoup],
     bbox: &mut IntBbox,
@@ -232,7 +244,7 @@ fn flatten_cubic(
             cubic.p2,
             cubic.p3,
             scale,
-            local_to_device.clone(),
+            *local_to_device,
         )
     };
     let err_v = (p2 - p1) * 3.0 + p0 - p3;
@@ -261,7 +273,10 @@ fn flatten_cubic(
     }
 
     let mut n0 = offset * cubic_start_normal(p0, p1, p2, p3);
-    let n = ((val * (0.5 / scaled_sqrt_tol)).ceil() as u32).max(1);
+    let mut n = ((val * (0.5 / scaled_sqrt_tol)).ceil() as u32).max(1);
+    if max_segments != 0 {
+        n = n.min(max_segments);
+    }
     let mut lp0 = p0;
     qp0 = p0;
     let v_step = val / (n as f32);
@@ -317,6 +332,47 @@ fn flatten_cubic(
     }
 }
 
+/// Returns true if the device space control points of a curve all lie past the same edge of
+/// the target, in which case the line between its end points covers the same pixels.
+fn hull_outside_target<const N: usize>(config: &ConfigUniform, points: [Vec2; N]) -> bool {
+    let (width, height) = (config.target_width as f32, config.target_height as f32);
+    points.iter().all(|p| p.x < 0.0)
+        || points.iter().all(|p| p.y < 0.0)
+        || points.iter().all(|p| p.x > width)
+        || points.iter().all(|p| p.y > height)
+}
+
+fn flatten_quad(
+    p0: Vec2,
+    p1: Vec2,
+    p2: Vec2,
+    path_ix: u32,
+    max_segments: u32,
+    line_ix: &mut usize,
+    lines: &mut [LineSoup],
+    bbox: &mut IntBbox,
+) {
+    const ACCURACY: f32 = 0.25;
+    let sqrt_tol = ACCURACY.sqrt();
+    let params = estimate_subdiv(p0, p1, p2, sqrt_tol);
+    let mut n = ((params.val * (0.5 / sqrt_tol)).ceil() as u32).max(1);
+    if max_segments != 0 {
+        n = n.min(max_segments);
+    }
+    let u0 = approx_parabola_inv_integral(params.a0);
+    let u2 = approx_parabola_inv_integral(params.a2);
+    let uscale = (u2 - u0).recip();
+    let mut lp0 = p0;
+    for i in 1..n {
+        let a = params.a0 + (params.a2 - params.a0) * (i as f32 / n as f32);
+        let t = (approx_parabola_inv_integral(a) - u0) * uscale;
+        let lp1 = eval_quad(p0, p1, p2, t);
+        output_line(path_ix, lp0, lp1, line_ix, bbox, lines);
+        lp0 = lp1;
+    }
+    output_line(path_ix, lp0, p2, line_ix, bbox, lines);
+}
+
 fn flatten_arc(
     path_ix: u32,
     begin: Vec2,
@@ -646,6 +702,7 @@ fn flatten_main(
         let style_ix = tag.monoid.style_ix;
         let trans_ix = tag.monoid.trans_ix;
         let style_flags = scene[(config.layout.style_base.wrapping_add(style_ix)) as usize];
+        let clip_rect = scene[(config.layout.style_base.wrapping_add(style_ix) + 2) as usize];
         if (tag.tag_byte & PATH_TAG_PATH) != 0 {
             let out = &mut path_bboxes[path_ix as usize];
             out.draw_flags = if (style_flags & Style::FLAGS_FILL_BIT) == 0 {
@@ -653,20 +710,42 @@ fn flatten_main(
             } else {
                 DRAW_INFO_FLAGS_FILL_RULE_BIT
             };
+            if (style_flags & Style::FLAGS_GLYPH_BIT) != 0 {
+                out.draw_flags |= DRAW_INFO_FLAGS_GLYPH_BIT;
+            }
+            if (style_flags & Style::FLAGS_ALIASED_BIT) != 0 {
+                out.draw_flags |= DRAW_INFO_FLAGS_ALIASED_BIT;
+            }
+            out.draw_flags |= clip_rect << DRAW_INFO_FLAGS_CLIP_RECT_SHIFT;
             out.trans_ix = trans_ix;
         }
 
         let seg_type = tag.tag_byte & PATH_TAG_SEG_TYPE;
+        let first_line = line_ix;
         if seg_type != 0 {
             let is_stroke = (style_flags & Style::FLAGS_STYLE_BIT) != 0;
-            let transform = Transform::read(config.layout.transform_base, trans_ix, scene);
-            let pts = read_path_segment(&tag, is_stroke, pathdata);
+            let mut transform = Transform::read(config.layout.transform_base, trans_ix, scene);
+            let mut pts = read_path_segment(&tag, is_stroke, pathdata);
 
             if is_stroke {
                 let linewidth =
                     f32::from_bits(scene[(config.layout.style_base + style_ix + 1) as usize]);
                 let offset = 0.5 * linewidth;
 
+                // A non-scaling stroke is offset in device space, so the segment is transformed
+                // up front and the offset curves aren't transformed again.
+                let local_to_device = transform;
+                let non_scaling = (style_flags & Style::FLAGS_NON_SCALING_BIT) != 0;
+                if non_scaling {
+                    pts = CubicPoints {
+                        p0: transform.apply(pts.p0),
+                        p1: transform.apply(pts.p1),
+                        p2: transform.apply(pts.p2),
+                        p3: transform.apply(pts.p3),
+                    };
+                    transform = Transform::identity();
+                }
+
                 let is_open = seg_type != PATH_TAG_LINETO;
                 let is_stroke_cap_marker = (tag.tag_byte & PathTag::SUBPATH_END_BIT) != 0;
                 if is_stroke_cap_marker {
@@ -697,6 +776,7 @@ fn flatten_main(
                         path_ix,
                         &transform,
                         offset,
+                        config.max_curve_segments,
                         &mut line_ix,
                         lines,
                         &mut bbox,
@@ -706,7 +786,14 @@ fn flatten_main(
                     let neighbor =
                         read_neighboring_segment(ix + 1, pathtags, pathdata, tag_monoids);
                     let tan_prev = cubic_end_tangent(pts.p0, pts.p1, pts.p2, pts.p3);
-                    let tan_next = neighbor.tangent;
+                    let mut tan_next = neighbor.tangent;
+                    if non_scaling {
+                        let z = local_to_device.0;
+                        tan_next = Vec2::new(
+                            z[0] * tan_next.x + z[2] * tan_next.y,
+                            z[1] * tan_next.x + z[3] * tan_next.y,
+                        );
+                    }
                     let offset_tangent = offset * tan_prev.normalize();
                     let n_prev = Vec2::new(-offset_tangent.y, offset_tangent.x);
                     let tan_next_norm = tan_next.normalize();
@@ -741,12 +828,40 @@ fn flatten_main(
                         );
                     }
                 }
+            } else if seg_type == PATH_TAG_QUADTO {
+                // Undo the degree raise in `read_path_segment` to recover the quadratic.
+                let p0 = transform.apply(pts.p0);
+                let p1 = transform.apply(pts.p1 * 1.5 - pts.p0 * 0.5);
+                let p2 = transform.apply(pts.p3);
+                if config.cull_offscreen_curves != 0 && hull_outside_target(config, [p0, p1, p2]) {
+                    output_line(path_ix, p0, p2, &mut line_ix, &mut bbox, lines);
+                } else {
+                    flatten_quad(
+                        p0,
+                        p1,
+                        p2,
+                        path_ix,
+                        config.max_curve_segments,
+                        &mut line_ix,
+                        lines,
+                        &mut bbox,
+                    );
+                }
+            } else if config.cull_offscreen_curves != 0
+                && hull_outside_target(
+                    config,
+                    [pts.p0, pts.p1, pts.p2, pts.p3].map(|p| transform.apply(p)),
+                )
+            {
+                let (p0, p3) = (transform.apply(pts.p0), transform.apply(pts.p3));
+                output_line(path_ix, p0, p3, &mut line_ix, &mut bbox, lines);
             } else {
                 flatten_cubic(
                     &pts,
                     path_ix,
                     &transform,
                     /*offset*/ 0.,
+                    config.max_curve_segments,
                     &mut line_ix,
                     lines,
                     &mut bbox,
@@ -754,6 +869,25 @@ fn flatten_main(
             }
         }
 
+        if config.horizontal_epsilon > 0.0 {
+            // The bounding box keeps the points from before they were moved, which only
+            // makes it slightly more conservative.
+            for line in &mut lines[first_line..line_ix] {
+                line.p0[1] = snap_to_row(line.p0[1], config.horizontal_epsilon);
+                line.p1[1] = snap_to_row(line.p1[1], config.horizontal_epsilon);
+            }
+        }
+
+        // A draw clipped to a rectangle is bounded by it, so that the tiles outside of the
+        // rectangle are culled by binning and tile allocation.
+        if clip_rect != 0 {
+            let rect_ix = (config.layout.clip_rect_base + (clip_rect - 1) * 4) as usize;
+            let rect = &scene[rect_ix..rect_ix + 4];
+            bbox.x0 = bbox.x0.max(f32::from_bits(rect[0]).floor() as i32);
+            bbox.y0 = bbox.y0.max(f32::from_bits(rect[1]).floor() as i32);
+            bbox.x1 = bbox.x1.min(f32::from_bits(rect[2]).ceil() as i32);
+            bbox.y1 = bbox.y1.min(f32::from_bits(rect[3]).ceil() as i32);
+        }
         if (path_ix as usize) < path_bboxes.len() && (bbox.x1 > bbox.x0 || bbox.y1 > bbox.y0) {
             let out = &mut path_bboxes[path_ix as usize];
             out.x0 = out.x0.min(bbox.x0);
@@ -782,3 +916,62 @@ pub fn flatten(n_wg: u32, resources: &[CpuBinding]) {
         &mut lines,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flattens a cubic without a transform, and returns the lines it produces.
+    fn flatten(cubic: &CubicPoints, max_segments: u32) -> Vec<LineSoup> {
+        let mut lines = vec![LineSoup::default(); 1 << 16];
+        let mut line_ix = 0;
+        let mut bbox = IntBbox::default();
+        let transform = Transform::identity();
+        flatten_cubic(
+            cubic,
+            0,
+            &transform,
+            0.,
+            max_segments,
+            &mut line_ix,
+            &mut lines,
+            &mut bbox,
+        );
+        lines.truncate(line_ix);
+        lines
+    }
+
+    #[test]
+    fn test_flatten_max_segments() {
+        // The control points are far from the nearly coincident end points, so flattening the
+        // curve to the tolerance takes a huge number of lines.
+        let cubic = CubicPoints {
+            p0: Vec2::new(0., 0.),
+            p1: Vec2::new(1.0e5, 1.0e5),
+            p2: Vec2::new(-1.0e5, 1.0e5),
+            p3: Vec2::new(0.01, 0.),
+        };
+        let unbounded = flatten(&cubic, 0);
+        assert!(unbounded.len() > 100, "{} lines", unbounded.len());
+        let bounded = flatten(&cubic, 16);
+        assert!(bounded.len() <= 16, "{} lines", bounded.len());
+        // The lines still form a connected chain between the end points.
+        assert_eq!(bounded[0].p0, [0., 0.]);
+        assert_eq!(bounded[bounded.len() - 1].p1, [0.01, 0.]);
+        for pair in bounded.windows(2) {
+            assert_eq!(pair[0].p1, pair[1].p0);
+        }
+    }
+
+    #[test]
+    fn test_snap_to_row() {
+        let epsilon = 1.0 / 1024.0;
+        assert_eq!(snap_to_row(15.999996, epsilon), 16.);
+        assert_eq!(snap_to_row(16.000004, epsilon), 16.);
+        assert_eq!(snap_to_row(-3.0001, epsilon), -3.);
+        // Points further from a boundary, or with the snapping disabled, stay in place.
+        assert_eq!(snap_to_row(15.99, epsilon), 15.99);
+        assert_eq!(snap_to_row(16.5, epsilon), 16.5);
+        assert_eq!(snap_to_row(15.999996, 0.), 15.999996);
+    }
+}
@@ -59,3 +59,8 @@ const CMD_IMAGE: u32 = 8;
 const CMD_BEGIN_CLIP: u32 = 9;
 const CMD_END_CLIP: u32 = 10;
 const CMD_JUMP: u32 = 11;
+const CMD_SKIP: u32 = 12;
+const CMD_FEATHER: u32 = 13;
+const CMD_COLOR_MATRIX: u32 = 14;
+const CMD_BILINEAR_GRAD: u32 = 15;
+const CMD_CLIP_RECT: u32 = 16;
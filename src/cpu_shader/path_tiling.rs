@@ -77,11 +77,17 @@ fn path_tiling_main(
         let tile_xy = Vec2::new(x as f32 * TILE_WIDTH as f32, y as f32 * TILE_HEIGHT as f32);
         let tile_xy1 = tile_xy + Vec2::new(TILE_WIDTH as f32, TILE_HEIGHT as f32);
 
+        // The crossings with the tile edges are computed from the whole line, rather than from
+        // the part left after clipping the other end, so that the tiles on either side of an
+        // edge agree exactly on the crossing and thin lines are continuous across seams.
+        let (line_xy0, line_xy1) = (xy0, xy1);
         if seg_within_line > 0 {
             let z_prev = (a * (seg_within_line as f32 - 1.0) + b).floor();
             if z == z_prev {
                 // Top edge is clipped
-                let mut xt = xy0.x + (xy1.x - xy0.x) * (tile_xy.y - xy0.y) / (xy1.y - xy0.y);
+                let mut xt = line_xy0.x
+                    + (line_xy1.x - line_xy0.x) * (tile_xy.y - line_xy0.y)
+                        / (line_xy1.y - line_xy0.y);
                 xt = xt.clamp(tile_xy.x + 1e-3, tile_xy1.x);
                 xy0 = Vec2::new(xt, tile_xy.y);
             } else {
@@ -91,7 +97,8 @@ fn path_tiling_main(
                 } else {
                     tile_xy1.x
                 };
-                let mut yt = xy0.y + (xy1.y - xy0.y) * (x_clip - xy0.x) / (xy1.x - xy0.x);
+                let mut yt = line_xy0.y
+                    + (line_xy1.y - line_xy0.y) * (x_clip - line_xy0.x) / (line_xy1.x - line_xy0.x);
                 yt = yt.clamp(tile_xy.y + 1e-3, tile_xy1.y);
                 xy0 = Vec2::new(x_clip, yt);
             }
@@ -100,7 +107,9 @@ fn path_tiling_main(
             let z_next = (a * (seg_within_line as f32 + 1.0) + b).floor();
             if z == z_next {
                 // Bottom edge is clipped
-                let mut xt = xy0.x + (xy1.x - xy0.x) * (tile_xy1.y - xy0.y) / (xy1.y - xy0.y);
+                let mut xt = line_xy0.x
+                    + (line_xy1.x - line_xy0.x) * (tile_xy1.y - line_xy0.y)
+                        / (line_xy1.y - line_xy0.y);
                 xt = xt.clamp(tile_xy.x + 1e-3, tile_xy1.x);
                 xy1 = Vec2::new(xt, tile_xy1.y);
             } else {
@@ -110,7 +119,8 @@ fn path_tiling_main(
                 } else {
                     tile_xy.x
                 };
-                let mut yt = xy0.y + (xy1.y - xy0.y) * (x_clip - xy0.x) / (xy1.x - xy0.x);
+                let mut yt = line_xy0.y
+                    + (line_xy1.y - line_xy0.y) * (x_clip - line_xy0.x) / (line_xy1.x - line_xy0.x);
                 yt = yt.clamp(tile_xy.y + 1e-3, tile_xy1.y);
                 xy1 = Vec2::new(x_clip, yt);
             }
@@ -183,3 +193,70 @@ pub fn path_tiling(_n_wg: u32, resources: &[CpuBinding]) {
         &mut segments,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_meet_at_tile_edges() {
+        let lines = [
+            ([0.25, 0.5], [4000.3, 2999.7]),
+            ([3.7, 1.1], [1517.9, 4090.3]),
+            ([4095.1, 2.9], [1.3, 4000.9]),
+            ([0.0, 0.0], [4096.0, 4096.0]),
+        ];
+        for (p0, p1) in lines {
+            let line = LineSoup {
+                path_ix: 0,
+                _padding: 0,
+                p0,
+                p1,
+            };
+            let s0 = Vec2::from_array(p0) * TILE_SCALE;
+            let s1 = Vec2::from_array(p1) * TILE_SCALE;
+            let count = span(s0.x, s1.x) - 1 + span(s0.y, s1.y);
+            let mut path = Path::default();
+            path.bbox = [0, 0, 257, 257];
+            // Every tile writes its segment at the index of the segment within the line.
+            let tile = Tile {
+                backdrop: 0,
+                segment_count_or_ix: !0,
+            };
+            let tiles = vec![tile; 257 * 257];
+            let seg_counts: Vec<_> = (0..count)
+                .map(|i| SegmentCount {
+                    line_ix: 0,
+                    counts: (i << 16) | i,
+                })
+                .collect();
+            let mut bump = BumpAllocators {
+                seg_counts: count,
+                ..Default::default()
+            };
+            let mut segments = vec![PathSegment::default(); count as usize];
+            path_tiling_main(
+                &mut bump,
+                &seg_counts,
+                &[line],
+                &[path],
+                &tiles,
+                &mut segments,
+            );
+            // Each segment ends where the next one, in the neighboring tile, starts.
+            for pair in segments.windows(2) {
+                let (end, start) = (pair[0].point1, pair[1].point0);
+                let (along, across) = if end[1] == TILE_HEIGHT as f32 && start[1] == 0.0 {
+                    (end[0] - start[0], 0.0)
+                } else {
+                    (
+                        end[1] - start[1],
+                        (end[0] - start[0]).abs() % TILE_WIDTH as f32,
+                    )
+                };
+                assert!(along.abs() <= 1e-5, "{p0:?} to {p1:?}: {pair:?}");
+                assert!(across <= 1e-5 || across >= 16.0 - 1e-5, "{pair:?}");
+            }
+        }
+    }
+}
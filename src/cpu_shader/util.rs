@@ -108,7 +108,7 @@ impl Vec2 {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
 pub struct Transform(pub [f32; 6]);
 
 impl Transform {
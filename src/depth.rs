@@ -0,0 +1,65 @@
+// Copyright 2023 The Vello authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of an encoded scene into one which draws the depth of each draw object.
+
+use peniko::{Color, Mix};
+use vello_encoding::{DrawBeginClip, DrawColor, DrawMonoid, DrawTag, Encoding, Monoid, Patch};
+
+/// Returns a copy of `encoding` where every draw object is filled with an opaque gray whose
+/// level is its depth, and every layer is a plain clip.
+///
+/// Rendering the result gives the depth of the topmost draw object at each pixel, without
+/// any blending between the depths of overlapping objects.
+pub(crate) fn depth_encoding(encoding: &Encoding) -> Encoding {
+    let mut depth = encoding.clone();
+    let draw_data = &encoding.draw_data;
+    let mut data = Vec::with_capacity(draw_data.len());
+    // Offsets of the draw objects in the old and new draw data, for moving patches.
+    let mut moved = Vec::with_capacity(encoding.draw_tags.len() + 1);
+    let mut depths = encoding.draw_depths.iter().peekable();
+    let mut current = 0.0;
+    let mut offset = 0;
+    for (ix, tag) in depth.draw_tags.iter_mut().enumerate() {
+        while let Some(&(_, next)) = depths.next_if(|(start, _)| *start <= ix) {
+            current = next;
+        }
+        moved.push((offset, data.len()));
+        let size = DrawMonoid::new(*tag).scene_offset as usize * 4;
+        let old = &draw_data[offset..offset + size];
+        offset += size;
+        match *tag {
+            DrawTag::NOP | DrawTag::END_CLIP => data.extend_from_slice(old),
+            DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => {
+                let mut clip: DrawBeginClip =
+                    bytemuck::pod_read_unaligned(&old[..std::mem::size_of::<DrawBeginClip>()]);
+                // Feathering is kept, as it shapes the clip.
+                clip.blend_mode = DrawBeginClip::new(Mix::Clip.into(), 1.0).blend_mode;
+                clip.alpha = 1.0;
+                *tag = DrawTag::BEGIN_CLIP;
+                data.extend_from_slice(bytemuck::bytes_of(&clip));
+            }
+            _ => {
+                let level = (current.clamp(0.0, 1.0) * 255.0).round() as u8;
+                let color = DrawColor::new(Color::rgb8(level, level, level));
+                *tag = DrawTag::COLOR;
+                data.extend_from_slice(bytemuck::bytes_of(&color));
+            }
+        }
+    }
+    moved.push((offset, data.len()));
+    let move_offset = |old: usize| {
+        let ix = moved.partition_point(|&(start, _)| start < old);
+        moved.get(ix).map_or(data.len(), |&(_, new)| new)
+    };
+    let resources = &mut depth.resources;
+    // Gradients and images are replaced by colors, so only glyph runs need resolving.
+    resources
+        .patches
+        .retain(|patch| matches!(patch, Patch::GlyphRun { .. }));
+    for run in &mut resources.glyph_runs {
+        run.stream_offsets.draw_data = move_offset(run.stream_offsets.draw_data);
+    }
+    depth.draw_data = data;
+    depth
+}
@@ -16,12 +16,12 @@
 
 //! Support for glyph rendering.
 
-use crate::scene::Scene;
+use crate::scene::{font_ref, Scene};
 use {
-    peniko::kurbo::Affine,
-    peniko::{Brush, Color, Fill, Style},
+    peniko::kurbo::{Affine, BezPath},
+    peniko::{Brush, Color, Fill, Font, Style},
     skrifa::{
-        instance::{NormalizedCoord, Size},
+        instance::{LocationRef, NormalizedCoord, Size},
         outline::OutlinePen,
         raw::FontRef,
         setting::Setting,
@@ -139,8 +139,84 @@ impl<'a> GlyphProvider<'a> {
     }
 }
 
+/// How [`layout_line`] spaces glyphs along the baseline.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GlyphSpacing {
+    /// Advance by the width of each glyph in the font, scaled to the font size.
+    #[default]
+    Proportional,
+    /// Advance by the same distance for every glyph, in the units of the run.
+    Monospace(f32),
+}
+
+/// Lays out `text` along a baseline at the origin, as glyphs to draw with
+/// [`DrawGlyphs`](crate::DrawGlyphs).
+///
+/// Each character is mapped to a glyph with the character map of the font, and placed after the
+/// previous one according to `spacing`. This is enough for basic Latin text; it doesn't apply
+/// kerning, ligatures or any other shaping, and doesn't break lines. Characters without a glyph
+/// in the font are laid out as the `.notdef` glyph. Returns no glyphs if the font can't be read.
+pub fn layout_line(font: &Font, font_size: f32, text: &str, spacing: GlyphSpacing) -> Vec<Glyph> {
+    let Some(font) = font_ref(font) else {
+        return vec![];
+    };
+    let charmap = font.charmap();
+    let metrics = font.glyph_metrics(Size::new(font_size), LocationRef::default());
+    let mut x = 0.0;
+    text.chars()
+        .map(|ch| {
+            let id = charmap.map(ch).unwrap_or_default();
+            let glyph = Glyph {
+                id: id.to_u16() as u32,
+                x,
+                y: 0.0,
+            };
+            x += match spacing {
+                GlyphSpacing::Proportional => metrics.advance_width(id).unwrap_or_default(),
+                GlyphSpacing::Monospace(advance) => advance,
+            };
+            glyph
+        })
+        .collect()
+}
+
+/// Returns the outlines of `glyphs` joined into a single path, such as for exporting text to
+/// a plotter or cutter.
+///
+/// The glyphs are positioned as when drawn with [`DrawGlyphs`](crate::DrawGlyphs) at
+/// `font_size`, with y pointing down, for example as laid out by [`layout_line`]. The outline
+/// of each glyph is added as its own subpaths, which are meant to be filled with the non-zero
+/// fill rule. Glyphs without an outline, such as spaces, add nothing. Returns an empty path if
+/// the font can't be read.
+pub fn glyphs_to_path(
+    font: &Font,
+    font_size: f32,
+    glyphs: impl IntoIterator<Item = Glyph>,
+) -> BezPath {
+    let mut path = BezPath::new();
+    let Some(font) = font_ref(font) else {
+        return path;
+    };
+    let outlines = font.outline_glyphs();
+    let size = Size::new(font_size);
+    for glyph in glyphs {
+        let Some(outline) = outlines.get(GlyphId::new(glyph.id as u16)) else {
+            continue;
+        };
+        let mut pen = BezPathPen::default();
+        let draw_settings = DrawSettings::unhinted(size, LocationRef::default());
+        if outline.draw(draw_settings, &mut pen).is_err() {
+            continue;
+        }
+        // Outlines are defined with y pointing up.
+        let transform = Affine::new([1.0, 0.0, 0.0, -1.0, glyph.x as f64, glyph.y as f64]);
+        path.extend(transform * pen.0);
+    }
+    path
+}
+
 #[derive(Default)]
-struct BezPathPen(peniko::kurbo::BezPath);
+struct BezPathPen(BezPath);
 
 impl OutlinePen for BezPathPen {
     fn move_to(&mut self, x: f32, y: f32) {
@@ -18,11 +18,18 @@
 
 mod cpu_dispatch;
 mod cpu_shader;
+#[cfg(feature = "wgpu")]
+mod depth;
 mod engine;
 mod render;
 mod scene;
 mod shaders;
 #[cfg(feature = "wgpu")]
+mod stream;
+mod svg;
+#[cfg(feature = "svg")]
+mod svg_import;
+#[cfg(feature = "wgpu")]
 mod wgpu_engine;
 
 /// Styling and composition primitives.
@@ -39,7 +46,10 @@ pub mod glyph;
 pub mod util;
 
 pub use render::Render;
-pub use scene::{DrawGlyphs, Scene};
+pub use scene::{
+    stripes, transformed_stroke_width, ClipSpace, DrawGlyphs, InnerShadow, LayerFlags,
+    LayerTransform, MissingGlyph, PositionedGlyph, Scene, SceneStats,
+};
 #[cfg(feature = "wgpu")]
 pub use util::block_on_wgpu;
 
@@ -48,11 +58,22 @@ pub use engine::{
 };
 pub use shaders::FullShaders;
 #[cfg(feature = "wgpu")]
+pub use stream::SceneStream;
+#[cfg(feature = "wgpu")]
+pub use wgpu_engine::{BufferAllocator, CancellationToken, Cancelled, WgpuBufferAllocator};
+#[cfg(feature = "wgpu")]
 use wgpu_engine::{ExternalResource, WgpuEngine};
+#[cfg(all(feature = "wgpu", feature = "debug"))]
+pub use wgpu_engine::{StageBuffer, StageCallback, StageInfo};
 
+#[cfg(feature = "wgpu")]
+use std::sync::Arc;
+pub use vello_encoding::AlphaType;
 /// Temporary export, used in `with_winit` for stats
 pub use vello_encoding::BumpAllocators;
 #[cfg(feature = "wgpu")]
+use vello_encoding::{DrawTag, Patch};
+#[cfg(feature = "wgpu")]
 use wgpu::{Device, Queue, SurfaceTexture, TextureFormat, TextureView};
 #[cfg(feature = "wgpu-profiler")]
 use wgpu_profiler::{GpuProfiler, GpuProfilerSettings};
@@ -64,7 +85,7 @@ pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Represents the antialiasing method to use during a render pass.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AaConfig {
     Area,
     Msaa8,
@@ -96,25 +117,120 @@ impl AaSupport {
     }
 }
 
+/// The error returned from a render that needs a feature the renderer can't provide, instead
+/// of leaving out the affected content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnsupportedFeature {
+    /// The antialiasing method was not enabled in the [`AaSupport`] of the renderer.
+    AntialiasingMethod(AaConfig),
+    /// An image is larger than the image atlas, whose size is limited by the device's
+    /// `max_texture_dimension_2d` and by [`vello_encoding::MAX_ATLAS_SIZE`].
+    ImageSize { width: u32, height: u32 },
+    /// Clips or layers are nested more deeply than [`RendererOptions::max_clip_depth`].
+    ClipDepth { depth: u32, max: u32 },
+}
+
+impl std::fmt::Display for UnsupportedFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AntialiasingMethod(method) => {
+                write!(f, "antialiasing method {method:?} is not enabled")
+            }
+            Self::ImageSize { width, height } => {
+                write!(f, "{width}x{height} image is larger than the image atlas")
+            }
+            Self::ClipDepth { depth, max } => {
+                write!(
+                    f,
+                    "clips are nested {depth} deep, more than the maximum of {max}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnsupportedFeature {}
+
+/// Dimensions, in tiles, of the bins that the binning stage sorts draw objects into.
+///
+/// Each bin is processed by one workgroup of the coarse rasterization stage, so a bin always
+/// contains [`BinSize::TILES`] tiles, but its shape can be changed. Bins which match the shape
+/// of clusters of geometry in a scene reduce the number of bins that each draw object lands
+/// in, which saves binning memory and coarse work.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BinSize {
+    /// The number of tiles in each bin.
+    pub const TILES: u32 = vello_encoding::N_TILES_PER_BIN;
+
+    /// Square bins of 16 by 16 tiles.
+    pub const SQUARE: Self = Self::new(16, 16);
+
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// Returns an error unless this bin size can be used by the pipeline.
+    pub fn validate(&self) -> Result<()> {
+        if !self.width.is_power_of_two() || self.width.saturating_mul(self.height) != Self::TILES {
+            return Err(format!(
+                "bin size {}x{} is not supported; bins must be {} tiles with a power of two width",
+                self.width,
+                self.height,
+                Self::TILES
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for BinSize {
+    fn default() -> Self {
+        Self::SQUARE
+    }
+}
+
 /// Renders a scene into a texture or surface.
 #[cfg(feature = "wgpu")]
 pub struct Renderer {
-    #[cfg_attr(not(feature = "hot_reload"), allow(dead_code))]
     options: RendererOptions,
     engine: WgpuEngine,
     shaders: FullShaders,
+    /// The vertex shader of the full screen passes, which draws a quad covering the target.
+    fullscreen_vs: wgpu::ShaderModule,
     blit: Option<BlitPipeline>,
+    composite: Option<CompositePipeline>,
+    accumulate: Option<ResolvePipeline>,
+    mask: Option<ResolvePipeline>,
+    dither: Option<ResolvePipeline>,
+    depth: Option<ResolvePipeline>,
+    upscale: Option<ResolvePipeline>,
+    blur: Option<BlurPipeline>,
     target: Option<TargetTexture>,
+    last_encode_duration: Option<std::time::Duration>,
     #[cfg(feature = "wgpu-profiler")]
     profiler: GpuProfiler,
     #[cfg(feature = "wgpu-profiler")]
     pub profile_result: Option<Vec<wgpu_profiler::GpuTimerQueryResult>>,
+    /// Timings of the frames profiled since the last [`Renderer::export_trace`].
+    #[cfg(feature = "wgpu-profiler")]
+    trace: Vec<wgpu_profiler::GpuTimerQueryResult>,
 }
 
 /// Parameters used in a single render that are configurable by the client.
+#[derive(Clone)]
 pub struct RenderParams {
     /// The background color applied to the target. This value is only applicable to the full
     /// pipeline.
+    ///
+    /// The color may be translucent or fully transparent. Partially covered pixels on the edges
+    /// of shapes are blended against it with premultiplied alpha, so they don't darken towards
+    /// black, and the target then holds the result with separate (unpremultiplied) alpha.
     pub base_color: peniko::Color,
 
     /// Dimensions of the rasterization target
@@ -124,6 +240,341 @@ pub struct RenderParams {
     /// The anti-aliasing algorithm. The selected algorithm must have been initialized while
     /// constructing the `Renderer`.
     pub antialiasing_method: AaConfig,
+
+    /// Optional bitmask of the tiles that need to be redrawn.
+    ///
+    /// The tile at `(x, y)` (in units of 16x16 pixel tiles) has index `y * width_in_tiles + x`,
+    /// and is dirty if bit `index % 32` of word `index / 32` is set. Clean tiles are skipped by
+    /// the coarse and fine stages, leaving the existing contents of the target untouched. If
+    /// `None`, the entire target is rendered.
+    pub dirty_tiles: Option<Vec<u32>>,
+
+    /// If true, [`Renderer::render_to_fitted_texture`] scales down targets which exceed the
+    /// device's `max_texture_dimension_2d` limit instead of returning an error.
+    pub auto_fit_limits: bool,
+
+    /// If true, [`Renderer::render_to_texture`] reads back the GPU's allocation counters after
+    /// the coarse stages, and reruns them with larger buffers whenever one of them overflowed.
+    ///
+    /// This waits for the coarse stages to finish before fine rasterization is submitted. It has
+    /// no effect when the renderer runs its stages on the CPU.
+    pub auto_grow: bool,
+
+    /// If true, the scene is mirrored horizontally across the center of the target, as for
+    /// right-to-left layouts.
+    ///
+    /// The whole scene is reflected, including text, which reads backwards unless it was laid
+    /// out mirrored. The scene itself is unchanged.
+    pub flip_x: bool,
+
+    /// If true, the scene is mirrored vertically across the center of the target.
+    pub flip_y: bool,
+
+    /// The expected complexity of the scene, used to size the intermediate buffers up front.
+    ///
+    /// Scenes which overflow the default buffer sizes otherwise need [`auto_grow`] to render
+    /// correctly, which repeats the coarse stages with larger buffers.
+    ///
+    /// [`auto_grow`]: Self::auto_grow
+    pub complexity_hint: Option<ComplexityHint>,
+
+    /// Gamma used to correct the antialiased coverage of glyphs drawn with solid colors.
+    ///
+    /// Blending coverage in sRGB space makes dark text on a light background look bolder than
+    /// light text on a dark background. With a gamma, typically around 2.2, the coverage of
+    /// each glyph is adjusted based on the luminance of its color to approximate blending in
+    /// linear light, which balances the stem weights. `None` disables the correction.
+    pub glyph_gamma: Option<f32>,
+
+    /// Standard deviation, in pixels, of a Gaussian blur applied to the whole frame before it
+    /// is written to the target.
+    ///
+    /// The blur is separable, running as a horizontal then a vertical pass over the rendered
+    /// frame with premultiplied alpha, and pixels past the edges of the target repeat the
+    /// nearest edge. `None` or a value which isn't positive leaves the frame sharp.
+    pub final_blur: Option<f32>,
+
+    /// Whether any pixel touched by a fill is treated as fully covered.
+    ///
+    /// This disables antialiasing in favor of conservative rasterization, so that partially
+    /// covered pixels on the edges of shapes, or along thin lines, are drawn at full
+    /// strength. It's meant for hit testing and selection masks, such as those rendered
+    /// with [`Renderer::render_coverage_mask`], where every pixel a shape touches should be
+    /// marked.
+    pub conservative_coverage: bool,
+
+    /// Maximum depth to which the flatten stage subdivides each curve, regardless of the
+    /// tolerance.
+    ///
+    /// A depth of `n` flattens a curve into at most `2^n` lines, which bounds the work done
+    /// for pathological curves, such as those with huge or nearly coincident control points,
+    /// at the cost of accuracy for them. `None` flattens every curve to the tolerance.
+    pub max_subdivision_depth: Option<u32>,
+
+    /// Procedural film grain added to every pixel of the output by fine rasterization.
+    ///
+    /// `None` leaves the output unchanged.
+    pub grain: Option<Grain>,
+
+    /// Seed for the stochastic effects of the render.
+    ///
+    /// It selects the noise pattern of [`grain`](Self::grain), and the offset of the dither
+    /// matrix used by [`Renderer::render_to_texture_dithered`]. Rendering the same scene with
+    /// the same parameters, including the seed, gives identical output, while the geometry
+    /// of the scene is the same whatever the seed.
+    pub seed: u32,
+
+    /// Distance in pixels within which the points of flattened paths are moved onto the
+    /// nearest boundary between pixel rows.
+    ///
+    /// An edge which is almost horizontal can cross a row boundary by a tiny amount, which the
+    /// winding and coverage computations can't resolve precisely, leaving a thin row of
+    /// artifacts along it. Moving its ends onto the boundary makes it exactly horizontal.
+    /// [`DEFAULT_HORIZONTAL_EPSILON`](Self::DEFAULT_HORIZONTAL_EPSILON) is small enough to
+    /// be invisible; zero disables this, which can help when debugging such artifacts.
+    pub horizontal_epsilon: f32,
+
+    /// Transfer function used to encode the colors written to the target, in place of sRGB.
+    ///
+    /// Colors are blended as sRGB encoded values, and normally written out that way. With a
+    /// transfer function, fine rasterization decodes each output color to linear light with
+    /// the sRGB curve, then encodes it with the lookup table instead, for displays with a
+    /// non-standard response. Alpha is unchanged. `None` writes sRGB.
+    pub output_transfer: Option<TransferLut>,
+
+    /// Whether filled paths, including the shapes of layers, are simplified where they extend
+    /// past the edges of the target. This should normally be true, as it is by default.
+    ///
+    /// Each curve which lies entirely beyond one edge of the target is flattened into a single
+    /// line between its end points instead of following the curve, which leaves every pixel
+    /// of the target unchanged. A clip shape much larger than the target, such as a huge
+    /// circle whose edge crosses it, would otherwise be flattened into many lines which are
+    /// all walked by the tiling stages. This can be turned off to check the output against
+    /// the unoptimized path.
+    pub clip_paths_to_viewport: bool,
+
+    /// How the textures which the renderer creates and returns, such as those of
+    /// [`Renderer::render_to_fitted_texture`] and [`Renderer::render_to_textures_tiled`], will be
+    /// used. This has no effect on targets created by the caller.
+    pub target_usage: TargetUsage,
+}
+
+/// How the caller will use a texture created by the renderer, set by
+/// [`RenderParams::target_usage`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TargetUsage {
+    /// The texture is sampled and also copied from, such as to read it back to the CPU.
+    #[default]
+    Readback,
+    /// The texture is only sampled or presented, so it isn't created as a copy source, which
+    /// lets the driver skip preparing it for copies.
+    Sample,
+}
+
+#[cfg(feature = "wgpu")]
+impl TargetUsage {
+    /// Returns the usages of a texture created for this use.
+    ///
+    /// These can also be used for targets passed to [`Renderer::render_to_texture`].
+    pub fn texture_usages(self) -> wgpu::TextureUsages {
+        let usages = wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING;
+        match self {
+            Self::Readback => usages | wgpu::TextureUsages::COPY_SRC,
+            Self::Sample => usages,
+        }
+    }
+}
+
+/// How [`Renderer::render_to_texture_accumulated`] combines a render with the existing contents
+/// of the target.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Accumulation {
+    /// The render is drawn over the target with source-over alpha blending, after its alpha is
+    /// scaled by `opacity`.
+    ///
+    /// Drawing each new frame of an opaque scene with an opacity of `a` keeps an exponential
+    /// moving average of the frames, as used by temporal antialiasing.
+    Over { opacity: f32 },
+    /// The premultiplied colors of the render, scaled by `weight`, are added to the target.
+    ///
+    /// Adding `n` renders with a weight of `1 / n` averages them, as for the sub-frames of
+    /// motion blur.
+    Add { weight: f32 },
+}
+
+#[cfg(feature = "wgpu")]
+impl Accumulation {
+    fn blend_state(self) -> wgpu::BlendState {
+        let operation = |dst_factor| wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor,
+            operation: wgpu::BlendOperation::Add,
+        };
+        match self {
+            Self::Over { .. } => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            Self::Add { .. } => wgpu::BlendState {
+                color: operation(wgpu::BlendFactor::One),
+                alpha: operation(wgpu::BlendFactor::One),
+            },
+        }
+    }
+
+    fn weight(self) -> f32 {
+        match self {
+            Self::Over { opacity } => opacity,
+            Self::Add { weight } => weight,
+        }
+    }
+}
+
+/// A one-dimensional lookup table from linear light to encoded color values, as set by
+/// [`RenderParams::output_transfer`].
+///
+/// The entries are spaced evenly over linear intensities from 0 to 1, and the table is
+/// interpolated linearly between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransferLut {
+    table: Vec<f32>,
+}
+
+impl TransferLut {
+    /// Number of entries in the tables made by [`TransferLut::gamma`].
+    pub const GAMMA_ENTRIES: usize = 1024;
+
+    /// Creates a lookup table from its entries, which are encoded values between 0 and 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table` has fewer than two entries.
+    pub fn new(table: Vec<f32>) -> Self {
+        assert!(
+            table.len() >= 2,
+            "a transfer LUT needs at least two entries"
+        );
+        Self { table }
+    }
+
+    /// Creates a lookup table for a pure power law, which encodes a linear intensity `x` as
+    /// `x^(1 / gamma)`.
+    pub fn gamma(gamma: f32) -> Self {
+        let last = (Self::GAMMA_ENTRIES - 1) as f32;
+        let table = (0..Self::GAMMA_ENTRIES)
+            .map(|i| (i as f32 / last).powf(1.0 / gamma))
+            .collect();
+        Self { table }
+    }
+
+    /// Returns the entries of the table.
+    pub fn table(&self) -> &[f32] {
+        &self.table
+    }
+}
+
+/// Per-pixel noise added to a render for a film grain look, as set by [`RenderParams::grain`].
+///
+/// The noise is monochrome, and depends only on the position of each pixel and
+/// [`RenderParams::seed`], so the same seed produces the same grain from frame to frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Grain {
+    /// The largest change made to each color channel, as a fraction of full intensity. Zero
+    /// adds no noise.
+    pub intensity: f32,
+}
+
+/// Statistics of a frame, as returned by [`Renderer::render_to_texture_with_stats`].
+///
+/// Tiles are counted once for each draw object covering them, so a tile under several
+/// overlapping objects is counted several times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// Number of tiles that a draw object covers entirely, which fine rasterization fills
+    /// with its brush without computing coverage.
+    pub solid_tiles: u32,
+    /// Number of tiles crossed by the edges of a draw object, which need the coverage of
+    /// each pixel to be computed from the segments of the path.
+    pub coverage_tiles: u32,
+    /// Number of lines that the paths of the scene were flattened into, which the tiling
+    /// stages walk through to find the tiles each line crosses.
+    pub lines: u32,
+}
+
+/// Expected amounts of the geometry in a scene, for sizing the buffers of a render.
+///
+/// Buffers are never made smaller than their default sizes, so an underestimate is harmless.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComplexityHint {
+    /// Number of tiles covered by the bounding boxes of the paths, summed over all paths.
+    pub tiles: u32,
+    /// Number of line segments in the paths after flattening.
+    pub segments: u32,
+}
+
+impl ComplexityHint {
+    /// The minimum lengths of the bump allocated buffers needed by this much geometry.
+    fn bump_sizes(&self) -> BumpAllocators {
+        BumpAllocators {
+            tile: self.tiles,
+            lines: self.segments,
+            seg_counts: self.segments,
+            segments: self.segments,
+            ..Default::default()
+        }
+    }
+}
+
+impl RenderParams {
+    /// The usual value of [`horizontal_epsilon`](Self::horizontal_epsilon).
+    pub const DEFAULT_HORIZONTAL_EPSILON: f32 = 1.0 / 1024.0;
+
+    /// The reflection applied to the scene by [`flip_x`](Self::flip_x) and
+    /// [`flip_y`](Self::flip_y), or `None` if neither is set.
+    pub fn flip_transform(&self) -> Option<peniko::kurbo::Affine> {
+        if !self.flip_x && !self.flip_y {
+            return None;
+        }
+        let (sx, tx) = if self.flip_x {
+            (-1.0, self.width as f64)
+        } else {
+            (1.0, 0.0)
+        };
+        let (sy, ty) = if self.flip_y {
+            (-1.0, self.height as f64)
+        } else {
+            (1.0, 0.0)
+        };
+        Some(peniko::kurbo::Affine::new([sx, 0.0, 0.0, sy, tx, ty]))
+    }
+}
+
+/// The usual parameters: a black background, area antialiasing, and none of the optional
+/// features of a render enabled.
+///
+/// The `width` and `height` are zero, so they always need to be set to the size of the target.
+impl Default for RenderParams {
+    fn default() -> Self {
+        Self {
+            base_color: peniko::Color::BLACK,
+            width: 0,
+            height: 0,
+            antialiasing_method: AaConfig::Area,
+            dirty_tiles: None,
+            auto_fit_limits: false,
+            auto_grow: false,
+            flip_x: false,
+            flip_y: false,
+            complexity_hint: None,
+            glyph_gamma: None,
+            final_blur: None,
+            conservative_coverage: false,
+            max_subdivision_depth: None,
+            grain: None,
+            seed: 0,
+            horizontal_epsilon: Self::DEFAULT_HORIZONTAL_EPSILON,
+            output_transfer: None,
+            clip_paths_to_viewport: true,
+            target_usage: TargetUsage::Readback,
+        }
+    }
 }
 
 #[cfg(feature = "wgpu")]
@@ -140,24 +591,78 @@ pub struct RendererOptions {
     /// Represents the enabled set of AA configurations. This will be used to determine which
     /// pipeline permutations should be compiled at startup.
     pub antialiasing_support: AaSupport,
+
+    /// The shape of the bins used by the binning and coarse rasterization stages.
+    pub bin_size: BinSize,
+
+    /// The maximum number of clips and layers that may be open at the same time in a scene.
+    ///
+    /// Each open clip needs space in the clip buffers, so limiting the nesting bounds the memory
+    /// used for them. Rendering a scene which nests clips more deeply fails with
+    /// [`UnsupportedFeature::ClipDepth`]. If None, the depth is not limited.
+    pub max_clip_depth: Option<u32>,
+}
+
+#[cfg(feature = "wgpu")]
+impl RendererOptions {
+    /// Returns the device features used by a renderer created with these options, which should
+    /// be requested when the device is created.
+    ///
+    /// All of the antialiasing pipelines, including the MSAA permutations, only use core
+    /// WebGPU, so this is empty unless the `wgpu-profiler` feature is enabled. The profiler
+    /// then uses timestamp queries to time each stage.
+    pub fn required_features(&self) -> wgpu::Features {
+        #[allow(unused_mut)]
+        let mut features = wgpu::Features::empty();
+        #[cfg(feature = "wgpu-profiler")]
+        {
+            features |= GpuProfiler::ALL_WGPU_TIMER_FEATURES;
+        }
+        features
+    }
 }
 
 #[cfg(feature = "wgpu")]
 impl Renderer {
     /// Creates a new renderer for the specified device.
     pub fn new(device: &Device, options: RendererOptions) -> Result<Self> {
+        Self::with_buffer_allocator(device, options, Box::new(WgpuBufferAllocator))
+    }
+
+    /// Creates a new renderer for the specified device, which creates its GPU buffers
+    /// through `allocator`.
+    pub fn with_buffer_allocator(
+        device: &Device,
+        options: RendererOptions,
+        allocator: Box<dyn BufferAllocator>,
+    ) -> Result<Self> {
+        options.bin_size.validate()?;
         let mut engine = WgpuEngine::new(options.use_cpu);
+        engine.set_buffer_allocator(allocator);
         let shaders = shaders::full_shaders(device, &mut engine, &options)?;
+        let fullscreen_vs = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("full screen vertex shader"),
+            source: wgpu::ShaderSource::Wgsl(FULLSCREEN_VS.into()),
+        });
         let blit = options
             .surface_format
-            .map(|surface_format| BlitPipeline::new(device, surface_format));
+            .map(|surface_format| BlitPipeline::new(device, &fullscreen_vs, surface_format));
 
         Ok(Self {
             options,
             engine,
             shaders,
+            fullscreen_vs,
             blit,
+            composite: None,
+            accumulate: None,
+            mask: None,
+            dither: None,
+            depth: None,
+            upscale: None,
+            blur: None,
             target: None,
+            last_encode_duration: None,
             // Use 3 pending frames
             #[cfg(feature = "wgpu-profiler")]
             profiler: GpuProfiler::new(GpuProfilerSettings {
@@ -165,14 +670,157 @@ impl Renderer {
             })?,
             #[cfg(feature = "wgpu-profiler")]
             profile_result: None,
+            #[cfg(feature = "wgpu-profiler")]
+            trace: vec![],
         })
     }
 
+    /// Returns when the GPU last finished the work submitted by this renderer, or `None` if
+    /// no submitted work has completed yet.
+    ///
+    /// The time is recorded from the queue's submitted work done callback, which only runs
+    /// while the device is polled, so this lags behind until the next [`Device::poll`] after
+    /// a render. Comparing it with the time a frame was submitted gives the GPU latency of
+    /// the frame for frame pacing.
+    ///
+    /// The time is an [`instant::Instant`], which is [`std::time::Instant`] except on wasm32,
+    /// where submitted work isn't tracked and this always returns `None`.
+    pub fn last_submit_completed_at(&self) -> Option<instant::Instant> {
+        self.engine.last_submit_completed_at()
+    }
+
+    /// Returns how long the last render spent on the CPU encoding its scene for the GPU, or
+    /// `None` before the first render.
+    ///
+    /// This covers merging the fragments of the scene, resolving its glyphs, gradients and
+    /// images into the packed scene buffer, and recording the dispatches of the pipeline. The
+    /// GPU work is timed separately, by the profiler when the `wgpu-profiler` feature is
+    /// enabled, so the two can be compared to find out which one limits the frame rate.
+    pub fn last_encode_duration(&self) -> Option<std::time::Duration> {
+        self.last_encode_duration
+    }
+
+    /// Sets a token which can be used to cancel renders from another thread.
+    ///
+    /// A cancelled render returns a [`Cancelled`] error and leaves the target unmodified. The
+    /// token must be [reset](CancellationToken::reset) before rendering can resume.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.engine.set_cancellation_token(token);
+    }
+
+    /// Sets a callback which is invoked with each pipeline stage of a render as it is
+    /// recorded, for debuggers which inspect or dump the intermediate buffers.
+    ///
+    /// The stages are passed in the order they run, with the buffers bound to each one.
+    #[cfg(feature = "debug")]
+    pub fn set_stage_callback(&mut self, callback: Option<StageCallback>) {
+        self.engine.set_stage_callback(callback);
+    }
+
+    /// Sets the number of draw objects kept together in each bin by the binning stage.
+    ///
+    /// Binning processes draw objects in partitions of 256, and stores the objects of each
+    /// partition that land in a bin in a chunk of the binning buffer. Objects past `capacity` in
+    /// a bin spill into a second, overflow chunk.
+    ///
+    /// With a capacity below the default of [`BinSize::TILES`], which is the size of a
+    /// partition, the binning buffer is sized to hold a full chunk for each bin of every
+    /// partition instead of having a fixed default size, so a smaller capacity uses less memory.
+    /// Overflow chunks don't fit in that, so scenes whose bins spill need
+    /// [`RenderParams::auto_grow`] to grow the buffer rather than drop draw objects. When the
+    /// stages run on the CPU, which can't grow the buffer, it keeps its default size.
+    pub fn set_bin_capacity(&mut self, capacity: u32) {
+        self.shaders.bin_capacity = capacity;
+    }
+
+    /// Replaces the built-in sample pattern of an MSAA method with `positions`, or restores
+    /// the built-in pattern if `None`, such as to match the output of another renderer.
+    ///
+    /// Positions are relative to the top left of the pixel, in `[0, 1)` on both axes. The
+    /// number of positions must match the number of samples of `method`, and each row of a
+    /// grid with that many rows must hold exactly one of them, as for the built-in patterns.
+    /// Sample rasterization only uses the row of each position, as if it was at the center of
+    /// its row. Returns an error if `method` is [`AaConfig::Area`] or the positions don't
+    /// meet these requirements.
+    pub fn set_msaa_sample_positions(
+        &mut self,
+        method: AaConfig,
+        positions: Option<&[kurbo::Point]>,
+    ) -> Result<()> {
+        match method {
+            AaConfig::Area => Err("area antialiasing has no sample positions".into()),
+            AaConfig::Msaa8 => {
+                self.shaders.msaa8_positions = positions.map(sample_columns).transpose()?;
+                Ok(())
+            }
+            AaConfig::Msaa16 => {
+                self.shaders.msaa16_positions = positions.map(sample_columns).transpose()?;
+                Ok(())
+            }
+        }
+    }
+
+    /// The profiler which times the stages of each render.
+    ///
+    /// Queries opened on it by the application are recorded in the same frame as the renderer's
+    /// own, so application passes are reported alongside the Vello stages, for example in
+    /// `profile_result` after [`Self::render_to_surface_async`].
+    #[cfg(feature = "wgpu-profiler")]
+    pub fn profiler(&mut self) -> &mut GpuProfiler {
+        &mut self.profiler
+    }
+
+    /// Ends the profiler frame holding the renders submitted since the previous one, and waits
+    /// for its timings, which are stored in `profile_result` and added to the trace written by
+    /// [`Self::export_trace`].
+    ///
+    /// This is only needed when rendering to textures, as [`Self::render_to_surface_async`]
+    /// ends a frame after each render.
+    #[cfg(feature = "wgpu-profiler")]
+    pub fn end_profiler_frame(&mut self, device: &Device, queue: &Queue) -> Result<()> {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("end_profiler_frame"),
+        });
+        self.profiler.resolve_queries(&mut encoder);
+        queue.submit([encoder.finish()]);
+        self.engine.track_submit(queue);
+        self.profiler.end_frame()?;
+        device.poll(wgpu::Maintain::Wait);
+        self.process_profiler_frame(queue);
+        Ok(())
+    }
+
+    #[cfg(feature = "wgpu-profiler")]
+    fn process_profiler_frame(&mut self, queue: &Queue) {
+        if let Some(result) = self
+            .profiler
+            .process_finished_frame(queue.get_timestamp_period())
+        {
+            self.trace.extend_from_slice(&result);
+            self.profile_result = Some(result);
+        }
+    }
+
+    /// Writes the per-stage timings of the frames profiled since the previous call as a
+    /// Chrome trace `.json` file, which can be viewed in `about:tracing` or Perfetto.
+    ///
+    /// Timings are only available when the device supports
+    /// [`wgpu::Features::TIMESTAMP_QUERY`]; otherwise the trace has no events.
+    #[cfg(feature = "wgpu-profiler")]
+    pub fn export_trace(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        wgpu_profiler::chrometrace::write_chrometrace(path.as_ref(), &self.trace)?;
+        self.trace.clear();
+        Ok(())
+    }
+
     /// Renders a scene to the target texture.
     ///
     /// The texture is assumed to be of the specified dimensions and have been created with
     /// the [`wgpu::TextureFormat::Rgba8Unorm`] format and the [`wgpu::TextureUsages::STORAGE_BINDING`]
     /// flag set.
+    ///
+    /// Returns an [`UnsupportedFeature`] error without rendering anything if the scene or
+    /// `params` need a feature the renderer can't provide.
     pub fn render_to_texture(
         &mut self,
         device: &Device,
@@ -181,7 +829,17 @@ impl Renderer {
         texture: &TextureView,
         params: &RenderParams,
     ) -> Result<()> {
+        self.check_support(device, scene, params)?;
+        if let Some(std_dev) = params.final_blur.filter(|std_dev| *std_dev > 0.) {
+            return self.render_to_texture_blurred(device, queue, scene, texture, params, std_dev);
+        }
+        if params.auto_grow && !self.options.use_cpu {
+            self.render_to_texture_auto_grow(device, queue, scene, texture, params)?;
+            return Ok(());
+        }
+        let start = instant::Instant::now();
         let (recording, target) = render::render_full(scene, &self.shaders, params);
+        self.last_encode_duration = Some(start.elapsed());
         let external_resources = [ExternalResource::Image(
             *target.as_image().unwrap(),
             texture,
@@ -198,87 +856,1319 @@ impl Renderer {
         Ok(())
     }
 
-    /// Renders a scene to the target surface.
+    /// Renders a scene to the target texture like [`Self::render_to_texture`], returning
+    /// statistics about how it was rasterized.
     ///
-    /// This renders to an intermediate texture and then runs a render pass to blit to the
-    /// specified surface texture.
-    ///
-    /// The surface is assumed to be of the specified dimensions and have been configured with
-    /// the same format passed in the constructing [`RendererOptions`]' `surface_format`.
-    /// Panics if `surface_format` was `None`
-    pub fn render_to_surface(
+    /// The statistics are read back from the GPU, which waits for the coarse stages of the
+    /// render to finish. The bump allocated buffers are grown as needed, as with
+    /// [`RenderParams::auto_grow`], and [`RenderParams::final_blur`] is not applied.
+    pub fn render_to_texture_with_stats(
         &mut self,
         device: &Device,
         queue: &Queue,
         scene: &Scene,
-        surface: &SurfaceTexture,
+        texture: &TextureView,
         params: &RenderParams,
-    ) -> Result<()> {
-        let width = params.width;
-        let height = params.height;
-        let mut target = self
-            .target
-            .take()
-            .unwrap_or_else(|| TargetTexture::new(device, width, height));
-        // TODO: implement clever resizing semantics here to avoid thrashing the memory allocator
-        // during resize, specifically on metal.
-        if target.width != width || target.height != height {
-            target = TargetTexture::new(device, width, height);
+    ) -> Result<FrameStats> {
+        self.check_support(device, scene, params)?;
+        let bump = self.render_to_texture_auto_grow(device, queue, scene, texture, params)?;
+        Ok(FrameStats {
+            solid_tiles: bump.solid_tiles,
+            coverage_tiles: bump.coverage_tiles,
+            lines: bump.lines,
+        })
+    }
+
+    /// Returns an [`UnsupportedFeature`] error for the first feature needed by `scene` or
+    /// `params` that the renderer can't provide on `device`.
+    fn check_support(&self, device: &Device, scene: &Scene, params: &RenderParams) -> Result<()> {
+        let aa_supported = match params.antialiasing_method {
+            AaConfig::Area => self.shaders.fine_area.is_some(),
+            AaConfig::Msaa8 => self.shaders.fine_msaa8.is_some(),
+            AaConfig::Msaa16 => self.shaders.fine_msaa16.is_some(),
+        };
+        if !aa_supported {
+            return Err(UnsupportedFeature::AntialiasingMethod(params.antialiasing_method).into());
         }
-        self.render_to_texture(device, queue, scene, &target.view, params)?;
-        let blit = self
-            .blit
-            .as_ref()
-            .expect("renderer should have configured surface_format to use on a surface");
-        let mut encoder =
-            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
-        {
-            let surface_view = surface
-                .texture
-                .create_view(&wgpu::TextureViewDescriptor::default());
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: None,
-                layout: &blit.bind_layout,
-                entries: &[wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&target.view),
-                }],
-            });
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &surface_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::default()),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
-            render_pass.set_pipeline(&blit.pipeline);
-            render_pass.set_bind_group(0, &bind_group, &[]);
-            render_pass.draw(0..6, 0..1);
+        if let Some(max) = self.options.max_clip_depth {
+            let depth = scene.encoding().max_clip_depth();
+            if depth > max {
+                return Err(UnsupportedFeature::ClipDepth { depth, max }.into());
+            }
+        }
+        let max_image_size = device
+            .limits()
+            .max_texture_dimension_2d
+            .min(vello_encoding::MAX_ATLAS_SIZE);
+        for patch in &scene.encoding().resources.patches {
+            if let Patch::Image { image, .. } = patch {
+                if image.width > max_image_size || image.height > max_image_size {
+                    return Err(UnsupportedFeature::ImageSize {
+                        width: image.width,
+                        height: image.height,
+                    }
+                    .into());
+                }
+            }
         }
-        queue.submit(Some(encoder.finish()));
-        self.target = Some(target);
         Ok(())
     }
 
-    /// Reload the shaders. This should only be used during `vello` development
-    #[cfg(feature = "hot_reload")]
-    pub async fn reload_shaders(&mut self, device: &Device) -> Result<()> {
-        device.push_error_scope(wgpu::ErrorFilter::Validation);
-        let mut engine = WgpuEngine::new(self.options.use_cpu);
-        let shaders = shaders::full_shaders(device, &mut engine, &self.options)?;
-        let error = device.pop_error_scope().await;
-        if let Some(error) = error {
-            return Err(error.into());
-        }
+    /// Renders a scene into an intermediate texture, then blurs it into the target texture.
+    fn render_to_texture_blurred(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &TextureView,
+        params: &RenderParams,
+        std_dev: f32,
+    ) -> Result<()> {
+        let mut blur = self
+            .blur
+            .take()
+            .unwrap_or_else(|| BlurPipeline::new(device, params.width, params.height));
+        blur.resize(device, params.width, params.height);
+        let sharp_params = RenderParams {
+            final_blur: None,
+            ..params.clone()
+        };
+        let result = self.render_to_texture(device, queue, scene, &blur.source.view, &sharp_params);
+        if result.is_ok() {
+            blur.blur(device, queue, texture, std_dev);
+            self.engine.track_submit(queue);
+        }
+        self.blur = Some(blur);
+        result
+    }
+
+    /// Renders a scene to the target texture, growing the bump allocated buffers until the
+    /// coarse stages no longer overflow them.
+    ///
+    /// Returns the counters of the bump allocators from the successful attempt.
+    fn render_to_texture_auto_grow(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &TextureView,
+        params: &RenderParams,
+    ) -> Result<BumpAllocators> {
+        let max_size = u64::from(device.limits().max_storage_buffer_binding_size);
+        let mut min_sizes = BumpAllocators::default();
+        loop {
+            let mut render = Render::new();
+            render.reserve_bump_buffers(min_sizes);
+            let start = instant::Instant::now();
+            let recording =
+                render.render_encoding_coarse(scene.encoding(), &self.shaders, params, true);
+            self.last_encode_duration = Some(start.elapsed());
+            let target = render.out_image();
+            let bump_buf = render.bump_buf();
+            self.engine.run_recording(
+                device,
+                queue,
+                &recording,
+                &[],
+                "auto_grow_coarse",
+                #[cfg(feature = "wgpu-profiler")]
+                &mut self.profiler,
+            )?;
+            let download = self
+                .engine
+                .get_download(bump_buf)
+                .ok_or("bump buffer was not downloaded")?;
+            let slice = download.slice(..);
+            let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+            slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+            block_on_wgpu(device, receiver.receive()).ok_or("channel was closed")??;
+            let bump: BumpAllocators = bytemuck::pod_read_unaligned(&slice.get_mapped_range());
+            download.unmap();
+            self.engine.free_download(bump_buf);
+
+            let mut recording = Recording::default();
+            let done = match render.grow_bump_buffers(&bump) {
+                Some(next) => {
+                    render.discard_fine(&mut recording);
+                    min_sizes = next;
+                    false
+                }
+                None => {
+                    render.record_fine(&self.shaders, &mut recording);
+                    true
+                }
+            };
+            let external_resources = [ExternalResource::Image(target, texture)];
+            self.engine.run_recording(
+                device,
+                queue,
+                &recording,
+                if done { &external_resources } else { &[] },
+                "auto_grow_fine",
+                #[cfg(feature = "wgpu-profiler")]
+                &mut self.profiler,
+            )?;
+            if done {
+                return Ok(bump);
+            }
+            let size = render.max_bump_buffer_size();
+            if size > max_size {
+                return Err(format!(
+                    "scene needs a {size} byte buffer, more than the device limit of {max_size}"
+                )
+                .into());
+            }
+        }
+    }
+
+    /// Renders a scene to a single mip level of the target texture.
+    ///
+    /// The texture must meet the same requirements as for [`Self::render_to_texture`], and
+    /// `params.width` and `params.height` must match the size of `mip_level`.
+    pub fn render_to_texture_mip(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        mip_level: u32,
+        params: &RenderParams,
+    ) -> Result<()> {
+        if mip_level >= texture.mip_level_count() {
+            return Err(format!(
+                "mip level {mip_level} is out of range for a texture with {} levels",
+                texture.mip_level_count()
+            )
+            .into());
+        }
+        let width = (texture.width() >> mip_level).max(1);
+        let height = (texture.height() >> mip_level).max(1);
+        if (width, height) != (params.width, params.height) {
+            return Err(format!(
+                "render size {}x{} doesn't match the size {width}x{height} of mip level {mip_level}",
+                params.width, params.height
+            )
+            .into());
+        }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            base_mip_level: mip_level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        });
+        self.render_to_texture(device, queue, scene, &view, params)
+    }
+
+    /// Renders a scene to one face of a cube texture, such as an environment map.
+    ///
+    /// The texture must be a 2D texture with six array layers of equal width and height, which
+    /// otherwise meets the same requirements as for [`Self::render_to_texture`]. Only the
+    /// array layer of `face` is written. The scene is drawn as seen from the center of the cube
+    /// looking towards the face, in the orientation WebGPU uses for cube map faces, so no
+    /// further flipping is needed.
+    pub fn render_to_cubemap_face(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        texture: &wgpu::Texture,
+        face: CubeFace,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<()> {
+        let size = texture.size();
+        if texture.dimension() != wgpu::TextureDimension::D2
+            || size.depth_or_array_layers != 6
+            || size.width != size.height
+        {
+            return Err(format!(
+                "a {}x{}x{} texture is not a cube texture",
+                size.width, size.height, size.depth_or_array_layers
+            )
+            .into());
+        }
+        if (size.width, size.height) != (params.width, params.height) {
+            return Err(format!(
+                "render size {}x{} doesn't match the face size {}x{}",
+                params.width, params.height, size.width, size.height
+            )
+            .into());
+        }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_array_layer: face as u32,
+            array_layer_count: Some(1),
+            ..Default::default()
+        });
+        self.render_to_texture(device, queue, scene, &view, params)
+    }
+
+    /// Returns the intermediate target texture, first creating it if there is none of the
+    /// size `width` by `height`.
+    fn ensure_target(&mut self, device: &Device, width: u32, height: u32) -> &TargetTexture {
+        // TODO: implement clever resizing semantics here to avoid thrashing the memory allocator
+        // during resize, specifically on metal.
+        if !matches!(&self.target, Some(target) if target.width == width && target.height == height)
+        {
+            self.target = Some(TargetTexture::new(device, width, height));
+        }
+        self.target.as_ref().unwrap()
+    }
+
+    /// Renders a scene to the target surface.
+    ///
+    /// This renders to an intermediate texture and then runs a render pass to blit to the
+    /// specified surface texture.
+    ///
+    /// The surface is assumed to be of the specified dimensions and have been configured with
+    /// the same format passed in the constructing [`RendererOptions`]' `surface_format`.
+    /// Panics if `surface_format` was `None`
+    pub fn render_to_surface(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        surface: &SurfaceTexture,
+        params: &RenderParams,
+    ) -> Result<()> {
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        self.render_to_texture(device, queue, scene, &target, params)?;
+        let blit = self
+            .blit
+            .as_ref()
+            .expect("renderer should have configured surface_format to use on a surface");
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let surface_view = surface
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &blit.bind_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&target),
+                }],
+            });
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &surface_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::default()),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&blit.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..6, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Starts rendering a scene into `texture` in chunks as it is built, for scenes too large
+    /// to encode in full.
+    ///
+    /// The encoded size of each chunk, as measured by [`SceneStats::size_in_bytes`], is at
+    /// most `memory_cap` bytes, which also bounds the GPU buffers needed to render it. The
+    /// first chunk is rendered over `params.base_color`, which should be opaque, and each
+    /// later chunk is composited over the earlier ones with [`Self::render_to_texture_composited`].
+    /// Draws are blended the same as in a single scene, except that a layer's blend mode only
+    /// sees the content of its own chunk. [`RenderParams::final_blur`] can't be applied to the
+    /// frame as a whole, so rendering a chunk with it set returns an error.
+    ///
+    /// The texture must meet the requirements of both [`Self::render_to_texture`] and
+    /// [`Self::render_to_texture_composited`].
+    pub fn stream_to_texture<'a>(
+        &'a mut self,
+        device: &'a Device,
+        queue: &'a Queue,
+        texture: &'a wgpu::Texture,
+        params: &RenderParams,
+        memory_cap: usize,
+    ) -> SceneStream<'a> {
+        SceneStream::new(self, device, queue, texture, params, memory_cap)
+    }
+
+    /// Renders a scene and composites the result onto the existing contents of `texture`.
+    ///
+    /// Rather than overwriting the target, the output of the render is combined with the
+    /// current contents of the texture using `blend_mode`, as if the whole scene were drawn in
+    /// a layer. The existing contents are treated as premultiplied, and the result is written
+    /// premultiplied. In most cases `params.base_color` should be transparent, as it forms
+    /// part of the composited layer.
+    ///
+    /// The texture is assumed to be of the specified dimensions and have been created with
+    /// the [`wgpu::TextureUsages::RENDER_ATTACHMENT`] and [`wgpu::TextureUsages::COPY_SRC`]
+    /// flags set.
+    pub fn render_to_texture_composited(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+        blend_mode: impl Into<peniko::BlendMode>,
+    ) -> Result<()> {
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        self.render_to_texture(device, queue, scene, &target, params)?;
+        let format = texture.format();
+        if self.composite.as_ref().map(|c| c.format) != Some(format) {
+            self.composite = Some(CompositePipeline::new(device, &self.fullscreen_vs, format));
+        }
+        let composite = self.composite.as_mut().unwrap();
+        // The texture can't be read while it is being rendered to, so make a copy of the
+        // existing contents to use as the backdrop.
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        composite.resize_backdrop(device, size);
+        let composite = &*composite;
+        let backdrop = composite.backdrop.as_ref().unwrap();
+        let backdrop_view = backdrop.create_view(&wgpu::TextureViewDescriptor::default());
+        let blend_mode = vello_encoding::DrawBeginClip::new(blend_mode.into(), 1.0).blend_mode;
+        let blend_buf = &composite.blend_buf;
+        queue.write_buffer(blend_buf, 0, bytemuck::bytes_of(&[blend_mode, 0, 0, 0]));
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_texture(texture.as_image_copy(), backdrop.as_image_copy(), size);
+        {
+            let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &composite.bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&target),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&backdrop_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: blend_buf.as_entire_binding(),
+                    },
+                ],
+            });
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &texture_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&composite.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..6, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders a scene and accumulates the result into `texture` without clearing it.
+    ///
+    /// The render is blended into the existing contents of the target with fixed function
+    /// blending, as set by `accumulation`, so repeated calls build up multiple renders in a
+    /// persistent buffer, such as for motion blur or temporal antialiasing. The contents are
+    /// treated as premultiplied, and the result is written premultiplied. `params.base_color`
+    /// is part of each render, so it should normally be transparent.
+    ///
+    /// The texture is assumed to be of the specified dimensions, have a blendable format and
+    /// have been created with the [`wgpu::TextureUsages::RENDER_ATTACHMENT`] flag set. Unlike
+    /// [`Self::render_to_texture_composited`], no copy of the target is made, and a format
+    /// with more precision than 8 bits, such as [`wgpu::TextureFormat::Rgba16Float`], can be
+    /// used to accumulate many renders without banding.
+    pub fn render_to_texture_accumulated(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+        accumulation: Accumulation,
+    ) -> Result<()> {
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        self.render_to_texture(device, queue, scene, &target, params)?;
+        let format = texture.format();
+        let blend = accumulation.blend_state();
+        if self.accumulate.as_ref().map(|a| (a.format, a.blend)) != Some((format, Some(blend))) {
+            self.accumulate = Some(ResolvePipeline::with_blend(
+                device,
+                &self.fullscreen_vs,
+                format,
+                ACCUMULATE_FS,
+                true,
+                Some(blend),
+            ));
+        }
+        let accumulate = self.accumulate.as_ref().unwrap();
+        accumulate.write_uniforms(
+            queue,
+            bytemuck::bytes_of(&[accumulation.weight(), 0., 0., 0.]),
+        );
+        accumulate.resolve(device, queue, &target, texture);
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders the coverage of a scene into a single channel mask.
+    ///
+    /// The alpha of each pixel of the rendered scene, drawn over a transparent background, is
+    /// written to the red channel of `texture`, so fully covered pixels become 255 and
+    /// uncovered pixels 0. Brushes should be opaque for the output to be a pure coverage mask;
+    /// their colors are otherwise ignored. `params.base_color` is ignored.
+    ///
+    /// The texture is assumed to be of the specified dimensions, have the
+    /// [`wgpu::TextureFormat::R8Unorm`] format and have been created with the
+    /// [`wgpu::TextureUsages::RENDER_ATTACHMENT`] flag set.
+    pub fn render_coverage_mask(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+    ) -> Result<()> {
+        if texture.format() != TextureFormat::R8Unorm {
+            return Err(format!(
+                "coverage masks must be rendered to R8Unorm textures, got {:?}",
+                texture.format()
+            )
+            .into());
+        }
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        let params = RenderParams {
+            base_color: peniko::Color::TRANSPARENT,
+            ..params.clone()
+        };
+        self.render_to_texture(device, queue, scene, &target, &params)?;
+        let mask = self.mask.get_or_insert_with(|| {
+            ResolvePipeline::new(device, &self.fullscreen_vs, TextureFormat::R8Unorm, MASK_FS)
+        });
+        mask.resolve(device, queue, &target, texture);
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders a scene dithered to black and white, for 1-bit displays such as e-ink panels.
+    ///
+    /// Each pixel is set to black or white by comparing the luminance of the rendered scene
+    /// against a 4x4 ordered (Bayer) dither matrix, so the density of white pixels tracks the
+    /// brightness of the input. The result is written to every color channel of `texture`, with
+    /// an opaque alpha, so a single channel format such as [`wgpu::TextureFormat::R8Unorm`] can
+    /// be used to hold it. `params.base_color` should be opaque.
+    ///
+    /// The texture is assumed to be of the specified dimensions and have been created with
+    /// the [`wgpu::TextureUsages::RENDER_ATTACHMENT`] flag set.
+    pub fn render_to_texture_dithered(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+    ) -> Result<()> {
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        self.render_to_texture(device, queue, scene, &target, params)?;
+        let format = texture.format();
+        if self.dither.as_ref().map(|d| d.format) != Some(format) {
+            self.dither = Some(ResolvePipeline::with_uniforms(
+                device,
+                &self.fullscreen_vs,
+                format,
+                DITHER_FS,
+                true,
+            ));
+        }
+        let dither = self.dither.as_ref().unwrap();
+        // The seed picks one of the 16 offsets of the matrix.
+        let offset = params.seed.wrapping_mul(0x9e3779b9) >> 28;
+        dither.write_uniforms(queue, bytemuck::bytes_of(&[offset & 3, offset >> 2, 0, 0]));
+        dither.resolve(device, queue, &target, texture);
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders the depths assigned by [`Scene::set_depth`] into a depth buffer, as a companion
+    /// to the color rendered by [`Self::render_to_texture`] with the same parameters.
+    ///
+    /// Each pixel of the red channel of `texture` holds the depth of the topmost draw object
+    /// covering it, linearly mapped from 0..=1 and quantized to 256 levels, regardless of the
+    /// brushes, blend modes or opacity used for the color. Depths aren't blended across the
+    /// edges of shapes: a pixel touched by the edge of a draw object takes its depth.
+    /// Pixels which nothing is drawn over have a depth of 0, as do layers themselves.
+    ///
+    /// The texture is assumed to be of the specified dimensions and have been created with
+    /// the [`wgpu::TextureUsages::RENDER_ATTACHMENT`] flag set. A single channel format such
+    /// as [`wgpu::TextureFormat::R8Unorm`] is enough to hold the result.
+    pub fn render_depth(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+    ) -> Result<()> {
+        let width = params.width;
+        let height = params.height;
+        let target = self.ensure_target(device, width, height).view.clone();
+        let params = RenderParams {
+            base_color: peniko::Color::TRANSPARENT,
+            conservative_coverage: true,
+            grain: None,
+            ..params.clone()
+        };
+        self.render_to_texture(device, queue, &scene.depth_scene(), &target, &params)?;
+        let format = texture.format();
+        if self.depth.as_ref().map(|d| d.format) != Some(format) {
+            self.depth = Some(ResolvePipeline::new(
+                device,
+                &self.fullscreen_vs,
+                format,
+                DEPTH_FS,
+            ));
+        }
+        let depth = self.depth.as_ref().unwrap();
+        depth.resolve(device, queue, &target, texture);
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders a scene at a fraction of the size of `texture`, then resamples it to fill the
+    /// texture using `filter`.
+    ///
+    /// The scene is rendered at `internal_scale` times the size in `params`, rounded to the
+    /// nearest pixel, which saves fill rate where a lower resolution is acceptable.
+    /// `internal_scale` must be greater than 0 and at most 1. The resampling happens with
+    /// premultiplied alpha.
+    ///
+    /// The texture is assumed to be of the specified dimensions and have been created with
+    /// the [`wgpu::TextureUsages::RENDER_ATTACHMENT`] flag set.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_to_texture_scaled(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &wgpu::Texture,
+        params: &RenderParams,
+        internal_scale: f64,
+        filter: UpscaleFilter,
+    ) -> Result<()> {
+        if !(internal_scale > 0. && internal_scale <= 1.) {
+            return Err(format!("internal scale {internal_scale} is not in (0, 1]").into());
+        }
+        let scaled_size = |size: u32| ((size as f64 * internal_scale).round() as u32).max(1);
+        let width = scaled_size(params.width);
+        let height = scaled_size(params.height);
+        let target = self.ensure_target(device, width, height).view.clone();
+        let mut scaled = Scene::new();
+        scaled.append(scene, Some(peniko::kurbo::Affine::scale(internal_scale)));
+        // Dirty tiles are given in units of the requested size, so can't be used here.
+        let scaled_params = RenderParams {
+            width,
+            height,
+            dirty_tiles: None,
+            ..params.clone()
+        };
+        self.render_to_texture(device, queue, &scaled, &target, &scaled_params)?;
+        let format = texture.format();
+        if self.upscale.as_ref().map(|u| u.format) != Some(format) {
+            self.upscale = Some(ResolvePipeline::with_uniforms(
+                device,
+                &self.fullscreen_vs,
+                format,
+                UPSCALE_FS,
+                true,
+            ));
+        }
+        let upscale = self.upscale.as_ref().unwrap();
+        let filter = filter as u32;
+        upscale.write_uniforms(
+            queue,
+            bytemuck::bytes_of(&[filter, 0, params.width, params.height]),
+        );
+        upscale.resolve(device, queue, &target, texture);
+        self.engine.track_submit(queue);
+        Ok(())
+    }
+
+    /// Renders a scene that may be too large for a single texture into a grid of textures.
+    ///
+    /// `params.width` and `params.height` give the size of the whole canvas. It is split into
+    /// the smallest grid of equally sized tiles (apart from the last row and column) that are
+    /// no larger than `max_tile_size` in either dimension, defaulting to the device's
+    /// `max_texture_dimension_2d` limit. Each tile is rendered to its own
+    /// [`wgpu::TextureFormat::Rgba8Unorm`] texture with the scene translated by the tile's
+    /// origin, and the tiles are returned in row-major order. `params.flip_x` and
+    /// `params.flip_y` flip the whole canvas, so each tile holds the same pixels as the
+    /// matching region of a single render. The same goes for `params.final_blur`: each tile
+    /// is rendered with a border as wide as the blur, which is cropped off by a copy, so the
+    /// tile textures also have [`wgpu::TextureUsages::COPY_DST`] set when blurring.
+    ///
+    /// `params.dirty_tiles` is ignored.
+    pub fn render_to_textures_tiled(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+        max_tile_size: Option<u32>,
+    ) -> Result<Vec<CanvasTile>> {
+        let max_tile_size = max_tile_size
+            .unwrap_or(device.limits().max_texture_dimension_2d)
+            .max(1);
+        let tile_width = params
+            .width
+            .div_ceil(params.width.div_ceil(max_tile_size).max(1));
+        let tile_height = params
+            .height
+            .div_ceil(params.height.div_ceil(max_tile_size).max(1));
+        // The flips are applied across the whole canvas, not within each tile.
+        let flip = params.flip_transform().unwrap_or_default();
+        // The blur reads this many pixels past the edges of a tile, so each tile is rendered
+        // with a border of the neighbouring content, which is cropped off afterwards.
+        let border = params
+            .final_blur
+            .filter(|std_dev| *std_dev > 0.)
+            .map_or(0, BlurPipeline::radius);
+        let mut usage = params.target_usage.texture_usages();
+        if border > 0 {
+            usage |= wgpu::TextureUsages::COPY_DST;
+        }
+        let mut tiles = vec![];
+        for y in (0..params.height).step_by(tile_height.max(1) as usize) {
+            for x in (0..params.width).step_by(tile_width.max(1) as usize) {
+                let width = tile_width.min(params.width - x);
+                let height = tile_height.min(params.height - y);
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("canvas tile"),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    usage,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    view_formats: &[],
+                });
+                // The border stops at the edges of the canvas, where the blur repeats the edge
+                // pixels as it does for a single render.
+                let x0 = x.saturating_sub(border);
+                let y0 = y.saturating_sub(border);
+                let x1 = (x + width + border).min(params.width);
+                let y1 = (y + height + border).min(params.height);
+                let bordered = (border > 0).then(|| {
+                    device.create_texture(&wgpu::TextureDescriptor {
+                        label: Some("canvas tile with border"),
+                        size: wgpu::Extent3d {
+                            width: x1 - x0,
+                            height: y1 - y0,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        view_formats: &[],
+                    })
+                });
+                let view = bordered
+                    .as_ref()
+                    .unwrap_or(&texture)
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                let mut tile_scene = Scene::new();
+                let origin = peniko::kurbo::Affine::translate((-(x0 as f64), -(y0 as f64)));
+                tile_scene.append(scene, Some(origin * flip));
+                let tile_params = RenderParams {
+                    width: x1 - x0,
+                    height: y1 - y0,
+                    dirty_tiles: None,
+                    flip_x: false,
+                    flip_y: false,
+                    ..params.clone()
+                };
+                self.render_to_texture(device, queue, &tile_scene, &view, &tile_params)?;
+                if let Some(bordered) = &bordered {
+                    let mut encoder = device
+                        .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+                    encoder.copy_texture_to_texture(
+                        wgpu::ImageCopyTexture {
+                            texture: bordered,
+                            mip_level: 0,
+                            origin: wgpu::Origin3d {
+                                x: x - x0,
+                                y: y - y0,
+                                z: 0,
+                            },
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        texture.as_image_copy(),
+                        wgpu::Extent3d {
+                            width,
+                            height,
+                            depth_or_array_layers: 1,
+                        },
+                    );
+                    queue.submit(Some(encoder.finish()));
+                    self.engine.track_submit(queue);
+                }
+                tiles.push(CanvasTile {
+                    x,
+                    y,
+                    width,
+                    height,
+                    texture,
+                });
+            }
+        }
+        Ok(tiles)
+    }
+
+    /// Renders each of `layers` into its own new [`wgpu::TextureFormat::Rgba8Unorm`]
+    /// texture, for exporting a design as separate, named layers.
+    ///
+    /// Each layer is a scene holding one top-level object or group, paired with the name the
+    /// author gave it, and is rendered at the full size of `params` over a transparent
+    /// background so that its texture only contains that object. The layers are returned in
+    /// the order they were given. The GPU buffers of the renderer are pooled, so they are
+    /// reused across the layers rather than being allocated for each of them.
+    pub fn render_layers_for_export(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        layers: &[(&str, &Scene)],
+        params: &RenderParams,
+    ) -> Result<Vec<ExportedLayer>> {
+        let params = RenderParams {
+            base_color: peniko::Color::TRANSPARENT,
+            ..params.clone()
+        };
+        let mut exported = Vec::with_capacity(layers.len());
+        for &(name, scene) in layers {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("export layer"),
+                size: wgpu::Extent3d {
+                    width: params.width,
+                    height: params.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                usage: params.target_usage.texture_usages(),
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.render_to_texture(device, queue, scene, &view, &params)?;
+            exported.push(ExportedLayer {
+                name: name.to_owned(),
+                texture,
+            });
+        }
+        Ok(exported)
+    }
+
+    /// Renders a scene into a new [`wgpu::TextureFormat::Rgba8Unorm`] texture.
+    ///
+    /// If `params.width` or `params.height` exceeds the device's `max_texture_dimension_2d`
+    /// limit, this returns an error, unless `params.auto_fit_limits` is set. In that case the
+    /// scene is instead scaled down uniformly to the largest size that fits, and the applied
+    /// scale is reported in the result.
+    pub fn render_to_fitted_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<FittedTexture> {
+        let max = device.limits().max_texture_dimension_2d;
+        let largest = params.width.max(params.height);
+        let mut scale = 1.0;
+        let (mut width, mut height) = (params.width, params.height);
+        if largest > max {
+            if !params.auto_fit_limits {
+                return Err(format!(
+                    "render size {}x{} exceeds the device limit of {max}",
+                    params.width, params.height
+                )
+                .into());
+            }
+            scale = max as f64 / largest as f64;
+            let fit = |size: u32| ((size as f64 * scale).round() as u32).clamp(1, max);
+            (width, height) = (fit(params.width), fit(params.height));
+        }
+        let texture =
+            create_target_texture(device, "fitted target", width, height, params.target_usage);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let fitted_params = RenderParams {
+            width,
+            height,
+            ..params.clone()
+        };
+        if scale == 1.0 {
+            self.render_to_texture(device, queue, scene, &view, &fitted_params)?;
+        } else {
+            let mut scaled = Scene::new();
+            scaled.append(scene, Some(peniko::kurbo::Affine::scale(scale)));
+            // Dirty tiles are given in units of the requested size, so can't be used here.
+            let fitted_params = RenderParams {
+                dirty_tiles: None,
+                ..fitted_params
+            };
+            self.render_to_texture(device, queue, &scaled, &view, &fitted_params)?;
+        }
+        Ok(FittedTexture {
+            width,
+            height,
+            scale,
+            texture,
+        })
+    }
+
+    /// Renders a scene into a new [`wgpu::TextureFormat::Rgba8Unorm`] texture of the size in
+    /// `params`, also returning the bounds of the content drawn into it.
+    ///
+    /// The bounds are those computed by the pipeline for binning, so they cover the
+    /// rasterized extent of each draw object, including stroke widths and glyphs, after
+    /// clipping to its layers and to the target. Layers themselves don't add to the bounds.
+    /// This waits for the coarse stages to finish before fine rasterization is submitted.
+    pub fn render_with_bounds(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<BoundedTexture> {
+        self.check_support(device, scene, params)?;
+        let texture = create_target_texture(
+            device,
+            "bounded target",
+            params.width,
+            params.height,
+            params.target_usage,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut render = Render::new();
+        render.download_draw_bboxes();
+        let start = instant::Instant::now();
+        let encoding = scene.encoding();
+        let recording = render.render_encoding_coarse(encoding, &self.shaders, params, false);
+        self.last_encode_duration = Some(start.elapsed());
+        let target = render.out_image();
+        let draw_bbox_buf = render.draw_bbox_buf().unwrap();
+        self.engine.run_recording(
+            device,
+            queue,
+            &recording,
+            &[],
+            "bounds_coarse",
+            #[cfg(feature = "wgpu-profiler")]
+            &mut self.profiler,
+        )?;
+        let download = self
+            .engine
+            .get_download(draw_bbox_buf)
+            .ok_or("draw bbox buffer was not downloaded")?;
+        let slice = download.slice(..);
+        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+        slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+        block_on_wgpu(device, receiver.receive()).ok_or("channel was closed")??;
+        let mut bounds: Option<[f32; 4]> = None;
+        {
+            let mapped = slice.get_mapped_range();
+            let bboxes: &[[f32; 4]] = bytemuck::cast_slice(&mapped);
+            let (width, height) = (params.width as f32, params.height as f32);
+            for (&tag, bbox) in encoding.draw_tags.iter().zip(bboxes) {
+                if matches!(
+                    tag,
+                    DrawTag::NOP
+                        | DrawTag::BEGIN_CLIP
+                        | DrawTag::BEGIN_CLIP_COLOR_MATRIX
+                        | DrawTag::END_CLIP
+                ) {
+                    continue;
+                }
+                let [x0, y0] = [bbox[0].max(0.0), bbox[1].max(0.0)];
+                let [x1, y1] = [bbox[2].min(width), bbox[3].min(height)];
+                if x0 < x1 && y0 < y1 {
+                    bounds = Some(bounds.map_or([x0, y0, x1, y1], |b| {
+                        [b[0].min(x0), b[1].min(y0), b[2].max(x1), b[3].max(y1)]
+                    }));
+                }
+            }
+        }
+        download.unmap();
+        self.engine.free_download(draw_bbox_buf);
+
+        let mut recording = Recording::default();
+        render.record_fine(&self.shaders, &mut recording);
+        let external_resources = [ExternalResource::Image(target, &view)];
+        self.engine.run_recording(
+            device,
+            queue,
+            &recording,
+            &external_resources,
+            "bounds_fine",
+            #[cfg(feature = "wgpu-profiler")]
+            &mut self.profiler,
+        )?;
+        let bounds = bounds.map(|[x0, y0, x1, y1]| {
+            let (x, y) = (x0.floor() as u32, y0.floor() as u32);
+            PixelRect {
+                x,
+                y,
+                width: x1.ceil() as u32 - x,
+                height: y1.ceil() as u32 - y,
+            }
+        });
+        Ok(BoundedTexture { texture, bounds })
+    }
+
+    /// Renders a scene once with each of the antialiasing methods in `modes`, such as to show
+    /// their output side by side.
+    ///
+    /// Each render goes into a new [`wgpu::TextureFormat::Rgba8Unorm`] texture of the size in
+    /// `params`, returned in the order of `modes`, while the intermediate buffers are shared
+    /// between the renders. The value of `params.antialiasing_method` is ignored, and every
+    /// method in `modes` must have been enabled when constructing the renderer.
+    pub fn render_aa_comparison(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+        modes: &[AaConfig],
+    ) -> Result<Vec<wgpu::Texture>> {
+        let mut textures = Vec::with_capacity(modes.len());
+        for &mode in modes {
+            let texture = create_target_texture(
+                device,
+                "aa comparison target",
+                params.width,
+                params.height,
+                params.target_usage,
+            );
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let mode_params = RenderParams {
+                antialiasing_method: mode,
+                ..params.clone()
+            };
+            self.render_to_texture(device, queue, scene, &view, &mode_params)?;
+            textures.push(texture);
+        }
+        Ok(textures)
+    }
+
+    /// Renders a scene and returns it as a `data:image/png;base64,...` URL.
+    ///
+    /// This is meant for debugging, as the URL can be logged or opened in a browser. The
+    /// scene is rendered as by [`Renderer::render_to_fitted_texture`], so the image may be
+    /// smaller than requested when `params.auto_fit_limits` is set.
+    #[cfg(feature = "data_url")]
+    pub fn render_to_data_url(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<String> {
+        use base64::Engine;
+
+        let fitted = self.render_to_fitted_texture(device, queue, scene, params)?;
+        let rect = PixelRect {
+            x: 0,
+            y: 0,
+            width: fitted.width,
+            height: fitted.height,
+        };
+        let pixels = self.read_region(device, queue, &fitted.texture, rect)?;
+        let mut png_data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png_data, fitted.width, fitted.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&pixels)?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(png_data);
+        Ok(format!("data:image/png;base64,{encoded}"))
+    }
+
+    /// Renders a scene and reads the result back into a [`peniko::Image`].
+    ///
+    /// The image holds unpremultiplied RGBA pixels, so it can be used directly as an image
+    /// brush in another scene. The scene is rendered as by
+    /// [`Renderer::render_to_fitted_texture`], so the image may be smaller than requested when
+    /// `params.auto_fit_limits` is set.
+    pub fn render_to_peniko_image(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<peniko::Image> {
+        let fitted = self.render_to_fitted_texture(device, queue, scene, params)?;
+        let rect = PixelRect {
+            x: 0,
+            y: 0,
+            width: fitted.width,
+            height: fitted.height,
+        };
+        let pixels = self.read_region(device, queue, &fitted.texture, rect)?;
+        Ok(peniko::Image::new(
+            pixels.into(),
+            peniko::Format::Rgba8,
+            fitted.width,
+            fitted.height,
+        ))
+    }
+
+    /// Renders a scene and reads the result back into an image with the given alpha type.
+    ///
+    /// Unlike [`Renderer::render_to_peniko_image`], which always returns straight alpha, the
+    /// pixels can be premultiplied, and the returned image records which they are. Drawing it
+    /// with [`Scene::draw_rendered_image`] then treats the alpha correctly either way.
+    pub fn render_to_image(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+        alpha_type: AlphaType,
+    ) -> Result<RenderedImage> {
+        let mut image = self.render_to_peniko_image(device, queue, scene, params)?;
+        if alpha_type == AlphaType::Premultiplied {
+            let mut pixels = image.data.data().to_vec();
+            for pixel in pixels.chunks_exact_mut(4) {
+                let a = pixel[3] as u32;
+                for c in &mut pixel[..3] {
+                    *c = ((*c as u32 * a + 127) / 255) as u8;
+                }
+            }
+            image.data = pixels.into();
+        }
+        Ok(RenderedImage { image, alpha_type })
+    }
+
+    /// Renders a scene and reads the result back into a [`peniko::Image`] composited over
+    /// `background`, as when exporting to a format without transparency.
+    ///
+    /// The frame is blended over the background with premultiplied alpha, so translucent
+    /// pixels mix with it in proportion to their coverage, and the image is opaque wherever
+    /// the background is. As with [`Renderer::render_to_peniko_image`], the pixels are
+    /// unpremultiplied RGBA.
+    pub fn render_to_image_over(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+        background: peniko::Color,
+    ) -> Result<peniko::Image> {
+        let mut image = self.render_to_peniko_image(device, queue, scene, params)?;
+        let mut pixels = image.data.data().to_vec();
+        composite_over(&mut pixels, background);
+        image.data = pixels.into();
+        Ok(image)
+    }
+
+    /// Renders a scene to the target texture, and returns the number of draw objects which
+    /// touched each tile, for visualizing overdraw as a heatmap.
+    ///
+    /// There is a count for each 16x16 pixel tile of the target, in row-major order with
+    /// `params.width.div_ceil(16)` tiles in each row. A draw object counts towards every tile
+    /// it covers any part of, unless the tile is entirely clipped out. Clips themselves aren't
+    /// counted, and tiles left out of `params.dirty_tiles` report no draws.
+    ///
+    /// The texture must meet the same requirements as for [`Self::render_to_texture`]. This
+    /// waits for the coarse stages to finish before fine rasterization is submitted.
+    pub fn render_overdraw(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        texture: &TextureView,
+        params: &RenderParams,
+    ) -> Result<Vec<u32>> {
+        self.check_support(device, scene, params)?;
+        let mut render = Render::new();
+        render.count_overdraw();
+        let start = instant::Instant::now();
+        let recording =
+            render.render_encoding_coarse(scene.encoding(), &self.shaders, params, false);
+        self.last_encode_duration = Some(start.elapsed());
+        let target = render.out_image();
+        let overdraw_buf = render.overdraw_buf().unwrap();
+        self.engine.run_recording(
+            device,
+            queue,
+            &recording,
+            &[],
+            "overdraw_coarse",
+            #[cfg(feature = "wgpu-profiler")]
+            &mut self.profiler,
+        )?;
+        let download = self
+            .engine
+            .get_download(overdraw_buf)
+            .ok_or("overdraw buffer was not downloaded")?;
+        let slice = download.slice(..);
+        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+        slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+        block_on_wgpu(device, receiver.receive()).ok_or("channel was closed")??;
+        let counts: Vec<u32> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        download.unmap();
+        self.engine.free_download(overdraw_buf);
+
+        let mut recording = Recording::default();
+        render.record_fine(&self.shaders, &mut recording);
+        let external_resources = [ExternalResource::Image(target, texture)];
+        self.engine.run_recording(
+            device,
+            queue,
+            &recording,
+            &external_resources,
+            "overdraw_fine",
+            #[cfg(feature = "wgpu-profiler")]
+            &mut self.profiler,
+        )?;
+        Ok(counts)
+    }
+
+    /// Reads back the pixels of `rect` in mip level 0 of `texture`.
+    ///
+    /// Only the requested region is copied from the GPU. The result holds the rows of the
+    /// region tightly packed, top to bottom, so row `y` starts at byte
+    /// `y * rect.width * bytes_per_pixel`. The texture must have been created with
+    /// [`wgpu::TextureUsages::COPY_SRC`] and have an uncompressed color format.
+    pub fn read_region(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        texture: &wgpu::Texture,
+        rect: PixelRect,
+    ) -> Result<Vec<u8>> {
+        let format = texture.format();
+        let bytes_per_pixel = match format.block_copy_size(None) {
+            Some(size) if format.block_dimensions() == (1, 1) => size,
+            _ => return Err(format!("can't read back pixels of {format:?} textures").into()),
+        };
+        let fits =
+            |start: u32, len: u32, size: u32| start.checked_add(len).is_some_and(|end| end <= size);
+        if !fits(rect.x, rect.width, texture.width())
+            || !fits(rect.y, rect.height, texture.height())
+        {
+            return Err(format!(
+                "region {rect:?} is outside of the {}x{} texture",
+                texture.width(),
+                texture.height()
+            )
+            .into());
+        }
+        if rect.width == 0 || rect.height == 0 {
+            return Ok(vec![]);
+        }
+        // Rows of a copy into a buffer have to start at a multiple of the alignment.
+        let row_bytes = rect.width * bytes_per_pixel;
+        let padded_row_bytes = row_bytes.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("read_region"),
+            size: padded_row_bytes as u64 * rect.height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("read_region"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: rect.x,
+                    y: rect.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: rect.width,
+                height: rect.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit([encoder.finish()]);
+        self.engine.track_submit(queue);
+        let slice = buffer.slice(..);
+        let (sender, receiver) = futures_intrusive::channel::shared::oneshot_channel();
+        slice.map_async(wgpu::MapMode::Read, move |v| sender.send(v).unwrap());
+        block_on_wgpu(device, receiver.receive()).ok_or("channel was closed")??;
+        let mapped = slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity(row_bytes as usize * rect.height as usize);
+        for row in mapped.chunks(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+        Ok(pixels)
+    }
+
+    /// Reload the shaders. This should only be used during `vello` development
+    #[cfg(feature = "hot_reload")]
+    pub async fn reload_shaders(&mut self, device: &Device) -> Result<()> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut engine = WgpuEngine::new(self.options.use_cpu);
+        let shaders = shaders::full_shaders(device, &mut engine, &self.options)?;
+        let error = device.pop_error_scope().await;
+        if let Some(error) = error {
+            return Err(error.into());
+        }
+        engine.take_buffer_pool(&mut self.engine);
         self.engine = engine;
-        self.shaders = shaders;
+        // Keep the settings made on the previous shaders.
+        self.shaders = FullShaders {
+            bin_capacity: self.shaders.bin_capacity,
+            msaa8_positions: self.shaders.msaa8_positions,
+            msaa16_positions: self.shaders.msaa16_positions,
+            ..shaders
+        };
         Ok(())
     }
 
@@ -301,11 +2191,14 @@ impl Renderer {
         texture: &TextureView,
         params: &RenderParams,
     ) -> Result<Option<BumpAllocators>> {
+        self.check_support(device, scene, params)?;
         let mut render = Render::new();
+        let start = instant::Instant::now();
         let encoding = scene.encoding();
         // TODO: turn this on; the download feature interacts with CPU dispatch
         let robust = false;
         let recording = render.render_encoding_coarse(encoding, &self.shaders, params, robust);
+        self.last_encode_duration = Some(start.elapsed());
         let target = render.out_image();
         let bump_buf = render.bump_buf();
         self.engine.run_recording(
@@ -361,17 +2254,9 @@ impl Renderer {
     ) -> Result<Option<BumpAllocators>> {
         let width = params.width;
         let height = params.height;
-        let mut target = self
-            .target
-            .take()
-            .unwrap_or_else(|| TargetTexture::new(device, width, height));
-        // TODO: implement clever resizing semantics here to avoid thrashing the memory allocator
-        // during resize, specifically on metal.
-        if target.width != width || target.height != height {
-            target = TargetTexture::new(device, width, height);
-        }
+        let target = self.ensure_target(device, width, height).view.clone();
         let bump = self
-            .render_to_texture_async(device, queue, scene, &target.view, params)
+            .render_to_texture_async(device, queue, scene, &target, params)
             .await?;
         let blit = self
             .blit
@@ -388,7 +2273,7 @@ impl Renderer {
                 layout: &blit.bind_layout,
                 entries: &[wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&target.view),
+                    resource: wgpu::BindingResource::TextureView(&target),
                 }],
             });
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -412,99 +2297,638 @@ impl Renderer {
         #[cfg(feature = "wgpu-profiler")]
         self.profiler.resolve_queries(&mut encoder);
         queue.submit(Some(encoder.finish()));
-        self.target = Some(target);
+        self.engine.track_submit(queue);
         #[cfg(feature = "wgpu-profiler")]
         self.profiler.end_frame().unwrap();
         #[cfg(feature = "wgpu-profiler")]
-        if let Some(result) = self
-            .profiler
-            .process_finished_frame(queue.get_timestamp_period())
-        {
-            self.profile_result = Some(result);
+        self.process_profiler_frame(queue);
+        Ok(bump)
+    }
+}
+
+/// One texture of a canvas rendered with [`Renderer::render_to_textures_tiled`].
+#[cfg(feature = "wgpu")]
+pub struct CanvasTile {
+    /// Horizontal position of the tile in the canvas, in pixels.
+    pub x: u32,
+    /// Vertical position of the tile in the canvas, in pixels.
+    pub y: u32,
+    /// Width of the tile, in pixels.
+    pub width: u32,
+    /// Height of the tile, in pixels.
+    pub height: u32,
+    /// The texture holding the rendered tile.
+    pub texture: wgpu::Texture,
+}
+
+/// One layer of a design rendered with [`Renderer::render_layers_for_export`].
+#[cfg(feature = "wgpu")]
+pub struct ExportedLayer {
+    /// Name the author gave the layer.
+    pub name: String,
+    /// The texture holding only the content of the layer.
+    pub texture: wgpu::Texture,
+}
+
+/// A face of a cube texture, as rendered by [`Renderer::render_to_cubemap_face`].
+///
+/// The discriminant of each face is the index of its array layer.
+#[cfg(feature = "wgpu")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX = 0,
+    NegativeX = 1,
+    PositiveY = 2,
+    NegativeY = 3,
+    PositiveZ = 4,
+    NegativeZ = 5,
+}
+
+/// How [`Renderer::render_to_texture_scaled`] resamples a scene rendered at a lower
+/// resolution to the size of the target.
+#[cfg(feature = "wgpu")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpscaleFilter {
+    /// Each pixel takes the color of the nearest rendered pixel, which keeps the hard edges
+    /// of pixel art.
+    Nearest = 0,
+    /// Linear interpolation between the four nearest rendered pixels.
+    #[default]
+    Bilinear = 1,
+    /// Catmull-Rom interpolation between the sixteen nearest rendered pixels, which is
+    /// sharper than bilinear filtering.
+    Bicubic = 2,
+}
+
+/// An image read back from a render, along with whether its pixels are premultiplied.
+///
+/// Returned by [`Renderer::render_to_image`], and drawn by [`Scene::draw_rendered_image`],
+/// which respects the alpha type.
+#[derive(Clone, Debug)]
+pub struct RenderedImage {
+    /// The pixels of the render.
+    pub image: peniko::Image,
+    /// Whether the color channels of `image` are premultiplied by alpha.
+    pub alpha_type: AlphaType,
+}
+
+/// A rectangle of pixels within a texture, as read by [`Renderer::read_region`].
+#[cfg(feature = "wgpu")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    /// Horizontal position of the left edge, in pixels.
+    pub x: u32,
+    /// Vertical position of the top edge, in pixels.
+    pub y: u32,
+    /// Width of the rectangle, in pixels.
+    pub width: u32,
+    /// Height of the rectangle, in pixels.
+    pub height: u32,
+}
+
+/// Composites unpremultiplied RGBA8 `pixels` over `background` in place.
+#[cfg(feature = "wgpu")]
+fn composite_over(pixels: &mut [u8], background: peniko::Color) {
+    let unit = |c: u8| c as f32 / 255.0;
+    let bg = [background.r, background.g, background.b].map(unit);
+    let bg_alpha = unit(background.a);
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = unit(pixel[3]);
+        let bg_weight = bg_alpha * (1.0 - alpha);
+        let out_alpha = alpha + bg_weight;
+        for (c, bg) in pixel[..3].iter_mut().zip(bg) {
+            let premul = unit(*c) * alpha + bg * bg_weight;
+            *c = if out_alpha > 0.0 {
+                (premul / out_alpha * 255.0).round() as u8
+            } else {
+                0
+            };
+        }
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+/// Creates a texture which can be used as the target of [`Renderer::render_to_texture`], and
+/// read back if `usage` asks for it.
+#[cfg(feature = "wgpu")]
+fn create_target_texture(
+    device: &Device,
+    label: &str,
+    width: u32,
+    height: u32,
+    usage: TargetUsage,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        usage: usage.texture_usages(),
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        view_formats: &[],
+    })
+}
+
+/// The result of [`Renderer::render_to_fitted_texture`].
+#[cfg(feature = "wgpu")]
+pub struct FittedTexture {
+    /// Width of the texture, in pixels.
+    pub width: u32,
+    /// Height of the texture, in pixels.
+    pub height: u32,
+    /// The scale which was applied to the scene to fit it within the device limits, or 1.0 if
+    /// the requested size already fit.
+    pub scale: f64,
+    /// The texture holding the rendered scene.
+    pub texture: wgpu::Texture,
+}
+
+/// The result of [`Renderer::render_with_bounds`].
+#[cfg(feature = "wgpu")]
+pub struct BoundedTexture {
+    /// The texture holding the rendered scene.
+    pub texture: wgpu::Texture,
+    /// The pixels of the texture covered by the content of the scene, or `None` if nothing
+    /// was drawn within it.
+    pub bounds: Option<PixelRect>,
+}
+
+#[cfg(feature = "wgpu")]
+struct TargetTexture {
+    view: Arc<TextureView>,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(feature = "wgpu")]
+impl TargetTexture {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            view_formats: &[],
+        });
+        let view = Arc::new(texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        Self {
+            view,
+            width,
+            height,
+        }
+    }
+}
+
+/// Vertex shader shared by the full screen render passes, which draws a quad covering the
+/// target as two triangles.
+#[cfg(feature = "wgpu")]
+const FULLSCREEN_VS: &str = r#"
+    @vertex
+    fn vs_main(@builtin(vertex_index) ix: u32) -> @builtin(position) vec4<f32> {
+        // Generate a full screen quad in NDCs
+        var vertex = vec2(-1.0, 1.0);
+        switch ix {
+            case 1u: {
+                vertex = vec2(-1.0, -1.0);
+            }
+            case 2u, 4u: {
+                vertex = vec2(1.0, -1.0);
+            }
+            case 5u: {
+                vertex = vec2(1.0, 1.0);
+            }
+            default: {}
+        }
+        return vec4(vertex, 0.0, 1.0);
+    }
+"#;
+
+#[cfg(feature = "wgpu")]
+struct BlitPipeline {
+    bind_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+#[cfg(feature = "wgpu")]
+impl BlitPipeline {
+    fn new(device: &Device, vertex: &wgpu::ShaderModule, format: TextureFormat) -> Self {
+        const SHADERS: &str = r#"
+            @group(0) @binding(0)
+            var fine_output: texture_2d<f32>;
+
+            @fragment
+            fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+                let rgba_sep = textureLoad(fine_output, vec2<i32>(pos.xy), 0);
+                return vec4(rgba_sep.rgb * rgba_sep.a, rgba_sep.a);
+            }
+        "#;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("blit shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADERS.into()),
+        });
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[wgpu::BindGroupLayoutEntry {
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                binding: 0,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: vertex,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+        Self {
+            bind_layout,
+            pipeline,
         }
-        Ok(bump)
     }
 }
 
 #[cfg(feature = "wgpu")]
-struct TargetTexture {
-    view: TextureView,
-    width: u32,
-    height: u32,
+struct CompositePipeline {
+    format: TextureFormat,
+    bind_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    /// The uniform holding the blend mode.
+    blend_buf: wgpu::Buffer,
+    /// The copy of the target's contents from the last composite, reused while the size of
+    /// the target stays the same.
+    backdrop: Option<wgpu::Texture>,
 }
 
 #[cfg(feature = "wgpu")]
-impl TargetTexture {
-    pub fn new(device: &Device, width: u32, height: u32) -> Self {
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
+impl CompositePipeline {
+    fn new(device: &Device, vertex: &wgpu::ShaderModule, format: TextureFormat) -> Self {
+        const SHADERS: &str = r#"
+            @group(0) @binding(0)
+            var fine_output: texture_2d<f32>;
+
+            @group(0) @binding(1)
+            var backdrop: texture_2d<f32>;
+
+            @group(0) @binding(2)
+            var<uniform> blend_mode: u32;
+
+            @fragment
+            fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+                let rgba_sep = textureLoad(fine_output, vec2<i32>(pos.xy), 0);
+                let src = vec4(rgba_sep.rgb * rgba_sep.a, rgba_sep.a);
+                let bg = textureLoad(backdrop, vec2<i32>(pos.xy), 0);
+                return blend_mix_compose(bg, src, blend_mode);
+            }
+        "#;
+
+        let source = [include_str!("../shader/shared/blend.wgsl"), SHADERS].concat();
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("composite shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            binding,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: None,
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    binding: 2,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: vertex,
+                entry_point: "vs_main",
+                buffers: &[],
             },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            view_formats: &[],
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+        let blend_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("composite blend mode"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
         Self {
-            view,
-            width,
-            height,
+            format,
+            bind_layout,
+            pipeline,
+            blend_buf,
+            backdrop: None,
+        }
+    }
+
+    /// Reallocates the backdrop texture if it doesn't match the size of the target.
+    fn resize_backdrop(&mut self, device: &Device, size: wgpu::Extent3d) {
+        if self.backdrop.as_ref().map(wgpu::Texture::size) != Some(size) {
+            self.backdrop = Some(device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("composite backdrop"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+                format: self.format,
+                view_formats: &[],
+            }));
         }
     }
 }
 
+/// Fragment shader for [`Renderer::render_coverage_mask`].
 #[cfg(feature = "wgpu")]
-struct BlitPipeline {
-    bind_layout: wgpu::BindGroupLayout,
-    pipeline: wgpu::RenderPipeline,
-}
+const MASK_FS: &str = r#"
+    @fragment
+    fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+        let alpha = textureLoad(fine_output, vec2<i32>(pos.xy), 0).a;
+        return vec4(alpha, 0.0, 0.0, 1.0);
+    }
+"#;
 
+/// Fragment shader for [`Renderer::render_to_texture_accumulated`].
 #[cfg(feature = "wgpu")]
-impl BlitPipeline {
-    fn new(device: &Device, format: TextureFormat) -> Self {
-        const SHADERS: &str = r#"
-            @vertex
-            fn vs_main(@builtin(vertex_index) ix: u32) -> @builtin(position) vec4<f32> {
-                // Generate a full screen quad in NDCs
-                var vertex = vec2(-1.0, 1.0);
-                switch ix {
-                    case 1u: {
-                        vertex = vec2(-1.0, -1.0);
-                    }
-                    case 2u, 4u: {
-                        vertex = vec2(1.0, -1.0);
-                    }
-                    case 5u: {
-                        vertex = vec2(1.0, 1.0);
+const ACCUMULATE_FS: &str = r#"
+    @group(0) @binding(1)
+    var<uniform> weight: f32;
+
+    @fragment
+    fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+        let rgba_sep = textureLoad(fine_output, vec2<i32>(pos.xy), 0);
+        return vec4(rgba_sep.rgb * rgba_sep.a, rgba_sep.a) * weight;
+    }
+"#;
+
+/// Fragment shader for [`Renderer::render_to_texture_scaled`].
+#[cfg(feature = "wgpu")]
+const UPSCALE_FS: &str = r#"
+    struct Upscale {
+        filter_kind: u32,
+        target_size: vec2<u32>,
+    }
+
+    @group(0) @binding(1)
+    var<uniform> upscale: Upscale;
+
+    fn load_premul(xy: vec2<i32>) -> vec4<f32> {
+        let size = vec2<i32>(textureDimensions(fine_output));
+        let rgba = textureLoad(fine_output, clamp(xy, vec2(0), size - 1), 0);
+        return vec4(rgba.rgb * rgba.a, rgba.a);
+    }
+
+    // Catmull-Rom weights of the samples at -1, 0, 1 and 2, for a position `t` between 0
+    // and 1.
+    fn cubic_weights(t: f32) -> vec4<f32> {
+        let t2 = t * t;
+        let t3 = t2 * t;
+        return vec4(
+            -0.5 * t3 + t2 - 0.5 * t,
+            1.5 * t3 - 2.5 * t2 + 1.0,
+            -1.5 * t3 + 2.0 * t2 + 0.5 * t,
+            0.5 * t3 - 0.5 * t2,
+        );
+    }
+
+    @fragment
+    fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+        let scale = vec2<f32>(textureDimensions(fine_output)) / vec2<f32>(upscale.target_size);
+        // The center of the pixel in the rendered texture, relative to the centers of its
+        // pixels.
+        let xy = pos.xy * scale - 0.5;
+        let base = vec2<i32>(floor(xy));
+        let t = fract(xy);
+        var premul: vec4<f32>;
+        switch upscale.filter_kind {
+            // Nearest
+            case 0u: {
+                premul = load_premul(vec2<i32>(floor(pos.xy * scale)));
+            }
+            // Bilinear
+            case 1u: {
+                let top = mix(load_premul(base), load_premul(base + vec2(1, 0)), t.x);
+                let bottom = mix(
+                    load_premul(base + vec2(0, 1)),
+                    load_premul(base + vec2(1, 1)),
+                    t.x,
+                );
+                premul = mix(top, bottom, t.y);
+            }
+            // Bicubic
+            default: {
+                var wx = cubic_weights(t.x);
+                var wy = cubic_weights(t.y);
+                premul = vec4(0.0);
+                for (var j = 0; j < 4; j += 1) {
+                    var row = vec4(0.0);
+                    for (var i = 0; i < 4; i += 1) {
+                        row += wx[i] * load_premul(base + vec2(i - 1, j - 1));
                     }
-                    default: {}
+                    premul += wy[j] * row;
                 }
-                return vec4(vertex, 0.0, 1.0);
+                // The negative lobes of the kernel overshoot at edges.
+                premul = clamp(premul, vec4(0.0), vec4(1.0));
+                premul = vec4(min(premul.rgb, vec3(premul.a)), premul.a);
             }
+        }
+        let rgb = select(vec3(0.0), premul.rgb / premul.a, premul.a > 0.0);
+        return vec4(rgb, premul.a);
+    }
+"#;
+
+/// Fragment shader for [`Renderer::render_depth`].
+#[cfg(feature = "wgpu")]
+const DEPTH_FS: &str = r#"
+    @fragment
+    fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+        // Depths are drawn as opaque gray levels, so any channel holds the depth.
+        let depth = textureLoad(fine_output, vec2<i32>(pos.xy), 0).r;
+        return vec4(depth, depth, depth, 1.0);
+    }
+"#;
+
+/// Fragment shader for [`Renderer::render_to_texture_dithered`].
+#[cfg(feature = "wgpu")]
+const DITHER_FS: &str = r#"
+    // Rank of the pixel in a 4x4 Bayer matrix, built by interleaving the bits of x ^ y and
+    // y in reverse order.
+    fn bayer_rank(xy: vec2<u32>) -> u32 {
+        let v = xy.x ^ xy.y;
+        return ((v & 1u) << 3u) | ((xy.y & 1u) << 2u) | (v & 2u) | ((xy.y & 2u) >> 1u);
+    }
+
+    // Offset of the matrix from the origin of the target, chosen by the seed.
+    @group(0) @binding(1)
+    var<uniform> offset: vec4<u32>;
+
+    @fragment
+    fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+        let xy = vec2<u32>(pos.xy);
+        let rgba = textureLoad(fine_output, vec2<i32>(xy), 0);
+        let luminance = dot(rgba.rgb, vec3(0.2126, 0.7152, 0.0722));
+        let threshold = (f32(bayer_rank(xy + offset.xy)) + 0.5) / 16.0;
+        let value = select(0.0, 1.0, luminance > threshold);
+        return vec4(vec3(value), 1.0);
+    }
+"#;
+
+/// A full screen pass which converts the output of fine rasterization into the format of
+/// another texture, using a fragment shader which reads from `fine_output`.
+#[cfg(feature = "wgpu")]
+struct ResolvePipeline {
+    format: TextureFormat,
+    /// Blending of the output with the existing contents of the target, which are cleared
+    /// if this is `None`.
+    blend: Option<wgpu::BlendState>,
+    bind_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    /// The uniform buffer at binding 1, if the pipeline was created with one.
+    uniforms: Option<wgpu::Buffer>,
+}
+
+#[cfg(feature = "wgpu")]
+impl ResolvePipeline {
+    fn new(
+        device: &Device,
+        vertex: &wgpu::ShaderModule,
+        format: TextureFormat,
+        fragment: &str,
+    ) -> Self {
+        Self::with_uniforms(device, vertex, format, fragment, false)
+    }
+
+    /// Like [`Self::new`], with a uniform buffer at binding 1 if `uniforms` is set, which the
+    /// fragment shader must declare.
+    fn with_uniforms(
+        device: &Device,
+        vertex: &wgpu::ShaderModule,
+        format: TextureFormat,
+        fragment: &str,
+        uniforms: bool,
+    ) -> Self {
+        Self::with_blend(device, vertex, format, fragment, uniforms, None)
+    }
 
+    /// Like [`Self::with_uniforms`], blending the output into the target with `blend`
+    /// rather than replacing it, if set.
+    fn with_blend(
+        device: &Device,
+        vertex: &wgpu::ShaderModule,
+        format: TextureFormat,
+        fragment: &str,
+        uniforms: bool,
+        blend: Option<wgpu::BlendState>,
+    ) -> Self {
+        const BINDINGS: &str = r#"
             @group(0) @binding(0)
             var fine_output: texture_2d<f32>;
-
-            @fragment
-            fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
-                let rgba_sep = textureLoad(fine_output, vec2<i32>(pos.xy), 0);
-                return vec4(rgba_sep.rgb * rgba_sep.a, rgba_sep.a);
-            }
         "#;
 
+        let source = [BINDINGS, fragment].concat();
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("blit shaders"),
-            source: wgpu::ShaderSource::Wgsl(SHADERS.into()),
+            label: Some("resolve shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
         });
-        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: None,
-            entries: &[wgpu::BindGroupLayoutEntry {
+        let entries = [
+            wgpu::BindGroupLayoutEntry {
                 visibility: wgpu::ShaderStages::FRAGMENT,
                 binding: 0,
                 ty: wgpu::BindingType::Texture {
@@ -513,7 +2937,21 @@ impl BlitPipeline {
                     multisampled: false,
                 },
                 count: None,
-            }],
+            },
+            wgpu::BindGroupLayoutEntry {
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                binding: 1,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ];
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &entries[..1 + usize::from(uniforms)],
         });
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: None,
@@ -524,7 +2962,7 @@ impl BlitPipeline {
             label: None,
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: vertex,
                 entry_point: "vs_main",
                 buffers: &[],
             },
@@ -533,7 +2971,7 @@ impl BlitPipeline {
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
                     format,
-                    blend: None,
+                    blend,
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
@@ -554,9 +2992,284 @@ impl BlitPipeline {
             },
             multiview: None,
         });
+        let uniforms = uniforms.then(|| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("resolve uniforms"),
+                size: 16,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+        Self {
+            format,
+            blend,
+            bind_layout,
+            pipeline,
+            uniforms,
+        }
+    }
+
+    /// Writes the 16 bytes of `data` into the uniform buffer of the pipeline, which must have
+    /// been created with one.
+    fn write_uniforms(&self, queue: &Queue, data: &[u8]) {
+        queue.write_buffer(self.uniforms.as_ref().unwrap(), 0, data);
+    }
+
+    /// Runs the pass over the whole of `texture`, reading from `source`, and from the uniform
+    /// buffer if the pipeline was created with one.
+    fn resolve(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        source: &TextureView,
+        texture: &wgpu::Texture,
+    ) {
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let mut entries = vec![wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(source),
+            }];
+            if let Some(buffer) = &self.uniforms {
+                entries.push(wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: buffer.as_entire_binding(),
+                });
+            }
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &self.bind_layout,
+                entries: &entries,
+            });
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &texture_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: match self.blend {
+                            Some(_) => wgpu::LoadOp::Load,
+                            None => wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        },
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..6, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// Compute shader for one direction of the separable blur in [`RenderParams::final_blur`].
+#[cfg(feature = "wgpu")]
+const BLUR_CS: &str = r#"
+    struct BlurParams {
+        direction: vec2<i32>,
+        radius: i32,
+        std_dev: f32,
+    }
+
+    @group(0) @binding(0)
+    var<uniform> params: BlurParams;
+
+    @group(0) @binding(1)
+    var input: texture_2d<f32>;
+
+    @group(0) @binding(2)
+    var output: texture_storage_2d<rgba8unorm, write>;
+
+    @compute @workgroup_size(8, 8)
+    fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+        let size = vec2<i32>(textureDimensions(input));
+        let xy = vec2<i32>(global_id.xy);
+        if any(xy >= size) {
+            return;
+        }
+        // The frame holds separate alpha, so blur the premultiplied colors to keep transparent
+        // pixels from bleeding their color into their neighbours.
+        var sum = vec4(0.0);
+        var total_weight = 0.0;
+        for (var i = -params.radius; i <= params.radius; i += 1) {
+            let weight = exp(-f32(i * i) / (2.0 * params.std_dev * params.std_dev));
+            let sample_xy = clamp(xy + i * params.direction, vec2(0), size - 1);
+            let rgba = textureLoad(input, sample_xy, 0);
+            sum += weight * vec4(rgba.rgb * rgba.a, rgba.a);
+            total_weight += weight;
+        }
+        let premul = sum / total_weight;
+        let rgb = select(vec3(0.0), premul.rgb / premul.a, premul.a > 0.0);
+        textureStore(output, xy, vec4(rgb, premul.a));
+    }
+"#;
+
+/// The passes of [`RenderParams::final_blur`], along with the intermediate textures the
+/// frame is rendered into and blurred horizontally into.
+#[cfg(feature = "wgpu")]
+struct BlurPipeline {
+    bind_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    /// The uniforms of the horizontal and vertical passes, which are submitted together.
+    params: [wgpu::Buffer; 2],
+    source: TargetTexture,
+    horizontal: TargetTexture,
+}
+
+#[cfg(feature = "wgpu")]
+impl BlurPipeline {
+    fn new(device: &Device, width: u32, height: u32) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("blur shader"),
+            source: wgpu::ShaderSource::Wgsl(BLUR_CS.into()),
+        });
+        let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    binding: 0,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    binding: 1,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    binding: 2,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: TextureFormat::Rgba8Unorm,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("blur"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "main",
+        });
+        let params = [(); 2].map(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("blur params"),
+                size: 16,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
         Self {
             bind_layout,
             pipeline,
+            params,
+            source: TargetTexture::new(device, width, height),
+            horizontal: TargetTexture::new(device, width, height),
+        }
+    }
+
+    /// Reallocates the intermediate textures if they don't match the size of the frame.
+    fn resize(&mut self, device: &Device, width: u32, height: u32) {
+        if self.source.width != width || self.source.height != height {
+            self.source = TargetTexture::new(device, width, height);
+            self.horizontal = TargetTexture::new(device, width, height);
+        }
+    }
+
+    /// The number of pixels on each side of a pixel that are blurred into it.
+    fn radius(std_dev: f32) -> u32 {
+        // Weights past three standard deviations are too small to affect 8 bit output.
+        (3. * std_dev).ceil() as u32
+    }
+
+    /// Blurs the contents of `self.source` into `texture`.
+    fn blur(&self, device: &Device, queue: &Queue, texture: &TextureView, std_dev: f32) {
+        let radius = Self::radius(std_dev) as i32;
+        let passes = [
+            ([1, 0], &*self.source.view, &*self.horizontal.view),
+            ([0, 1], &self.horizontal.view, texture),
+        ];
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        for ((direction, input, output), params) in passes.into_iter().zip(&self.params) {
+            let uniforms = [direction[0], direction[1], radius, std_dev.to_bits() as i32];
+            queue.write_buffer(params, 0, bytemuck::cast_slice(&uniforms));
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &self.bind_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: params.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(input),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(output),
+                    },
+                ],
+            });
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("blur"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(
+                self.source.width.div_ceil(8),
+                self.source.height.div_ceil(8),
+                1,
+            );
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+/// Returns the horizontal position of the sample in each of the `N` rows of a pixel, given
+/// the positions of `N` samples with one in each row.
+#[cfg(feature = "wgpu")]
+fn sample_columns<const N: usize>(positions: &[kurbo::Point]) -> Result<[f32; N]> {
+    if positions.len() != N {
+        return Err(format!("expected {N} sample positions, got {}", positions.len()).into());
+    }
+    let mut columns = [f32::NAN; N];
+    for position in positions {
+        let in_pixel = |value: f64| (0.0..1.0).contains(&value);
+        if !in_pixel(position.x) || !in_pixel(position.y) {
+            return Err(format!("sample position {position:?} is outside of the pixel").into());
+        }
+        let row = (position.y * N as f64) as usize;
+        if !columns[row].is_nan() {
+            return Err(format!("more than one sample position in row {row}").into());
         }
+        columns[row] = position.x as f32;
     }
+    Ok(columns)
 }
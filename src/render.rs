@@ -3,15 +3,24 @@
 use crate::{
     engine::{BufProxy, ImageFormat, ImageProxy, Recording, ResourceProxy},
     shaders::FullShaders,
-    AaConfig, RenderParams, Scene,
+    AaConfig, BinSize, RenderParams, Scene,
+};
+use vello_encoding::{
+    make_mask_lut, make_mask_lut_16, make_mask_lut_16_for_positions, make_mask_lut_for_positions,
+    BufferSize, BumpAllocators, Encoding, RenderConfig, Transform, WorkgroupSize,
 };
-use vello_encoding::{make_mask_lut, make_mask_lut_16, Encoding, WorkgroupSize};
 
 /// State for a render in progress.
 pub struct Render {
     fine_wg_count: Option<WorkgroupSize>,
     fine_resources: Option<FineResources>,
     mask_buf: Option<ResourceProxy>,
+    min_bump_sizes: BumpAllocators,
+    config: Option<RenderConfig>,
+    overdraw_buf: Option<BufProxy>,
+    count_overdraw: bool,
+    draw_bbox_buf: Option<BufProxy>,
+    download_draw_bboxes: bool,
 }
 
 /// Resources produced by pipeline, needed for fine rasterization.
@@ -26,6 +35,7 @@ struct FineResources {
     gradient_image: ResourceProxy,
     info_bin_data_buf: ResourceProxy,
     image_atlas: ResourceProxy,
+    transfer_lut_buf: ResourceProxy,
 
     out_image: ImageProxy,
 }
@@ -66,9 +76,62 @@ impl Render {
             fine_wg_count: None,
             fine_resources: None,
             mask_buf: None,
+            min_bump_sizes: BumpAllocators::default(),
+            config: None,
+            overdraw_buf: None,
+            count_overdraw: false,
+            draw_bbox_buf: None,
+            download_draw_bboxes: false,
         }
     }
 
+    /// Makes the next coarse phase count the draw objects in each tile, and download the
+    /// counts into [`Self::overdraw_buf`].
+    pub fn count_overdraw(&mut self) {
+        self.count_overdraw = true;
+    }
+
+    /// Makes the next coarse phase download the bounding box of each draw object, after
+    /// clipping, into [`Self::draw_bbox_buf`].
+    pub fn download_draw_bboxes(&mut self) {
+        self.download_draw_bboxes = true;
+    }
+
+    /// Set the minimum lengths of the bump allocated buffers used by the next coarse phase.
+    pub fn reserve_bump_buffers(&mut self, min: BumpAllocators) {
+        self.min_bump_sizes = min;
+    }
+
+    /// Returns the bump buffer lengths needed to retry the prepared coarse phase, given the
+    /// counters read back from it, or `None` if it didn't overflow.
+    ///
+    /// Afterwards, [`Self::max_bump_buffer_size`] reports the sizes needed by the retry.
+    pub fn grow_bump_buffers(&mut self, bump: &BumpAllocators) -> Option<BumpAllocators> {
+        let config = self.config.as_mut().unwrap();
+        let next = bump.grow(config)?;
+        config.reserve_bump_buffers(&next);
+        Some(next)
+    }
+
+    /// Returns the size in bytes of the largest bump allocated buffer of the coarse phase.
+    pub fn max_bump_buffer_size(&self) -> u64 {
+        fn bytes<T>(size: BufferSize<T>) -> u64 {
+            u64::from(size.len()) * std::mem::size_of::<T>() as u64
+        }
+        let sizes = &self.config.as_ref().unwrap().buffer_sizes;
+        [
+            bytes(sizes.bin_data),
+            bytes(sizes.tiles),
+            bytes(sizes.lines),
+            bytes(sizes.seg_counts),
+            bytes(sizes.segments),
+            bytes(sizes.ptcl),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
     /// Prepare a recording for the coarse rasterization phase.
     ///
     /// The `robust` parameter controls whether we're preparing for readback
@@ -80,8 +143,18 @@ impl Render {
         params: &RenderParams,
         robust: bool,
     ) -> Recording {
-        use vello_encoding::{RenderConfig, Resolver};
+        use vello_encoding::Resolver;
 
+        let flipped;
+        let encoding = match params.flip_transform() {
+            Some(flip) => {
+                let mut encoding_flipped = Encoding::new();
+                encoding_flipped.append(encoding, &Some(Transform::from_kurbo(&flip)));
+                flipped = encoding_flipped;
+                &flipped
+            }
+            None => encoding,
+        };
         let mut recording = Recording::default();
         let mut resolver = Resolver::new();
         let mut packed = vec![];
@@ -113,8 +186,37 @@ impl Render {
             );
         }
 
-        let cpu_config =
-            RenderConfig::new(&layout, params.width, params.height, &params.base_color);
+        let mut cpu_config = RenderConfig::new(
+            &layout,
+            params.width,
+            params.height,
+            &params.base_color,
+            shaders.bin_width,
+        );
+        // A bin capacity below the size of a partition sizes the binning buffer from its chunks,
+        // while the default capacity keeps the default size. The CPU stages can't report an
+        // overflow of the buffer, so they always keep the default size.
+        if shaders.bin_capacity < BinSize::TILES && !shaders.pathtag_is_cpu {
+            cpu_config.set_bin_capacity(shaders.bin_capacity);
+        } else {
+            cpu_config.gpu.bin_capacity = shaders.bin_capacity;
+        }
+        cpu_config.gpu.glyph_gamma = params.glyph_gamma.unwrap_or(1.0);
+        cpu_config.gpu.conservative_coverage = params.conservative_coverage as u32;
+        cpu_config.gpu.max_curve_segments = params
+            .max_subdivision_depth
+            .map_or(0, |depth| 1 << depth.min(31));
+        if let Some(grain) = params.grain {
+            cpu_config.gpu.grain_intensity = grain.intensity.max(0.0);
+        }
+        cpu_config.gpu.seed = params.seed;
+        cpu_config.gpu.horizontal_epsilon = params.horizontal_epsilon.max(0.0);
+        cpu_config.gpu.cull_offscreen_curves = params.clip_paths_to_viewport as u32;
+        cpu_config.gpu.opaque = (params.base_color.a == 255 && encoding.is_opaque()) as u32;
+        cpu_config.reserve_bump_buffers(&self.min_bump_sizes);
+        if let Some(hint) = &params.complexity_hint {
+            cpu_config.reserve_bump_buffers(&hint.bump_sizes());
+        }
         let buffer_sizes = &cpu_config.buffer_sizes;
         let wg_counts = &cpu_config.workgroup_counts;
 
@@ -319,6 +421,11 @@ impl Render {
                 tile_buf,
             ],
         );
+        if self.download_draw_bboxes {
+            let buf = *draw_bbox_buf.as_buf().unwrap();
+            recording.download(buf);
+            self.draw_bbox_buf = Some(buf);
+        }
         recording.free_resource(draw_bbox_buf);
         recording.free_resource(tagmonoid_buf);
         let indirect_count_buf = BufProxy::new(
@@ -345,6 +452,26 @@ impl Render {
             wg_counts.backdrop,
             [config_buf, path_buf, tile_buf],
         );
+        let n_tiles = cpu_config.gpu.width_in_tiles * cpu_config.gpu.height_in_tiles;
+        let n_mask_words = n_tiles.div_ceil(32).max(1) as usize;
+        let dirty_tiles = match &params.dirty_tiles {
+            Some(mask) => {
+                let mut mask = mask.clone();
+                mask.resize(n_mask_words, 0);
+                mask
+            }
+            None => vec![!0; n_mask_words],
+        };
+        let dirty_tiles_buf = ResourceProxy::Buf(
+            recording.upload("dirty_tiles_buf", bytemuck::cast_slice(&dirty_tiles)),
+        );
+        // Coarse only writes the counts if the buffer holds one for each tile.
+        let overdraw_len = if self.count_overdraw {
+            n_tiles.max(1)
+        } else {
+            1
+        };
+        let overdraw_buf = BufProxy::new(u64::from(overdraw_len) * 4, "overdraw_buf");
         recording.dispatch(
             shaders.coarse,
             wg_counts.coarse,
@@ -358,8 +485,16 @@ impl Render {
                 tile_buf,
                 bump_buf,
                 ptcl_buf,
+                dirty_tiles_buf,
+                overdraw_buf.into(),
             ],
         );
+        recording.free_resource(dirty_tiles_buf);
+        if self.count_overdraw {
+            recording.download(overdraw_buf);
+            self.overdraw_buf = Some(overdraw_buf);
+        }
+        recording.free_buf(overdraw_buf);
         recording.dispatch(
             shaders.path_tiling_setup,
             wg_counts.path_tiling_setup,
@@ -386,6 +521,15 @@ impl Render {
         recording.free_resource(bin_header_buf);
         recording.free_resource(path_buf);
         let out_image = ImageProxy::new(params.width, params.height, ImageFormat::Rgba8);
+        // Fine rasterization skips the transfer function if the table has fewer than two
+        // entries.
+        let transfer_lut: &[f32] = match &params.output_transfer {
+            Some(lut) => lut.table(),
+            None => &[0.0],
+        };
+        let transfer_lut_buf = ResourceProxy::Buf(
+            recording.upload("transfer_lut_buf", bytemuck::cast_slice(transfer_lut)),
+        );
         self.fine_wg_count = Some(wg_counts.fine);
         self.fine_resources = Some(FineResources {
             aa_config: params.antialiasing_method,
@@ -397,12 +541,14 @@ impl Render {
             gradient_image,
             info_bin_data_buf,
             image_atlas: ResourceProxy::Image(image_atlas),
+            transfer_lut_buf,
             out_image,
         });
         if robust {
             recording.download(*bump_buf.as_buf().unwrap());
         }
         recording.free_resource(bump_buf);
+        self.config = Some(cpu_config);
         recording
     }
 
@@ -425,14 +571,21 @@ impl Render {
                         ResourceProxy::Image(fine.out_image),
                         fine.gradient_image,
                         fine.image_atlas,
+                        fine.transfer_lut_buf,
                     ],
                 );
             }
             _ => {
                 if self.mask_buf.is_none() {
                     let mask_lut = match fine.aa_config {
-                        AaConfig::Msaa16 => make_mask_lut_16(),
-                        AaConfig::Msaa8 => make_mask_lut(),
+                        AaConfig::Msaa16 => match &shaders.msaa16_positions {
+                            Some(positions) => make_mask_lut_16_for_positions(positions),
+                            None => make_mask_lut_16(),
+                        },
+                        AaConfig::Msaa8 => match &shaders.msaa8_positions {
+                            Some(positions) => make_mask_lut_for_positions(positions),
+                            None => make_mask_lut(),
+                        },
                         _ => unreachable!(),
                     };
                     let buf = recording.upload("mask lut", mask_lut);
@@ -458,11 +611,24 @@ impl Render {
                         ResourceProxy::Image(fine.out_image),
                         fine.gradient_image,
                         fine.image_atlas,
+                        fine.transfer_lut_buf,
                         self.mask_buf.unwrap(),
                     ],
                 );
             }
         }
+        self.free_fine_resources(fine, recording);
+    }
+
+    /// Release the resources of the coarse phase without running fine rasterization, such as
+    /// when the coarse phase overflowed and is going to be retried.
+    pub fn discard_fine(&mut self, recording: &mut Recording) {
+        self.fine_wg_count = None;
+        let fine = self.fine_resources.take().unwrap();
+        self.free_fine_resources(fine, recording);
+    }
+
+    fn free_fine_resources(&mut self, fine: FineResources, recording: &mut Recording) {
         recording.free_resource(fine.config_buf);
         recording.free_resource(fine.tile_buf);
         recording.free_resource(fine.segments_buf);
@@ -470,6 +636,7 @@ impl Render {
         recording.free_resource(fine.gradient_image);
         recording.free_resource(fine.image_atlas);
         recording.free_resource(fine.info_bin_data_buf);
+        recording.free_resource(fine.transfer_lut_buf);
         // TODO: make mask buf persistent
         if let Some(mask_buf) = self.mask_buf.take() {
             recording.free_resource(mask_buf);
@@ -484,6 +651,19 @@ impl Render {
         self.fine_resources.as_ref().unwrap().out_image
     }
 
+    /// The per-tile draw counts downloaded by the coarse phase, if it was asked to
+    /// [count overdraw](Self::count_overdraw).
+    pub fn overdraw_buf(&self) -> Option<BufProxy> {
+        self.overdraw_buf
+    }
+
+    /// The draw object bounding boxes downloaded by the coarse phase, if it was asked to
+    /// [download them](Self::download_draw_bboxes). Each is an `[x0, y0, x1, y1]` array of
+    /// `f32` in pixels, which is empty if the object is clipped out.
+    pub fn draw_bbox_buf(&self) -> Option<BufProxy> {
+        self.draw_bbox_buf
+    }
+
     pub fn bump_buf(&self) -> BufProxy {
         *self
             .fine_resources
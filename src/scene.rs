@@ -14,15 +14,249 @@
 //
 // Also licensed under MIT license, at your choice.
 
-use peniko::kurbo::{Affine, Rect, Shape, Stroke};
-use peniko::{BlendMode, BrushRef, Color, Fill, Font, Image, StyleRef};
-use skrifa::instance::NormalizedCoord;
-use vello_encoding::{Encoding, Glyph, GlyphRun, Patch, Transform};
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+use peniko::kurbo::{
+    Affine, BezPath, Cap, Line, PathEl, Point, Rect, RoundedRectRadii, Shape, Stroke, Vec2,
+};
+use peniko::{
+    BlendMode, BrushRef, Color, ColorStop, ColorStops, ColorStopsSource, Extend, Fill, Font,
+    Gradient, Image, Mix, Style, StyleRef,
+};
+use skrifa::instance::{LocationRef, NormalizedCoord, Size};
+use skrifa::metrics::Decoration;
+use skrifa::raw::TableProvider;
+use skrifa::{GlyphId, MetadataProvider};
+use vello_encoding::{
+    DrawBeginClip, DrawBilinearGradient, Encoding, Glyph, GlyphRun, ImageSampler, Patch,
+    RenderConfig, Resolver, Transform,
+};
+#[cfg(feature = "wgpu")]
+use vello_encoding::{ImageAddressMode, ImageFilter};
+
+use crate::{BinSize, RenderParams, RenderedImage, Result};
 
 /// Encoded definition of a scene and associated resources.
 #[derive(Clone, Default)]
 pub struct Scene {
-    encoding: Encoding,
+    encoding: ZOrderedEncoding,
+    snap_fills_to_pixels: bool,
+    arc_tolerance: Option<f64>,
+    transform_origin: Option<Point>,
+    depth: f32,
+    /// Clips pushed by [`Scene::push_rect_clip`] which haven't been popped yet.
+    rect_clips: Vec<RectClip>,
+}
+
+/// The content of a scene, split up by z index.
+///
+/// This derefs to the encoding of the content at the current z index. The content at all z
+/// indexes is merged into one encoding in drawing order when it's first needed, and kept
+/// until the content is changed through [`DerefMut`] or one of the methods below.
+#[derive(Clone, Default)]
+struct ZOrderedEncoding {
+    current: Encoding,
+    z_index: i32,
+    /// Content drawn at z indexes other than `z_index`, sorted by z index.
+    fragments: Vec<(i32, Encoding)>,
+    merged: OnceLock<Encoding>,
+}
+
+impl ZOrderedEncoding {
+    fn new(current: Encoding) -> Self {
+        Self {
+            current,
+            ..Default::default()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.current.reset();
+        self.z_index = 0;
+        self.fragments.clear();
+        self.merged.take();
+    }
+
+    /// Returns all of the content, drawn in increasing z index order.
+    fn merged(&self) -> &Encoding {
+        if self.fragments.is_empty() {
+            return &self.current;
+        }
+        self.merged.get_or_init(|| {
+            let mut encoding = Encoding::new();
+            let split = self.fragments.partition_point(|(z, _)| *z < self.z_index);
+            let (below, above) = self.fragments.split_at(split);
+            for (_, fragment) in below {
+                encoding.append(fragment, &None);
+            }
+            encoding.append(&self.current, &None);
+            for (_, fragment) in above {
+                encoding.append(fragment, &None);
+            }
+            encoding.path_snapping = self.current.path_snapping;
+            encoding.aliased = self.current.aliased;
+            encoding.clip_rect = self.current.clip_rect;
+            encoding
+        })
+    }
+
+    /// Makes `current` hold the content at `z_index`, returning the encoding it held before.
+    fn switch_to(&mut self, z_index: i32) -> Encoding {
+        self.merged.take();
+        let index = self.fragments.partition_point(|(z, _)| *z < z_index);
+        let encoding = match self.fragments.get(index) {
+            Some((z, _)) if *z == z_index => self.fragments.remove(index).1,
+            _ => Encoding::new(),
+        };
+        self.z_index = z_index;
+        std::mem::replace(&mut self.current, encoding)
+    }
+
+    /// Keeps the content at `z_index` for merging, unless there is none.
+    fn insert_fragment(&mut self, z_index: i32, encoding: Encoding) {
+        if !encoding.is_empty() {
+            let index = self.fragments.partition_point(|(z, _)| *z < z_index);
+            self.fragments.insert(index, (z_index, encoding));
+        }
+    }
+
+    /// Returns the encoding of the content at `z_index`, if there is any.
+    fn at_z_index_mut(&mut self, z_index: i32) -> Option<&mut Encoding> {
+        self.merged.take();
+        if z_index == self.z_index {
+            return Some(&mut self.current);
+        }
+        let ix = self
+            .fragments
+            .binary_search_by_key(&z_index, |(z, _)| *z)
+            .ok()?;
+        Some(&mut self.fragments[ix].1)
+    }
+}
+
+impl Deref for ZOrderedEncoding {
+    type Target = Encoding;
+
+    fn deref(&self) -> &Encoding {
+        &self.current
+    }
+}
+
+impl DerefMut for ZOrderedEncoding {
+    fn deref_mut(&mut self) -> &mut Encoding {
+        self.merged.take();
+        &mut self.current
+    }
+}
+
+/// How a clip pushed by [`Scene::push_rect_clip`] is undone.
+#[derive(Copy, Clone, Debug)]
+enum RectClip {
+    /// Restores the clip rect of the encoding from before the push.
+    Rect(Option<[f32; 4]>),
+    /// Pops the layer the clip was pushed as, as its transform isn't axis-aligned.
+    Layer,
+}
+
+/// Coordinate space of the clip shape of a layer, as passed to [`Scene::push_layer_in`].
+///
+/// This only matters once the scene holding the layer is appended to another with a
+/// transform, by [`Scene::append`]. Either way, the transform passed along with the clip
+/// shape is applied to it first.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ClipSpace {
+    /// The clip is in the same space as the content of the scene, so the transform of an
+    /// append applies to both and the clip moves along with the content.
+    #[default]
+    Scene,
+    /// The clip is in the space of the render target. Transforms of appends are ignored,
+    /// so the clip stays in place while the content inside of it moves.
+    Target,
+}
+
+/// Transparency group behavior of a layer, as passed to [`Scene::push_layer_with_flags`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LayerFlags {
+    /// Whether the content of the layer is drawn onto a transparent backdrop, rather than
+    /// onto a copy of the content underneath the layer. This is the default.
+    pub isolated: bool,
+    /// Whether each draw object in the layer is composited with the initial backdrop of the
+    /// layer, knocking out the objects drawn before it in the layer where they overlap.
+    pub knockout: bool,
+}
+
+impl Default for LayerFlags {
+    fn default() -> Self {
+        Self {
+            isolated: true,
+            knockout: false,
+        }
+    }
+}
+
+/// A shadow cast onto the inside of a fill from its edges, as passed to
+/// [`Scene::fill_with_inner_shadow`], for inset elements such as pressed buttons.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InnerShadow {
+    /// The color of the shadow at the edges of the fill.
+    pub color: Color,
+    /// How far the shadow is moved from the edges of the fill, in the units of the shape. A
+    /// positive y offset darkens the top edge of the fill more than the bottom.
+    pub offset: Vec2,
+    /// The distance over which the shadow fades out towards the inside of the fill, in the
+    /// units of the shape. Zero gives a hard edged shadow.
+    pub blur_radius: f64,
+}
+
+/// Handle to the transform of a layer's clip shape, returned by
+/// [`Scene::push_layer_tracked`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LayerTransform {
+    z_index: i32,
+    /// Index of the transform in the encoding holding the layer.
+    index: usize,
+    /// Translation to the transform origin when the layer was pushed.
+    offset: Vec2,
+}
+
+/// Sizes of the encoded streams of a [`Scene`], used to reserve capacity up front.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SceneStats {
+    /// Number of path tags.
+    pub path_tags: usize,
+    /// Size of the path data in bytes.
+    pub path_data: usize,
+    /// Number of draw tags.
+    pub draw_tags: usize,
+    /// Size of the draw data in bytes.
+    pub draw_data: usize,
+    /// Number of transforms.
+    pub transforms: usize,
+    /// Number of styles.
+    pub styles: usize,
+    /// Number of rectangles referenced by draws clipped with [`Scene::push_rect_clip`].
+    pub clip_rects: usize,
+    /// Whether every draw object is opaque and drawn without layers, so the renderer can
+    /// write colors directly rather than blending them. See [`Encoding::is_opaque`].
+    pub opaque: bool,
+}
+
+impl SceneStats {
+    /// Returns the total size in bytes of the encoded streams.
+    ///
+    /// This doesn't include resources which are only resolved when the scene is rendered,
+    /// such as images, gradient ramps and glyph outlines.
+    pub fn size_in_bytes(&self) -> usize {
+        use std::mem::size_of;
+        self.path_tags * size_of::<vello_encoding::PathTag>()
+            + self.path_data
+            + self.draw_tags * size_of::<vello_encoding::DrawTag>()
+            + self.draw_data
+            + self.transforms * size_of::<Transform>()
+            + self.styles * size_of::<vello_encoding::Style>()
+            + self.clip_rects * size_of::<[f32; 4]>()
+    }
 }
 
 impl Scene {
@@ -31,37 +265,561 @@ impl Scene {
         Self::default()
     }
 
+    /// Creates a new scene with room for at least as much content as described by `hint`
+    /// before any of its buffers need to grow.
+    ///
+    /// The hint is usually the [`stats`](Self::stats) of a similar, previously built scene.
+    pub fn with_capacity(hint: SceneStats) -> Self {
+        let mut scene = Self::new();
+        let encoding = &mut scene.encoding;
+        encoding.path_tags.reserve(hint.path_tags);
+        encoding.path_data.reserve(hint.path_data);
+        encoding.draw_tags.reserve(hint.draw_tags);
+        encoding.draw_data.reserve(hint.draw_data);
+        encoding.transforms.reserve(hint.transforms);
+        encoding.styles.reserve(hint.styles);
+        encoding.clip_rects.reserve(hint.clip_rects);
+        scene
+    }
+
+    /// Creates a new scene containing each of the fragments in turn.
+    ///
+    /// Fragments are independent scenes, so they can be built in parallel on worker threads
+    /// and then merged here. Later fragments are drawn over earlier ones, exactly as if their
+    /// content had been encoded into a single scene in the same order.
+    pub fn from_fragments(fragments: impl IntoIterator<Item = Scene>) -> Self {
+        let fragments: Vec<Scene> = fragments.into_iter().collect();
+        let mut hint = SceneStats::default();
+        for stats in fragments.iter().map(Scene::stats) {
+            hint.path_tags += stats.path_tags;
+            hint.path_data += stats.path_data;
+            hint.draw_tags += stats.draw_tags;
+            hint.draw_data += stats.draw_data;
+            hint.transforms += stats.transforms;
+            hint.styles += stats.styles;
+            hint.clip_rects += stats.clip_rects;
+        }
+        let mut scene = Self::with_capacity(hint);
+        for fragment in &fragments {
+            scene.append(fragment, None);
+        }
+        scene
+    }
+
+    /// Returns the sizes of the buffers holding the content of the scene.
+    pub fn stats(&self) -> SceneStats {
+        let encoding = self.encoding();
+        SceneStats {
+            path_tags: encoding.path_tags.len(),
+            path_data: encoding.path_data.len(),
+            draw_tags: encoding.draw_tags.len(),
+            draw_data: encoding.draw_data.len(),
+            transforms: encoding.transforms.len(),
+            styles: encoding.styles.len(),
+            clip_rects: encoding.clip_rects.len(),
+            opaque: encoding.is_opaque(),
+        }
+    }
+
+    /// Serializes the content of the scene into bytes which can be cached and later loaded by
+    /// [`Scene::from_bytes`], including on machines with a different byte order.
+    ///
+    /// Scenes containing glyphs can't be serialized, as their fonts aren't included.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.encoding().to_bytes()?)
+    }
+
+    /// Returns an SVG document approximating the scene.
+    ///
+    /// Fills, strokes, gradients and clips are converted to their SVG equivalents, with
+    /// transforms kept as `transform` attributes, and shapes which are axis aligned
+    /// rectangles written as `<rect>` elements. Images and glyphs are left out, bilinear
+    /// gradients are drawn with their average color, and layers lose their color matrices,
+    /// feathering and compositing operators.
+    pub fn to_svg(&self) -> String {
+        crate::svg::encoding_to_svg(self.encoding())
+    }
+
+    /// Returns the bounding box in device space of each draw object in the scene, along with
+    /// the index of the draw object.
+    ///
+    /// Draw objects are indexed in the order they were encoded, counting both the start and
+    /// end of each layer, as in [`SceneStats::draw_tags`]. The boxes are computed from the
+    /// encoded paths, and include half the width of strokes and the rectangles of
+    /// [`Scene::push_rect_clip`]. Glyphs are left out, as their outlines are only added when
+    /// the scene is rendered, as are the ends of layers, along with empty paths.
+    pub fn draw_bounds(&self) -> Vec<(usize, Rect)> {
+        crate::svg::encoding_draw_bounds(self.encoding())
+    }
+
+    /// Returns a scene which draws the depth of each draw object of this one, as an opaque
+    /// gray level, in place of its brush.
+    #[cfg(feature = "wgpu")]
+    pub(crate) fn depth_scene(&self) -> Scene {
+        Scene {
+            encoding: ZOrderedEncoding::new(crate::depth::depth_encoding(self.encoding())),
+            ..Default::default()
+        }
+    }
+
+    /// Loads a scene from an SVG document.
+    ///
+    /// Only a subset of SVG is supported: paths and basic shapes, filled and stroked with
+    /// colors or linear and radial gradients, inside groups with transforms and opacity.
+    /// Anything else is skipped; use [`Scene::from_svg_with_diagnostics`] to find out what.
+    #[cfg(feature = "svg")]
+    pub fn from_svg(svg: &str) -> Result<Self> {
+        Ok(crate::svg_import::scene_from_svg(svg)?.0)
+    }
+
+    /// Loads a scene from an SVG document like [`Scene::from_svg`], also returning a
+    /// description of each unsupported element, attribute or value which was skipped.
+    #[cfg(feature = "svg")]
+    pub fn from_svg_with_diagnostics(svg: &str) -> Result<(Self, Vec<String>)> {
+        crate::svg_import::scene_from_svg(svg)
+    }
+
+    /// Creates a scene from bytes returned by [`Scene::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            encoding: ZOrderedEncoding::new(Encoding::from_bytes(bytes)?),
+            ..Self::default()
+        })
+    }
+
+    /// Returns a hash of the content of the scene.
+    ///
+    /// Two scenes built from the same sequence of draw calls hash equal, so an application
+    /// can compare this against the hash of the previous frame and skip rendering and
+    /// presenting when nothing has changed. See [`Encoding::content_hash`].
+    pub fn content_hash(&self) -> u64 {
+        self.encoding().content_hash()
+    }
+
+    /// Estimates the GPU memory in bytes needed to render the scene with `params`, without
+    /// touching the GPU.
+    ///
+    /// This covers the packed scene data, the gradient ramps and image atlas, the buffers used
+    /// by the pipeline stages and an `Rgba8Unorm` target of the requested size. Pipelines and
+    /// other state owned by the [`Renderer`](crate::Renderer) are not included.
+    pub fn estimate_memory(&self, params: &RenderParams) -> u64 {
+        let mut resolver = Resolver::new();
+        let mut packed = vec![];
+        let (layout, ramps, images) = resolver.resolve(self.encoding(), &mut packed);
+        let mut config = RenderConfig::new(
+            &layout,
+            params.width,
+            params.height,
+            &params.base_color,
+            BinSize::default().width,
+        );
+        if let Some(hint) = &params.complexity_hint {
+            config.reserve_bump_buffers(&hint.bump_sizes());
+        }
+        let texture_size = |width: u32, height: u32| width as u64 * height as u64 * 4;
+        packed.len() as u64
+            + config.buffer_sizes.total_size_in_bytes()
+            + texture_size(ramps.width, ramps.height)
+            + texture_size(images.width, images.height)
+            + texture_size(params.width, params.height)
+    }
+
+    /// Returns whether the scene can be rendered with `params` using at most `budget_bytes`
+    /// of GPU memory, according to [`estimate_memory`](Self::estimate_memory).
+    ///
+    /// This allows oversized requests to be rejected before any GPU resources are allocated.
+    pub fn fits_within(&self, budget_bytes: u64, params: &RenderParams) -> bool {
+        self.estimate_memory(params) <= budget_bytes
+    }
+
     /// Removes all content from the scene.
     pub fn reset(&mut self) {
         self.encoding.reset();
+        self.depth = 0.0;
+        self.rect_clips.clear();
+    }
+
+    /// Sets the z index of content drawn from now on. The default is 0.
+    ///
+    /// The scene is drawn in increasing z index order, and content with equal z indexes in the
+    /// order it was added, so a shape can be placed behind earlier content without rebuilding
+    /// the scene. The content is put in z order as it's encoded, so the draw objects reach the
+    /// GPU stages already sorted, rather than by the coarse stage. Only the top level of the
+    /// scene is sorted: calls made while a layer is open are ignored, so that the layer stays
+    /// in one piece, and an appended scene is drawn at the current z index as a whole.
+    pub fn set_z_index(&mut self, z_index: i32) {
+        if self.encoding.n_open_clips != 0 {
+            return;
+        }
+        let previous_z_index = self.encoding.z_index;
+        if z_index == previous_z_index {
+            return;
+        }
+        let previous = self.encoding.switch_to(z_index);
+        self.encoding.path_snapping = previous.path_snapping;
+        self.encoding.aliased = previous.aliased;
+        self.encoding.clip_rect = previous.clip_rect;
+        if self.encoding.current_depth() != self.depth {
+            self.encoding.encode_depth(self.depth);
+        }
+        self.encoding.insert_fragment(previous_z_index, previous);
+    }
+
+    /// Returns the z index of content drawn from now on.
+    pub fn z_index(&self) -> i32 {
+        self.encoding.z_index
+    }
+
+    /// Sets the depth of content drawn from now on, for the depth buffer rendered by
+    /// [`Renderer::render_depth`]. The default is 0.
+    ///
+    /// Depth doesn't change the order in which content is drawn, or its color. It's a value
+    /// between 0 and 1 carried along with each draw object, so that later passes can apply
+    /// effects such as parallax based on how far away each part of the image is.
+    ///
+    /// [`Renderer::render_depth`]: crate::Renderer::render_depth
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth;
+        if self.encoding.current_depth() != depth {
+            self.encoding.encode_depth(depth);
+        }
+    }
+
+    /// Returns the depth of content drawn from now on.
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// Snaps the coordinates of paths added from now on to a fixed-point grid with
+    /// `fractional_bits` bits after the binary point, or stops snapping if `None`.
+    ///
+    /// This trades precision for reproducibility: geometry which only differs by small rounding
+    /// errors in its inputs encodes identically, and the encoded coordinates are exactly
+    /// representable. Coordinates are snapped before the transform is applied.
+    pub fn set_path_snapping(&mut self, fractional_bits: Option<u32>) {
+        self.encoding.path_snapping = fractional_bits;
+    }
+
+    /// Rounds the device space coordinates of shapes filled from now on to whole pixels.
+    ///
+    /// When two opaque fills share an edge that falls part way through a pixel, antialiasing
+    /// leaves that pixel partially covered by each of them, and the background shows through
+    /// as a faint seam. Snapping moves the shared edge onto a pixel boundary in both fills so
+    /// that they meet exactly. This is intended for axis-aligned, polygonal geometry, as the
+    /// control points of curves are rounded as well.
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        self.snap_fills_to_pixels = enabled;
+    }
+
+    /// Sets whether fills, strokes and the clip shapes of layers drawn from now on are
+    /// antialiased. The default is true.
+    ///
+    /// Without antialiasing, each pixel is either covered by the shape or not, depending on
+    /// whether the shape covers at least half of it. This keeps thin, axis-aligned lines crisp
+    /// in content which mixes them with smooth curves. Glyphs are always antialiased.
+    pub fn set_antialias(&mut self, antialias: bool) {
+        self.encoding.aliased = !antialias;
+    }
+
+    /// Converts arcs, circles, ellipses and rounded corners of shapes added from now on into
+    /// cubic Béziers which stay within `tolerance` of the true curve, or uses the defaults if
+    /// `None`.
+    ///
+    /// The tolerance is in the coordinates of the shape, before its transform is applied. The
+    /// defaults of 0.1 for fills and 0.01 for strokes suit shapes drawn at roughly their own
+    /// size, but a large arc viewed at high zoom needs a finer tolerance, and so more cubic
+    /// segments, to look smooth.
+    pub fn set_arc_tolerance(&mut self, tolerance: Option<f64>) {
+        self.arc_tolerance = tolerance;
+    }
+
+    /// Returns the tolerance for converting arcs into cubic Béziers in shapes filled from now
+    /// on. See [`set_arc_tolerance`](Self::set_arc_tolerance).
+    pub fn arc_tolerance(&self) -> f64 {
+        self.arc_tolerance.unwrap_or(0.1)
+    }
+
+    /// Encodes the shapes of fills and layers added from now on relative to `origin`, or
+    /// directly in their own coordinates if `None`.
+    ///
+    /// Coordinates are stored as `f32` on the GPU, which can't represent fractions of a unit
+    /// at magnitudes around 1e7, and loses sub-pixel precision well before that. When shapes use
+    /// large world coordinates with a transform that moves them back in view, setting an
+    /// origin near the visible region subtracts it from the shapes in `f64` before they are
+    /// encoded, and folds it into the transform instead, so that both stay small.
+    pub fn set_transform_origin(&mut self, origin: Option<Point>) {
+        self.transform_origin = origin;
+    }
+
+    /// Encodes `transform`, the `style` and `shape`, rebasing the shape onto the transform
+    /// origin if one is set. Returns whether the shape was encoded.
+    fn encode_fill_shape(&mut self, style: Fill, transform: Affine, shape: &impl Shape) -> bool {
+        let tolerance = self.arc_tolerance();
+        let Some(origin) = self.transform_origin else {
+            self.encoding
+                .encode_transform(Transform::from_kurbo(&transform));
+            self.encoding.encode_fill_style(style);
+            return self
+                .encoding
+                .encode_path_elements(shape.path_elements(tolerance), true);
+        };
+        let offset = origin.to_vec2();
+        self.encoding.encode_transform(Transform::from_kurbo(
+            &(transform * Affine::translate(offset)),
+        ));
+        self.encoding.encode_fill_style(style);
+        self.encoding.encode_path_elements(
+            shape
+                .path_elements(tolerance)
+                .map(|el| Affine::translate(-offset) * el),
+            true,
+        )
+    }
+
+    /// Encodes the clip shape of a layer like [`encode_fill_shape`](Self::encode_fill_shape).
+    ///
+    /// Clip shapes aren't clipped by the rect clip stack themselves, as the content of the
+    /// layer is.
+    fn encode_clip_shape(&mut self, style: Fill, transform: Affine, shape: &impl Shape) -> bool {
+        let clip_rect = self.encoding.clip_rect.take();
+        let encoded = self.encode_fill_shape(style, transform, shape);
+        self.encoding.clip_rect = clip_rect;
+        encoded
     }
 
     /// Returns the underlying raw encoding.
+    ///
+    /// When content has been drawn at more than one z index, this is the content at each z
+    /// index merged in drawing order. The merged encoding is built on first use and kept
+    /// until the scene is changed.
     pub fn encoding(&self) -> &Encoding {
-        &self.encoding
+        self.encoding.merged()
     }
 
     /// Pushes a new layer bound by the specified shape and composed with
     /// previous layers using the specified blend mode.
+    ///
+    /// The shape is drawn with `transform`, in the coordinate space of the scene. The
+    /// content inside of the layer isn't affected by `transform`, and keeps the transforms it
+    /// is drawn with, so a clip which should rotate with its content needs the same rotation.
+    /// When the scene is appended to another with a transform, that applies to the clip as
+    /// well as the content; see [`push_layer_in`](Self::push_layer_in) to choose otherwise.
     pub fn push_layer(
         &mut self,
         blend: impl Into<BlendMode>,
         alpha: f32,
         transform: Affine,
         shape: &impl Shape,
+    ) {
+        self.push_layer_with_flags(LayerFlags::default(), blend, alpha, transform, shape);
+    }
+
+    /// Pushes a new layer like [`push_layer`](Self::push_layer), composited as a transparency
+    /// group with the isolation and knockout behavior given by `flags`.
+    ///
+    /// This follows the PDF transparency model. A non-isolated layer starts out as a copy of
+    /// its backdrop, so the blend modes of nested layers mix with the content underneath the
+    /// layer, and the result replaces the backdrop in proportion to the alpha and clip
+    /// coverage of the layer; its own blend mode is not applied. In a knockout layer, each
+    /// fill and nested layer is composited with the initial backdrop of the layer and
+    /// replaces whatever was drawn in the layer before it, so overlapping translucent fills
+    /// don't show through each other.
+    pub fn push_layer_with_flags(
+        &mut self,
+        flags: LayerFlags,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        shape: &impl Shape,
     ) {
         let blend = blend.into();
-        self.encoding
-            .encode_transform(Transform::from_kurbo(&transform));
-        self.encoding.encode_fill_style(Fill::NonZero);
-        if !self.encoding.encode_shape(shape, true) {
+        if !self.encode_clip_shape(Fill::NonZero, transform, shape) {
             // If the layer shape is invalid, encode a valid empty path. This suppresses
             // all drawing until the layer is popped.
             self.encoding
                 .encode_shape(&Rect::new(0.0, 0.0, 0.0, 0.0), true);
         }
+        let mut group_flags = 0;
+        if !flags.isolated {
+            group_flags |= DrawBeginClip::NON_ISOLATED_FLAG;
+        }
+        if flags.knockout {
+            group_flags |= DrawBeginClip::KNOCKOUT_FLAG;
+        }
         self.encoding
-            .encode_begin_clip(blend, alpha.clamp(0.0, 1.0));
+            .encode_begin_group_clip(blend, alpha.clamp(0.0, 1.0), group_flags);
+    }
+
+    /// Pushes a new layer like [`push_layer`](Self::push_layer), with the clip shape in the
+    /// given coordinate space.
+    ///
+    /// With [`ClipSpace::Target`], the clip shape gets a transform of its own, which is left
+    /// unchanged when the scene is appended to another with a transform.
+    pub fn push_layer_in(
+        &mut self,
+        space: ClipSpace,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        shape: &impl Shape,
+    ) {
+        match space {
+            ClipSpace::Scene => self.push_layer(blend, alpha, transform, shape),
+            ClipSpace::Target => {
+                self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+                self.push_layer(blend, alpha, transform, shape);
+                self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+                let index = self.encoding.transforms.len() - 1;
+                self.encoding.fixed_transforms.push(index);
+            }
+        }
+    }
+
+    /// Pushes a new layer like [`push_layer`](Self::push_layer), returning a handle with which
+    /// the transform of its clip shape can be changed later.
+    ///
+    /// The layer gets a transform of its own, which isn't shared with the content drawn
+    /// before or inside of it. This suits scroll containers: when the whole container is
+    /// appended to a frame with a scroll offset, the clip can be held in place by
+    /// [`set_layer_transform`](Self::set_layer_transform) instead of encoding the container
+    /// again.
+    pub fn push_layer_tracked(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        shape: &impl Shape,
+    ) -> LayerTransform {
+        self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+        self.push_layer(blend, alpha, transform, shape);
+        self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+        LayerTransform {
+            z_index: self.encoding.z_index,
+            index: self.encoding.transforms.len() - 1,
+            offset: self.transform_origin.unwrap_or_default().to_vec2(),
+        }
+    }
+
+    /// Replaces the transform of the clip shape of a layer pushed by
+    /// [`push_layer_tracked`](Self::push_layer_tracked), without encoding the shape again.
+    ///
+    /// Content inside of the layer keeps its own transforms. The scene must not have been
+    /// reset since the layer was pushed.
+    pub fn set_layer_transform(&mut self, layer: LayerTransform, transform: Affine) {
+        let encoding = self
+            .encoding
+            .at_z_index_mut(layer.z_index)
+            .expect("layer transform from a reset scene");
+        encoding.transforms[layer.index] =
+            Transform::from_kurbo(&(transform * Affine::translate(layer.offset)));
+    }
+
+    /// Pushes a new clip layer bound by the union of `rects`, such as a list of damaged
+    /// regions tracked by a compositor.
+    ///
+    /// Content drawn until the layer is popped only shows up inside of the rectangles, which
+    /// may overlap. An empty list clips away everything. To also leave the target untouched
+    /// away from the rectangles, combine this with [`RenderParams::dirty_tiles`].
+    pub fn push_clip_rects(&mut self, transform: Affine, rects: &[Rect]) {
+        let mut path = BezPath::new();
+        for rect in rects {
+            // Normalized rectangles all wind the same way, so the non-zero fill of the layer
+            // covers their union.
+            path.extend(rect.abs().path_elements(0.1));
+        }
+        self.push_layer(Mix::Clip, 1.0, transform, &path);
+    }
+
+    /// Pushes a new layer which is drawn everywhere except inside of the specified shape.
+    ///
+    /// The clip coverage is one minus the coverage of `shape`, as for a cutout or a spotlight.
+    /// The shape is combined with a rectangle covering any target using the even-odd rule, so
+    /// regions where the shape overlaps itself alternate between being cut out and not.
+    pub fn push_inverse_clip_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        shape: &impl Shape,
+    ) {
+        const EXTENT: f64 = 1e6;
+        let everywhere = Rect::new(-EXTENT, -EXTENT, EXTENT, EXTENT);
+        // The rectangle is in device space, so the shape is transformed here to share its path.
+        let tolerance = self.arc_tolerance();
+        let clip_rect = self.encoding.clip_rect.take();
+        self.encoding.encode_transform(Transform::IDENTITY);
+        self.encoding.encode_fill_style(Fill::EvenOdd);
+        self.encoding.encode_path_elements(
+            everywhere
+                .path_elements(0.1)
+                .chain(shape.path_elements(tolerance).map(|el| transform * el)),
+            true,
+        );
+        self.encoding.clip_rect = clip_rect;
+        self.encoding
+            .encode_begin_clip(blend.into(), alpha.clamp(0.0, 1.0));
+    }
+
+    /// Pushes a new layer bound by the specified shape whose content is transformed by a
+    /// color matrix before it is composed with previous layers.
+    ///
+    /// `matrix` is a 4x5 matrix in row-major order with the same meaning as the values of an
+    /// SVG `feColorMatrix` element: each output channel is a weighted sum of the
+    /// unpremultiplied red, green, blue and alpha input channels, plus the value in the fifth
+    /// column. This can express effects such as grayscale, sepia or hue rotation.
+    pub fn push_color_matrix_layer(
+        &mut self,
+        matrix: [f32; 20],
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        shape: &impl Shape,
+    ) {
+        if !self.encode_clip_shape(Fill::NonZero, transform, shape) {
+            self.encoding
+                .encode_shape(&Rect::new(0.0, 0.0, 0.0, 0.0), true);
+        }
+        self.encoding
+            .encode_begin_color_matrix_clip(blend.into(), alpha.clamp(0.0, 1.0), matrix);
+    }
+
+    /// Pushes a new layer clipped by a rectangle with soft edges.
+    ///
+    /// The clip coverage ramps from 1 to 0 over a band of width `feather` centered on the
+    /// edges of `rect`. The feathered edges follow the device space bounding box of the
+    /// transformed rectangle, so `transform` should only scale and translate.
+    pub fn push_feathered_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        rect: &Rect,
+        feather: f64,
+    ) {
+        let feather = feather.max(0.0);
+        let device_rect = transform.transform_rect_bbox(*rect);
+        let clip_rect = self.encoding.clip_rect.take();
+        self.encoding.encode_transform(Transform::IDENTITY);
+        self.encoding.encode_fill_style(Fill::NonZero);
+        if !self
+            .encoding
+            .encode_shape(&device_rect.inflate(feather * 0.5, feather * 0.5), true)
+        {
+            self.encoding
+                .encode_shape(&Rect::new(0.0, 0.0, 0.0, 0.0), true);
+        }
+        self.encoding.clip_rect = clip_rect;
+        self.encoding.encode_begin_feathered_clip(
+            blend.into(),
+            alpha.clamp(0.0, 1.0),
+            [
+                device_rect.x0 as f32,
+                device_rect.y0 as f32,
+                device_rect.x1 as f32,
+                device_rect.y1 as f32,
+            ],
+            feather as f32,
+        );
     }
 
     /// Pops the current layer.
@@ -69,6 +827,53 @@ impl Scene {
         self.encoding.encode_end_clip();
     }
 
+    /// Clips the fills, strokes and glyphs drawn until the matching
+    /// [`pop_rect_clip`](Self::pop_rect_clip) to a rectangle.
+    ///
+    /// Unlike a clip layer, this doesn't encode anything by itself: the intersection of the
+    /// rectangles on the stack is kept in device space, carried along with each draw in four
+    /// floats, and tested per pixel in fine rasterization. Deeply nested scroll views and
+    /// table cells can be clipped this way without the memory for a clip stack. Draws inside
+    /// of layers are clipped, but the clip shapes of the layers themselves are not.
+    ///
+    /// The rectangle can only be kept as is when `transform` maps it to an axis-aligned
+    /// rectangle. Otherwise, it is pushed as a clip layer instead.
+    pub fn push_rect_clip(&mut self, transform: Affine, rect: &Rect) {
+        let [a, b, c, d, _, _] = transform.as_coeffs();
+        if !(b == 0.0 && c == 0.0 || a == 0.0 && d == 0.0) {
+            self.push_layer(Mix::Clip, 1.0, transform, rect);
+            self.rect_clips.push(RectClip::Layer);
+            return;
+        }
+        let device_rect = transform.transform_rect_bbox(*rect);
+        let rect = [
+            device_rect.x0 as f32,
+            device_rect.y0 as f32,
+            device_rect.x1 as f32,
+            device_rect.y1 as f32,
+        ];
+        let previous = self.encoding.clip_rect;
+        self.encoding.clip_rect = Some(match previous {
+            Some(clip) => [
+                clip[0].max(rect[0]),
+                clip[1].max(rect[1]),
+                clip[2].min(rect[2]),
+                clip[3].min(rect[3]),
+            ],
+            None => rect,
+        });
+        self.rect_clips.push(RectClip::Rect(previous));
+    }
+
+    /// Pops the clip pushed by the last [`push_rect_clip`](Self::push_rect_clip).
+    pub fn pop_rect_clip(&mut self) {
+        match self.rect_clips.pop() {
+            Some(RectClip::Rect(previous)) => self.encoding.clip_rect = previous,
+            Some(RectClip::Layer) => self.pop_layer(),
+            None => debug_assert!(false, "pop_rect_clip without a matching push_rect_clip"),
+        }
+    }
+
     /// Fills a shape using the specified style and brush.
     pub fn fill<'b>(
         &mut self,
@@ -78,10 +883,106 @@ impl Scene {
         brush_transform: Option<Affine>,
         shape: &impl Shape,
     ) {
-        self.encoding
-            .encode_transform(Transform::from_kurbo(&transform));
-        self.encoding.encode_fill_style(style);
-        if self.encoding.encode_shape(shape, true) {
+        self.fill_with_hints(style, transform, brush, &[], brush_transform, shape);
+    }
+
+    /// Fills a shape using the specified style and gradient, with interpolation hints
+    /// controlling where the midpoint of each transition between color stops falls.
+    ///
+    /// The hint at index `i` is the relative position of the midpoint between stops `i`
+    /// and `i + 1`, where `0.5` is linear interpolation.
+    pub fn fill_gradient_with_hints(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        gradient: &Gradient,
+        hints: &[f32],
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.fill_with_hints(style, transform, gradient, hints, brush_transform, shape);
+    }
+
+    /// Fills a shape using the specified style and gradient, quantized into `steps` bands of
+    /// solid color with hard edges between them, as for posterized designs.
+    ///
+    /// The first band has the color at the start of the gradient and the last band the color
+    /// at its end, with the bands in between sampled evenly. With fewer than two steps, the
+    /// shape is filled with the color at the start of the gradient.
+    pub fn fill_stepped_gradient(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        gradient: &Gradient,
+        steps: u32,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        if steps < 2 {
+            let color = gradient
+                .stops
+                .first()
+                .map_or(Color::TRANSPARENT, |stop| stop.color);
+            self.fill(style, transform, color, None, shape);
+            return;
+        }
+        let mut stepped = gradient.clone();
+        stepped.stops = stepped_stops(&gradient.stops, steps);
+        self.fill(style, transform, &stepped, brush_transform, shape);
+    }
+
+    fn fill_with_hints<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        hints: &[f32],
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.fill_with(style, transform, brush_transform, shape, |encoding| {
+            encoding.encode_brush_with_hints(brush, 1.0, hints);
+        });
+    }
+
+    /// Fills a shape with the brush encoded by `encode_brush`.
+    fn fill_with(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+        encode_brush: impl FnOnce(&mut Encoding),
+    ) {
+        if self.snap_fills_to_pixels {
+            // Snapping has to happen in device space, so apply the transform here and leave
+            // the brush in the original coordinate system.
+            self.encoding.encode_transform(Transform::IDENTITY);
+            self.encoding.encode_fill_style(style);
+            let path_snapping = self.encoding.path_snapping.replace(0);
+            let path = shape.path_elements(self.arc_tolerance());
+            let encoded = self
+                .encoding
+                .encode_path_elements(path.map(|el| transform * el), true);
+            self.encoding.path_snapping = path_snapping;
+            if encoded {
+                let brush_transform = transform * brush_transform.unwrap_or(Affine::IDENTITY);
+                if self
+                    .encoding
+                    .encode_transform(Transform::from_kurbo(&brush_transform))
+                {
+                    self.encoding.swap_last_path_tags();
+                }
+                encode_brush(&mut self.encoding);
+            }
+            return;
+        }
+        if self.encode_fill_shape(style, transform, shape) {
+            // A rebased path is encoded with a different transform, so the brush needs its own.
+            let brush_transform = match (brush_transform, self.transform_origin) {
+                (None, Some(_)) => Some(Affine::IDENTITY),
+                (brush_transform, _) => brush_transform,
+            };
             if let Some(brush_transform) = brush_transform {
                 if self
                     .encoding
@@ -90,11 +991,70 @@ impl Scene {
                     self.encoding.swap_last_path_tags();
                 }
             }
-            self.encoding.encode_brush(brush, 1.0);
+            encode_brush(&mut self.encoding);
         }
     }
 
+    /// Fills a shape with the brush, and then darkens the inside of it near its edges with an
+    /// inner shadow.
+    ///
+    /// The shadow is drawn in a layer clipped by the coverage of the fill itself. It covers
+    /// everything outside of the shape moved by the offset of the shadow, and fades out
+    /// linearly over the blur radius towards the inside of the moved shape, which
+    /// approximates a blur in a handful of strokes of increasing width.
+    pub fn fill_with_inner_shadow<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+        shadow: &InnerShadow,
+    ) {
+        const MAX_BLUR_STEPS: usize = 16;
+        self.fill(style, transform, brush, brush_transform, shape);
+        if !self.encode_clip_shape(style, transform, shape) {
+            return;
+        }
+        self.encoding.encode_begin_clip(Mix::Clip.into(), 1.0);
+        let shadow_transform = transform * Affine::translate(shadow.offset);
+        let margin = shadow.offset.x.abs() + shadow.offset.y.abs() + 1.0;
+        let bounds = shape.bounding_box().inflate(margin, margin);
+        // The shadow at full strength, wherever the moved shape doesn't reach.
+        self.push_inverse_clip_layer(Mix::Clip, 1.0, shadow_transform, shape);
+        self.fill(Fill::NonZero, transform, shadow.color, None, &bounds);
+        self.pop_layer();
+        if shadow.blur_radius > 0.0 {
+            // Strokes centered on the edge of the moved shape cover the points up to half of
+            // their width inside of it, so the narrower strokes stack over the deeper ones.
+            // Their alphas are chosen so that the total falls off linearly with depth.
+            let scale = transform.determinant().abs().sqrt();
+            let steps = ((shadow.blur_radius * scale).ceil() as usize).clamp(1, MAX_BLUR_STEPS);
+            let alpha = shadow.color.a as f64 / 255.0;
+            let remaining = |covering: usize| 1.0 - alpha * covering as f64 / steps as f64;
+            self.push_layer(Mix::Clip, 1.0, shadow_transform, shape);
+            for step in 1..=steps {
+                let step_alpha = 1.0 - remaining(steps - step + 1) / remaining(steps - step);
+                let color = Color {
+                    a: (step_alpha * 255.0).round() as u8,
+                    ..shadow.color
+                };
+                let width = 2.0 * shadow.blur_radius * step as f64 / steps as f64;
+                self.stroke(&Stroke::new(width), shadow_transform, color, None, shape);
+            }
+            self.pop_layer();
+        }
+        self.pop_layer();
+    }
+
     /// Strokes a shape using the specified style and brush.
+    ///
+    /// The width of the stroke is in the units of the shape, so it scales with `transform`.
+    /// See [`Scene::stroke_non_scaling`] for a stroke whose width is in device pixels.
+    ///
+    /// With a dash pattern, each dash is stroked as a single subpath, so a dash running
+    /// through a join of the shape is drawn with that join rather than as two capped pieces,
+    /// matching SVG.
     pub fn stroke<'b>(
         &mut self,
         style: &Stroke,
@@ -102,11 +1062,47 @@ impl Scene {
         brush: impl Into<BrushRef<'b>>,
         brush_transform: Option<Affine>,
         shape: &impl Shape,
+    ) {
+        self.encode_stroke(
+            style,
+            false,
+            transform,
+            brush.into(),
+            brush_transform,
+            shape,
+        );
+    }
+
+    /// Strokes a shape with a width in device pixels, whatever the scale of `transform`.
+    ///
+    /// This is the SVG `vector-effect: non-scaling-stroke` property: the shape is
+    /// transformed first, and then stroked, so a hairline stays a hairline when the scene is
+    /// zoomed. The lengths of the dash pattern are in device pixels too. The brush is still
+    /// transformed by `transform`, as for [`Scene::stroke`].
+    pub fn stroke_non_scaling<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.encode_stroke(style, true, transform, brush.into(), brush_transform, shape);
+    }
+
+    fn encode_stroke(
+        &mut self,
+        style: &Stroke,
+        non_scaling: bool,
+        transform: Affine,
+        brush: BrushRef<'_>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
     ) {
         // The setting for tolerance are a compromise. For most applications,
         // shape tolerance doesn't matter, as the input is likely Bézier paths,
-        // which is exact. Note that shape tolerance is hard-coded as 0.1 in
-        // the encoding crate.
+        // which is exact. The default can be overridden with
+        // `set_arc_tolerance`, for large arcs viewed at high zoom.
         //
         // Stroke tolerance is a different matter. Generally, the cost scales
         // with inverse O(n^6), so there is moderate rendering cost to setting
@@ -116,21 +1112,37 @@ impl Scene {
         // this is a compromise.
         const SHAPE_TOLERANCE: f64 = 0.01;
         const STROKE_TOLERANCE: f64 = SHAPE_TOLERANCE;
+        let shape_tolerance = self.arc_tolerance.unwrap_or(SHAPE_TOLERANCE);
 
         const GPU_STROKES: bool = false; // Set this to `true` to enable GPU-side stroking
         if GPU_STROKES {
             self.encoding
                 .encode_transform(Transform::from_kurbo(&transform));
-            self.encoding.encode_stroke_style(style);
+            if non_scaling {
+                self.encoding.encode_non_scaling_stroke_style(style);
+            } else {
+                self.encoding.encode_stroke_style(style);
+            }
 
             // We currently don't support dashing on the GPU. If the style has a dash pattern, then
             // we convert it into stroked paths on the CPU and encode those as individual draw
             // objects.
             let encode_result = if style.dash_pattern.is_empty() {
-                self.encoding.encode_shape(shape, false)
+                self.encoding
+                    .encode_path_elements(shape.path_elements(shape_tolerance), false)
+            } else if non_scaling {
+                // Dash in device space, and then map the dashes back to be encoded with the
+                // transform of the shape.
+                let device = shape
+                    .path_elements(shape_tolerance)
+                    .map(|el| transform * el);
+                let inverse = transform.inverse();
+                let dashed = peniko::kurbo::dash(device, style.dash_offset, &style.dash_pattern)
+                    .map(|el| inverse * el);
+                self.encoding.encode_path_elements(dashed, false)
             } else {
                 let dashed = peniko::kurbo::dash(
-                    shape.path_elements(SHAPE_TOLERANCE),
+                    shape.path_elements(shape_tolerance),
                     style.dash_offset,
                     &style.dash_pattern,
                 );
@@ -147,9 +1159,23 @@ impl Scene {
                 }
                 self.encoding.encode_brush(brush, 1.0);
             }
+        } else if non_scaling {
+            let device = shape
+                .path_elements(shape_tolerance)
+                .map(|el| transform * el);
+            let stroked =
+                peniko::kurbo::stroke(device, style, &Default::default(), STROKE_TOLERANCE);
+            let brush_transform = transform * brush_transform.unwrap_or(Affine::IDENTITY);
+            self.fill(
+                Fill::NonZero,
+                Affine::IDENTITY,
+                brush,
+                Some(brush_transform),
+                &stroked,
+            );
         } else {
             let stroked = peniko::kurbo::stroke(
-                shape.path_elements(SHAPE_TOLERANCE),
+                shape.path_elements(shape_tolerance),
                 style,
                 &Default::default(),
                 STROKE_TOLERANCE,
@@ -158,6 +1184,110 @@ impl Scene {
         }
     }
 
+    /// Strokes the open polyline through `points`, such as a plotted data series.
+    ///
+    /// This is equivalent to stroking a path of lines between consecutive points, but reads
+    /// the points directly instead of requiring a path to be built first. Fewer than two
+    /// points draw nothing.
+    pub fn stroke_polyline<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        points: &[Point],
+    ) {
+        // Matches the stroke tolerance of `stroke`.
+        const STROKE_TOLERANCE: f64 = 0.01;
+        if points.len() < 2 {
+            return;
+        }
+        let lines = points.iter().enumerate().map(|(i, &point)| {
+            if i == 0 {
+                PathEl::MoveTo(point)
+            } else {
+                PathEl::LineTo(point)
+            }
+        });
+        let stroked = peniko::kurbo::stroke(lines, style, &Default::default(), STROKE_TOLERANCE);
+        self.fill(Fill::NonZero, transform, brush, brush_transform, &stroked);
+    }
+
+    /// Strokes a shape with a gradient which follows the path rather than a fixed axis.
+    ///
+    /// The color at each point along the stroke is the color of `stops` at the fraction of
+    /// the total length of the path up to that point, so the stroke starts with the color at
+    /// offset 0 and ends with the color at offset 1. The path is flattened, and each line is
+    /// stroked with a linear gradient covering its part of the stops. Lines within a subpath
+    /// are joined with round caps, which overlap slightly, so translucent colors are a little
+    /// denser at the joins. The dash pattern of `style` is ignored.
+    pub fn stroke_gradient_along(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        stops: impl ColorStopsSource,
+        shape: &impl Shape,
+    ) {
+        const FLATTEN_TOLERANCE: f64 = 0.05;
+        let stops = Gradient::default().with_stops(stops).stops;
+        // Lines of each subpath, in order.
+        let mut subpaths: Vec<Vec<Line>> = vec![];
+        let (mut start, mut last) = (Point::ZERO, Point::ZERO);
+        let path = shape.path_elements(self.arc_tolerance());
+        peniko::kurbo::flatten(path, FLATTEN_TOLERANCE, |el| match el {
+            PathEl::MoveTo(p) => {
+                subpaths.push(vec![]);
+                (start, last) = (p, p);
+            }
+            PathEl::LineTo(p) => {
+                if let Some(lines) = subpaths.last_mut() {
+                    lines.push(Line::new(last, p));
+                }
+                last = p;
+            }
+            PathEl::ClosePath => {
+                if let Some(lines) = subpaths.last_mut() {
+                    lines.push(Line::new(last, start));
+                }
+                last = start;
+            }
+            _ => unreachable!("flattening only produces lines"),
+        });
+        let total: f64 = subpaths.iter().flatten().map(|line| line.length()).sum();
+        if total == 0.0 {
+            return;
+        }
+        let mut distance = 0.0;
+        for lines in &subpaths {
+            let Some(last_ix) = lines.iter().rposition(|line| line.length() > 0.0) else {
+                continue;
+            };
+            let first_ix = lines.iter().position(|line| line.length() > 0.0).unwrap();
+            for (ix, line) in lines.iter().enumerate() {
+                let length = line.length();
+                if length == 0.0 {
+                    continue;
+                }
+                let t0 = (distance / total) as f32;
+                distance += length;
+                let t1 = (distance / total) as f32;
+                let mut piece = Stroke::new(style.width)
+                    .with_join(style.join)
+                    .with_miter_limit(style.miter_limit)
+                    .with_caps(Cap::Round);
+                if ix == first_ix {
+                    piece.start_cap = style.start_cap;
+                }
+                if ix == last_ix {
+                    piece.end_cap = style.end_cap;
+                }
+                let mut gradient = Gradient::new_linear(line.p0, line.p1);
+                gradient.stops = stops_between(&stops, t0, t1);
+                self.stroke(&piece, transform, &gradient, None, line);
+            }
+        }
+    }
+
     /// Draws an image at its natural size with the given transform.
     pub fn draw_image(&mut self, image: &Image, transform: Affine) {
         self.fill(
@@ -169,26 +1299,254 @@ impl Scene {
         );
     }
 
+    /// Draws an image read back by [`Renderer::render_to_image`] at its natural size with the
+    /// given transform.
+    ///
+    /// The image is sampled according to its alpha type, so premultiplied pixels aren't
+    /// multiplied by their alpha a second time.
+    ///
+    /// [`Renderer::render_to_image`]: crate::Renderer::render_to_image
+    pub fn draw_rendered_image(&mut self, image: &RenderedImage, transform: Affine) {
+        let sampler = ImageSampler {
+            alpha_type: image.alpha_type,
+            ..Default::default()
+        };
+        let image = &image.image;
+        let rect = Rect::new(0.0, 0.0, image.width as f64, image.height as f64);
+        self.fill_with(Fill::NonZero, transform, None, &rect, |encoding| {
+            encoding.encode_image_with_sampler(image, 1.0, sampler);
+        });
+    }
+
+    /// Draws an image at its natural size with each of its colors multiplied by `tint`.
+    ///
+    /// Drawing a white image, such as an icon or a mask, with a tint draws it in the color of
+    /// the tint.
+    pub fn draw_image_with_tint(&mut self, image: &Image, transform: Affine, tint: Color) {
+        let rect = Rect::new(0.0, 0.0, image.width as f64, image.height as f64);
+        self.fill_with(Fill::NonZero, transform, None, &rect, |encoding| {
+            encoding.encode_image_with_tint(image, 1.0, ImageSampler::default(), tint);
+        });
+    }
+
+    /// Draws an image stretched over `rect` with its corners rounded by `radii`, as for an
+    /// avatar.
+    ///
+    /// The rounded rectangle is filled with the image directly, so this is as cheap as drawing
+    /// the image on its own, rather than needing a clip layer. The corners are antialiased
+    /// like any other fill.
+    pub fn draw_image_rounded(
+        &mut self,
+        image: &Image,
+        transform: Affine,
+        rect: Rect,
+        radii: impl Into<RoundedRectRadii>,
+    ) {
+        let rect = rect.abs();
+        if image.width == 0 || image.height == 0 || rect.area() == 0.0 {
+            return;
+        }
+        let scale_x = rect.width() / image.width as f64;
+        let scale_y = rect.height() / image.height as f64;
+        let image_transform = Affine::new([scale_x, 0.0, 0.0, scale_y, rect.x0, rect.y0]);
+        self.fill(
+            Fill::NonZero,
+            transform,
+            image,
+            Some(image_transform),
+            &rect.to_rounded_rect(radii),
+        );
+    }
+
+    /// Fills a rectangle with a bilinear gradient patch, interpolating between a color at
+    /// each of its corners.
+    ///
+    /// The corners are given in the order top left, top right, bottom left and bottom
+    /// right, in the coordinate system of the rectangle before `transform` is applied.
+    pub fn fill_bilinear_patch(&mut self, transform: Affine, rect: Rect, colors: [Color; 4]) {
+        if rect.width() == 0.0 || rect.height() == 0.0 {
+            return;
+        }
+        // Maps the unit square of the patch onto the rectangle.
+        let patch_transform =
+            Affine::new([rect.width(), 0.0, 0.0, rect.height(), rect.x0, rect.y0]);
+        self.fill_with(
+            Fill::NonZero,
+            transform,
+            Some(patch_transform),
+            &rect,
+            |encoding| encoding.encode_bilinear_gradient(DrawBilinearGradient::new(colors)),
+        );
+    }
+
+    /// Draws a filled rounded rectangle with a border, as is common for UI elements.
+    ///
+    /// The border is stroked just inside of `rect`, so the element covers exactly `rect`
+    /// whatever the width of its border, and the fill extends underneath the border. A
+    /// `border_width` of zero only draws the fill.
+    pub fn rounded_rect<'b>(
+        &mut self,
+        transform: Affine,
+        rect: Rect,
+        radii: impl Into<RoundedRectRadii>,
+        fill_brush: impl Into<BrushRef<'b>>,
+        border_width: f64,
+        border_brush: impl Into<BrushRef<'b>>,
+    ) {
+        let radii = radii.into();
+        self.fill(
+            Fill::NonZero,
+            transform,
+            fill_brush,
+            None,
+            &rect.to_rounded_rect(radii),
+        );
+        if border_width > 0.0 {
+            let half = border_width / 2.0;
+            let inset = |radius: f64| (radius - half).max(0.0);
+            let border = rect.inset(-half).to_rounded_rect(RoundedRectRadii::new(
+                inset(radii.top_left),
+                inset(radii.top_right),
+                inset(radii.bottom_right),
+                inset(radii.bottom_left),
+            ));
+            self.stroke(
+                &Stroke::new(border_width),
+                transform,
+                border_brush,
+                None,
+                &border,
+            );
+        }
+    }
+
+    /// Fills a shape with an image, which is sampled according to `sampler`.
+    ///
+    /// The address modes and the magnification filter of the descriptor are used. Images are
+    /// packed into an atlas without mipmaps, so the other fields have no effect. Unlike the
+    /// default sampling, which only draws inside of the image, the repeat and clamp to edge
+    /// address modes extend the image across the whole shape.
+    #[cfg(feature = "wgpu")]
+    pub fn fill_image_with_sampler(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        image: &Image,
+        sampler: &wgpu::SamplerDescriptor,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        let address_mode = |mode| match mode {
+            wgpu::AddressMode::ClampToEdge => ImageAddressMode::ClampToEdge,
+            wgpu::AddressMode::Repeat => ImageAddressMode::Repeat,
+            wgpu::AddressMode::MirrorRepeat => ImageAddressMode::MirrorRepeat,
+            wgpu::AddressMode::ClampToBorder => ImageAddressMode::ClampToBorder,
+        };
+        let sampler = ImageSampler {
+            address_mode_x: address_mode(sampler.address_mode_u),
+            address_mode_y: address_mode(sampler.address_mode_v),
+            filter: match sampler.mag_filter {
+                wgpu::FilterMode::Nearest => ImageFilter::Nearest,
+                wgpu::FilterMode::Linear => ImageFilter::Linear,
+            },
+            ..Default::default()
+        };
+        self.fill_with(style, transform, brush_transform, shape, |encoding| {
+            encoding.encode_image_with_sampler(image, 1.0, sampler);
+        });
+    }
+
     /// Returns a builder for encoding a glyph run.
     pub fn draw_glyphs(&mut self, font: &Font) -> DrawGlyphs {
         DrawGlyphs::new(&mut self.encoding, font)
     }
 
+    /// Fills a run of glyphs which has already been laid out by a shaper, with the origin of
+    /// the first glyph at the origin of `transform`.
+    ///
+    /// This is a shorthand for [`draw_glyphs`](Self::draw_glyphs) followed by
+    /// [`DrawGlyphs::draw_shaped`]; use those directly to set other properties of the run.
+    pub fn draw_glyphs_shaped<'b>(
+        &'b mut self,
+        font: &Font,
+        font_size: f32,
+        transform: Affine,
+        glyphs: &[PositionedGlyph],
+        brush: impl Into<BrushRef<'b>>,
+    ) {
+        self.draw_glyphs(font)
+            .font_size(font_size)
+            .transform(transform)
+            .brush(brush)
+            .draw_shaped(Fill::NonZero, glyphs);
+    }
+
     /// Appends a fragment to the scene.
     pub fn append(&mut self, other: &Scene, transform: Option<Affine>) {
         self.encoding.append(
-            &other.encoding,
+            other.encoding(),
             &transform.map(|xform| Transform::from_kurbo(&xform)),
         );
+        // Content of the other scene keeps its own depths.
+        if self.encoding.current_depth() != self.depth {
+            self.encoding.encode_depth(self.depth);
+        }
     }
 }
 
+/// A glyph of a run as positioned by a text shaper.
+///
+/// Distances are in pixels, with the y axis pointing down as in the rest of the scene. Shapers
+/// which use a y axis pointing up need their vertical advances and offsets negated.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PositionedGlyph {
+    /// Glyph identifier in the font.
+    pub id: u32,
+    /// Horizontal distance from the origin of this glyph to the origin of the next.
+    pub x_advance: f32,
+    /// Vertical distance from the origin of this glyph to the origin of the next.
+    pub y_advance: f32,
+    /// Horizontal offset of the glyph from its origin, such as for a mark.
+    pub x_offset: f32,
+    /// Vertical offset of the glyph from its origin.
+    pub y_offset: f32,
+}
+
 /// Builder for encoding a glyph run.
 pub struct DrawGlyphs<'a> {
     encoding: &'a mut Encoding,
     run: GlyphRun,
     brush: BrushRef<'a>,
     brush_alpha: f32,
+    outline: Option<(Stroke, BrushRef<'a>)>,
+    underline: bool,
+    strikethrough: bool,
+    missing_glyph: MissingGlyph<'a>,
+}
+
+/// What [`DrawGlyphs`] draws in place of a glyph id which doesn't exist in the font.
+#[derive(Clone, Copy, Default)]
+pub enum MissingGlyph<'a> {
+    /// Draw nothing for the glyph.
+    #[default]
+    Skip,
+    /// Draw the `.notdef` glyph of the font, glyph 0, which is usually an empty box.
+    Notdef,
+    /// Draw the glyph returned by the callback instead, or nothing if it returns `None`.
+    ///
+    /// The callback is passed the missing glyph, and can substitute a glyph from the same
+    /// font, such as a replacement character, at the same or a different position.
+    Fallback(&'a dyn Fn(Glyph) -> Option<Glyph>),
+}
+
+impl std::fmt::Debug for MissingGlyph<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => f.write_str("Skip"),
+            Self::Notdef => f.write_str("Notdef"),
+            Self::Fallback(_) => f.write_str("Fallback(..)"),
+        }
+    }
 }
 
 impl<'a> DrawGlyphs<'a> {
@@ -208,11 +1566,16 @@ impl<'a> DrawGlyphs<'a> {
                 hint: false,
                 normalized_coords: coords_start..coords_start,
                 style: Fill::NonZero.into(),
+                clip_rect: 0,
                 glyphs: glyphs_start..glyphs_start,
                 stream_offsets,
             },
             brush: Color::BLACK.into(),
             brush_alpha: 1.0,
+            outline: None,
+            underline: false,
+            strikethrough: false,
+            missing_glyph: MissingGlyph::Skip,
         }
     }
 
@@ -281,28 +1644,376 @@ impl<'a> DrawGlyphs<'a> {
         self
     }
 
+    /// Sets an outline which is stroked around each glyph, behind the glyph itself.
+    ///
+    /// The outline extends `width` pixels beyond the edges of the glyphs and uses round
+    /// joins. The default is no outline.
+    pub fn outline(mut self, width: f64, brush: impl Into<BrushRef<'a>>) -> Self {
+        self.outline = Some((Stroke::new(width * 2.0), brush.into()));
+        self
+    }
+
+    /// Sets whether to draw an underline beneath the run, at the position and thickness
+    /// specified by the font.
+    ///
+    /// The underline spans from the origin of the first glyph to the advance of the last,
+    /// and is drawn behind the glyphs with the brush of the run. Fonts which don't specify
+    /// underline metrics are drawn without one. The default value is `false`.
+    pub fn underline(mut self, underline: bool) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    /// Sets whether to draw a strikethrough across the run, at the position and thickness
+    /// specified by the font.
+    ///
+    /// This spans the same extent as the [`underline`](Self::underline), but is drawn in
+    /// front of the glyphs. The default value is `false`.
+    pub fn strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
+        self
+    }
+
+    /// Sets what is drawn for glyph ids which don't exist in the font.
+    ///
+    /// The default is [`MissingGlyph::Skip`].
+    pub fn missing_glyph(mut self, missing_glyph: MissingGlyph<'a>) -> Self {
+        self.missing_glyph = missing_glyph;
+        self
+    }
+
     /// Encodes a fill or stroke for for the given sequence of glyphs and consumes
     /// the builder.
     ///
     /// The `style` parameter accepts either `Fill` or `&Stroke` types.
-    pub fn draw(mut self, style: impl Into<StyleRef<'a>>, glyphs: impl Iterator<Item = Glyph>) {
+    pub fn draw(self, style: impl Into<StyleRef<'a>>, glyphs: impl Iterator<Item = Glyph>) {
+        self.draw_style(style.into().to_owned(), glyphs);
+    }
+
+    /// Encodes a fill or stroke for a run of glyphs laid out by a shaper and consumes the
+    /// builder.
+    ///
+    /// The origin of each glyph is the sum of the advances of the glyphs before it, starting
+    /// from the origin of the run, and the glyph is drawn at its offset from that origin.
+    pub fn draw_shaped(self, style: impl Into<StyleRef<'a>>, glyphs: &[PositionedGlyph]) {
+        let (mut x, mut y) = (0.0, 0.0);
+        let glyphs = glyphs.iter().map(|glyph| {
+            let positioned = Glyph {
+                id: glyph.id,
+                x: x + glyph.x_offset,
+                y: y + glyph.y_offset,
+            };
+            x += glyph.x_advance;
+            y += glyph.y_advance;
+            positioned
+        });
+        self.draw(style, glyphs);
+    }
+
+    /// Encodes only the outlines of the given glyphs, stroked with the given width, and consumes
+    /// the builder.
+    ///
+    /// Unlike [`outline`](Self::outline), the interiors of the glyphs are left unfilled, which
+    /// gives hollow lettering.
+    pub fn draw_hollow(self, width: f64, glyphs: impl Iterator<Item = Glyph>) {
+        self.draw_style(Style::Stroke(Stroke::new(width)), glyphs);
+    }
+
+    /// Encodes a fill or stroke for the given sequence of glyphs, each with an optional brush
+    /// which replaces the brush of the run, and consumes the builder.
+    ///
+    /// Consecutive glyphs with the same brush are encoded together as a single run.
+    pub fn draw_with_brushes(
+        self,
+        style: impl Into<StyleRef<'a>>,
+        glyphs: impl Iterator<Item = (Glyph, Option<BrushRef<'a>>)>,
+    ) {
+        let style = style.into().to_owned();
+        let mut groups: Vec<(BrushRef<'a>, Vec<Glyph>)> = vec![];
+        for (glyph, brush) in glyphs {
+            let brush = brush.unwrap_or_else(|| self.brush.clone());
+            match groups.last_mut() {
+                Some((last, group)) if *last == brush => group.push(glyph),
+                _ => groups.push((brush, vec![glyph])),
+            }
+        }
+        if groups.is_empty() {
+            self.draw_style(style, std::iter::empty());
+            return;
+        }
+        let Self {
+            encoding,
+            run,
+            brush_alpha,
+            outline,
+            underline,
+            strikethrough,
+            missing_glyph,
+            ..
+        } = self;
+        // Every group shares the normalized coordinates of the run, so they're only removed if
+        // none of the groups are encoded.
+        let mut encoded = false;
+        for (brush, group) in groups {
+            let glyphs_start = encoding.resources.glyphs.len();
+            let run = GlyphRun {
+                glyphs: glyphs_start..glyphs_start,
+                stream_offsets: encoding.stream_offsets(),
+                ..run.clone()
+            };
+            encoded |= DrawGlyphs {
+                encoding: &mut *encoding,
+                run,
+                brush,
+                brush_alpha,
+                outline: outline.clone(),
+                underline,
+                strikethrough,
+                missing_glyph,
+            }
+            .encode_style(style.clone(), group.into_iter());
+        }
+        if !encoded {
+            encoding
+                .resources
+                .normalized_coords
+                .truncate(run.normalized_coords.start);
+        }
+    }
+
+    fn draw_style(mut self, style: Style, glyphs: impl Iterator<Item = Glyph>) {
+        if !self.encode_style(style, glyphs) {
+            self.encoding
+                .resources
+                .normalized_coords
+                .truncate(self.run.normalized_coords.start);
+        }
+    }
+
+    /// Encodes the glyph run with the given glyphs, returning false if none of them exist in
+    /// the font, in which case nothing is encoded.
+    fn encode_style(&mut self, style: Style, glyphs: impl Iterator<Item = Glyph>) -> bool {
+        // Fonts which can't be parsed keep all of their glyphs, as there's no way to tell
+        // which are missing, and draw nothing for them.
+        let n_glyphs = font_ref(&self.run.font)
+            .and_then(|font| font.maxp().ok())
+            .map_or(u32::MAX, |maxp| maxp.num_glyphs() as u32);
+        let missing_glyph = self.missing_glyph;
         let resources = &mut self.encoding.resources;
-        self.run.style = style.into().to_owned();
-        resources.glyphs.extend(glyphs);
+        self.run.style = style;
+        resources.glyphs.extend(glyphs.filter_map(|glyph| {
+            if glyph.id < n_glyphs {
+                return Some(glyph);
+            }
+            match missing_glyph {
+                MissingGlyph::Skip => None,
+                MissingGlyph::Notdef => Some(Glyph { id: 0, ..glyph }),
+                MissingGlyph::Fallback(fallback) => {
+                    fallback(glyph).filter(|glyph| glyph.id < n_glyphs)
+                }
+            }
+        }));
         self.run.glyphs.end = resources.glyphs.len();
         if self.run.glyphs.is_empty() {
-            resources
-                .normalized_coords
-                .truncate(self.run.normalized_coords.start);
-            return;
+            return false;
+        }
+        let (underline, strikethrough) = self.decorations();
+        if let Some(rect) = underline {
+            self.encode_decoration(rect);
+            self.run.stream_offsets = self.encoding.stream_offsets();
+        }
+        if let Some((stroke, brush)) = self.outline.take() {
+            // The outline shares the glyphs of the run and is drawn first, so that the fill
+            // covers its inner half.
+            let outline = GlyphRun {
+                style: Style::Stroke(stroke),
+                ..self.run.clone()
+            };
+            encode_glyph_run(self.encoding, outline, brush, self.brush_alpha);
+            self.run.stream_offsets = self.encoding.stream_offsets();
+        }
+        let brush = self.brush.clone();
+        encode_glyph_run(self.encoding, self.run.clone(), brush, self.brush_alpha);
+        if let Some(rect) = strikethrough {
+            self.encode_decoration(rect);
+        }
+        true
+    }
+
+    /// Returns the underline and strikethrough rectangles of the run, in the coordinate space
+    /// of the run transform, for those decorations which are enabled.
+    fn decorations(&self) -> (Option<Rect>, Option<Rect>) {
+        if !self.underline && !self.strikethrough {
+            return (None, None);
+        }
+        let Some(font) = font_ref(&self.run.font) else {
+            return (None, None);
+        };
+        let resources = &self.encoding.resources;
+        let glyphs = &resources.glyphs[self.run.glyphs.clone()];
+        let coords = &resources.normalized_coords[self.run.normalized_coords.clone()];
+        let size = Size::new(self.run.font_size);
+        let location = LocationRef::new(coords);
+        let advances = font.glyph_metrics(size, location);
+        let (mut x0, mut x1) = (f32::INFINITY, f32::NEG_INFINITY);
+        for glyph in glyphs {
+            let advance = advances
+                .advance_width(GlyphId::new(glyph.id as u16))
+                .unwrap_or_default();
+            x0 = x0.min(glyph.x);
+            x1 = x1.max(glyph.x + advance);
+        }
+        let baseline = glyphs[0].y as f64;
+        let metrics = font.metrics(size, location);
+        // Decoration offsets are to the top of the line and point up from the baseline.
+        let rect = |enabled: bool, decoration: Option<Decoration>| {
+            let decoration = decoration.filter(|_| enabled)?;
+            let y0 = baseline - decoration.offset as f64;
+            Some(Rect::new(
+                x0 as f64,
+                y0,
+                x1 as f64,
+                y0 + decoration.thickness as f64,
+            ))
+        };
+        (
+            rect(self.underline, metrics.underline),
+            rect(self.strikethrough, metrics.strikeout),
+        )
+    }
+
+    fn encode_decoration(&mut self, rect: Rect) {
+        self.encoding.encode_transform(self.run.transform);
+        self.encoding.encode_fill_style(Fill::NonZero);
+        if self.encoding.encode_shape(&rect, true) {
+            self.encoding
+                .encode_brush(self.brush.clone(), self.brush_alpha);
+        }
+    }
+}
+
+/// Parses the font, or the font at its index in a collection.
+pub(crate) fn font_ref(font: &Font) -> Option<skrifa::FontRef<'_>> {
+    match skrifa::raw::FileRef::new(font.data.as_ref()).ok()? {
+        skrifa::raw::FileRef::Font(font) => Some(font),
+        skrifa::raw::FileRef::Collection(collection) => collection.get(font.index).ok(),
+    }
+}
+
+fn encode_glyph_run(encoding: &mut Encoding, mut run: GlyphRun, brush: BrushRef, brush_alpha: f32) {
+    run.clip_rect = encoding.clip_rect_index();
+    let resources = &mut encoding.resources;
+    let index = resources.glyph_runs.len();
+    resources.glyph_runs.push(run);
+    resources.patches.push(Patch::GlyphRun { index });
+    encoding.encode_brush(brush, brush_alpha);
+    // Glyph run resolve step affects transform and style state in a way
+    // that is opaque to the current encoding.
+    // See <https://github.com/linebender/vello/issues/424>
+    encoding.force_next_transform_and_style();
+}
+
+/// Returns a linear gradient which repeats `colors` as stripes of the given `width`, for
+/// filling with a stripe pattern.
+///
+/// The stripes run perpendicular to the direction at `angle` radians clockwise from the
+/// positive x axis, and the first stripe starts at the origin of the brush space. The edges
+/// between stripes are hard. An empty slice of colors gives a gradient which draws nothing.
+pub fn stripes(angle: f64, width: f64, colors: &[Color]) -> Gradient {
+    let period = width * colors.len() as f64;
+    let end = Point::new(angle.cos() * period, angle.sin() * period);
+    let mut gradient = Gradient::new_linear(Point::ORIGIN, end).with_extend(Extend::Repeat);
+    let n = colors.len() as f32;
+    for (i, &color) in colors.iter().enumerate() {
+        for edge in [i, i + 1] {
+            gradient.stops.push(ColorStop {
+                offset: edge as f32 / n,
+                color,
+            });
+        }
+    }
+    gradient
+}
+
+/// Returns the smallest and largest widths, in device pixels, that `stroke` renders at when
+/// drawn with `transform`.
+///
+/// The two are equal for transforms which scale uniformly, such as a rotation combined with
+/// a DPI scale. Under a non-uniform scale or a skew, the width of the stroke varies with the
+/// direction of the path, from the first value to the second. Translation has no effect.
+pub fn transformed_stroke_width(stroke: &Stroke, transform: Affine) -> (f64, f64) {
+    // The extremes are the singular values of the linear part of the transform.
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    let sum = (a + d).hypot(b - c);
+    let difference = (a - d).hypot(b + c);
+    let width = stroke.width.abs();
+    (
+        0.5 * (sum - difference).abs() * width,
+        0.5 * (sum + difference) * width,
+    )
+}
+
+/// Returns the color of the gradient with `stops` at offset `t`, interpolated in the same
+/// unpremultiplied space as the gradient ramps.
+fn color_at(stops: &ColorStops, t: f32) -> Color {
+    let Some(next) = stops.iter().position(|stop| stop.offset >= t) else {
+        return stops.last().map_or(Color::TRANSPARENT, |stop| stop.color);
+    };
+    if next == 0 {
+        return stops[0].color;
+    }
+    let (a, b) = (stops[next - 1], stops[next]);
+    let du = b.offset - a.offset;
+    if du < 1e-9 {
+        return b.color;
+    }
+    let t = ((t - a.offset) / du) as f64;
+    let lerp = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * t).round() as u8;
+    Color::rgba8(
+        lerp(a.color.r, b.color.r),
+        lerp(a.color.g, b.color.g),
+        lerp(a.color.b, b.color.b),
+        lerp(a.color.a, b.color.a),
+    )
+}
+
+/// Returns the part of `stops` between offsets `t0` and `t1`, rescaled to cover `0..=1`.
+fn stops_between(stops: &ColorStops, t0: f32, t1: f32) -> ColorStops {
+    let color_at = |t: f32| color_at(stops, t);
+    let scale = |t: f32| if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+    let mut result = ColorStops::new();
+    result.push(ColorStop {
+        offset: 0.0,
+        color: color_at(t0),
+    });
+    result.extend(
+        stops
+            .iter()
+            .filter(|stop| stop.offset > t0 && stop.offset < t1)
+            .map(|stop| ColorStop {
+                offset: scale(stop.offset),
+                color: stop.color,
+            }),
+    );
+    result.push(ColorStop {
+        offset: 1.0,
+        color: color_at(t1),
+    });
+    result
+}
+
+/// Returns stops which divide `0..=1` into `steps` equal bands of solid color, sampling
+/// `stops` evenly from the first band to the last.
+fn stepped_stops(stops: &ColorStops, steps: u32) -> ColorStops {
+    let mut result = ColorStops::new();
+    for k in 0..steps {
+        let color = color_at(stops, k as f32 / (steps - 1) as f32);
+        for edge in [k, k + 1] {
+            result.push(ColorStop {
+                offset: edge as f32 / steps as f32,
+                color,
+            });
         }
-        let index = resources.glyph_runs.len();
-        resources.glyph_runs.push(self.run);
-        resources.patches.push(Patch::GlyphRun { index });
-        self.encoding.encode_brush(self.brush, self.brush_alpha);
-        // Glyph run resolve step affects transform and style state in a way
-        // that is opaque to the current encoding.
-        // See <https://github.com/linebender/vello/issues/424>
-        self.encoding.force_next_transform_and_style();
     }
+    result
 }
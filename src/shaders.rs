@@ -29,7 +29,7 @@ use crate::{
 };
 
 #[cfg(feature = "wgpu")]
-use crate::{wgpu_engine::WgpuEngine, RendererOptions};
+use crate::{wgpu_engine::WgpuEngine, BinSize, RendererOptions};
 
 macro_rules! shader {
     ($name:expr) => {&{
@@ -84,6 +84,15 @@ pub struct FullShaders {
     // 2-level dispatch works for CPU pathtag scan even for large
     // inputs, 3-level is not yet implemented.
     pub pathtag_is_cpu: bool,
+    // Width of the bins used by binning and coarse, in tiles.
+    pub bin_width: u32,
+    // Number of draw objects in each bin of a partition before binning spills into an
+    // overflow chunk.
+    pub bin_capacity: u32,
+    // Horizontal position of the sample in each row of a pixel for the MSAA modes, if the
+    // built-in patterns are replaced.
+    pub msaa8_positions: Option<[f32; 8]>,
+    pub msaa16_positions: Option<[f32; 16]>,
 }
 
 #[cfg(feature = "wgpu")]
@@ -249,6 +258,8 @@ pub fn full_shaders(
             Buffer,
             Buffer,
             Buffer,
+            BufReadOnly,
+            Buffer,
         ],
         &empty
     );
@@ -273,6 +284,8 @@ pub fn full_shaders(
         BindType::Image(ImageFormat::Rgba8),
         BindType::ImageRead(ImageFormat::Rgba8),
         BindType::ImageRead(ImageFormat::Rgba8),
+        // Output transfer function LUT buffer.
+        BindType::BufReadOnly,
         // Mask LUT buffer, used only when MSAA is enabled.
         BindType::BufReadOnly,
     ];
@@ -327,6 +340,10 @@ pub fn full_shaders(
         fine_msaa8,
         fine_msaa16,
         pathtag_is_cpu: options.use_cpu,
+        bin_width: options.bin_size.width,
+        bin_capacity: BinSize::TILES,
+        msaa8_positions: None,
+        msaa16_positions: None,
     })
 }
 
@@ -0,0 +1,136 @@
+// Copyright 2023 The Vello authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use peniko::{BlendMode, Color, Mix};
+use wgpu::{Device, Queue, Texture};
+
+use crate::{RenderParams, Renderer, Result, Scene};
+
+/// A scene which is rendered in chunks as it is built, so that it never has to be held in
+/// memory as a whole.
+///
+/// Created by [`Renderer::stream_to_texture`]. Each call to [`draw`](Self::draw) encodes a
+/// group of draws, and whenever the next group would take the encoded chunk past the
+/// memory cap, the chunk is rendered and composited over the contents of the target before
+/// the group is added to a new chunk. Call [`finish`](Self::finish) to render the last
+/// chunk.
+pub struct SceneStream<'a> {
+    renderer: &'a mut Renderer,
+    device: &'a Device,
+    queue: &'a Queue,
+    texture: &'a Texture,
+    params: RenderParams,
+    memory_cap: usize,
+    chunk: Scene,
+    pending: Scene,
+    chunks_rendered: usize,
+    peak_chunk_size: usize,
+}
+
+impl<'a> SceneStream<'a> {
+    pub(crate) fn new(
+        renderer: &'a mut Renderer,
+        device: &'a Device,
+        queue: &'a Queue,
+        texture: &'a Texture,
+        params: &RenderParams,
+        memory_cap: usize,
+    ) -> Self {
+        Self {
+            renderer,
+            device,
+            queue,
+            texture,
+            params: params.clone(),
+            memory_cap,
+            chunk: Scene::new(),
+            pending: Scene::new(),
+            chunks_rendered: 0,
+            peak_chunk_size: 0,
+        }
+    }
+
+    /// Encodes the draws made by `f`, first rendering the current chunk if they don't fit
+    /// alongside it.
+    ///
+    /// The draws are kept together in one chunk, so any layers pushed by `f` must also be
+    /// popped by it. Returns an error without encoding anything if `f` leaves a layer open,
+    /// or if the draws alone are larger than the memory cap.
+    pub fn draw(&mut self, f: impl FnOnce(&mut Scene)) -> Result<()> {
+        self.pending.reset();
+        f(&mut self.pending);
+        let open_layers = self.pending.encoding().n_open_clips;
+        if open_layers != 0 {
+            return Err(format!("draws left {open_layers} layers open").into());
+        }
+        let size = self.pending.stats().size_in_bytes();
+        if size > self.memory_cap {
+            return Err(format!(
+                "draws need {size} bytes, more than the memory cap of {}",
+                self.memory_cap
+            )
+            .into());
+        }
+        if self.chunk.stats().size_in_bytes() + size > self.memory_cap {
+            self.flush()?;
+        }
+        self.chunk.append(&self.pending, None);
+        Ok(())
+    }
+
+    /// Renders the last chunk, completing the frame.
+    pub fn finish(mut self) -> Result<()> {
+        if self.chunks_rendered == 0 || !self.chunk.encoding().is_empty() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// The number of chunks rendered so far.
+    pub fn chunks_rendered(&self) -> usize {
+        self.chunks_rendered
+    }
+
+    /// The encoded size in bytes of the largest chunk so far, as measured by
+    /// [`SceneStats::size_in_bytes`](crate::SceneStats::size_in_bytes).
+    pub fn peak_chunk_size(&self) -> usize {
+        self.peak_chunk_size.max(self.chunk.stats().size_in_bytes())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        // Blurring each chunk before it is composited would blur the earlier chunks again.
+        if self.params.final_blur.is_some_and(|std_dev| std_dev > 0.) {
+            return Err("final_blur is not supported when streaming a scene".into());
+        }
+        self.peak_chunk_size = self.peak_chunk_size();
+        if self.chunks_rendered == 0 {
+            let view = self
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            self.renderer.render_to_texture(
+                self.device,
+                self.queue,
+                &self.chunk,
+                &view,
+                &self.params,
+            )?;
+        } else {
+            // Later chunks are drawn over the earlier ones, as if they were a single scene.
+            let params = RenderParams {
+                base_color: Color::TRANSPARENT,
+                ..self.params.clone()
+            };
+            self.renderer.render_to_texture_composited(
+                self.device,
+                self.queue,
+                &self.chunk,
+                self.texture,
+                &params,
+                BlendMode::from(Mix::Normal),
+            )?;
+        }
+        self.chunks_rendered += 1;
+        self.chunk.reset();
+        Ok(())
+    }
+}
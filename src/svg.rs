@@ -0,0 +1,541 @@
+// Copyright 2023 The Vello authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of an encoded scene into an SVG document, and the bounds of its draw objects.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use peniko::kurbo::{Affine, BezPath, PathEl, Point, Rect, Shape};
+use peniko::{Extend, Mix};
+use vello_encoding::{
+    math::f16_to_f32, DrawBeginClip, DrawBilinearGradient, DrawColor, DrawLinearGradient,
+    DrawMonoid, DrawRadialGradient, DrawTag, Encoding, Monoid, Patch, PathTag, Style, Transform,
+};
+
+type Range = std::ops::Range<usize>;
+
+/// An encoded segment, with its start point followed by the rest of its control points.
+type Segment = (PathTag, Vec<Point>);
+
+/// A path object decoded from the path tag stream.
+struct DecodedPath {
+    /// Transform of the first segment, which the path is expressed relative to.
+    transform: Affine,
+    /// Transform at the path marker, which applies to the brush.
+    brush_transform: Affine,
+    style: Style,
+    path: BezPath,
+    /// The path as a single axis aligned rectangle, if it is one.
+    rect: Option<Rect>,
+}
+
+/// Reads the paths of `encoding` in order, one for each path marker.
+fn decode_paths(encoding: &Encoding) -> Vec<DecodedPath> {
+    let mut paths = vec![];
+    let mut transform = Transform::IDENTITY;
+    let mut style = Style::from_fill(peniko::Fill::NonZero);
+    let mut n_transforms = 0;
+    let mut n_styles = 0;
+    let mut offset = 0;
+    let data = &encoding.path_data;
+    // Segments of the current path, grouped into subpaths along with the transform of each.
+    let mut subpaths: Vec<(Vec<Segment>, Affine)> = vec![];
+    let mut current: Vec<Segment> = vec![];
+    let mut first_transform = None;
+    let mut uniform_transform = true;
+    for &tag in &encoding.path_tags {
+        if tag == PathTag::TRANSFORM {
+            transform = encoding
+                .transforms
+                .get(n_transforms)
+                .copied()
+                .unwrap_or(Transform::IDENTITY);
+            n_transforms += 1;
+        } else if tag == PathTag::STYLE {
+            if let Some(next) = encoding.styles.get(n_styles) {
+                style = *next;
+            }
+            n_styles += 1;
+        } else if tag == PathTag::PATH {
+            let is_stroke = style.flags_and_miter_limit & Style::FLAGS_STYLE_BIT != 0;
+            let path_transform = first_transform.take().unwrap_or(transform.to_kurbo());
+            let mut path = BezPath::new();
+            let mut rect = None;
+            let n_subpaths = subpaths.len();
+            for (mut segments, segment_transform) in subpaths.drain(..) {
+                // Strokes end every subpath with a marker segment carrying the cap
+                // information, which isn't part of the shape. It's a line for a closed
+                // subpath.
+                let closed = if is_stroke {
+                    segments
+                        .pop()
+                        .is_some_and(|(tag, _)| tag.path_segment_type().0 == 1)
+                } else {
+                    true
+                };
+                // Paths drawn with several transforms are emitted in device space.
+                let to_path = if uniform_transform {
+                    Affine::IDENTITY
+                } else {
+                    segment_transform
+                };
+                let mut first = true;
+                for (tag, points) in &segments {
+                    let pts: Vec<Point> = points.iter().map(|p| to_path * *p).collect();
+                    if first {
+                        path.move_to(pts[0]);
+                        first = false;
+                    }
+                    match tag.path_segment_type().0 {
+                        1 => path.line_to(pts[1]),
+                        2 => path.quad_to(pts[1], pts[2]),
+                        _ => path.curve_to(pts[1], pts[2], pts[3]),
+                    }
+                }
+                if closed && !segments.is_empty() {
+                    path.close_path();
+                }
+                if n_subpaths == 1 && closed {
+                    rect = as_rect(&segments);
+                }
+            }
+            paths.push(DecodedPath {
+                transform: if uniform_transform {
+                    path_transform
+                } else {
+                    Affine::IDENTITY
+                },
+                brush_transform: transform.to_kurbo(),
+                style,
+                path,
+                rect: rect.filter(|_| uniform_transform),
+            });
+            current.clear();
+            uniform_transform = true;
+        } else if tag.is_path_segment() {
+            let point_size = if tag.is_f32() { 8 } else { 4 };
+            let read_point = |offset: usize| {
+                if tag.is_f32() {
+                    let xy: [f32; 2] = bytemuck::pod_read_unaligned(&data[offset..offset + 8]);
+                    Point::new(xy[0] as f64, xy[1] as f64)
+                } else {
+                    let xy: [i16; 2] = bytemuck::pod_read_unaligned(&data[offset..offset + 4]);
+                    Point::new(xy[0] as f64, xy[1] as f64)
+                }
+            };
+            let n_points = tag.path_segment_type().0 as usize;
+            let n_read = n_points + current.is_empty() as usize;
+            if offset + n_read * point_size > data.len() {
+                break;
+            }
+            // A segment's start point is the end of the previous segment, except at the
+            // start of a subpath, where it is encoded on its own.
+            let start = match current.last() {
+                Some((_, points)) => *points.last().unwrap(),
+                None => {
+                    let start = read_point(offset);
+                    offset += point_size;
+                    start
+                }
+            };
+            let mut points = vec![start];
+            for _ in 0..n_points {
+                points.push(read_point(offset));
+                offset += point_size;
+            }
+            let affine = transform.to_kurbo();
+            match first_transform {
+                None => first_transform = Some(affine),
+                Some(first) if first != affine => uniform_transform = false,
+                _ => {}
+            }
+            current.push((tag, points));
+            if tag.is_subpath_end() {
+                subpaths.push((std::mem::take(&mut current), affine));
+            }
+        }
+    }
+    paths
+}
+
+/// Returns the indices of the draw objects of glyph runs, which get their paths when the scene
+/// is resolved, so they have no path here.
+fn glyph_draws(encoding: &Encoding) -> HashSet<usize> {
+    encoding
+        .resources
+        .glyph_runs
+        .iter()
+        .map(|run| run.stream_offsets.draw_tags)
+        .collect()
+}
+
+/// Returns the bounding box of a path in device space, widened by half the line width of
+/// strokes, or `None` if it has no segments.
+fn device_bbox(path: &DecodedPath) -> Option<Rect> {
+    if path.path.elements().is_empty() {
+        return None;
+    }
+    let mut bbox = path.path.bounding_box();
+    if path.style.flags_and_miter_limit & Style::FLAGS_STYLE_BIT != 0 {
+        let half_width = path.style.line_width as f64 * 0.5;
+        bbox = bbox.inflate(half_width, half_width);
+    }
+    Some(path.transform.transform_rect_bbox(bbox))
+}
+
+/// Returns the rectangle traced by a closed subpath of four axis aligned lines.
+fn as_rect(segments: &[Segment]) -> Option<Rect> {
+    if segments.len() != 4
+        || segments
+            .iter()
+            .any(|(tag, _)| tag.path_segment_type().0 != 1)
+    {
+        return None;
+    }
+    let points: Vec<Point> = segments.iter().map(|(_, points)| points[0]).collect();
+    if segments[3].1[1] != points[0] {
+        return None;
+    }
+    let [p0, p1, p2, p3] = [points[0], points[1], points[2], points[3]];
+    // Consecutive edges alternate between horizontal and vertical.
+    let axis_aligned = (p0.x == p1.x && p1.y == p2.y && p2.x == p3.x && p3.y == p0.y)
+        || (p0.y == p1.y && p1.x == p2.x && p2.y == p3.y && p3.x == p0.x);
+    axis_aligned.then(|| Rect::from_points(points[0], points[2]))
+}
+
+fn write_transform(svg: &mut String, name: &str, transform: Affine) {
+    if transform != Affine::IDENTITY {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        let _ = write!(svg, r#" {name}="matrix({a} {b} {c} {d} {e} {f})""#);
+    }
+}
+
+/// Writes the commands of `path`, with coordinates at the precision they were encoded with.
+fn write_path_data(svg: &mut String, path: &BezPath) {
+    for (i, el) in path.elements().iter().enumerate() {
+        if i > 0 {
+            svg.push(' ');
+        }
+        let (command, points) = match *el {
+            PathEl::MoveTo(p) => ('M', [p].to_vec()),
+            PathEl::LineTo(p) => ('L', [p].to_vec()),
+            PathEl::QuadTo(p1, p2) => ('Q', [p1, p2].to_vec()),
+            PathEl::CurveTo(p1, p2, p3) => ('C', [p1, p2, p3].to_vec()),
+            PathEl::ClosePath => ('Z', vec![]),
+        };
+        svg.push(command);
+        for (j, p) in points.iter().enumerate() {
+            let separator = if j > 0 { " " } else { "" };
+            let _ = write!(svg, "{separator}{} {}", p.x as f32, p.y as f32);
+        }
+    }
+}
+
+fn write_shape(svg: &mut String, path: &DecodedPath) {
+    match path.rect {
+        Some(rect) => {
+            let _ = write!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}""#,
+                rect.x0,
+                rect.y0,
+                rect.width(),
+                rect.height()
+            );
+        }
+        None => {
+            svg.push_str(r#"<path d=""#);
+            write_path_data(svg, &path.path);
+            svg.push('"');
+        }
+    }
+    write_transform(svg, "transform", path.transform);
+}
+
+/// Returns the unpremultiplied color of a packed premultiplied color, and its opacity.
+fn unpremultiply(rgba: u32) -> (String, f32) {
+    let a = rgba & 0xff;
+    if a == 0 {
+        return ("#000000".into(), 0.0);
+    }
+    let channel = |shift: u32| ((rgba >> shift & 0xff) * 255 + a / 2) / a;
+    let color = format!("#{:02x}{:02x}{:02x}", channel(24), channel(16), channel(8));
+    (color, a as f32 / 255.0)
+}
+
+fn paint_attributes(svg: &mut String, paint: &str, color: &str, opacity: f32) {
+    let _ = write!(svg, r#" {paint}="{color}""#);
+    if opacity < 1.0 {
+        let _ = write!(svg, r#" {paint}-opacity="{opacity}""#);
+    }
+}
+
+fn style_attributes(svg: &mut String, style: &Style, paint: &str, opacity: f32) {
+    let flags = style.flags_and_miter_limit;
+    if flags & Style::FLAGS_STYLE_BIT == 0 {
+        paint_attributes(svg, "fill", paint, opacity);
+        if flags & Style::FLAGS_FILL_BIT != 0 {
+            svg.push_str(r#" fill-rule="evenodd""#);
+        }
+        return;
+    }
+    svg.push_str(r#" fill="none""#);
+    paint_attributes(svg, "stroke", paint, opacity);
+    let _ = write!(svg, r#" stroke-width="{}""#, style.line_width);
+    let join = match flags & Style::FLAGS_JOIN_MASK {
+        Style::FLAGS_JOIN_BITS_BEVEL => "bevel",
+        Style::FLAGS_JOIN_BITS_ROUND => "round",
+        _ => "miter",
+    };
+    let _ = write!(svg, r#" stroke-linejoin="{join}""#);
+    if join == "miter" {
+        let miter_limit = f16_to_f32((flags & Style::MITER_LIMIT_MASK) as u16);
+        let _ = write!(svg, r#" stroke-miterlimit="{miter_limit}""#);
+    }
+    // SVG has a single cap style, so the end cap is used for both ends.
+    let cap = match flags & Style::FLAGS_END_CAP_MASK {
+        Style::FLAGS_END_CAP_BITS_ROUND => "round",
+        Style::FLAGS_END_CAP_BITS_SQUARE => "square",
+        _ => "butt",
+    };
+    let _ = write!(svg, r#" stroke-linecap="{cap}""#);
+}
+
+fn write_stops(svg: &mut String, encoding: &Encoding, ramp: Option<&(Range, Extend)>) {
+    let Some((stops, _)) = ramp else {
+        return;
+    };
+    for stop in &encoding.resources.color_stops[stops.clone()] {
+        let color = stop.color;
+        let _ = write!(
+            svg,
+            r##"<stop offset="{}" stop-color="#{:02x}{:02x}{:02x}""##,
+            stop.offset, color.r, color.g, color.b
+        );
+        if color.a < 255 {
+            let _ = write!(svg, r#" stop-opacity="{}""#, color.a as f32 / 255.0);
+        }
+        svg.push_str("/>");
+    }
+}
+
+fn spread_method(ramp: Option<&(Range, Extend)>) -> &'static str {
+    match ramp.map(|(_, extend)| *extend) {
+        Some(Extend::Repeat) => "repeat",
+        Some(Extend::Reflect) => "reflect",
+        _ => "pad",
+    }
+}
+
+fn mix_blend_mode(mix: u32) -> Option<&'static str> {
+    const MODES: [&str; 16] = [
+        "normal",
+        "multiply",
+        "screen",
+        "overlay",
+        "darken",
+        "lighten",
+        "color-dodge",
+        "color-burn",
+        "hard-light",
+        "soft-light",
+        "difference",
+        "exclusion",
+        "hue",
+        "saturation",
+        "color",
+        "luminosity",
+    ];
+    if mix == Mix::Normal as u32 {
+        return None;
+    }
+    MODES.get(mix as usize).copied()
+}
+
+/// Writes `encoding` as an SVG document.
+pub(crate) fn encoding_to_svg(encoding: &Encoding) -> String {
+    let paths = decode_paths(encoding);
+    let ramps: HashMap<usize, (Range, Extend)> = encoding
+        .resources
+        .patches
+        .iter()
+        .filter_map(|patch| match patch {
+            Patch::Ramp {
+                draw_data_offset,
+                stops,
+                extend,
+                ..
+            } => Some((*draw_data_offset, (stops.clone(), *extend))),
+            _ => None,
+        })
+        .collect();
+    let glyph_draws = glyph_draws(encoding);
+
+    let mut body = String::new();
+    let mut bounds: Option<Rect> = None;
+    let mut paths = paths.iter();
+    let mut draw_data_offset = 0;
+    let mut n_defs = 0;
+    let mut open_groups = 0;
+    for (ix, &tag) in encoding.draw_tags.iter().enumerate() {
+        let offset = draw_data_offset;
+        draw_data_offset += DrawMonoid::new(tag).scene_offset as usize * 4;
+        let Some(draw_data) = encoding.draw_data.get(offset..draw_data_offset) else {
+            break;
+        };
+        if tag == DrawTag::NOP || glyph_draws.contains(&ix) {
+            continue;
+        }
+        let Some(path) = paths.next() else {
+            break;
+        };
+        if tag == DrawTag::END_CLIP {
+            if open_groups > 0 {
+                body.push_str("</g>");
+                open_groups -= 1;
+            }
+            continue;
+        }
+        if let Some(device_bbox) = device_bbox(path) {
+            bounds = Some(bounds.map_or(device_bbox, |b| b.union(device_bbox)));
+        }
+        // Gradients are defined in brush space, which is expressed relative to the
+        // coordinate system of the path.
+        let gradient_transform = path.transform.inverse() * path.brush_transform;
+        let ramp = ramps.get(&offset);
+        let paint = match tag {
+            DrawTag::COLOR => {
+                let color: DrawColor = bytemuck::pod_read_unaligned(draw_data);
+                let (color, opacity) = unpremultiply(color.rgba);
+                write_shape(&mut body, path);
+                style_attributes(&mut body, &path.style, &color, opacity);
+                body.push_str("/>");
+                continue;
+            }
+            DrawTag::LINEAR_GRADIENT => {
+                let gradient: DrawLinearGradient = bytemuck::pod_read_unaligned(draw_data);
+                let id = format!("gradient{n_defs}");
+                let _ = write!(
+                    body,
+                    r#"<defs><linearGradient id="{id}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}" spreadMethod="{}""#,
+                    gradient.p0[0],
+                    gradient.p0[1],
+                    gradient.p1[0],
+                    gradient.p1[1],
+                    spread_method(ramp)
+                );
+                write_transform(&mut body, "gradientTransform", gradient_transform);
+                body.push('>');
+                write_stops(&mut body, encoding, ramp);
+                body.push_str("</linearGradient></defs>");
+                format!("url(#{id})")
+            }
+            DrawTag::RADIAL_GRADIENT => {
+                let gradient: DrawRadialGradient = bytemuck::pod_read_unaligned(draw_data);
+                let id = format!("gradient{n_defs}");
+                let _ = write!(
+                    body,
+                    r#"<defs><radialGradient id="{id}" gradientUnits="userSpaceOnUse" fx="{}" fy="{}" fr="{}" cx="{}" cy="{}" r="{}" spreadMethod="{}""#,
+                    gradient.p0[0],
+                    gradient.p0[1],
+                    gradient.r0,
+                    gradient.p1[0],
+                    gradient.p1[1],
+                    gradient.r1,
+                    spread_method(ramp)
+                );
+                write_transform(&mut body, "gradientTransform", gradient_transform);
+                body.push('>');
+                write_stops(&mut body, encoding, ramp);
+                body.push_str("</radialGradient></defs>");
+                format!("url(#{id})")
+            }
+            DrawTag::BILINEAR_GRADIENT => {
+                // There's no equivalent in SVG, so the patch is drawn with its average color.
+                let gradient: DrawBilinearGradient = bytemuck::pod_read_unaligned(draw_data);
+                let channel = |shift: u32| {
+                    let sum: u32 = gradient.colors.iter().map(|c| c >> shift & 0xff).sum();
+                    (sum + 2) / 4
+                };
+                let rgba = channel(24) << 24 | channel(16) << 16 | channel(8) << 8 | channel(0);
+                let (color, opacity) = unpremultiply(rgba);
+                write_shape(&mut body, path);
+                style_attributes(&mut body, &path.style, &color, opacity);
+                body.push_str("/>");
+                continue;
+            }
+            DrawTag::BEGIN_CLIP | DrawTag::BEGIN_CLIP_COLOR_MATRIX => {
+                let clip: DrawBeginClip = bytemuck::pod_read_unaligned(&draw_data[..28]);
+                let id = format!("clip{n_defs}");
+                n_defs += 1;
+                let _ = write!(body, r#"<clipPath id="{id}">"#);
+                write_shape(&mut body, path);
+                if path.style.flags_and_miter_limit & Style::FLAGS_FILL_BIT != 0 {
+                    body.push_str(r#" clip-rule="evenodd""#);
+                }
+                let _ = write!(body, r#"/></clipPath><g clip-path="url(#{id})""#);
+                if clip.alpha < 1.0 {
+                    let _ = write!(body, r#" opacity="{}""#, clip.alpha);
+                }
+                let mix = clip.blend_mode >> 8 & 0xff;
+                if let Some(mode) = mix_blend_mode(mix) {
+                    let _ = write!(body, r#" style="mix-blend-mode:{mode}""#);
+                }
+                body.push('>');
+                open_groups += 1;
+                continue;
+            }
+            // Images aren't embedded in the document.
+            _ => continue,
+        };
+        n_defs += 1;
+        write_shape(&mut body, path);
+        style_attributes(&mut body, &path.style, &paint, 1.0);
+        body.push_str("/>");
+    }
+    for _ in 0..open_groups {
+        body.push_str("</g>");
+    }
+
+    // The view covers the origin, where the target would be, and everything drawn.
+    let bounds = bounds.unwrap_or_default().union_pt(Point::ORIGIN).expand();
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{} {} {w} {h}">{body}</svg>"#,
+        bounds.x0,
+        bounds.y0,
+        w = bounds.width(),
+        h = bounds.height(),
+    )
+}
+
+/// Returns the device space bounding box of each draw object of `encoding` with a path,
+/// along with its index in the draw tag stream.
+pub(crate) fn encoding_draw_bounds(encoding: &Encoding) -> Vec<(usize, Rect)> {
+    let glyph_draws = glyph_draws(encoding);
+    let mut paths = decode_paths(encoding).into_iter();
+    let mut bounds = vec![];
+    for (ix, &tag) in encoding.draw_tags.iter().enumerate() {
+        if tag == DrawTag::NOP || glyph_draws.contains(&ix) {
+            continue;
+        }
+        let Some(path) = paths.next() else {
+            break;
+        };
+        // The end of a layer repeats the bounds of its clip shape.
+        if tag == DrawTag::END_CLIP {
+            continue;
+        }
+        let Some(mut bbox) = device_bbox(&path) else {
+            continue;
+        };
+        let clip_rect = (path.style.clip_rect as usize)
+            .checked_sub(1)
+            .and_then(|ix| encoding.clip_rects.get(ix));
+        if let Some(&[x0, y0, x1, y1]) = clip_rect {
+            bbox = bbox.intersect(Rect::new(x0 as f64, y0 as f64, x1 as f64, y1 as f64));
+        }
+        bounds.push((ix, bbox));
+    }
+    bounds
+}
@@ -0,0 +1,633 @@
+// Copyright 2023 The Vello authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Loading of scenes from a subset of SVG.
+
+use std::collections::HashMap;
+
+use peniko::kurbo::{
+    Affine, BezPath, Cap, Circle, Ellipse, Join, Line, Point, Rect, RoundedRect, Shape, Stroke,
+};
+use peniko::{Brush, Color, ColorStop, Extend, Fill, Gradient, Mix};
+use roxmltree::{Document, Node};
+
+use crate::{Result, Scene};
+
+/// Tolerance used when converting shapes into paths.
+const TOLERANCE: f64 = 0.1;
+
+/// Attributes which are read by the importer, rather than reported as unsupported.
+const SUPPORTED_ATTRIBUTES: &[&str] = &[
+    "id",
+    "style",
+    "transform",
+    "opacity",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "stroke",
+    "stroke-opacity",
+    "stroke-width",
+    "stroke-linejoin",
+    "stroke-linecap",
+    "stroke-miterlimit",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+];
+
+/// Element specific attributes read by the importer.
+fn element_attributes(name: &str) -> &'static [&'static str] {
+    match name {
+        "svg" => &["width", "height", "viewBox", "version"],
+        "path" => &["d"],
+        "rect" => &["x", "y", "width", "height", "rx", "ry"],
+        "circle" => &["cx", "cy", "r"],
+        "ellipse" => &["cx", "cy", "rx", "ry"],
+        "line" => &["x1", "y1", "x2", "y2"],
+        "polyline" | "polygon" => &["points"],
+        _ => &[],
+    }
+}
+
+/// A fill or stroke paint.
+#[derive(Clone)]
+enum Paint {
+    Color(Color),
+    /// Reference to a gradient by id.
+    Url(String),
+}
+
+/// Presentation properties which are inherited by the children of an element.
+#[derive(Clone)]
+struct Inherited {
+    fill_paint: Option<Paint>,
+    fill_opacity: f32,
+    fill_rule: Fill,
+    stroke_paint: Option<Paint>,
+    stroke_opacity: f32,
+    stroke: Stroke,
+}
+
+impl Default for Inherited {
+    fn default() -> Self {
+        Self {
+            fill_paint: Some(Paint::Color(Color::BLACK)),
+            fill_opacity: 1.0,
+            fill_rule: Fill::NonZero,
+            stroke_paint: None,
+            stroke_opacity: 1.0,
+            stroke: Stroke::new(1.0),
+        }
+    }
+}
+
+struct Importer<'a, 'input> {
+    scene: Scene,
+    gradients: HashMap<&'a str, Node<'a, 'input>>,
+    /// Area covered by layers, as they need a clip shape.
+    viewport: Rect,
+    diagnostics: Vec<String>,
+}
+
+/// Parses `svg` into a scene, returning it along with descriptions of the parts of the
+/// document which were skipped.
+pub(crate) fn scene_from_svg(svg: &str) -> Result<(Scene, Vec<String>)> {
+    let document = Document::parse(svg)?;
+    let root = document.root_element();
+    if !root.has_tag_name("svg") {
+        return Err(format!(
+            "expected an <svg> root element, found <{}>",
+            root.tag_name().name()
+        )
+        .into());
+    }
+    let gradients = root
+        .descendants()
+        .filter(|node| node.has_tag_name("linearGradient") || node.has_tag_name("radialGradient"))
+        .filter_map(|node| Some((node.attribute("id")?, node)))
+        .collect();
+    let mut importer = Importer {
+        scene: Scene::new(),
+        gradients,
+        viewport: Rect::ZERO,
+        diagnostics: vec![],
+    };
+    let transform = importer.viewport_transform(root);
+    importer.element(root, transform, &Inherited::default());
+    Ok((importer.scene, importer.diagnostics))
+}
+
+impl<'a, 'input> Importer<'a, 'input> {
+    fn unsupported(&mut self, message: String) {
+        self.diagnostics.push(message);
+    }
+
+    /// Returns the transform from the user space of the root element to the viewport, and
+    /// records the viewport.
+    fn viewport_transform(&mut self, root: Node) -> Affine {
+        let view_box = root.attribute("viewBox").map(numbers);
+        let view_box = view_box
+            .filter(|values| values.len() == 4 && values[2] > 0.0 && values[3] > 0.0)
+            .map(|values| {
+                Rect::new(
+                    values[0],
+                    values[1],
+                    values[0] + values[2],
+                    values[1] + values[3],
+                )
+            });
+        let width = root
+            .attribute("width")
+            .and_then(|w| self.length(root, "width", w));
+        let height = root
+            .attribute("height")
+            .and_then(|h| self.length(root, "height", h));
+        let Some(view_box) = view_box else {
+            self.viewport = Rect::new(0.0, 0.0, width.unwrap_or(1e6), height.unwrap_or(1e6));
+            return Affine::IDENTITY;
+        };
+        let width = width.unwrap_or(view_box.width());
+        let height = height.unwrap_or(view_box.height());
+        self.viewport = Rect::new(0.0, 0.0, width, height);
+        // The view box is scaled uniformly and centered, as with the default value of
+        // `preserveAspectRatio`.
+        let scale = (width / view_box.width()).min(height / view_box.height());
+        let offset = (
+            (width - view_box.width() * scale) * 0.5,
+            (height - view_box.height() * scale) * 0.5,
+        );
+        Affine::translate(offset)
+            * Affine::scale(scale)
+            * Affine::translate((-view_box.x0, -view_box.y0))
+    }
+
+    fn length(&mut self, node: Node, name: &str, value: &str) -> Option<f64> {
+        let value = value.trim();
+        let number = value.strip_suffix("px").unwrap_or(value);
+        match number.trim().parse() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                self.unsupported(format!(
+                    "unsupported length `{value}` for `{name}` on <{}>",
+                    node.tag_name().name()
+                ));
+                None
+            }
+        }
+    }
+
+    fn number(&mut self, node: Node, name: &str, default: f64) -> f64 {
+        match node.attribute(name) {
+            Some(value) => self.length(node, name, value).unwrap_or(default),
+            None => default,
+        }
+    }
+
+    fn paint(&mut self, node: Node, value: &str) -> Option<Paint> {
+        let value = value.trim();
+        if value == "none" {
+            return None;
+        }
+        if let Some(id) = value
+            .strip_prefix("url(")
+            .and_then(|url| url.split(')').next())
+            .map(|url| url.trim().trim_start_matches('#'))
+        {
+            return Some(Paint::Url(id.to_string()));
+        }
+        match parse_color(value) {
+            Some(color) => Some(Paint::Color(color)),
+            None => {
+                self.unsupported(format!(
+                    "unsupported paint `{value}` on <{}>",
+                    node.tag_name().name()
+                ));
+                None
+            }
+        }
+    }
+
+    fn inherit(&mut self, node: Node, parent: &Inherited) -> Inherited {
+        let mut style = parent.clone();
+        if let Some(fill) = property(node, "fill") {
+            style.fill_paint = self.paint(node, fill);
+        }
+        if let Some(stroke) = property(node, "stroke") {
+            style.stroke_paint = self.paint(node, stroke);
+        }
+        if let Some(opacity) = property(node, "fill-opacity").and_then(parse_opacity) {
+            style.fill_opacity = opacity;
+        }
+        if let Some(opacity) = property(node, "stroke-opacity").and_then(parse_opacity) {
+            style.stroke_opacity = opacity;
+        }
+        match property(node, "fill-rule") {
+            Some("evenodd") => style.fill_rule = Fill::EvenOdd,
+            Some("nonzero") => style.fill_rule = Fill::NonZero,
+            _ => {}
+        }
+        if let Some(width) = property(node, "stroke-width") {
+            if let Some(width) = self.length(node, "stroke-width", width) {
+                style.stroke.width = width;
+            }
+        }
+        match property(node, "stroke-linejoin") {
+            Some("miter") => style.stroke.join = Join::Miter,
+            Some("round") => style.stroke.join = Join::Round,
+            Some("bevel") => style.stroke.join = Join::Bevel,
+            _ => {}
+        }
+        let cap = match property(node, "stroke-linecap") {
+            Some("butt") => Some(Cap::Butt),
+            Some("round") => Some(Cap::Round),
+            Some("square") => Some(Cap::Square),
+            _ => None,
+        };
+        if let Some(cap) = cap {
+            style.stroke.start_cap = cap;
+            style.stroke.end_cap = cap;
+        }
+        if let Some(limit) = property(node, "stroke-miterlimit") {
+            if let Some(limit) = self.length(node, "stroke-miterlimit", limit) {
+                style.stroke.miter_limit = limit;
+            }
+        }
+        if let Some(dashes) = property(node, "stroke-dasharray") {
+            let pattern = if dashes.trim() == "none" {
+                vec![]
+            } else {
+                numbers(dashes)
+            };
+            style.stroke.dash_pattern = pattern.into_iter().collect();
+        }
+        if let Some(offset) = property(node, "stroke-dashoffset") {
+            if let Some(offset) = self.length(node, "stroke-dashoffset", offset) {
+                style.stroke.dash_offset = offset;
+            }
+        }
+        style
+    }
+
+    fn element(&mut self, node: Node<'a, 'input>, parent_transform: Affine, parent: &Inherited) {
+        let name = node.tag_name().name();
+        match name {
+            "svg" | "g" | "path" | "rect" | "circle" | "ellipse" | "line" | "polyline"
+            | "polygon" => {}
+            // Gradients are read when they are referenced.
+            "defs" | "linearGradient" | "radialGradient" | "title" | "desc" | "metadata" => {
+                return;
+            }
+            _ => {
+                self.unsupported(format!("unsupported element <{name}>"));
+                return;
+            }
+        }
+        for attribute in node.attributes() {
+            let supported = attribute.namespace().is_none()
+                && (SUPPORTED_ATTRIBUTES.contains(&attribute.name())
+                    || element_attributes(name).contains(&attribute.name()));
+            if !supported {
+                self.unsupported(format!(
+                    "unsupported attribute `{}` on <{name}>",
+                    attribute.name()
+                ));
+            }
+        }
+        let transform = match node.attribute("transform") {
+            Some(value) => match parse_transform(value) {
+                Some(transform) => parent_transform * transform,
+                None => {
+                    self.unsupported(format!("unsupported transform `{value}` on <{name}>"));
+                    parent_transform
+                }
+            },
+            None => parent_transform,
+        };
+        let style = self.inherit(node, parent);
+        let opacity = property(node, "opacity")
+            .and_then(parse_opacity)
+            .unwrap_or(1.0);
+        if opacity <= 0.0 {
+            return;
+        }
+        let layer = opacity < 1.0;
+        if layer {
+            let viewport = self.viewport;
+            self.scene
+                .push_layer(Mix::Normal, opacity, Affine::IDENTITY, &viewport);
+        }
+        match name {
+            "svg" | "g" => {
+                for child in node.children().filter(|child| child.is_element()) {
+                    self.element(child, transform, &style);
+                }
+            }
+            _ => {
+                if let Some(path) = self.shape(node) {
+                    self.draw(node, transform, &style, &path);
+                }
+            }
+        }
+        if layer {
+            self.scene.pop_layer();
+        }
+    }
+
+    fn shape(&mut self, node: Node) -> Option<BezPath> {
+        let name = node.tag_name().name();
+        let path = match name {
+            "path" => {
+                let data = node.attribute("d")?;
+                match BezPath::from_svg(data) {
+                    Ok(path) => path,
+                    Err(err) => {
+                        self.unsupported(format!("invalid path data `{data}`: {err}"));
+                        return None;
+                    }
+                }
+            }
+            "rect" => {
+                let x = self.number(node, "x", 0.0);
+                let y = self.number(node, "y", 0.0);
+                let width = self.number(node, "width", 0.0);
+                let height = self.number(node, "height", 0.0);
+                if width <= 0.0 || height <= 0.0 {
+                    return None;
+                }
+                let rx = node.attribute("rx").map(|_| self.number(node, "rx", 0.0));
+                let ry = node.attribute("ry").map(|_| self.number(node, "ry", 0.0));
+                let rect = Rect::new(x, y, x + width, y + height);
+                match (rx, ry) {
+                    (None, None) => rect.to_path(TOLERANCE),
+                    (rx, ry) => {
+                        let rx = rx.or(ry).unwrap_or_default();
+                        let ry = ry.unwrap_or(rx);
+                        if rx != ry {
+                            self.unsupported(format!(
+                                "elliptical corners of <rect> approximated with a radius of {rx}"
+                            ));
+                        }
+                        RoundedRect::from_rect(rect, rx.min(width * 0.5).min(height * 0.5))
+                            .to_path(TOLERANCE)
+                    }
+                }
+            }
+            "circle" => {
+                let center = Point::new(self.number(node, "cx", 0.0), self.number(node, "cy", 0.0));
+                let r = self.number(node, "r", 0.0);
+                if r <= 0.0 {
+                    return None;
+                }
+                Circle::new(center, r).to_path(TOLERANCE)
+            }
+            "ellipse" => {
+                let center = Point::new(self.number(node, "cx", 0.0), self.number(node, "cy", 0.0));
+                let rx = self.number(node, "rx", 0.0);
+                let ry = self.number(node, "ry", 0.0);
+                if rx <= 0.0 || ry <= 0.0 {
+                    return None;
+                }
+                Ellipse::new(center, (rx, ry), 0.0).to_path(TOLERANCE)
+            }
+            "line" => {
+                let p0 = Point::new(self.number(node, "x1", 0.0), self.number(node, "y1", 0.0));
+                let p1 = Point::new(self.number(node, "x2", 0.0), self.number(node, "y2", 0.0));
+                Line::new(p0, p1).to_path(TOLERANCE)
+            }
+            "polyline" | "polygon" => {
+                let values = numbers(node.attribute("points")?);
+                let mut path = BezPath::new();
+                for (i, xy) in values.chunks_exact(2).enumerate() {
+                    let point = Point::new(xy[0], xy[1]);
+                    if i == 0 {
+                        path.move_to(point);
+                    } else {
+                        path.line_to(point);
+                    }
+                }
+                if name == "polygon" {
+                    path.close_path();
+                }
+                path
+            }
+            _ => return None,
+        };
+        Some(path)
+    }
+
+    fn draw(&mut self, node: Node, transform: Affine, style: &Inherited, path: &BezPath) {
+        // Lines have no interior, so are only ever stroked.
+        let fill = style
+            .fill_paint
+            .as_ref()
+            .filter(|_| node.tag_name().name() != "line");
+        if let Some(paint) = fill {
+            if let Some((brush, brush_transform)) = self.brush(paint, style.fill_opacity, path) {
+                self.scene
+                    .fill(style.fill_rule, transform, &brush, brush_transform, path);
+            }
+        }
+        if let Some(paint) = &style.stroke_paint {
+            if style.stroke.width > 0.0 {
+                if let Some((brush, brush_transform)) =
+                    self.brush(paint, style.stroke_opacity, path)
+                {
+                    self.scene
+                        .stroke(&style.stroke, transform, &brush, brush_transform, path);
+                }
+            }
+        }
+    }
+
+    /// Returns the brush for `paint` applied to `path`, along with its transform.
+    fn brush(
+        &mut self,
+        paint: &Paint,
+        opacity: f32,
+        path: &BezPath,
+    ) -> Option<(Brush, Option<Affine>)> {
+        let id = match paint {
+            Paint::Color(color) => {
+                return Some((Brush::Solid(color.with_alpha_factor(opacity)), None));
+            }
+            Paint::Url(id) => id,
+        };
+        let Some(&node) = self.gradients.get(id.as_str()) else {
+            self.unsupported(format!("reference to unknown gradient `{id}`"));
+            return None;
+        };
+        if node
+            .attributes()
+            .any(|attribute| attribute.name() == "href")
+        {
+            self.unsupported(format!("gradient `{id}` references another gradient"));
+        }
+        let user_space = node.attribute("gradientUnits") == Some("userSpaceOnUse");
+        // Percentages are relative to the bounding box, or to the viewport in user space.
+        let base = if user_space {
+            self.viewport
+        } else {
+            Rect::new(0.0, 0.0, 1.0, 1.0)
+        };
+        let coord = |name: &str, default: &str, extent: f64| {
+            let value = node.attribute(name).unwrap_or(default).trim();
+            match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f64>().ok().map(|p| p / 100.0 * extent),
+                None => value.strip_suffix("px").unwrap_or(value).parse().ok(),
+            }
+        };
+        let (w, h) = (base.width(), base.height());
+        let diagonal = (w * w + h * h).sqrt() / std::f64::consts::SQRT_2;
+        let mut gradient = if node.has_tag_name("linearGradient") {
+            let x1 = coord("x1", "0%", w)?;
+            let y1 = coord("y1", "0%", h)?;
+            let x2 = coord("x2", "100%", w)?;
+            let y2 = coord("y2", "0%", h)?;
+            Gradient::new_linear((x1, y1), (x2, y2))
+        } else {
+            let cx = coord("cx", "50%", w)?;
+            let cy = coord("cy", "50%", h)?;
+            let r = coord("r", "50%", diagonal)?;
+            let fx = coord("fx", node.attribute("cx").unwrap_or("50%"), w)?;
+            let fy = coord("fy", node.attribute("cy").unwrap_or("50%"), h)?;
+            let fr = coord("fr", "0%", diagonal)?;
+            Gradient::new_two_point_radial((fx, fy), fr as f32, (cx, cy), r as f32)
+        };
+        gradient.extend = match node.attribute("spreadMethod") {
+            Some("repeat") => Extend::Repeat,
+            Some("reflect") => Extend::Reflect,
+            _ => Extend::Pad,
+        };
+        for stop in node.children().filter(|child| child.has_tag_name("stop")) {
+            let offset = stop.attribute("offset").unwrap_or("0").trim();
+            let offset = match offset.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().map(|p| p / 100.0),
+                None => offset.parse(),
+            }
+            .unwrap_or(0.0)
+            .clamp(0.0, 1.0);
+            let color = property(stop, "stop-color")
+                .and_then(parse_color)
+                .unwrap_or(Color::BLACK);
+            let stop_opacity = property(stop, "stop-opacity")
+                .and_then(parse_opacity)
+                .unwrap_or(1.0);
+            // Stop offsets never decrease.
+            let offset = gradient
+                .stops
+                .last()
+                .map_or(offset, |last| offset.max(last.offset));
+            gradient.stops.push(ColorStop {
+                offset,
+                color: color.with_alpha_factor(stop_opacity * opacity),
+            });
+        }
+        if gradient.stops.is_empty() {
+            self.unsupported(format!("gradient `{id}` has no stops"));
+            return None;
+        }
+        let mut brush_transform = match node.attribute("gradientTransform") {
+            Some(value) => parse_transform(value).unwrap_or_else(|| {
+                self.unsupported(format!(
+                    "unsupported transform `{value}` on gradient `{id}`"
+                ));
+                Affine::IDENTITY
+            }),
+            None => Affine::IDENTITY,
+        };
+        if !user_space {
+            let bbox = path.bounding_box();
+            if bbox.width() <= 0.0 || bbox.height() <= 0.0 {
+                return None;
+            }
+            brush_transform =
+                Affine::new([bbox.width(), 0.0, 0.0, bbox.height(), bbox.x0, bbox.y0])
+                    * brush_transform;
+        }
+        Some((Brush::Gradient(gradient), Some(brush_transform)))
+    }
+}
+
+/// Returns the value of a presentation attribute, with the `style` attribute taking
+/// precedence.
+fn property<'n>(node: Node<'n, '_>, name: &str) -> Option<&'n str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    });
+    from_style.or_else(|| node.attribute(name))
+}
+
+/// Parses a list of numbers separated by commas or whitespace.
+fn numbers(value: &str) -> Vec<f64> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+fn parse_opacity(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(args) = value
+        .strip_prefix("rgb(")
+        .or_else(|| value.strip_prefix("rgba("))
+        .and_then(|args| args.strip_suffix(')'))
+    {
+        let channels: Vec<&str> = args.split(',').map(str::trim).collect();
+        let channel = |s: &str| match s.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().ok().map(|p| p / 100.0),
+            None => s.parse::<f64>().ok().map(|c| c / 255.0),
+        };
+        return match channels[..] {
+            [r, g, b] => Some(Color::rgb(channel(r)?, channel(g)?, channel(b)?)),
+            [r, g, b, a] => Some(Color::rgba(
+                channel(r)?,
+                channel(g)?,
+                channel(b)?,
+                parse_opacity(a)? as f64,
+            )),
+            _ => None,
+        };
+    }
+    Color::parse(value)
+}
+
+/// Parses the value of a `transform` attribute.
+fn parse_transform(value: &str) -> Option<Affine> {
+    let mut transform = Affine::IDENTITY;
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (name, tail) = rest.split_once('(')?;
+        let (args, tail) = tail.split_once(')')?;
+        let args = numbers(args);
+        let next = match (name.trim(), &args[..]) {
+            ("matrix", &[a, b, c, d, e, f]) => Affine::new([a, b, c, d, e, f]),
+            ("translate", &[x]) => Affine::translate((x, 0.0)),
+            ("translate", &[x, y]) => Affine::translate((x, y)),
+            ("scale", &[s]) => Affine::scale(s),
+            ("scale", &[x, y]) => Affine::scale_non_uniform(x, y),
+            ("rotate", &[angle]) => Affine::rotate(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => {
+                Affine::rotate_about(angle.to_radians(), Point::new(x, y))
+            }
+            ("skewX", &[angle]) => Affine::skew(angle.to_radians().tan(), 0.0),
+            ("skewY", &[angle]) => Affine::skew(0.0, angle.to_radians().tan()),
+            _ => return None,
+        };
+        transform *= next;
+        rest = tail.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+    Some(transform)
+}
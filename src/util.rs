@@ -16,19 +16,23 @@
 
 //! Simple helpers for managing wgpu state and surfaces.
 
-use std::future::Future;
+use std::{future::Future, time::Duration};
+
+use instant::Instant;
 
 use super::Result;
 
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use wgpu::{
-    Adapter, Device, Instance, Limits, Queue, Surface, SurfaceConfiguration, TextureFormat,
+    Adapter, Device, Instance, Limits, Queue, Surface, SurfaceConfiguration, SurfaceTexture,
+    TextureFormat,
 };
 
 /// Simple render context that maintains wgpu state for rendering the pipeline.
 pub struct RenderContext {
     pub instance: Instance,
     pub devices: Vec<DeviceHandle>,
+    frame_pacer: FramePacer,
 }
 
 pub struct DeviceHandle {
@@ -44,10 +48,17 @@ impl RenderContext {
             dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
             ..Default::default()
         });
-        Ok(Self {
+        Ok(Self::from_instance(instance))
+    }
+
+    /// Creates a render context which uses an existing instance, such as one shared with
+    /// other subsystems of the application, to create its surfaces and devices.
+    pub fn from_instance(instance: Instance) -> Self {
+        Self {
             instance,
             devices: Vec::new(),
-        })
+            frame_pacer: FramePacer::default(),
+        }
     }
 
     /// Creates a new surface for the specified window and dimensions.
@@ -109,6 +120,21 @@ impl RenderContext {
         self.configure_surface(surface);
     }
 
+    /// Sets the minimum time between frames presented with [`Self::present`].
+    ///
+    /// A zero duration, the default, presents frames as soon as they are ready. Pacing only
+    /// slows presentation down, so with a vsync present mode a target shorter than the display
+    /// refresh interval has no effect.
+    pub fn set_target_frame_time(&mut self, target_frame_time: Duration) {
+        self.frame_pacer.set_target_frame_time(target_frame_time);
+    }
+
+    /// Presents a surface texture, first waiting as needed to hit the target frame time.
+    pub fn present(&mut self, surface_texture: SurfaceTexture) {
+        self.frame_pacer.wait();
+        surface_texture.present();
+    }
+
     fn configure_surface(&self, surface: &RenderSurface) {
         let device = &self.devices[surface.dev_id].device;
         // Temporary workaround for https://github.com/gfx-rs/wgpu/issues/4214
@@ -178,6 +204,55 @@ pub struct RenderSurface<'s> {
     pub format: TextureFormat,
 }
 
+/// Spaces out frames to approach a target frame time.
+#[derive(Default, Debug)]
+pub struct FramePacer {
+    target_frame_time: Duration,
+    next_frame: Option<Instant>,
+}
+
+impl FramePacer {
+    pub fn new(target_frame_time: Duration) -> Self {
+        Self {
+            target_frame_time,
+            next_frame: None,
+        }
+    }
+
+    pub fn target_frame_time(&self) -> Duration {
+        self.target_frame_time
+    }
+
+    pub fn set_target_frame_time(&mut self, target_frame_time: Duration) {
+        self.target_frame_time = target_frame_time;
+        self.next_frame = None;
+    }
+
+    /// Blocks until the target frame time has passed since the previous frame.
+    ///
+    /// Frames are scheduled relative to when the previous one was due rather than when it
+    /// actually finished waiting, so oversleeping doesn't accumulate. A frame which is already
+    /// late doesn't wait, and restarts the schedule from the current time.
+    ///
+    /// On wasm32 this never blocks, as the browser's main thread can't sleep; frames there
+    /// should be paced with `requestAnimationFrame` instead.
+    pub fn wait(&mut self) {
+        if self.target_frame_time.is_zero() {
+            return;
+        }
+        let now = Instant::now();
+        let frame = match self.next_frame {
+            Some(next_frame) if next_frame > now => {
+                #[cfg(not(target_arch = "wasm32"))]
+                std::thread::sleep(next_frame - now);
+                next_frame
+            }
+            _ => now,
+        };
+        self.next_frame = Some(frame + self.target_frame_time);
+    }
+}
+
 struct NullWake;
 
 impl std::task::Wake for NullWake {
@@ -5,8 +5,14 @@ use std::{
     borrow::Cow,
     cell::RefCell,
     collections::{hash_map::Entry, HashMap, HashSet},
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
+use instant::Instant;
 use wgpu::{
     BindGroup, BindGroupLayout, Buffer, BufferUsages, CommandEncoder, CommandEncoderDescriptor,
     ComputePipeline, Device, Queue, Texture, TextureAspect, TextureUsages, TextureView,
@@ -19,12 +25,84 @@ use crate::{
     BufProxy, Command, Id, ImageProxy, Recording, ResourceProxy, ShaderId,
 };
 
+/// Source of the GPU buffers used by the renderer.
+///
+/// Buffers are pooled and reused between renders, so `create_buffer` is only called when
+/// no pooled buffer of a suitable size and usage is available. Buffers are handed back through
+/// `free_buffer` once the renderer no longer needs them, which happens when it is dropped or
+/// when a readback buffer is released.
+pub trait BufferAllocator: Send {
+    /// Creates a buffer matching the descriptor.
+    fn create_buffer(&mut self, device: &Device, descriptor: &wgpu::BufferDescriptor) -> Buffer;
+
+    /// Releases a buffer previously returned from `create_buffer`.
+    fn free_buffer(&mut self, buffer: Buffer) {
+        drop(buffer);
+    }
+}
+
+/// The default allocator, which creates buffers directly from the device.
+#[derive(Default)]
+pub struct WgpuBufferAllocator;
+
+impl BufferAllocator for WgpuBufferAllocator {
+    fn create_buffer(&mut self, device: &Device, descriptor: &wgpu::BufferDescriptor) -> Buffer {
+        device.create_buffer(descriptor)
+    }
+}
+
+/// A flag which can be set from another thread to cancel in-flight renders.
+///
+/// The token is checked between pipeline stages. Once it is cancelled, no further stages are
+/// recorded, nothing is submitted to the GPU, and the render returns a [`Cancelled`] error.
+/// The token stays cancelled until [`reset`](Self::reset) is called.
+#[derive(Clone, Default, Debug)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that renders using this token stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears a previous cancellation, so that the token can be used for later renders.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The error returned from a render that was stopped by a [`CancellationToken`].
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("render was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
 #[derive(Default)]
 pub struct WgpuEngine {
     shaders: Vec<Shader>,
     pool: ResourcePool,
     bind_map: BindMap,
     downloads: HashMap<Id, Buffer>,
+    cancellation: Option<CancellationToken>,
+    #[cfg(feature = "debug")]
+    stage_callback: Option<StageCallback>,
+    /// When the GPU last finished all of the work submitted before it, set from the submitted
+    /// work done callback of the queue.
+    submit_completed: Arc<Mutex<Option<Instant>>>,
     pub(crate) use_cpu: bool,
 }
 
@@ -43,13 +121,67 @@ struct CpuShader {
     shader: fn(u32, &[CpuBinding]),
 }
 
+/// A pipeline stage which has just been recorded, passed to the stage callback.
+#[cfg(feature = "debug")]
+pub struct StageInfo<'a> {
+    /// The name of the shader, such as `"flatten"` or `"fine_area"`.
+    pub label: &'static str,
+    /// The buffers bound to the stage, in binding order.
+    pub buffers: Vec<StageBuffer<'a>>,
+}
+
+/// A buffer bound to a pipeline stage.
+#[cfg(feature = "debug")]
+pub struct StageBuffer<'a> {
+    pub name: &'static str,
+    /// The GPU buffer, or `None` if the buffer is held on the CPU, as it is for the stages
+    /// which run on the CPU.
+    pub buffer: Option<&'a Buffer>,
+}
+
+/// Callback invoked with each pipeline stage as it is recorded.
+///
+/// The GPU work of the stage has been recorded, but not yet submitted, so the buffers can be
+/// copied from with a later command encoder to inspect the state after the stage.
+#[cfg(feature = "debug")]
+pub type StageCallback = Box<dyn FnMut(&StageInfo) + Send>;
+
+/// Sink for the debug groups which label each stage in GPU captures.
+#[cfg(feature = "debug")]
+trait DebugMarkers {
+    fn push_debug_group(&mut self, label: &str);
+    fn pop_debug_group(&mut self);
+}
+
+#[cfg(feature = "debug")]
+impl DebugMarkers for CommandEncoder {
+    fn push_debug_group(&mut self, label: &str) {
+        CommandEncoder::push_debug_group(self, label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        CommandEncoder::pop_debug_group(self);
+    }
+}
+
+/// The shader run by `command`, if it is a dispatch.
+#[cfg(feature = "debug")]
+fn dispatched_shader(command: &Command) -> Option<ShaderId> {
+    match command {
+        Command::Dispatch(shader_id, ..) | Command::DispatchIndirect(shader_id, ..) => {
+            Some(*shader_id)
+        }
+        _ => None,
+    }
+}
+
 enum ShaderKind<'a> {
     Wgpu(&'a WgpuShader),
     Cpu(&'a CpuShader),
 }
 
 struct Shader {
-    #[allow(dead_code)]
+    #[cfg_attr(not(feature = "debug"), allow(dead_code))]
     label: &'static str,
     wgpu: Option<WgpuShader>,
     cpu: Option<CpuShader>,
@@ -100,9 +232,26 @@ struct BufferProperties {
     name: &'static str,
 }
 
-#[derive(Default)]
 struct ResourcePool {
     bufs: HashMap<BufferProperties, Vec<Buffer>>,
+    allocator: Box<dyn BufferAllocator>,
+}
+
+impl Default for ResourcePool {
+    fn default() -> Self {
+        Self {
+            bufs: HashMap::default(),
+            allocator: Box::new(WgpuBufferAllocator),
+        }
+    }
+}
+
+impl Drop for ResourcePool {
+    fn drop(&mut self) {
+        for buf in self.bufs.drain().flat_map(|(_, bufs)| bufs) {
+            self.allocator.free_buffer(buf);
+        }
+    }
 }
 
 /// The transient bind map contains short-lifetime resources.
@@ -130,6 +279,61 @@ impl WgpuEngine {
         }
     }
 
+    /// Replace the allocator used to create GPU buffers.
+    ///
+    /// Any buffers pooled from the previous allocator are returned to it.
+    pub fn set_buffer_allocator(&mut self, allocator: Box<dyn BufferAllocator>) {
+        self.pool = ResourcePool {
+            bufs: HashMap::default(),
+            allocator,
+        };
+    }
+
+    /// Set the token checked between stages of each recording.
+    pub fn set_cancellation_token(&mut self, token: Option<CancellationToken>) {
+        self.cancellation = token;
+    }
+
+    /// Set the callback invoked with each stage of a recording as it is recorded.
+    #[cfg(feature = "debug")]
+    pub fn set_stage_callback(&mut self, callback: Option<StageCallback>) {
+        self.stage_callback = callback;
+    }
+
+    /// Take over the buffer pool (and allocator) of another engine.
+    #[cfg(feature = "hot_reload")]
+    pub fn take_buffer_pool(&mut self, other: &mut WgpuEngine) {
+        std::mem::swap(&mut self.pool, &mut other.pool);
+        self.cancellation = other.cancellation.take();
+        #[cfg(feature = "debug")]
+        {
+            self.stage_callback = other.stage_callback.take();
+        }
+        self.submit_completed = other.submit_completed.clone();
+    }
+
+    /// Records the time at which the work submitted to `queue` so far completes.
+    ///
+    /// This does nothing on wasm32, where submissions aren't tracked.
+    pub fn track_submit(&self, queue: &Queue) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let submit_completed = self.submit_completed.clone();
+            queue.on_submitted_work_done(move || {
+                *submit_completed.lock().unwrap() = Some(Instant::now());
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = queue;
+    }
+
+    /// Returns when the GPU last finished the work submitted through [`track_submit`].
+    ///
+    /// [`track_submit`]: Self::track_submit
+    pub fn last_submit_completed_at(&self) -> Option<Instant> {
+        *self.submit_completed.lock().unwrap()
+    }
+
     /// Add a shader.
     ///
     /// This function is somewhat limited, it doesn't apply a label, only allows one bind group,
@@ -251,6 +455,60 @@ impl WgpuEngine {
         })
     }
 
+    /// Opens a debug group named after the stage if `command` is a dispatch.
+    ///
+    /// The group is closed by [`Self::pop_stage_marker`] once the command has been recorded.
+    #[cfg(feature = "debug")]
+    fn push_stage_marker(&self, markers: &mut impl DebugMarkers, command: &Command) {
+        if let Some(shader_id) = dispatched_shader(command) {
+            markers.push_debug_group(self.shaders[shader_id.0].label);
+        }
+    }
+
+    /// Closes the debug group opened by [`Self::push_stage_marker`] for `command`, if any.
+    #[cfg(feature = "debug")]
+    fn pop_stage_marker(markers: &mut impl DebugMarkers, command: &Command) {
+        if dispatched_shader(command).is_some() {
+            markers.pop_debug_group();
+        }
+    }
+
+    /// Passes the stage of `command` to the stage callback, if there is one and `command` is
+    /// a dispatch.
+    #[cfg(feature = "debug")]
+    fn notify_stage(&mut self, command: &Command, transient_map: &TransientBindMap) {
+        let Some(callback) = self.stage_callback.as_mut() else {
+            return;
+        };
+        let (Command::Dispatch(shader_id, _, bindings)
+        | Command::DispatchIndirect(shader_id, _, _, bindings)) = command
+        else {
+            return;
+        };
+        let buffers = bindings
+            .iter()
+            .filter_map(|binding| match binding {
+                ResourceProxy::Buf(proxy) => Some(proxy),
+                ResourceProxy::Image(_) => None,
+            })
+            .map(|proxy| {
+                let buffer = match transient_map.bufs.get(&proxy.id) {
+                    Some(TransientBuf::Gpu(buffer)) => Some(*buffer),
+                    Some(TransientBuf::Cpu(_)) => None,
+                    None => self.bind_map.get_gpu_buf(proxy.id),
+                };
+                StageBuffer {
+                    name: proxy.name,
+                    buffer,
+                }
+            })
+            .collect();
+        callback(&StageInfo {
+            label: self.shaders[shader_id.0].label,
+            buffers,
+        });
+    }
+
     pub fn run_recording(
         &mut self,
         device: &Device,
@@ -268,7 +526,20 @@ impl WgpuEngine {
             device.create_command_encoder(&CommandEncoderDescriptor { label: Some(label) });
         #[cfg(feature = "wgpu-profiler")]
         let query = profiler.begin_query(label, &mut encoder, device);
+        let mut cancelled = false;
         for command in &recording.commands {
+            if !cancelled {
+                cancelled = self
+                    .cancellation
+                    .as_ref()
+                    .is_some_and(CancellationToken::is_cancelled);
+            }
+            // Once cancelled, only release resources so that the pools stay consistent.
+            if cancelled && !matches!(command, Command::FreeBuf(_) | Command::FreeImage(_)) {
+                continue;
+            }
+            #[cfg(feature = "debug")]
+            self.push_stage_marker(&mut encoder, command);
             match command {
                 Command::Upload(buf_proxy, bytes) => {
                     transient_map
@@ -462,6 +733,10 @@ impl WgpuEngine {
                     }
                 }
                 Command::Download(proxy) => {
+                    // Buffers last written by a CPU shader are only on the CPU so far.
+                    if let Some(buf) = self.bind_map.buf_map.get_mut(&proxy.id) {
+                        buf.upload_if_needed(proxy, device, queue, &mut self.pool);
+                    }
                     let src_buf = self
                         .bind_map
                         .get_gpu_buf(proxy.id)
@@ -469,7 +744,9 @@ impl WgpuEngine {
                     let usage = BufferUsages::MAP_READ | BufferUsages::COPY_DST;
                     let buf = self.pool.get_buf(proxy.size, "download", usage, device);
                     encoder.copy_buffer_to_buffer(src_buf, 0, &buf, 0, proxy.size);
-                    self.downloads.insert(proxy.id, buf);
+                    if let Some(old) = self.downloads.insert(proxy.id, buf) {
+                        self.pool.allocator.free_buffer(old);
+                    }
                 }
                 Command::Clear(proxy, offset, size) => {
                     if let Some(buf) = self.bind_map.get_buf(*proxy) {
@@ -494,10 +771,23 @@ impl WgpuEngine {
                     free_images.insert(proxy.id);
                 }
             }
+            #[cfg(feature = "debug")]
+            Self::pop_stage_marker(&mut encoder, command);
+            #[cfg(feature = "debug")]
+            self.notify_stage(command, &transient_map);
         }
         #[cfg(feature = "wgpu-profiler")]
         profiler.end_query(&mut encoder, query);
-        queue.submit(Some(encoder.finish()));
+        // The last stage may have been recorded after the token was cancelled.
+        cancelled = cancelled
+            || self
+                .cancellation
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled);
+        if !cancelled {
+            queue.submit(Some(encoder.finish()));
+            self.track_submit(queue);
+        }
         for id in free_bufs {
             if let Some(buf) = self.bind_map.buf_map.remove(&id) {
                 if let MaterializedBuffer::Gpu(gpu_buf) = buf.buffer {
@@ -518,6 +808,9 @@ impl WgpuEngine {
                 drop(view);
             }
         }
+        if cancelled {
+            return Err(Box::new(Cancelled));
+        }
         Ok(())
     }
 
@@ -526,7 +819,9 @@ impl WgpuEngine {
     }
 
     pub fn free_download(&mut self, buf: BufProxy) {
-        self.downloads.remove(&buf.id);
+        if let Some(buf) = self.downloads.remove(&buf.id) {
+            self.pool.allocator.free_buffer(buf);
+        }
     }
 }
 
@@ -640,15 +935,18 @@ impl ResourcePool {
                 return buf;
             }
         }
-        device.create_buffer(&wgpu::BufferDescriptor {
-            #[cfg(feature = "buffer_labels")]
-            label: Some(name),
-            #[cfg(not(feature = "buffer_labels"))]
-            label: None,
-            size: rounded_size,
-            usage,
-            mapped_at_creation: false,
-        })
+        self.allocator.create_buffer(
+            device,
+            &wgpu::BufferDescriptor {
+                #[cfg(feature = "buffer_labels")]
+                label: Some(name),
+                #[cfg(not(feature = "buffer_labels"))]
+                label: None,
+                size: rounded_size,
+                usage,
+                mapped_at_creation: false,
+            },
+        )
     }
 
     /// Quantize a size up to the nearest size class.
@@ -859,3 +1157,107 @@ impl<'a> TransientBindMap<'a> {
             .collect()
     }
 }
+
+#[cfg(all(test, feature = "debug"))]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockMarkers {
+        events: Vec<String>,
+    }
+
+    impl DebugMarkers for MockMarkers {
+        fn push_debug_group(&mut self, label: &str) {
+            self.events.push(format!("push {label}"));
+        }
+
+        fn pop_debug_group(&mut self) {
+            self.events.push("pop".into());
+        }
+    }
+
+    #[test]
+    fn test_stage_markers() {
+        let mut engine = WgpuEngine::default();
+        let mut shader = |label| {
+            engine.shaders.push(Shader {
+                label,
+                wgpu: None,
+                cpu: Some(CpuShader { shader: |_, _| {} }),
+            });
+            ShaderId(engine.shaders.len() - 1)
+        };
+        let reduce = shader("pathtag_reduce");
+        let scan = shader("pathtag_scan");
+        let fine = shader("fine_area");
+
+        let mut recording = Recording::default();
+        let config = recording.upload_uniform("config", [0_u8; 4]);
+        let indirect = BufProxy::new(12, "indirect_count");
+        recording.dispatch(reduce, (1, 1, 1), [config]);
+        recording.dispatch(scan, (1, 1, 1), [config]);
+        recording.clear_all(indirect);
+        recording.dispatch_indirect(fine, indirect, 0, [config]);
+        recording.free_buf(config);
+
+        let mut markers = MockMarkers::default();
+        for command in &recording.commands {
+            engine.push_stage_marker(&mut markers, command);
+            WgpuEngine::pop_stage_marker(&mut markers, command);
+        }
+        assert_eq!(
+            markers.events,
+            [
+                "push pathtag_reduce",
+                "pop",
+                "push pathtag_scan",
+                "pop",
+                "push fine_area",
+                "pop",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_callback() {
+        let mut engine = WgpuEngine::default();
+        let mut shader = |label| {
+            engine.shaders.push(Shader {
+                label,
+                wgpu: None,
+                cpu: Some(CpuShader { shader: |_, _| {} }),
+            });
+            ShaderId(engine.shaders.len() - 1)
+        };
+        let flatten = shader("flatten");
+        let coarse = shader("coarse");
+
+        let mut recording = Recording::default();
+        let config = recording.upload_uniform("config", [0_u8; 4]);
+        let lines = BufProxy::new(16, "lines");
+        let indirect = BufProxy::new(12, "indirect_count");
+        recording.dispatch(flatten, (1, 1, 1), [config, lines]);
+        recording.clear_all(indirect);
+        recording.dispatch_indirect(coarse, indirect, 0, [lines]);
+        recording.free_buf(config);
+
+        let stages = Arc::new(Mutex::new(vec![]));
+        let sink = stages.clone();
+        engine.set_stage_callback(Some(Box::new(move |stage: &StageInfo| {
+            let names: Vec<_> = stage.buffers.iter().map(|buffer| buffer.name).collect();
+            sink.lock().unwrap().push((stage.label, names));
+        })));
+        let transient_map = TransientBindMap::new(&[]);
+        for command in &recording.commands {
+            engine.notify_stage(command, &transient_map);
+        }
+        assert_eq!(
+            *stages.lock().unwrap(),
+            [
+                ("flatten", vec!["config", "lines"]),
+                ("coarse", vec!["lines"]),
+            ]
+        );
+    }
+}